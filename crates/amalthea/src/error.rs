@@ -26,6 +26,7 @@ pub enum Error {
     UnknownMessageType(String),
     NoInstallDir,
     CreateDirFailed(std::io::Error),
+    InstallDirNotWritable(std::path::PathBuf),
     JsonSerializeSpecFailed(serde_json::Error),
     CreateSpecFailed(std::io::Error),
     WriteSpecFailed(std::io::Error),
@@ -118,6 +119,9 @@ impl fmt::Display for Error {
             Error::CreateDirFailed(err) => {
                 write!(f, "Could not create directory: {}", err)
             },
+            Error::InstallDirNotWritable(path) => {
+                write!(f, "Installation directory {:?} is not writable", path)
+            },
             Error::JsonSerializeSpecFailed(err) => {
                 write!(f, "Could not serialize kernel spec to JSON: {}", err)
             },
@@ -52,7 +52,9 @@ pub struct DummyFrontend {
 
 #[derive(Default)]
 pub struct ExecuteRequestOptions {
+    pub silent: bool,
     pub allow_stdin: bool,
+    pub user_expressions: serde_json::Value,
     pub positron: Option<ExecuteRequestPositron>,
 }
 
@@ -244,9 +246,9 @@ impl DummyFrontend {
     pub fn send_execute_request(&self, code: &str, options: ExecuteRequestOptions) -> String {
         self.send_shell(ExecuteRequest {
             code: String::from(code),
-            silent: false,
+            silent: options.silent,
             store_history: true,
-            user_expressions: serde_json::Value::Null,
+            user_expressions: options.user_expressions,
             allow_stdin: options.allow_stdin,
             stop_on_error: false,
             positron: options.positron,
@@ -265,9 +267,9 @@ impl DummyFrontend {
             &self.session,
             ExecuteRequest {
                 code: String::from(code),
-                silent: false,
+                silent: options.silent,
                 store_history: true,
-                user_expressions: serde_json::Value::Null,
+                user_expressions: options.user_expressions,
                 allow_stdin: options.allow_stdin,
                 stop_on_error: false,
                 positron: options.positron,
@@ -402,6 +404,18 @@ impl DummyFrontend {
         })
     }
 
+    /// Receive from Shell and assert `ExecuteReply` message with an `aborted`
+    /// status. Returns the parent message ID.
+    #[track_caller]
+    pub fn recv_shell_execute_reply_aborted(&self) -> String {
+        let msg = self.recv_shell();
+
+        assert_matches!(msg, Message::ExecuteReply(data) => {
+            assert_eq!(data.content.status, Status::Aborted);
+            data.parent_header.unwrap().msg_id
+        })
+    }
+
     /// Receive from Shell and assert `ExecuteReplyException` message.
     /// Returns `execution_count`.
     #[track_caller]
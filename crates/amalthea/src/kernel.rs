@@ -6,9 +6,12 @@
  */
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use chrono::DateTime;
+use chrono::FixedOffset;
 use crossbeam::channel::bounded;
 use crossbeam::channel::unbounded;
 use crossbeam::channel::Receiver;
@@ -42,12 +45,20 @@ use crate::wire::jupyter_message::OutboundMessage;
 use crate::wire::jupyter_message::Status;
 use crate::wire::subscription_message::SubscriptionMessage;
 
+/// How long to wait for the frontend to reply to the handshake request of a
+/// registration-based connection
+const HANDSHAKE_TIMEOUT_MS: i64 = 10_000;
+
+/// How often to log progress while waiting for the handshake reply
+const HANDSHAKE_POLL_INTERVAL_MS: i64 = 1_000;
+
 /// Possible behaviors for the stream capture thread. When set to `Capture`,
-/// the stream capture thread will capture all output to stdout and stderr.
-/// When set to `None`, no stream output is captured.
+/// the stream capture thread will capture all output to stdout and stderr,
+/// and also write it to the `tee` file if supplied. When set to `None`, no
+/// stream output is captured.
 #[derive(PartialEq)]
 pub enum StreamBehavior {
-    Capture,
+    Capture { tee: Option<PathBuf> },
     None,
 }
 
@@ -128,7 +139,13 @@ pub fn connect(
     // notifier watches `comm_manager_rx` and forwards events via `shell_comm_tx`.
     let (shell_comm_tx, shell_comm_rx) = unbounded::<CommEvent>();
 
+    // Set by Control on interrupt to the date of the interrupt request, so
+    // that Shell aborts the execute requests sent before it and queued behind
+    // the one being interrupted
+    let interrupt_date = Arc::new(Mutex::new(None));
+
     let iopub_tx_clone = channels.iopub_tx.clone();
+    let shell_interrupt_date = interrupt_date.clone();
     spawn!(format!("{name}-shell"), move || {
         shell_thread(
             shell_socket,
@@ -137,6 +154,7 @@ pub fn connect(
             shell_comm_rx,
             handlers.shell_handler,
             handlers.server_handlers,
+            shell_interrupt_date,
         )
     });
 
@@ -217,10 +235,10 @@ pub fn connect(
     });
 
     // Create the thread that handles stdout and stderr, if requested
-    if stream_behavior == StreamBehavior::Capture {
+    if let StreamBehavior::Capture { tee } = stream_behavior {
         let iopub_tx_clone = channels.iopub_tx.clone();
         spawn!(format!("{name}-output-capture"), move || {
-            output_capture_thread(iopub_tx_clone)
+            output_capture_thread(iopub_tx_clone, tee)
         });
     }
 
@@ -297,6 +315,7 @@ pub fn connect(
             iopub_tx_clone,
             handlers.control_handler,
             stdin_interrupt_tx,
+            interrupt_date,
         );
         log::error!("Control thread exited");
     });
@@ -381,8 +400,15 @@ fn control_thread(
     iopub_tx: Sender<IOPubMessage>,
     handler: Box<dyn ControlHandler>,
     stdin_interrupt_tx: Sender<bool>,
+    interrupt_date: Arc<Mutex<Option<DateTime<FixedOffset>>>>,
 ) {
-    let control = Control::new(socket, iopub_tx, handler, stdin_interrupt_tx);
+    let control = Control::new(
+        socket,
+        iopub_tx,
+        handler,
+        stdin_interrupt_tx,
+        interrupt_date,
+    );
     control.listen();
 }
 
@@ -394,6 +420,7 @@ fn shell_thread(
     comm_event_rx: Receiver<CommEvent>,
     shell_handler: Box<dyn ShellHandler>,
     server_handlers: HashMap<String, Arc<Mutex<dyn ServerHandler>>>,
+    interrupt_date: Arc<Mutex<Option<DateTime<FixedOffset>>>>,
 ) -> Result<(), Error> {
     let mut shell = Shell::new(
         socket,
@@ -402,6 +429,7 @@ fn shell_thread(
         comm_event_rx,
         shell_handler,
         server_handlers,
+        interrupt_date,
     );
     shell.listen();
     Ok(())
@@ -687,8 +715,14 @@ fn channel_bridge_thread(
 }
 
 /// Starts the output capture thread.
-fn output_capture_thread(iopub_tx: Sender<IOPubMessage>) -> Result<(), Error> {
-    let output_capture = StreamCapture::new(iopub_tx);
+fn output_capture_thread(
+    iopub_tx: Sender<IOPubMessage>,
+    tee: Option<PathBuf>,
+) -> Result<(), Error> {
+    let output_capture = match tee {
+        Some(tee) => StreamCapture::with_tee(iopub_tx, tee),
+        None => StreamCapture::new(iopub_tx),
+    };
     output_capture.listen();
     Ok(())
 }
@@ -699,34 +733,50 @@ fn handshake(
     session: &Session,
     request: HandshakeRequest,
 ) -> crate::Result<()> {
+    let endpoint = registration_file.endpoint();
+    log::info!("Handshake: connecting to registration socket at {endpoint}");
+
     // Create a temporary registration socket to send the handshake request over.
-    // This socket `Drop`s and closes when this function exits.
+    // This socket `Drop`s and closes when this function exits. Connecting
+    // succeeds even if the frontend hasn't bound the registration socket yet,
+    // in which case ZeroMQ keeps retrying the connection in the background and
+    // delivers our request once it goes through.
     let registration_socket = Socket::new(
         session.clone(),
         ctx.clone(),
         String::from("Registration"),
         zmq::REQ,
         None,
-        registration_file.endpoint(),
+        endpoint,
     )?;
 
+    log::info!("Handshake: sending ports {request:?}");
     let message = JupyterMessage::create(request, None, session);
-
     message.send(&registration_socket)?;
 
-    // Wait for the handshake reply with a 5 second timeout.
-    // If we don't get a handshake reply, we are going to eventually panic and shut down.
-    if !registration_socket
-        .poll_incoming(5000)
-        .map_err(|err| Error::ZmqError(registration_socket.name.clone(), err))?
-    {
-        return Err(crate::anyhow!(
-            "Timeout while waiting for connection information from registration socket"
-        ));
+    // Wait for the handshake reply, logging periodically so that slow
+    // handshakes can be diagnosed. If we don't get a handshake reply, we are
+    // going to eventually panic and shut down.
+    let mut waited = 0;
+    loop {
+        let ready = registration_socket
+            .poll_incoming(HANDSHAKE_POLL_INTERVAL_MS)
+            .map_err(|err| Error::ZmqError(registration_socket.name.clone(), err))?;
+        if ready {
+            break;
+        }
+
+        waited += HANDSHAKE_POLL_INTERVAL_MS;
+        if waited >= HANDSHAKE_TIMEOUT_MS {
+            return Err(crate::anyhow!(
+                "Timeout after {waited}ms while waiting for connection information from registration socket"
+            ));
+        }
+        log::info!("Handshake: still waiting for reply after {waited}ms");
     }
 
     // Read the `HandshakeReply` off the socket and confirm its message type
-    let reply = Message::read_from_socket(&registration_socket).unwrap();
+    let reply = Message::read_from_socket(&registration_socket)?;
     let status = match reply {
         Message::HandshakeReply(reply) => reply.content.status,
         _ => {
@@ -735,11 +785,14 @@ fn handshake(
             ));
         },
     };
+    log::info!("Handshake: received reply with status {status:?}");
 
     // Check that the client did indeed connect successfully
     match status {
         Status::Ok => Ok(()),
-        Status::Error => Err(crate::anyhow!("Client failed to connect to ports.")),
+        Status::Error | Status::Aborted => {
+            Err(crate::anyhow!("Client failed to connect to ports."))
+        },
     }
 }
 
@@ -6,6 +6,7 @@
  */
 
 use std::env;
+use std::path::Path;
 use std::path::PathBuf;
 
 /// Returns the path where Jupyter kernels should be/are installed.
@@ -16,6 +17,13 @@ pub fn jupyter_kernel_path() -> Option<PathBuf> {
     None
 }
 
+/// Returns the path where Jupyter kernels should be installed under a custom
+/// prefix, e.g. the root of a conda environment or virtualenv. This mirrors
+/// `jupyter kernelspec install --prefix`, which uses `PREFIX/share/jupyter/kernels`.
+pub fn jupyter_prefix_kernel_path(prefix: &Path) -> PathBuf {
+    prefix.join("share").join("jupyter").join("kernels")
+}
+
 /// Returns the root Jupyter directory; uses the `JUPYTER_PATH` environment
 /// variable if set, XDG values if not.
 pub fn jupyter_dir() -> Option<PathBuf> {
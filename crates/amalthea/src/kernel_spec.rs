@@ -8,6 +8,7 @@
 use std::fs;
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
 use log::trace;
@@ -17,6 +18,19 @@ use serde_json::Value;
 use crate::error::Error;
 use crate::kernel_dirs;
 
+/// How the frontend should interrupt the kernel.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum InterruptMode {
+    /// Send a `SIGINT` to the kernel process
+    #[default]
+    Signal,
+
+    /// Send an `interrupt_request` on the Control socket, for environments
+    /// where signals can't be delivered (e.g. Windows or some containers)
+    Message,
+}
+
 /// From the Jupyter documentation for [Kernel Specs](https://jupyter-client.readthedocs.io/en/stable/kernels.html#kernel-specs).
 #[derive(Serialize)]
 pub struct KernelSpec {
@@ -31,11 +45,27 @@ pub struct KernelSpec {
 
     // Environment variables to set for the kernel
     pub env: serde_json::Map<String, Value>,
+
+    /// How the kernel should be interrupted
+    pub interrupt_mode: InterruptMode,
+
+    /// Additional attributes about the kernel, used by clients to aid in
+    /// kernel selection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Map<String, Value>>,
 }
 
 impl KernelSpec {
     /// Install a kernel spec to disk.
-    pub fn install(&self, folder: String) -> Result<PathBuf, Error> {
+    ///
+    /// When `prefix` is supplied, the kernel spec is installed under
+    /// `PREFIX/share/jupyter/kernels/FOLDER` instead of the default Jupyter
+    /// search path.
+    pub fn install(&self, folder: String, prefix: Option<&Path>) -> Result<PathBuf, Error> {
+        if let Some(prefix) = prefix {
+            let kernel_dir = kernel_dirs::jupyter_prefix_kernel_path(prefix);
+            return self.install_to(kernel_dir.join(folder));
+        }
         if let Some(kernel_dir) = kernel_dirs::jupyter_kernel_path() {
             return self.install_to(kernel_dir.join(folder));
         }
@@ -47,6 +77,13 @@ impl KernelSpec {
         if let Err(err) = fs::create_dir_all(&path) {
             return Err(Error::CreateDirFailed(err));
         }
+        match fs::metadata(&path) {
+            Ok(metadata) if metadata.permissions().readonly() => {
+                return Err(Error::InstallDirNotWritable(path));
+            },
+            Ok(_) => {},
+            Err(err) => return Err(Error::CreateDirFailed(err)),
+        }
         let dest = path.join("kernel.json");
 
         // Serialize the kernel spec to JSON
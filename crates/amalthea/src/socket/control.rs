@@ -5,6 +5,11 @@
  *
  */
 
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use chrono::DateTime;
+use chrono::FixedOffset;
 use crossbeam::channel::SendError;
 use crossbeam::channel::Sender;
 use log::error;
@@ -33,6 +38,7 @@ pub struct Control {
     iopub_tx: Sender<IOPubMessage>,
     handler: Box<dyn ControlHandler>,
     stdin_interrupt_tx: Sender<bool>,
+    interrupt_date: Arc<Mutex<Option<DateTime<FixedOffset>>>>,
 }
 
 impl Control {
@@ -41,12 +47,14 @@ impl Control {
         iopub_tx: Sender<IOPubMessage>,
         handler: Box<dyn ControlHandler>,
         stdin_interrupt_tx: Sender<bool>,
+        interrupt_date: Arc<Mutex<Option<DateTime<FixedOffset>>>>,
     ) -> Self {
         Self {
             socket,
             iopub_tx,
             handler,
             stdin_interrupt_tx,
+            interrupt_date,
         }
     }
 
@@ -172,6 +180,19 @@ impl Control {
             req
         );
 
+        // Let Shell know that the execute requests sent before this interrupt
+        // and queued behind the current one should be aborted rather than run.
+        // This must happen before the handler interrupts the current request
+        // so Shell sees the date once that request completes.
+        let date = req.header.timestamp();
+        if date.is_none() {
+            warn!(
+                "Can't parse interrupt request date '{}', queued execute requests won't be aborted",
+                req.header.date
+            );
+        }
+        *self.interrupt_date.lock().unwrap() = date;
+
         // Notify StdIn socket first in case it's waiting for
         // input which is never going to come because of the
         // interrupt
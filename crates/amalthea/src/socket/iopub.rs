@@ -15,6 +15,7 @@ use stdext::result::ResultExt;
 
 use crate::comm::comm_channel::CommMsg;
 use crate::session::Session;
+use crate::wire::clear_output::ClearOutput;
 use crate::wire::comm_close::CommClose;
 use crate::wire::comm_msg::CommWireMsg;
 use crate::wire::comm_open::CommOpen;
@@ -93,6 +94,7 @@ pub enum IOPubMessage {
     Stream(StreamOutput),
     DisplayData(DisplayData),
     UpdateDisplayData(UpdateDisplayData),
+    ClearOutput(ClearOutput),
     Wait(Wait),
     /// Outgoing comm message from a backend. The String is the comm_id.
     CommOutgoing(String, CommMsg),
@@ -243,6 +245,12 @@ impl IOPub {
                     self.message_with_context(content, IOPubContextChannel::Shell),
                 ))
             },
+            IOPubMessage::ClearOutput(content) => {
+                self.flush_stream();
+                self.forward(Message::ClearOutput(
+                    self.message_with_context(content, IOPubContextChannel::Shell),
+                ))
+            },
             IOPubMessage::DebugEvent(content) => {
                 self.flush_stream();
                 self.forward(Message::DebugEvent(
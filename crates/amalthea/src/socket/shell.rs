@@ -10,6 +10,8 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use chrono::DateTime;
+use chrono::FixedOffset;
 use crossbeam::channel::Receiver;
 use crossbeam::channel::Select;
 use crossbeam::channel::Sender;
@@ -82,6 +84,20 @@ pub struct Shell {
 
     /// The set of currently open comm channels
     open_comms: Vec<CommSocket>,
+
+    /// Set by Control to the date of the interrupt request when an interrupt
+    /// is requested
+    interrupt_date: Arc<Mutex<Option<DateTime<FixedOffset>>>>,
+
+    /// The date of the interrupt that stopped the last execute request. Until
+    /// an execute request sent after that date comes in, execute requests
+    /// are aborted rather than run, since they were queued before the
+    /// interrupt.
+    abort_until: Option<DateTime<FixedOffset>>,
+
+    /// The execution count of the last completed execute request, reported
+    /// in the replies to aborted requests
+    execution_count: u32,
 }
 
 impl Shell {
@@ -93,6 +109,7 @@ impl Shell {
     /// * `comm_event_rx` - A channel that receives comm registration events from backend comms
     /// * `shell_handler` - The language's shell channel handler
     /// * `server_handlers` - A map of server handler target names to their handlers
+    /// * `interrupt_date` - Set by Control to the date of an interrupt request
     pub fn new(
         socket: Socket,
         iopub_tx: Sender<IOPubMessage>,
@@ -100,6 +117,7 @@ impl Shell {
         comm_event_rx: Receiver<CommEvent>,
         shell_handler: Box<dyn ShellHandler>,
         server_handlers: HashMap<String, Arc<Mutex<dyn ServerHandler>>>,
+        interrupt_date: Arc<Mutex<Option<DateTime<FixedOffset>>>>,
     ) -> Self {
         Self {
             socket,
@@ -109,6 +127,9 @@ impl Shell {
             comm_notif_socket,
             comm_event_rx,
             open_comms: Vec::new(),
+            interrupt_date,
+            abort_until: None,
+            execution_count: 0,
         }
     }
 
@@ -370,12 +391,19 @@ impl Shell {
     /// waits for Shell to drain comm events while Shell waits for the execute
     /// response.
     fn handle_execute_request(&mut self, req: JupyterMessage<ExecuteRequest>) -> crate::Result<()> {
+        if self.is_aborted(&req) {
+            return self.abort_queued_execute_request(req);
+        }
+
         self.iopub_tx
             .send(status(req.clone(), ExecutionState::Busy))
             .unwrap();
 
         log::info!("Received shell request: {req:?}");
 
+        // Interrupts received before this request started don't concern it
+        *self.interrupt_date.lock().unwrap() = None;
+
         // FIXME: We should ideally not pass the originator to the language kernel
         let originator = Originator::from(&req);
         let response_rx = self
@@ -385,11 +413,15 @@ impl Shell {
         let result = self.drain_comm_events_until(&response_rx);
 
         let result = match result {
-            Ok(reply) => req.send_reply(reply, &self.socket),
+            Ok(reply) => {
+                self.execution_count = reply.execution_count;
+                req.send_reply(reply, &self.socket)
+            },
             Err(crate::Error::ShellErrorReply(error)) => {
                 req.send_error::<ExecuteReply>(error, &self.socket)
             },
             Err(crate::Error::ShellErrorExecuteReply(error, exec_count)) => {
+                self.execution_count = exec_count;
                 req.send_execute_error(error, exec_count, &self.socket)
             },
             Err(err) => {
@@ -402,9 +434,60 @@ impl Shell {
             .send(status(req.clone(), ExecutionState::Idle))
             .unwrap();
 
+        // If this request was interrupted, the requests queued behind it are
+        // aborted as they come in
+        if let Some(date) = self.interrupt_date.lock().unwrap().take() {
+            self.abort_until = Some(date);
+        }
+
         result.and(Ok(()))
     }
 
+    /// Whether an execute request was sent before the last interrupt and
+    /// should be aborted. The abort window closes with the first request sent
+    /// after the interrupt: requests are read in the order they were sent, so
+    /// none of the following ones can predate the interrupt.
+    fn is_aborted(&mut self, req: &JupyterMessage<ExecuteRequest>) -> bool {
+        let Some(interrupt_date) = self.abort_until else {
+            return false;
+        };
+
+        match req.header.timestamp() {
+            Some(date) if date <= interrupt_date => true,
+            _ => {
+                self.abort_until = None;
+                false
+            },
+        }
+    }
+
+    /// Abort an execute request queued before an interrupt, so that
+    /// interrupting clears the pending batch instead of only the running
+    /// request. The request gets an `aborted` reply without being run.
+    fn abort_queued_execute_request(
+        &mut self,
+        req: JupyterMessage<ExecuteRequest>,
+    ) -> crate::Result<()> {
+        log::info!("Aborting queued execute request after interrupt: {req:?}");
+
+        self.iopub_tx
+            .send(status(req.clone(), ExecutionState::Busy))
+            .unwrap();
+
+        let reply = ExecuteReply {
+            status: Status::Aborted,
+            execution_count: self.execution_count,
+            user_expressions: serde_json::Value::Null,
+        };
+        let result = req.send_reply(reply, &self.socket);
+
+        self.iopub_tx
+            .send(status(req.clone(), ExecutionState::Idle))
+            .unwrap();
+
+        result
+    }
+
     fn handle_comm_msg_request(&mut self, req: JupyterMessage<CommWireMsg>) -> crate::Result<()> {
         self.handle_comm_request(req, |this, req| {
             let originator = Originator::from(req);
@@ -5,6 +5,8 @@
  *
  */
 
+use std::path::PathBuf;
+
 use crossbeam::channel::Sender;
 
 use crate::socket::iopub::IOPubMessage;
@@ -16,7 +18,14 @@ pub struct StreamCapture(sys::stream_capture::StreamCapture);
 
 impl StreamCapture {
     pub fn new(iopub_tx: Sender<IOPubMessage>) -> Self {
-        StreamCapture(sys::stream_capture::StreamCapture::new(iopub_tx))
+        StreamCapture(sys::stream_capture::StreamCapture::new(iopub_tx, None))
+    }
+
+    /// Also writes the raw captured bytes to the file at `tee`, truncating
+    /// it first. This provides a log of stdout and stderr that is independent
+    /// of IOPub, useful to diagnose output ordering issues.
+    pub fn with_tee(iopub_tx: Sender<IOPubMessage>, tee: PathBuf) -> Self {
+        StreamCapture(sys::stream_capture::StreamCapture::new(iopub_tx, Some(tee)))
     }
 
     /// Listens to stdout and stderr and sends the output to the IOPub socket.
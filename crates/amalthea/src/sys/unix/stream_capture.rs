@@ -5,10 +5,13 @@
  *
  */
 
+use std::fs::File;
+use std::io::Write;
 use std::os::fd::AsFd;
 use std::os::fd::AsRawFd;
 use std::os::fd::BorrowedFd;
 use std::os::fd::OwnedFd;
+use std::path::PathBuf;
 
 use crossbeam::channel::Sender;
 use log::warn;
@@ -20,15 +23,25 @@ use crate::wire::stream::StreamOutput;
 
 pub struct StreamCapture {
     iopub_tx: Sender<IOPubMessage>,
+    tee: Option<PathBuf>,
 }
 
 impl StreamCapture {
-    pub fn new(iopub_tx: Sender<IOPubMessage>) -> Self {
-        Self { iopub_tx }
+    pub fn new(iopub_tx: Sender<IOPubMessage>, tee: Option<PathBuf>) -> Self {
+        Self { iopub_tx, tee }
     }
 
     pub fn listen(&self) {
-        if let Err(err) = Self::output_capture(self.iopub_tx.clone()) {
+        // A tee file we can't create is not fatal, we still forward output
+        let tee = self.tee.as_ref().and_then(|path| match File::create(path) {
+            Ok(file) => Some(file),
+            Err(err) => {
+                warn!("Can't create stream capture log at {path:?}: {err}");
+                None
+            },
+        });
+
+        if let Err(err) = Self::output_capture(self.iopub_tx.clone(), tee) {
             warn!(
                 "Error capturing output; stdout/stderr won't be forwarded: {}",
                 err
@@ -37,7 +50,7 @@ impl StreamCapture {
     }
 
     /// Captures stdout and stderr streams
-    fn output_capture(iopub_tx: Sender<IOPubMessage>) -> Result<(), Error> {
+    fn output_capture(iopub_tx: Sender<IOPubMessage>, mut tee: Option<File>) -> Result<(), Error> {
         // Create redirected file descriptors for stdout and stderr. These are
         // pipes into which stdout/stderr are redirected.
         let stdout_fd = Self::redirect_fd(libc::STDOUT_FILENO)?;
@@ -103,7 +116,7 @@ impl StreamCapture {
                     };
 
                     // Read the data from the stream and send it to iopub.
-                    Self::fd_to_iopub(poll_fd.as_fd(), stream, iopub_tx.clone());
+                    Self::fd_to_iopub(poll_fd.as_fd(), stream, iopub_tx.clone(), tee.as_mut());
                 }
             }
         }
@@ -113,7 +126,13 @@ impl StreamCapture {
     }
 
     /// Reads data from a file descriptor and sends it to the IOPub socket.
-    fn fd_to_iopub(fd: BorrowedFd, stream: Stream, iopub_tx: Sender<IOPubMessage>) {
+    /// The raw bytes are also written to `tee` if supplied.
+    fn fd_to_iopub(
+        fd: BorrowedFd,
+        stream: Stream,
+        iopub_tx: Sender<IOPubMessage>,
+        tee: Option<&mut File>,
+    ) {
         // Read up to 1024 bytes from the stream into `buf`
         let mut buf = [0u8; 1024];
         let count = match nix::unistd::read(fd, &mut buf) {
@@ -129,6 +148,14 @@ impl StreamCapture {
             return;
         }
 
+        // Log the bytes as received, before any lossy conversion. Flush right
+        // away so the log is complete even if the process crashes.
+        if let Some(tee) = tee {
+            if let Err(err) = tee.write_all(&buf[..count]).and_then(|_| tee.flush()) {
+                warn!("Error writing stream data to capture log: {err}");
+            }
+        }
+
         // Convert the UTF-8 bytes to a string.
         let data = String::from_utf8_lossy(&buf[..count]).to_string();
         let output = StreamOutput {
@@ -186,3 +213,48 @@ impl StreamCapture {
         Ok(read)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::os::fd::AsFd;
+
+    use crate::socket::iopub::IOPubMessage;
+    use crate::sys::stream_capture::StreamCapture;
+    use crate::wire::stream::Stream;
+
+    #[test]
+    fn test_stream_capture_tee() {
+        let path = std::env::temp_dir().join(format!(
+            "amalthea-stream-capture-tee-{}.log",
+            std::process::id()
+        ));
+        let mut tee = std::fs::File::create(&path).unwrap();
+
+        let (read, write) = nix::unistd::pipe().unwrap();
+        let mut write = std::fs::File::from(write);
+        let (iopub_tx, iopub_rx) = crossbeam::channel::unbounded();
+
+        write.write_all(b"hello\n").unwrap();
+        StreamCapture::fd_to_iopub(
+            read.as_fd(),
+            Stream::Stdout,
+            iopub_tx.clone(),
+            Some(&mut tee),
+        );
+
+        write.write_all(b"caf\xc3").unwrap();
+        StreamCapture::fd_to_iopub(read.as_fd(), Stream::Stderr, iopub_tx, Some(&mut tee));
+
+        // Output is still forwarded to IOPub
+        let IOPubMessage::Stream(output) = iopub_rx.recv().unwrap() else {
+            panic!("Expected a stream message");
+        };
+        assert_eq!(output.text, "hello\n");
+
+        // The tee file has the raw bytes, including invalid UTF-8
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello\ncaf\xc3");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -5,18 +5,22 @@
  *
  */
 
+use std::path::PathBuf;
+
 use crossbeam::channel::Sender;
 
 use crate::socket::iopub::IOPubMessage;
 
 pub struct StreamCapture {
     _iopub_tx: Sender<IOPubMessage>,
+    _tee: Option<PathBuf>,
 }
 
 impl StreamCapture {
-    pub fn new(iopub_tx: Sender<IOPubMessage>) -> Self {
+    pub fn new(iopub_tx: Sender<IOPubMessage>, tee: Option<PathBuf>) -> Self {
         Self {
             _iopub_tx: iopub_tx,
+            _tee: tee,
         }
     }
 
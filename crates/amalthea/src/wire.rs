@@ -5,6 +5,7 @@
  *
  */
 
+pub mod clear_output;
 pub mod comm_close;
 pub mod comm_info_reply;
 pub mod comm_info_request;
@@ -0,0 +1,28 @@
+/*
+ * clear_output.rs
+ *
+ * Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+ *
+ */
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::wire::jupyter_message::MessageType;
+
+/// Clears the output of the cell that produced the message, e.g. to redraw a
+/// progress display or an animation frame
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ClearOutput {
+    /// When `true`, the frontend waits until new output is available before
+    /// clearing the existing output. This avoids flickering when the output
+    /// is immediately replaced. When `false`, the output is cleared right
+    /// away.
+    pub wait: bool,
+}
+
+impl MessageType for ClearOutput {
+    fn message_type() -> String {
+        String::from("clear_output")
+    }
+}
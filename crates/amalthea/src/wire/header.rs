@@ -5,6 +5,8 @@
  *
  */
 
+use chrono::DateTime;
+use chrono::FixedOffset;
 use chrono::Utc;
 use serde::Deserialize;
 use serde::Serialize;
@@ -44,4 +46,10 @@ impl JupyterHeader {
             version: String::from("5.3"),
         }
     }
+
+    /// Parses the date/time when the message was created. Returns `None` if
+    /// the date is missing or not in RFC 3339 format.
+    pub fn timestamp(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.date).ok()
+    }
 }
@@ -10,6 +10,7 @@ use serde::Serialize;
 use serde_json::json;
 use serde_json::Value;
 
+use super::clear_output::ClearOutput;
 use super::display_data::DisplayData;
 use super::handshake_reply::HandshakeReply;
 use super::handshake_request::HandshakeRequest;
@@ -130,6 +131,7 @@ pub enum Message {
     Stream(JupyterMessage<StreamOutput>),
     DisplayData(JupyterMessage<DisplayData>),
     UpdateDisplayData(JupyterMessage<UpdateDisplayData>),
+    ClearOutput(JupyterMessage<ClearOutput>),
     Welcome(JupyterMessage<Welcome>),
     // IOPub/Shell
     CommMsg(JupyterMessage<CommWireMsg>),
@@ -153,6 +155,9 @@ pub enum OutboundMessage {
 pub enum Status {
     Ok,
     Error,
+    /// The request was cancelled before it ran, e.g. an execute request that
+    /// was still queued when the kernel was interrupted
+    Aborted,
 }
 
 /// Conversion from a `Message` to a `WireMessage`; used to send messages over a
@@ -200,6 +205,7 @@ impl TryFrom<&Message> for WireMessage {
             Message::HandshakeRequest(msg) => WireMessage::try_from(msg),
             Message::DisplayData(msg) => WireMessage::try_from(msg),
             Message::UpdateDisplayData(msg) => WireMessage::try_from(msg),
+            Message::ClearOutput(msg) => WireMessage::try_from(msg),
             Message::Welcome(msg) => WireMessage::try_from(msg),
         }
     }
@@ -283,6 +289,9 @@ impl TryFrom<&WireMessage> for Message {
         if kind == UpdateDisplayData::message_type() {
             return Ok(Message::UpdateDisplayData(JupyterMessage::try_from(msg)?));
         }
+        if kind == ClearOutput::message_type() {
+            return Ok(Message::ClearOutput(JupyterMessage::try_from(msg)?));
+        }
         if kind == ShutdownReply::message_type() {
             return Ok(Message::ShutdownReply(JupyterMessage::try_from(msg)?));
         }
@@ -390,6 +399,7 @@ impl Message {
             Self::Stream(msg) => msg.parent_header.as_ref(),
             Self::DisplayData(msg) => msg.parent_header.as_ref(),
             Self::UpdateDisplayData(msg) => msg.parent_header.as_ref(),
+            Self::ClearOutput(msg) => msg.parent_header.as_ref(),
             Self::Welcome(msg) => msg.parent_header.as_ref(),
             Self::CommMsg(msg) => msg.parent_header.as_ref(),
             Self::CommOpen(msg) => msg.parent_header.as_ref(),
@@ -0,0 +1,73 @@
+/*
+ * connection.rs
+ *
+ * Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+ *
+ */
+
+use std::path::PathBuf;
+
+use amalthea::kernel::read_connection;
+
+fn write_connection_file(name: &str, contents: serde_json::Value) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("amalthea-{}-{name}.json", std::process::id()));
+    std::fs::write(&path, contents.to_string()).unwrap();
+    path
+}
+
+#[test]
+fn test_read_connection_with_fixed_ports() {
+    let path = write_connection_file(
+        "fixed",
+        serde_json::json!({
+            "control_port": 50001,
+            "shell_port": 50002,
+            "stdin_port": 50003,
+            "iopub_port": 50004,
+            "hb_port": 50005,
+            "transport": "tcp",
+            "signature_scheme": "hmac-sha256",
+            "ip": "127.0.0.1",
+            "key": "secret",
+        }),
+    );
+
+    let (connection, registration) = read_connection(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+
+    // The classic connection file has no handshake
+    assert!(registration.is_none());
+    assert_eq!(connection.control_port, 50001);
+    assert_eq!(connection.shell_port, 50002);
+    assert_eq!(connection.stdin_port, 50003);
+    assert_eq!(connection.iopub_port, 50004);
+    assert_eq!(connection.hb_port, 50005);
+    assert_eq!(connection.key, "secret");
+}
+
+#[test]
+fn test_read_connection_with_registration() {
+    let path = write_connection_file(
+        "registration",
+        serde_json::json!({
+            "registration_port": 50010,
+            "transport": "tcp",
+            "signature_scheme": "hmac-sha256",
+            "ip": "127.0.0.1",
+            "key": "secret",
+        }),
+    );
+
+    let (connection, registration) = read_connection(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+
+    // The kernel picks its own ports and reports them through the handshake
+    let registration = registration.unwrap();
+    assert_eq!(registration.endpoint(), "tcp://127.0.0.1:50010");
+    assert_eq!(connection.control_port, 0);
+    assert_eq!(connection.shell_port, 0);
+    assert_eq!(connection.stdin_port, 0);
+    assert_eq!(connection.iopub_port, 0);
+    assert_eq!(connection.hb_port, 0);
+    assert_eq!(connection.key, "secret");
+}
@@ -13,8 +13,18 @@ use amalthea::wire::interrupt_reply::InterruptReply;
 use amalthea::wire::jupyter_message::Status;
 use amalthea::wire::shutdown_reply::ShutdownReply;
 use amalthea::wire::shutdown_request::ShutdownRequest;
+use crossbeam::channel::Sender;
 
-pub struct Control {}
+pub struct Control {
+    /// Wakes up the shell while it runs the "wait" keyword
+    interrupt_tx: Sender<()>,
+}
+
+impl Control {
+    pub fn new(interrupt_tx: Sender<()>) -> Self {
+        Self { interrupt_tx }
+    }
+}
 
 impl ControlHandler for Control {
     fn handle_shutdown_request(&self, msg: &ShutdownRequest) -> Result<ShutdownReply, Exception> {
@@ -26,7 +36,8 @@ impl ControlHandler for Control {
     }
 
     fn handle_interrupt_request(&self) -> Result<InterruptReply, Exception> {
-        // NYI
+        // The shell only listens while running "wait"
+        let _ = self.interrupt_tx.try_send(());
         Ok(InterruptReply { status: Status::Ok })
     }
 
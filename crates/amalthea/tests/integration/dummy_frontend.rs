@@ -65,12 +65,15 @@ impl DummyAmaltheaFrontend {
         let (stdin_request_tx, stdin_request_rx) = bounded::<StdInRequest>(1);
         let (stdin_reply_tx, stdin_reply_rx) = unbounded();
 
+        let (interrupt_tx, interrupt_rx) = bounded::<()>(1);
+
         let shell = Box::new(shell::Shell::new(
             iopub_tx.clone(),
             stdin_request_tx,
             stdin_reply_rx,
+            interrupt_rx,
         ));
-        let control = Box::new(control::Control {});
+        let control = Box::new(control::Control::new(interrupt_tx));
 
         // Initialize logging
         env_logger::init();
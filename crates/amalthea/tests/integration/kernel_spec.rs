@@ -0,0 +1,44 @@
+/*
+ * kernel_spec.rs
+ *
+ * Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+ *
+ */
+
+use amalthea::kernel_spec::InterruptMode;
+use amalthea::kernel_spec::KernelSpec;
+
+fn test_spec(interrupt_mode: InterruptMode) -> KernelSpec {
+    KernelSpec {
+        argv: vec![String::from("amalthea")],
+        display_name: String::from("Amalthea Echo"),
+        language: String::from("Echo"),
+        env: serde_json::Map::new(),
+        interrupt_mode,
+        metadata: None,
+    }
+}
+
+#[test]
+fn test_kernel_spec_interrupt_mode() {
+    let spec = serde_json::to_value(test_spec(InterruptMode::Message)).unwrap();
+    assert_eq!(spec["interrupt_mode"], "message");
+
+    // `metadata` is left out when not supplied
+    assert!(spec.get("metadata").is_none());
+
+    let spec = serde_json::to_value(test_spec(InterruptMode::default())).unwrap();
+    assert_eq!(spec["interrupt_mode"], "signal");
+}
+
+#[test]
+fn test_kernel_spec_metadata() {
+    let mut spec = test_spec(InterruptMode::Signal);
+    spec.metadata = Some(serde_json::Map::from_iter([(
+        String::from("debugger"),
+        serde_json::Value::Bool(true),
+    )]));
+
+    let spec = serde_json::to_value(spec).unwrap();
+    assert_eq!(spec["metadata"]["debugger"], true);
+}
@@ -5,8 +5,10 @@
  *
  */
 
+mod connection;
 mod control;
 mod dummy_frontend;
+mod kernel_spec;
 mod shell;
 
 use amalthea::comm::comm_channel::CommMsg;
@@ -63,6 +65,145 @@ fn test_amalthea_execute_request() {
     frontend.recv_iopub_idle();
 }
 
+#[test]
+fn test_amalthea_interrupt_aborts_queued_execute_requests() {
+    let frontend = DummyAmaltheaFrontend::lock();
+
+    // Queue two requests behind one that blocks until interrupted
+    frontend.send_execute_request("wait", Default::default());
+    let id_1 = frontend.send_execute_request("1", Default::default());
+    let id_2 = frontend.send_execute_request("2", Default::default());
+
+    // Only interrupt once "wait" is running
+    frontend.recv_iopub_busy();
+    let input = frontend.recv_iopub_execute_input();
+    assert_eq!(input.code, "wait");
+
+    frontend.send_interrupt_request();
+    frontend.recv_control_interrupt_reply();
+
+    // The queued requests are aborted without being run
+    assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
+    assert_eq!(frontend.recv_shell_execute_reply_aborted(), id_1);
+    assert_eq!(frontend.recv_shell_execute_reply_aborted(), id_2);
+
+    // The busy and idle statuses of the interrupt request on Control
+    // interleave with the IOPub messages from Shell, so set them aside
+    let mut control_statuses = 0;
+    let mut shell_messages = Vec::new();
+
+    while shell_messages.len() < 6 {
+        match frontend.recv_iopub() {
+            Message::Status(msg)
+                if msg.parent_header.as_ref().unwrap().msg_type == "interrupt_request" =>
+            {
+                control_statuses += 1;
+            },
+            msg => shell_messages.push(msg),
+        }
+    }
+    while control_statuses < 2 {
+        assert_matches!(frontend.recv_iopub(), Message::Status(_));
+        control_statuses += 1;
+    }
+
+    let mut shell_messages = shell_messages.into_iter();
+    assert_matches!(shell_messages.next(), Some(Message::ExecuteResult(msg)) => {
+        assert_eq!(msg.content.data["text/plain"], "wait");
+    });
+    assert_matches!(shell_messages.next(), Some(Message::Status(msg)) => {
+        assert_eq!(msg.content.execution_state, ExecutionState::Idle);
+    });
+    for id in [id_1, id_2] {
+        assert_matches!(shell_messages.next(), Some(Message::Status(msg)) => {
+            assert_eq!(msg.content.execution_state, ExecutionState::Busy);
+            assert_eq!(msg.parent_header.unwrap().msg_id, id);
+        });
+        assert_matches!(shell_messages.next(), Some(Message::Status(msg)) => {
+            assert_eq!(msg.content.execution_state, ExecutionState::Idle);
+        });
+    }
+
+    // Requests sent after the interrupt run as usual
+    frontend.send_execute_request("42", Default::default());
+    frontend.recv_iopub_busy();
+    let input = frontend.recv_iopub_execute_input();
+    assert_eq!(frontend.recv_iopub_execute_result(), "42");
+    assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
+    frontend.recv_iopub_idle();
+}
+
+#[test]
+fn test_amalthea_interrupt_runs_execute_requests_sent_during_abort() {
+    let frontend = DummyAmaltheaFrontend::lock();
+
+    frontend.send_execute_request("wait", Default::default());
+    let id_1 = frontend.send_execute_request("1", Default::default());
+
+    frontend.recv_iopub_busy();
+    let input = frontend.recv_iopub_execute_input();
+    assert_eq!(input.code, "wait");
+
+    // This request arrives while Shell is still busy with "wait" and aborts
+    // the request queued behind it. It was sent after the interrupt, so it
+    // must run.
+    frontend.send_interrupt_request();
+    let id_3 = frontend.send_execute_request("3", Default::default());
+    frontend.recv_control_interrupt_reply();
+
+    assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
+    assert_eq!(frontend.recv_shell_execute_reply_aborted(), id_1);
+    frontend.recv_shell_execute_reply();
+
+    // Set aside the statuses of the interrupt request on Control, as in
+    // `test_amalthea_interrupt_aborts_queued_execute_requests()`
+    let mut control_statuses = 0;
+    let mut shell_messages = Vec::new();
+
+    while shell_messages.len() < 8 {
+        match frontend.recv_iopub() {
+            Message::Status(msg)
+                if msg.parent_header.as_ref().unwrap().msg_type == "interrupt_request" =>
+            {
+                control_statuses += 1;
+            },
+            msg => shell_messages.push(msg),
+        }
+    }
+    while control_statuses < 2 {
+        assert_matches!(frontend.recv_iopub(), Message::Status(_));
+        control_statuses += 1;
+    }
+
+    let mut shell_messages = shell_messages.into_iter();
+    assert_matches!(shell_messages.next(), Some(Message::ExecuteResult(msg)) => {
+        assert_eq!(msg.content.data["text/plain"], "wait");
+    });
+    assert_matches!(shell_messages.next(), Some(Message::Status(msg)) => {
+        assert_eq!(msg.content.execution_state, ExecutionState::Idle);
+    });
+    assert_matches!(shell_messages.next(), Some(Message::Status(msg)) => {
+        assert_eq!(msg.content.execution_state, ExecutionState::Busy);
+        assert_eq!(msg.parent_header.unwrap().msg_id, id_1);
+    });
+    assert_matches!(shell_messages.next(), Some(Message::Status(msg)) => {
+        assert_eq!(msg.content.execution_state, ExecutionState::Idle);
+    });
+    assert_matches!(shell_messages.next(), Some(Message::Status(msg)) => {
+        assert_eq!(msg.content.execution_state, ExecutionState::Busy);
+        assert_eq!(msg.parent_header.unwrap().msg_id, id_3);
+    });
+    assert_matches!(shell_messages.next(), Some(Message::ExecuteInput(msg)) => {
+        assert_eq!(msg.content.code, "3");
+    });
+    assert_matches!(shell_messages.next(), Some(Message::ExecuteResult(msg)) => {
+        assert_eq!(msg.content.data["text/plain"], "3");
+    });
+    assert_matches!(shell_messages.next(), Some(Message::Status(msg)) => {
+        assert_eq!(msg.content.execution_state, ExecutionState::Idle);
+    });
+}
+
 #[test]
 fn test_amalthea_shutdown_request() {
     let frontend = DummyAmaltheaFrontend::lock();
@@ -190,6 +331,23 @@ fn test_amalthea_comms() {
     frontend.recv_iopub_idle();
     frontend.assert_no_incoming();
 
+    // Filtering by the target name of the comm we opened returns it, mapped to
+    // its target name
+    frontend.send_shell(CommInfoRequest {
+        target_name: Some("variables".to_string()),
+    });
+    frontend.recv_iopub_busy();
+
+    assert_matches!(frontend.recv_shell(), Message::CommInfoReply(request) => {
+        let comms = request.content.comms;
+        assert_eq!(comms.len(), 1);
+        let info: CommInfoTargetName = serde_json::from_value(comms[comm_id].clone()).unwrap();
+        assert_eq!(info.target_name, "variables");
+    });
+
+    frontend.recv_iopub_idle();
+    frontend.assert_no_incoming();
+
     let comm_req_id = frontend.send_shell(CommWireMsg {
         comm_id: comm_id.to_string(),
         // Include `id` field to signal this is a request
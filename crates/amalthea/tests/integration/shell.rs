@@ -49,6 +49,7 @@ pub struct Shell {
     iopub: Sender<IOPubMessage>,
     stdin_request_tx: Sender<StdInRequest>,
     stdin_reply_rx: Receiver<amalthea::Result<InputReply>>,
+    interrupt_rx: Receiver<()>,
     execution_count: u32,
 }
 
@@ -58,11 +59,13 @@ impl Shell {
         iopub: Sender<IOPubMessage>,
         stdin_request_tx: Sender<StdInRequest>,
         stdin_reply_rx: Receiver<amalthea::Result<InputReply>>,
+        interrupt_rx: Receiver<()>,
     ) -> Self {
         Self {
             iopub,
             stdin_request_tx,
             stdin_reply_rx,
+            interrupt_rx,
             execution_count: 0,
         }
     }
@@ -154,6 +157,13 @@ impl Shell {
                 .unwrap();
         }
 
+        // Keyword: "wait"
+        //
+        // Block until the kernel is interrupted
+        if req.code == "wait" {
+            self.interrupt_rx.recv().unwrap();
+        }
+
         // For this toy echo language, generate a result that's just the input
         // echoed back.
         let data = json!({"text/plain": req.code });
@@ -59,7 +59,7 @@ unsafe fn ps_browse_url_impl(url: SEXP) -> anyhow::Result<SEXP> {
     // This is probably a file path? Send to the front end and ask for system
     // default opener.
     log::trace!("Treating as file path and asking system to open");
-    let path = r_normalize_path(url.into())?;
+    let path = r_normalize_path(&url_string, false)?;
     send_open_with_system_event(&path)?;
     Ok(Rf_ScalarLogical(1))
 }
@@ -50,6 +50,7 @@ use amalthea::wire::execute_reply::ExecuteReply;
 use amalthea::wire::execute_request::CodeLocation;
 use amalthea::wire::execute_request::ExecuteRequest;
 use amalthea::wire::execute_result::ExecuteResult;
+use amalthea::wire::help_link::HelpLink;
 use amalthea::wire::input_reply::InputReply;
 use amalthea::wire::input_request::InputRequest;
 use amalthea::wire::input_request::ShellInputRequest;
@@ -82,7 +83,6 @@ use harp::object::r_null_or_try_into;
 use harp::object::RObject;
 use harp::r_symbol;
 use harp::routines::r_register_routines;
-use harp::session::r_traceback;
 use harp::srcref::get_srcref_list;
 use harp::srcref::srcref_list_get;
 use harp::srcref::SrcFile;
@@ -123,6 +123,7 @@ pub(crate) use console_debug::DebugStoppedReason;
 pub(crate) use console_debug::FrameInfo;
 use console_debug::FrameInfoId;
 pub(crate) use console_debug::FrameSource;
+use console_error::last_error_traceback;
 use console_error::stack_overflow_occurred;
 use console_filter::strip_step_lines;
 use console_filter::ConsoleFilter;
@@ -136,12 +137,15 @@ pub(crate) use console_repl::r_suicide;
 pub(crate) use console_repl::r_write_console;
 pub(crate) use console_repl::selected_env;
 use console_repl::ActiveReadConsoleRequest;
+pub use console_repl::BannerMode;
 pub(crate) use console_repl::ConsoleNotification;
 pub(crate) use console_repl::ConsoleOutputCapture;
 pub(crate) use console_repl::KernelInfo;
 use console_repl::PendingInputs;
+use console_repl::PendingStream;
 use console_repl::ReadConsolePendingAction;
 pub use console_repl::SessionMode;
+pub use console_repl::CONSOLE_INPUT_TOO_LONG;
 
 use crate::comm_handler::ConsoleComm;
 use crate::comm_handler::EnvironmentChanged;
@@ -178,6 +182,7 @@ use crate::signals::set_interrupts_pending;
 use crate::srcref::ns_populate_srcref;
 use crate::srcref::resource_loaded_namespaces;
 use crate::startup;
+use crate::startup::StartupProfile;
 use crate::sys::console::console_to_utf8;
 
 thread_local! {
@@ -218,6 +223,11 @@ pub struct Console {
     /// IOPub channel for broadcasting outputs
     iopub_tx: Sender<IOPubMessage>,
 
+    /// Stream output buffered before it's sent to IOPub. Further writes to
+    /// the same stream are appended to it, and it's flushed when the stream
+    /// changes, on a fixed interval, and before any other IOPub message.
+    pending_stream: DebugRefCell<Option<PendingStream>>,
+
     /// Active request passed to `ReadConsole()`. Contains reply channel
     /// the reply should be send to once computation has finished.
     active_request: Option<ActiveReadConsoleRequest>,
@@ -272,6 +282,12 @@ pub struct Console {
     /// Stored in `Console` to avoid memory leakage when `Rf_error()` jumps.
     r_error_buffer: Option<CString>,
 
+    /// Name of the error thrown by `r_read_console()`, if it can be
+    /// classified. Reported as the `ename` of the next exception so frontends
+    /// can recognise the error. Reset at the start of each execute request in
+    /// case the error was caught and never reached top level.
+    r_error_name: Cell<Option<&'static str>>,
+
     /// When `Some`, console output is captured here instead of being sent to IOPub.
     /// Interact with this via `ConsoleOutputCapture` from `start_capture()`.
     captured_output: Option<String>,
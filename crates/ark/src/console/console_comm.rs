@@ -155,6 +155,19 @@ impl Console {
         self.drain_closed();
     }
 
+    /// Close all comms, notifying both their handlers and the frontend. Used
+    /// on shutdown so the frontend doesn't hold on to comms of a dead session.
+    pub(super) fn comm_close_all(&self) {
+        let comm_ids: Vec<String> = self.comms.borrow().keys().cloned().collect();
+
+        for comm_id in comm_ids {
+            if let Some(comm) = self.remove_comm(&comm_id) {
+                comm.handler.borrow_mut().handle_close(&comm.ctx);
+                self.comm_notify_closed(&comm_id, &comm);
+            }
+        }
+    }
+
     // -- Comms map helpers ------------------------------------------------
 
     fn lookup_comm(&self, comm_id: &str) -> Option<Rc<ConsoleComm>> {
@@ -44,6 +44,7 @@ pub(crate) enum DebugStoppedReason {
     Step,
     Pause,
     Condition { class: String, message: String },
+    DataBreakpoint { name: String },
 }
 
 #[derive(Debug)]
@@ -331,6 +332,42 @@ impl Console {
         let mut dap = self.debug_dap.lock().unwrap();
         dap.verify_breakpoints(&uri, srcref.line_virtual.start, srcref.line_virtual.end);
     }
+
+    /// Code that re-runs the call of the debugged frame under `debugonce()`.
+    /// Must be called on the R thread while stopped in the debugger. Fails
+    /// with an informative message when the call can't be reconstructed.
+    pub(crate) fn debug_restart_command(&self) -> Result<String> {
+        unsafe {
+            let mut protect = RProtect::new();
+
+            let functions = r_sys_functions()?;
+            protect.add(functions);
+
+            let frames = r_sys_frames()?;
+            protect.add(frames.sexp);
+
+            let calls = r_sys_calls()?;
+            protect.add(calls.sexp);
+
+            let env = self.eval_env();
+
+            restart_command(functions, frames.sexp, calls.sexp, env.sexp)
+        }
+    }
+}
+
+fn restart_command(functions: SEXP, frames: SEXP, calls: SEXP, env: SEXP) -> Result<String> {
+    let command = RFunction::new("", "debugger_restart_command")
+        .add(functions)
+        .add(frames)
+        .add(calls)
+        .add(env)
+        .call_in(ARK_ENVS.positron_ns)
+        .map_err(|err| match err {
+            harp::Error::TryCatchError(err) => anyhow!("{}", err.message),
+            err => anyhow!(err),
+        })?;
+    Ok(String::try_from(command)?)
 }
 
 /// Controls which categories of hidden frames to show (i.e., not filter out).
@@ -370,11 +407,13 @@ fn remove_condition_handling_frames(
     stack: &mut Vec<FrameInfo>,
     stopped_reason: &DebugStoppedReason,
 ) {
-    // Discard top frame when stopped due to exception breakpoint or pause,
-    // it points to our global handler that calls `browser()`
+    // Discard top frame when stopped due to exception breakpoint, pause, or
+    // data breakpoint, it points to our handler that calls `browser()`
     if matches!(
         stopped_reason,
-        DebugStoppedReason::Condition { .. } | DebugStoppedReason::Pause
+        DebugStoppedReason::Condition { .. } |
+            DebugStoppedReason::Pause |
+            DebugStoppedReason::DataBreakpoint { .. }
     ) && !stack.is_empty()
     {
         stack.remove(0);
@@ -816,6 +855,15 @@ pub unsafe extern "C-unwind" fn ps_debug_set_stopped_reason_pause() -> anyhow::R
     Ok(libr::R_NilValue)
 }
 
+#[harp::register]
+pub unsafe extern "C-unwind" fn ps_debug_set_stopped_reason_data_breakpoint(
+    name: SEXP,
+) -> anyhow::Result<SEXP> {
+    let name: String = RObject::view(name).try_into()?;
+    Console::get_mut().debug_stopped_reason = Some(DebugStoppedReason::DataBreakpoint { name });
+    Ok(libr::R_NilValue)
+}
+
 #[harp::register]
 pub unsafe extern "C-unwind" fn ps_is_interrupting_for_debugger() -> anyhow::Result<SEXP> {
     let console = Console::get_mut();
@@ -1127,6 +1175,49 @@ mod tests {
         ");
     }
 
+    #[test]
+    fn test_restart_command() {
+        crate::r_task(|| {
+            harp::parse_eval_global("f <- function(x, y) x").unwrap();
+            harp::parse_eval_global("g <- function() f(1, y = 'a')").unwrap();
+
+            let fns = harp::parse_eval_global("list(g, f)").unwrap();
+            let frames = harp::parse_eval_global("list(new.env(), new.env())").unwrap();
+            let calls = harp::parse_eval_global("list(quote(g()), quote(f(1, y = 'a')))").unwrap();
+
+            // Restarts the debugged frame, not the top-level call
+            let env = harp::list_get(frames.sexp, 1);
+            assert_eq!(
+                restart_command(fns.sexp, frames.sexp, calls.sexp, env).unwrap(),
+                "debugonce(f); f(1, y = \"a\")"
+            );
+
+            let env = harp::list_get(frames.sexp, 0);
+            assert_eq!(
+                restart_command(fns.sexp, frames.sexp, calls.sexp, env).unwrap(),
+                "debugonce(g); g()"
+            );
+
+            let fns = harp::parse_eval_global("list(f)").unwrap();
+            let frames = harp::parse_eval_global("list(new.env())").unwrap();
+            let env = harp::list_get(frames.sexp, 0);
+
+            // Inlined objects don't survive the round trip
+            let calls = harp::parse_eval_global("list(as.call(list(quote(f), data.frame(a = 1))))")
+                .unwrap();
+            let err = restart_command(fns.sexp, frames.sexp, calls.sexp, env).unwrap_err();
+            assert!(err.to_string().contains("can't be reconstructed"));
+
+            // Anonymous functions can't be looked up again
+            let fns = harp::parse_eval_global("list(function(x) x)").unwrap();
+            let calls = harp::parse_eval_global("list(quote((function(x) x)(1)))").unwrap();
+            let err = restart_command(fns.sexp, frames.sexp, calls.sexp, env).unwrap_err();
+            assert!(err.to_string().contains("must be called by name"));
+
+            harp::parse_eval_global("rm(f, g)").unwrap();
+        })
+    }
+
     #[test]
     fn test_breakpoint_label() {
         let uri = test_uri("test.R");
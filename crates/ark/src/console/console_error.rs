@@ -6,14 +6,19 @@
 //
 
 use amalthea::wire::exception::Exception;
+use harp::environment::Environment;
+use harp::environment::R_ENVS;
 use harp::exec::r_peek_error_buffer;
 use harp::exec::RE_STACK_OVERFLOW;
 use harp::object::RObject;
 use harp::session::r_format_traceback;
+use harp::session::r_trace_back_frames;
+use harp::session::TracebackFrame;
 use libr::R_NilValue;
 use libr::SEXP;
 use log::warn;
 use stdext::unwrap;
+use url::Url;
 
 use super::Console;
 
@@ -69,3 +74,88 @@ pub(super) fn stack_overflow_occurred() -> bool {
     let err_buf = r_peek_error_buffer();
     RE_STACK_OVERFLOW.is_match(&err_buf)
 }
+
+/// Traceback of the last error, read from `.Traceback`
+///
+/// Used when no error handler could record a traceback, e.g. on stack
+/// overflow. Calls with a source location link to that file and line so the
+/// frames are clickable in the console.
+pub(super) fn last_error_traceback() -> Vec<String> {
+    let calls = match Environment::view(R_ENVS.base).get(".Traceback") {
+        Ok(calls) => calls,
+        Err(err) => {
+            log::error!("Can't get traceback: {err:?}");
+            return vec![];
+        },
+    };
+
+    match r_trace_back_frames(calls.sexp, None) {
+        Ok(frames) => format_traceback_frames(&frames),
+        Err(err) => {
+            log::error!("Can't convert traceback: {err:?}");
+            vec![]
+        },
+    }
+}
+
+/// Format frames as `<n>. <call> at <file>:<line>`, matching the ordering and
+/// numbering of `format_traceback()` on the R side
+fn format_traceback_frames(frames: &[TracebackFrame]) -> Vec<String> {
+    let width = frames.len().to_string().len();
+
+    frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let prefix = format!("{:>width$}. ", i + 1);
+            match (&frame.file, frame.line) {
+                (Some(file), Some(line)) => {
+                    let location = format_location_link(file, line + 1);
+                    format!("{prefix}{} at {location}", frame.call)
+                },
+                _ => format!("{prefix}{}", frame.call),
+            }
+        })
+        .collect()
+}
+
+/// An OSC 8 terminal hyperlink to a 1-based `line` of `file`, in the format
+/// emitted by `cli::style_hyperlink()`
+fn format_location_link(file: &str, line: u32) -> String {
+    let text = format!("{file}:{line}");
+
+    let Ok(url) = Url::from_file_path(file) else {
+        return text;
+    };
+
+    format!("\x1b]8;line={line};{url}\x07{text}\x1b]8;;\x07")
+}
+
+#[cfg(test)]
+mod tests {
+    use harp::session::TracebackFrame;
+
+    use super::format_traceback_frames;
+
+    // Unix only because of the absolute file path
+    #[cfg(unix)]
+    #[test]
+    fn test_format_traceback_frames() {
+        let frame = |call: &str, file: Option<&str>, line: Option<u32>| TracebackFrame {
+            call: String::from(call),
+            file: file.map(String::from),
+            line,
+            env: None,
+        };
+
+        let frames = vec![
+            frame("f()", None, None),
+            frame("g()", Some("/tmp/test.R"), Some(1)),
+        ];
+
+        assert_eq!(format_traceback_frames(&frames), vec![
+            String::from("1. f()"),
+            String::from("2. g() at \x1b]8;line=2;file:///tmp/test.R\x07/tmp/test.R:2\x1b]8;;\x07"),
+        ]);
+    }
+}
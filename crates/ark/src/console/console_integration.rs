@@ -70,6 +70,14 @@ impl Console {
 
         RHelp::is_help_url(url, r_port)
     }
+
+    /// URL of a page of R's help server, as served through the help proxy.
+    /// `path` is relative to the server root, e.g. `library/base/doc/index.html`.
+    pub(crate) fn help_proxy_url(&self, path: &str) -> Option<String> {
+        let HelpPorts { proxy_port, .. } = self.help_ports.get()?;
+        let prefix = RHelp::help_url_prefix(proxy_port);
+        Some(format!("{prefix}{path}"))
+    }
 }
 
 /// LSP integration.
@@ -144,12 +152,11 @@ impl Console {
         // or restarts, we can notify it of all virtual documents it should know about
         // in the LSP channel setup step. It is common for the kernel to create the
         // virtual documents for base R packages before the LSP has started up.
-        self.lsp_virtual_documents
-            .insert(uri.clone(), contents.clone());
-
-        self.send_lsp_notification(KernelNotification::DidOpenVirtualDocument(
-            DidOpenVirtualDocumentParams { uri, contents },
-        ))
+        if let Some(notification) =
+            virtual_document_notification(&mut self.lsp_virtual_documents, uri, contents)
+        {
+            self.send_lsp_notification(notification);
+        }
     }
 
     pub(super) fn remove_virtual_document(&mut self, uri: String) {
@@ -176,6 +183,28 @@ impl Console {
     }
 }
 
+/// Record a virtual document and return the notification the LSP needs to
+/// stay in sync, if any. Re-inserting a document with new contents, e.g. when
+/// the srcrefs of a reloaded package are regenerated, opens it again so that
+/// the LSP replaces the stale contents and jump-to-definition points at the
+/// fresh source.
+fn virtual_document_notification(
+    virtual_documents: &mut HashMap<String, String>,
+    uri: String,
+    contents: String,
+) -> Option<KernelNotification> {
+    if let Some(old) = virtual_documents.insert(uri.clone(), contents.clone()) {
+        if old == contents {
+            return None;
+        }
+        log::trace!("Updating stale vdoc for `{uri}`");
+    }
+
+    Some(KernelNotification::DidOpenVirtualDocument(
+        DidOpenVirtualDocumentParams { uri, contents },
+    ))
+}
+
 /// Inline data explorer integration.
 impl Console {
     /// Open an inline data explorer for a data frame value and return the MIME
@@ -304,3 +333,46 @@ impl UiCommRef<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use stdext::assert_match;
+
+    use super::virtual_document_notification;
+    use crate::lsp::main_loop::KernelNotification;
+
+    #[test]
+    fn test_virtual_document_notification() {
+        let mut docs = HashMap::new();
+        let uri = String::from("namespace/foo.R");
+
+        let notification =
+            virtual_document_notification(&mut docs, uri.clone(), String::from("f <- 1"));
+        assert_match!(
+            notification,
+            Some(KernelNotification::DidOpenVirtualDocument(params)) => {
+                assert_eq!(params.uri, uri);
+                assert_eq!(params.contents, "f <- 1");
+            }
+        );
+
+        // Same contents, nothing to tell the LSP
+        let notification =
+            virtual_document_notification(&mut docs, uri.clone(), String::from("f <- 1"));
+        assert!(notification.is_none());
+
+        // New contents are sent again, replacing the stale ones
+        let notification =
+            virtual_document_notification(&mut docs, uri.clone(), String::from("f <- 2"));
+        assert_match!(
+            notification,
+            Some(KernelNotification::DidOpenVirtualDocument(params)) => {
+                assert_eq!(params.uri, uri);
+                assert_eq!(params.contents, "f <- 2");
+            }
+        );
+        assert_eq!(docs.get(&uri).unwrap(), "f <- 2");
+    }
+}
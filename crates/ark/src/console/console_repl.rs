@@ -12,27 +12,40 @@
 
 use std::path::Path;
 use std::rc::Rc;
+use std::time::Instant;
 
 use stdext::DebugRefCell;
 
 use super::*;
 use crate::dap::dap_notebook;
 use crate::data_explorer::r_data_explorer::POSITRON_DATA_EXPLORER_MIME;
+use crate::help::help_links::kernel_help_links;
 use crate::r_task::QueuedRTask;
 use crate::r_task::RTask;
+use crate::r_task::RTaskPriority;
 use crate::r_task::TryIdleTask;
 use crate::request::DebugRequest;
+use crate::watchdog;
 
 static RE_DEBUG_PROMPT: Lazy<Regex> = Lazy::new(|| Regex::new(r"Browse\[\d+\]").unwrap());
 
 /// All debug commands as documented in `?browser`
 const DEBUG_COMMANDS: &[&str] = &["c", "cont", "f", "help", "n", "s", "where", "r", "Q"];
 
+/// How long stream output is buffered in `Console::pending_stream` before
+/// it's sent to IOPub
+const STREAM_FLUSH_INTERVAL: Duration = Duration::from_millis(10);
+
 // Debug commands that exit the current browser: `n`, `f`, `c`, `cont` continue
 // execution past the current prompt, `Q` exits all nested browsers entirely.
 // These are not transient evals: they represent deliberate debugger navigation.
 const DEBUG_COMMANDS_CONTINUE: &[&str] = &["n", "f", "c", "cont", "Q"];
 
+/// `ename` of the exception reported when a single line of console input
+/// doesn't fit in R's internal console buffer. Frontends can match on it to
+/// suggest splitting the line rather than showing a raw R error.
+pub const CONSOLE_INPUT_TOO_LONG: &str = "ConsoleInputTooLong";
+
 thread_local! {
     /// When `true`, the global panic hook should return early instead of
     /// aborting, so that `catch_unwind` can catch the panic in `Console::with`.
@@ -65,6 +78,31 @@ pub enum SessionMode {
     Background,
 }
 
+/// What to do with the startup banner, i.e. the output emitted by R while it
+/// starts up.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BannerMode {
+    /// Forward the banner to the frontend in the kernel info.
+    Forward,
+
+    /// Discard the banner, e.g. for headless sessions.
+    Quiet,
+
+    /// Forward the banner and also log it.
+    Verbose,
+}
+
+impl BannerMode {
+    /// Background sessions aren't connected to any UI so they don't emit a
+    /// banner unless asked to.
+    pub fn default_for(session_mode: SessionMode) -> Self {
+        match session_mode {
+            SessionMode::Background => BannerMode::Quiet,
+            SessionMode::Console | SessionMode::Notebook => BannerMode::Forward,
+        }
+    }
+}
+
 /// Notifications from other components (e.g., LSP) to the Console
 #[derive(Debug)]
 pub(crate) enum ConsoleNotification {
@@ -222,6 +260,22 @@ enum WaitFor {
     ExecuteRequest,
 }
 
+/// Stream output buffered before it's sent to IOPub, see
+/// `Console::send_stream()`
+pub(super) struct PendingStream {
+    output: StreamOutput,
+    timestamp: Instant,
+}
+
+impl PendingStream {
+    fn new(output: StreamOutput) -> Self {
+        Self {
+            output,
+            timestamp: Instant::now(),
+        }
+    }
+}
+
 /// Represents the currently active execution request from the frontend. It
 /// resolves at the next invocation of the `ReadConsole()` frontend method.
 pub(super) struct ActiveReadConsoleRequest {
@@ -239,6 +293,7 @@ pub(crate) struct KernelInfo {
     pub(crate) input_prompt: Option<String>,
     pub(crate) continuation_prompt: Option<String>,
     pub(crate) session_mode: SessionMode,
+    pub(crate) help_links: Vec<HelpLink>,
 }
 
 /// The kind of prompt we're handling in the REPL.
@@ -391,8 +446,10 @@ impl Console {
         kernel_request_rx: Receiver<KernelRequest>,
         dap: Arc<Mutex<Dap>>,
         session_mode: SessionMode,
+        banner_mode: BannerMode,
         default_repos: DefaultRepos,
         console_notification_rx: AsyncUnboundedReceiver<ConsoleNotification>,
+        mut startup_profile: StartupProfile,
     ) {
         // Set the main thread ID.
         // Must happen before doing anything that checks `Console::on_main_thread()`,
@@ -469,6 +526,7 @@ impl Console {
             },
             Err(err) => log::error!("Failed to discover R envvars: {err}"),
         };
+        startup_profile.record("r_envvars");
 
         let libraries = RLibraries::from_r_home_path(console.r_home());
         libraries.initialize_pre_setup_r();
@@ -476,6 +534,7 @@ impl Console {
         crate::sys::console::setup_r(&r_args);
 
         libraries.initialize_post_setup_r();
+        startup_profile.record("setup_r");
 
         unsafe {
             // Register embedded routines
@@ -483,12 +542,14 @@ impl Console {
 
             // Initialize harp (after routine registration)
             harp::initialize();
+            startup_profile.record("harp");
 
             // Optionally run a frontend specified R startup script (after harp init)
             if let Some(file) = &startup_file {
-                harp::source(file)
+                harp::r_source_file(file, Some("UTF-8"), false, R_ENVS.base)
                     .context(format!("Failed to source startup file '{file}' due to"))
                     .log_err();
+                startup_profile.record("startup_file");
             }
 
             // Initialize support functions (after routine registration, after
@@ -502,6 +563,7 @@ impl Console {
                     console.positron_ns = Some(namespace);
                 },
             }
+            startup_profile.record("modules");
 
             // Populate srcrefs for namespaces already loaded in the session.
             // Namespaces of future loaded packages will be populated on load.
@@ -510,6 +572,7 @@ impl Console {
                 if let Err(err) = resource_loaded_namespaces() {
                     log::error!("Can't populate srcrefs for loaded packages: {err:?}");
                 }
+                startup_profile.record("resource_namespaces");
             }
 
             // Set default repositories
@@ -527,7 +590,13 @@ impl Console {
         log::info!(
             "R has started and ark handlers have been registered, completing initialization."
         );
-        Self::complete_initialization(console.banner.take(), console.session_mode, kernel_init_tx);
+        Self::complete_initialization(
+            console.banner.take(),
+            banner_mode,
+            console.session_mode,
+            kernel_init_tx,
+        );
+        startup_profile.record("complete_initialization");
 
         // Spawn handler loop for async messages from other components (e.g., LSP).
         // Note that we do it after init is complete to avoid deadlocking
@@ -551,6 +620,7 @@ impl Console {
         // R-side graphics device initialization. The `DeviceContext`
         // itself is already created as part of `Console::new()`.
         graphics_device::init_graphics_device();
+        startup_profile.record("graphics_device");
 
         // Now that R has started and libr and ark have fully initialized, run site and user
         // level R profiles, in that order
@@ -560,12 +630,15 @@ impl Console {
         if !ignore_user_r_profile {
             startup::source_user_r_profile();
         }
+        startup_profile.record("r_profiles");
 
         // Apply Positron's default options after profiles so that user-defined
         // options take precedence over our defaults
         if let Some(ref ns) = console.positron_ns {
             modules::initialize_options(ns.sexp).log_err();
         }
+        startup_profile.record("options");
+        startup_profile.report();
 
         // Start the REPL. Does not return!
         crate::sys::console::run_r();
@@ -619,6 +692,7 @@ impl Console {
     /// Can only be called from the R thread, and only once.
     fn complete_initialization(
         banner: Option<String>,
+        banner_mode: BannerMode,
         session_mode: SessionMode,
         mut kernel_init_tx: Bus<KernelInfo>,
     ) {
@@ -631,12 +705,31 @@ impl Console {
         let input_prompt = harp::get_input_prompt();
         let continuation_prompt = harp::get_continuation_prompt();
 
+        let banner = match banner_mode {
+            BannerMode::Forward => banner.unwrap_or_default(),
+            BannerMode::Quiet => String::new(),
+            BannerMode::Verbose => {
+                let banner = banner.unwrap_or_default();
+                log::info!("R startup banner:\n{banner}");
+                banner
+            },
+        };
+
+        let help_links = match kernel_help_links() {
+            Ok(help_links) => help_links,
+            Err(err) => {
+                log::error!("Can't compute help links: {err:?}");
+                Vec::new()
+            },
+        };
+
         let kernel_info = KernelInfo {
             version: version.clone(),
-            banner: banner.unwrap_or_default(),
+            banner,
             input_prompt: Some(input_prompt),
             continuation_prompt: Some(continuation_prompt),
             session_mode,
+            help_links,
         };
 
         // Set `R_INIT` before broadcasting so that threads unblocked by the
@@ -670,6 +763,7 @@ impl Console {
             stdin_request_tx,
             stdin_reply_rx,
             iopub_tx,
+            pending_stream: DebugRefCell::new(None),
             kernel_request_rx,
             active_request: None,
             comm_msg_originator: None,
@@ -692,6 +786,7 @@ impl Console {
             positron_ns: None,
             banner: None,
             r_error_buffer: None,
+            r_error_name: Cell::new(None),
             captured_output: None,
             debug_call_text: None,
             debug_last_line: None,
@@ -846,10 +941,71 @@ impl Console {
         self.r_home.as_path()
     }
 
+    /// Access the IOPub channel.
+    ///
+    /// Flushes pending stream output first so that messages sent through
+    /// the returned channel are ordered after any output emitted so far.
     pub(crate) fn iopub_tx(&self) -> &Sender<IOPubMessage> {
+        self.flush_stream();
         &self.iopub_tx
     }
 
+    /// Buffer stream output on its way to IOPub.
+    ///
+    /// Consecutive writes to the same stream are coalesced in
+    /// `pending_stream`, so that a tight loop of tiny `cat()` calls turns into
+    /// a few large messages instead of flooding the IOPub channel. The buffer
+    /// is flushed when the stream changes, once it's older than
+    /// `STREAM_FLUSH_INTERVAL`, before any other IOPub message is sent, and
+    /// when R goes idle or replies to an execute request.
+    fn send_stream(&self, output: StreamOutput) {
+        let mut pending = self.pending_stream.borrow_mut();
+
+        let pending_stream = match pending.take() {
+            Some(mut prev) if prev.output.name == output.name => {
+                prev.output.text.push_str(&output.text);
+                prev
+            },
+            Some(prev) => {
+                // Switching streams, preserve ordering of the previous output
+                self.send_iopub_stream(prev.output);
+                PendingStream::new(output)
+            },
+            None => PendingStream::new(output),
+        };
+
+        if pending_stream.timestamp.elapsed() >= STREAM_FLUSH_INTERVAL {
+            self.send_iopub_stream(pending_stream.output);
+        } else {
+            *pending = Some(pending_stream);
+        }
+    }
+
+    /// Send any pending stream output, blocking until the channel has room.
+    pub(crate) fn flush_stream(&self) {
+        let Some(pending) = self.pending_stream.borrow_mut().take() else {
+            return;
+        };
+        self.send_iopub_stream(pending.output);
+    }
+
+    /// Flush pending stream output once it's older than `STREAM_FLUSH_INTERVAL`
+    fn flush_stream_on_interval(&self) {
+        let expired = match &*self.pending_stream.borrow() {
+            Some(pending) => pending.timestamp.elapsed() >= STREAM_FLUSH_INTERVAL,
+            None => false,
+        };
+        if expired {
+            self.flush_stream();
+        }
+    }
+
+    fn send_iopub_stream(&self, output: StreamOutput) {
+        if let Err(err) = self.iopub_tx.send(IOPubMessage::Stream(output)) {
+            log::error!("Failed to send stream output to IOPub: {err:?}");
+        }
+    }
+
     pub(crate) fn comm_event_tx(&self) -> &Sender<CommEvent> {
         &self.comm_event_tx
     }
@@ -906,6 +1062,23 @@ impl Console {
         }
     }
 
+    /// Prepare for R to shut down after a `shutdown_request`.
+    ///
+    /// R can't be restarted within the same process, so a restart is a
+    /// regular shutdown after which the frontend launches a new kernel. We
+    /// close our comms so the frontend can tear down the UI of this session
+    /// (data explorers, plots, etc) right away, and reset the execution
+    /// counter so nothing is numbered after this session's inputs in the
+    /// meantime.
+    fn prepare_shutdown(&self, restart: bool) {
+        log::info!("Shutting down R (restart: {restart})");
+        self.comm_close_all();
+
+        if restart {
+            self.execution_count.set(0);
+        }
+    }
+
     fn init_execute_request(&mut self, req: &ExecuteRequest) -> (ConsoleInput, u32) {
         // Reset the autoprint buffer
         self.autoprint_output = String::new();
@@ -920,10 +1093,13 @@ impl Console {
         // If the code is not to be executed silently, re-broadcast the
         // execution to all frontends
         if !req.silent {
-            if let Err(err) = self.iopub_tx.send(IOPubMessage::ExecuteInput(ExecuteInput {
-                code: req.code.clone(),
-                execution_count,
-            })) {
+            if let Err(err) = self
+                .iopub_tx()
+                .send(IOPubMessage::ExecuteInput(ExecuteInput {
+                    code: req.code.clone(),
+                    execution_count,
+                }))
+            {
                 log::warn!(
                     "Could not broadcast execution input {execution_count} to all frontends: {err}",
                 );
@@ -993,6 +1169,10 @@ impl Console {
             self.debug_call_text = Some(update);
         }
 
+        // Output of the computation must reach IOPub before we reply or
+        // request input from the frontend
+        self.flush_stream();
+
         // Invariant: If we detect a browser prompt, `self.debug_is_debugging`
         // is true. Otherwise it is false.
         if matches!(info.kind, PromptKind::Browser) {
@@ -1194,6 +1374,10 @@ impl Console {
             // reset the flag
             set_interrupts_pending(false);
 
+            // R is idle, flush output emitted by tasks and event handlers
+            // during the previous iteration
+            self.flush_stream();
+
             // First handle execute requests and kernel requests outside of
             // `select` to ensure they have priority over idle tasks. `select`
             // chooses at random among ready channels, including idle tasks,
@@ -1213,6 +1397,14 @@ impl Console {
                 }
             }
 
+            // Then run due event handlers ahead of idle tasks. These drive
+            // user-visible updates such as `later` callbacks (Shiny, widgets)
+            // and GUI events, which would otherwise be starved under a steady
+            // stream of background work like srcref generation. Idle tasks
+            // still run whenever no tick is due.
+            run_if_due(&activity_handlers_rx, Self::run_activity_handlers);
+            run_if_due(&process_events_rx, Self::run_process_events);
+
             let oper = select.select();
 
             match oper.index() {
@@ -1382,6 +1574,21 @@ impl Console {
             }
         }
 
+        // Notebook frontends can render HTML tables (e.g. from gt or
+        // kableExtra) inline. Objects without an HTML representation fall
+        // back to the `text/plain` output.
+        if self.session_mode == SessionMode::Notebook {
+            match html_representation(value.sexp) {
+                Ok(Some(html)) => {
+                    data.insert("text/html".to_string(), json!(html));
+                },
+                Ok(None) => {},
+                Err(err) => {
+                    log::error!("Failed to render HTML output: {err:?}");
+                },
+            }
+        }
+
         data
     }
 
@@ -1404,11 +1611,11 @@ impl Console {
         let mut exception = if let Some(exception) = self.last_error.take() {
             exception
         } else if stack_overflow_occurred() {
-            // Call `base::traceback()` since we don't have a handled error
-            // object carrying a backtrace. This won't be formatted as a
-            // tree which is just as well since the recursive calls would
-            // push a tree too far to the right.
-            let traceback = r_traceback();
+            // Read `.Traceback` since we don't have a handled error object
+            // carrying a backtrace. This won't be formatted as a tree which
+            // is just as well since the recursive calls would push a tree
+            // too far to the right.
+            let traceback = last_error_traceback();
 
             let exception = Exception {
                 ename: String::from(""),
@@ -1424,6 +1631,10 @@ impl Console {
             return None;
         };
 
+        if let Some(ename) = self.r_error_name.take() {
+            exception.ename = String::from(ename);
+        }
+
         // Flush any accumulated output to StdOut. This can happen if
         // the last input errors out during autoprint.
         let autoprint = std::mem::take(&mut self.autoprint_output);
@@ -1432,7 +1643,7 @@ impl Console {
                 name: Stream::Stdout,
                 text: autoprint,
             });
-            self.iopub_tx.send(message).unwrap();
+            self.iopub_tx().send(message).unwrap();
         }
 
         // Jupyter clients typically discard the `evalue` when a `traceback` is
@@ -1470,11 +1681,18 @@ impl Console {
         // so that the `parent` message is set correctly in any Jupyter messages)
         self.graphics_on_did_execute_request();
 
-        let (reply, result) = Self::prepare_execute_reply(req.exec_count, value);
+        // Following the Jupyter protocol, user expressions are only evaluated
+        // when the main code succeeded
+        let user_expressions = match value {
+            ConsoleValue::Success(_) => self.eval_user_expressions(&req.request.user_expressions),
+            ConsoleValue::Error(_) => json!({}),
+        };
+
+        let (reply, result) = Self::prepare_execute_reply(req.exec_count, value, user_expressions);
 
         // Send execute result/error on IOPub
         if let Some(result) = result {
-            self.iopub_tx.send(result).unwrap();
+            self.iopub_tx().send(result).unwrap();
         }
 
         // Notify comm handlers about environment changes. This must happen
@@ -1488,6 +1706,9 @@ impl Console {
             continuation_prompt: continuation_prompt.to_string(),
         });
 
+        // Output emitted by comm handlers must reach IOPub before Idle
+        self.flush_stream();
+
         // Now unblock Shell, which sends Idle
         log::trace!("Sending `execute_reply`: {reply:?}");
         req.reply_tx.send(reply).unwrap();
@@ -1520,6 +1741,10 @@ impl Console {
                 // Extract input from request
                 let (input, exec_count) = { self.init_execute_request(&exec_req) };
 
+                // Forget about errors from a previous request that were caught
+                // before reaching top level, e.g. by `tryCatch()`
+                self.r_error_name.set(None);
+
                 // Save `ExecuteCode` request so we can respond to it at next prompt
                 self.active_request = Some(ActiveReadConsoleRequest {
                     exec_count,
@@ -1547,7 +1772,10 @@ impl Console {
                 input
             },
 
-            RRequest::Shutdown(_) => ConsoleInput::EndOfFile,
+            RRequest::Shutdown(restart) => {
+                self.prepare_shutdown(restart);
+                ConsoleInput::EndOfFile
+            },
 
             RRequest::DebugCommand(cmd) => {
                 // Just ignore command in case we left the debugging state already
@@ -1653,15 +1881,21 @@ impl Console {
         buf: *mut c_uchar,
         buflen: c_int,
     ) -> ConsoleResult {
-        let Some(originator) = self
-            .active_request
-            .as_ref()
-            .map(|req| req.originator.clone())
-        else {
+        let Some(active_request) = self.active_request.as_ref() else {
             // Invalid input request, propagate error to R
             return self.handle_invalid_input_request(buf, buflen);
         };
 
+        // The frontend told us it can't handle input requests for this
+        // execution. Fail early instead of waiting for a reply that would
+        // never come. Browser prompts routed to stdin are exempt since an
+        // error there would drop us right back at the browser prompt.
+        if !active_request.request.allow_stdin && matches!(info.kind, PromptKind::InputRequest) {
+            return self.handle_disallowed_input_request();
+        }
+
+        let originator = active_request.originator.clone();
+
         // Flush any buffered autoprint output as stream stdout so it
         // appears in the console before the input prompt. This happens
         // when `readline()` or `menu()` is called during auto-print of
@@ -1697,14 +1931,19 @@ impl Console {
             if let Ok(sym) = harp::RSymbol::new(input.expr.sexp) {
                 let mut sym = String::from(sym);
 
-                // When stopped at an exception breakpoint or pause, the top
-                // frame is the hidden handler that called `browser()`. Remap
-                // "step over" to "step out" so the user leaves the handler
-                // frame instead of stepping through internal code.
+                // When stopped at an exception breakpoint, pause, or data
+                // breakpoint, the top frame is the hidden handler that called
+                // `browser()`. Remap "step over" to "step out" so the user
+                // leaves the handler frame instead of stepping through
+                // internal code.
                 if sym == "n" &&
                     matches!(
                         self.debug_stopped_reason,
-                        Some(DebugStoppedReason::Condition { .. } | DebugStoppedReason::Pause)
+                        Some(
+                            DebugStoppedReason::Condition { .. } |
+                                DebugStoppedReason::Pause |
+                                DebugStoppedReason::DataBreakpoint { .. }
+                        )
                     )
                 {
                     sym = String::from("f");
@@ -1878,7 +2117,7 @@ impl Console {
             log::warn!("Detected `readline()` call in renv autoloader. Returning `'{input}'`.");
             match Self::on_console_input(buf, buflen, input) {
                 Ok(()) => return ConsoleResult::NewInput,
-                Err(err) => return ConsoleResult::Error(format!("{err}")),
+                Err(err) => return self.console_input_error(err),
             }
         }
 
@@ -1890,6 +2129,18 @@ impl Console {
         ConsoleResult::Error(message)
     }
 
+    fn handle_disallowed_input_request(&self) -> ConsoleResult {
+        log::warn!("Detected `input_request` while the frontend doesn't allow stdin. Preparing to throw an R error.");
+
+        let message = [
+            "Can't request input from the user at this time.",
+            "The frontend doesn't allow input for this execution (`allow_stdin` is `false`).",
+        ]
+        .join("\n");
+
+        ConsoleResult::Error(message)
+    }
+
     fn handle_invalid_input_request_after_error(&self) -> ConsoleResult {
         log::warn!("Detected invalid `input_request` after error (probably from `getOption('error')`). Preparing to throw an R error.");
 
@@ -1997,7 +2248,7 @@ impl Console {
                 let input = convert_line_endings(&input.value, LineEnding::Posix);
                 match Self::on_console_input(buf, buflen, input) {
                     Ok(()) => ConsoleResult::NewInput,
-                    Err(err) => ConsoleResult::Error(format!("{err:?}")),
+                    Err(err) => self.console_input_error(err),
                 }
             },
             Err(err) => ConsoleResult::Error(format!("{err:?}")),
@@ -2214,13 +2465,28 @@ impl Console {
     // Hitting this means a SINGLE line from the user was longer than the buffer size (>4000 characters)
     fn buffer_overflow_error() -> amalthea::Error {
         Error::InvalidConsoleInput(String::from(
-            "Can't pass console input on to R, a single line exceeds R's internal console buffer size."
+            "Can't pass console input on to R, a single line exceeds R's internal console buffer size. Split it into multiple lines."
         ))
     }
 
+    /// Turn an `on_console_input()` failure into an R error. Buffer overflows
+    /// are tagged with `CONSOLE_INPUT_TOO_LONG` so that the `ename` of the
+    /// resulting exception lets frontends tell them apart from regular R
+    /// errors.
+    fn console_input_error(&self, err: amalthea::Error) -> ConsoleResult {
+        match err {
+            Error::InvalidConsoleInput(message) => {
+                self.r_error_name.set(Some(CONSOLE_INPUT_TOO_LONG));
+                ConsoleResult::Error(message)
+            },
+            err => ConsoleResult::Error(format!("{err}")),
+        }
+    }
+
     fn prepare_execute_reply(
         exec_count: u32,
         value: ConsoleValue,
+        user_expressions: serde_json::Value,
     ) -> (amalthea::Result<ExecuteReply>, Option<IOPubMessage>) {
         log::trace!("Completing execution after receiving prompt");
 
@@ -2229,7 +2495,7 @@ impl Console {
                 let reply = Ok(ExecuteReply {
                     status: Status::Ok,
                     execution_count: exec_count,
-                    user_expressions: json!({}),
+                    user_expressions,
                 });
 
                 let result = if !data.is_empty() {
@@ -2257,6 +2523,50 @@ impl Console {
         }
     }
 
+    /// Evaluate the `user_expressions` of an execute request in the global
+    /// environment. Each expression reports its own result or error, keyed by
+    /// the name supplied by the client.
+    fn eval_user_expressions(&mut self, expressions: &serde_json::Value) -> serde_json::Value {
+        let Some(expressions) = expressions.as_object() else {
+            return json!({});
+        };
+
+        let mut results = serde_json::Map::new();
+
+        for (name, code) in expressions {
+            let result = match code.as_str() {
+                Some(code) => self.eval_user_expression(code),
+                None => Err(anyhow!("Expected R code as a string, got {code}")),
+            };
+
+            let result = match result {
+                Ok(text) => json!({
+                    "status": "ok",
+                    "data": { "text/plain": text },
+                    "metadata": {},
+                }),
+                Err(err) => json!({
+                    "status": "error",
+                    "ename": "",
+                    "evalue": format!("{err}"),
+                    "traceback": [],
+                }),
+            };
+
+            results.insert(name.clone(), result);
+        }
+
+        serde_json::Value::Object(results)
+    }
+
+    /// Evaluate a single user expression and return its printed value
+    fn eval_user_expression(&mut self, code: &str) -> anyhow::Result<String> {
+        let mut capture = self.start_capture();
+        let value = harp::parse_eval_global(code)?;
+        harp::utils::r_print(&value)?;
+        Ok(capture.take().trim_end().to_string())
+    }
+
     /// Sends a `Wait` message to IOPub, which responds when the IOPub thread
     /// actually processes the message, implying that all other IOPub messages
     /// in front of this one have been forwarded on to the frontend.
@@ -2266,7 +2576,7 @@ impl Console {
 
         let message = IOPubMessage::Wait(Wait { wait_tx });
 
-        if let Err(error) = self.iopub_tx.send(message) {
+        if let Err(error) = self.iopub_tx().send(message) {
             log::error!("Failed to send wait request to iopub: {error:?}");
             return;
         }
@@ -2377,15 +2687,17 @@ impl Console {
             Stream::Stderr
         };
 
-        // If active execution request is silent don't broadcast
-        // any output
-        if let Some(ref req) = console.active_request {
-            if req.request.silent {
+        // If active execution request is silent don't broadcast any output.
+        // Stdout still goes through the debug filter below (`emit_stdout()`
+        // drops what it emits) so that debugger state such as the location
+        // of an exception breakpoint is tracked as usual.
+        let silent = console.is_silent_request();
+
+        if stream == Stream::Stdout && is_auto_printing() {
+            if silent {
                 return;
             }
-        }
 
-        if stream == Stream::Stdout && is_auto_printing() {
             // If we are at top-level, we're handling visible output auto-printed by
             // the R REPL. We accumulate this output (it typically comes in multiple
             // parts) so we can emit it later on as part of the execution reply
@@ -2437,12 +2749,15 @@ impl Console {
                 console.emit_stdout(text);
             }
 
+            if silent {
+                return;
+            }
+
             // Now emit Stderr message
-            let message = IOPubMessage::Stream(StreamOutput {
+            console.send_stream(StreamOutput {
                 name: stream,
                 text: content,
             });
-            console.iopub_tx.send(message).unwrap();
             return;
         }
 
@@ -2453,11 +2768,19 @@ impl Console {
     }
 
     fn emit_stdout(&mut self, text: String) {
-        let message = IOPubMessage::Stream(StreamOutput {
+        if self.is_silent_request() {
+            return;
+        }
+        self.send_stream(StreamOutput {
             name: Stream::Stdout,
             text,
         });
-        self.iopub_tx.send(message).unwrap();
+    }
+
+    fn is_silent_request(&self) -> bool {
+        self.active_request
+            .as_ref()
+            .is_some_and(|req| req.request.silent)
     }
 
     /// Invoked by R to change busy state
@@ -2576,6 +2899,10 @@ impl Console {
         if let Some(text) = self.debug_filter.check_timeout() {
             self.emit_stdout(text);
         }
+
+        // Don't hold back buffered output when R goes quiet in the middle of
+        // a computation
+        self.flush_stream_on_interval();
     }
 
     pub(super) fn eval_env(&self) -> RObject {
@@ -2842,7 +3169,10 @@ fn r_read_console_impl(
     buflen: c_int,
     hist: c_int,
 ) -> i32 {
+    // Time spent waiting for input doesn't count towards the watchdog timeout
+    watchdog::notify_idle();
     let result = r_sandbox(|| console.read_console(prompt, buf, buflen, hist));
+    watchdog::notify_busy();
 
     let result = unwrap!(result, Err(err) => {
         panic!("Unexpected longjump while reading from console: {err:?}");
@@ -2924,6 +3254,16 @@ fn new_cstring(x: String) -> CString {
     CString::new(x).unwrap_or(CString::new("Can't create CString").unwrap())
 }
 
+/// Run `handler` if a tick is already queued on `rx`, consuming the tick so
+/// that the event loop's `select()` doesn't see it again. `select()` picks
+/// randomly among ready operations, so checking ahead of it ensures the
+/// handler isn't starved by other channels that are always ready.
+fn run_if_due(rx: &Receiver<Instant>, handler: impl FnOnce()) {
+    if rx.try_recv().is_ok() {
+        handler();
+    }
+}
+
 #[cfg_attr(not(test), no_mangle)]
 pub extern "C-unwind" fn r_write_console(buf: *const c_char, buflen: i32, otype: i32) {
     if let Err(err) = r_sandbox(|| Console::write_console(buf, buflen, otype)) {
@@ -2968,16 +3308,28 @@ unsafe extern "C-unwind" fn ps_onload_hook(pkg: SEXP, _path: SEXP) -> anyhow::Re
 
     // Populate fake source refs if needed
     if do_resource_namespaces() {
-        r_task::spawn(RTask::idle(async move |_| {
-            if let Err(err) = ns_populate_srcref(pkg.clone()).await {
-                log::error!("Can't populate srcref for `{pkg}`: {err:?}");
-            }
-        }));
+        r_task::spawn(RTask::with_priority(
+            RTaskPriority::TopLevelIdle,
+            async move |_| {
+                if let Err(err) = ns_populate_srcref(pkg.clone()).await {
+                    log::error!("Can't populate srcref for `{pkg}`: {err:?}");
+                }
+            },
+        ));
     }
 
     Ok(RObject::null().sexp)
 }
 
+/// HTML representation of `value`, if it's a table that notebook frontends
+/// can render inline
+fn html_representation(value: SEXP) -> anyhow::Result<Option<String>> {
+    let html = RFunction::from("html_representation")
+        .add(value)
+        .call_in(ARK_ENVS.positron_ns)?;
+    Ok(Option::<String>::try_from(html)?)
+}
+
 fn filter_debug_output() -> bool {
     let opt: Option<bool> =
         r_null_or_try_into(harp::get_option("ark.debugger.filter_debug_output"))
@@ -3112,3 +3464,63 @@ pub unsafe extern "C-unwind" fn ps_session_mode() -> anyhow::Result<SEXP> {
     };
     Ok(RObject::from(mode).sexp)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::r_task;
+
+    fn execute_request(code: &str) -> ExecuteRequest {
+        ExecuteRequest {
+            code: String::from(code),
+            silent: true,
+            store_history: true,
+            user_expressions: serde_json::json!({}),
+            allow_stdin: false,
+            stop_on_error: false,
+            positron: None,
+        }
+    }
+
+    #[test]
+    fn test_run_if_due_handles_queued_tick_before_select() {
+        let (tick_tx, tick_rx) = crossbeam::channel::unbounded();
+        let (idle_tx, idle_rx) = crossbeam::channel::unbounded();
+        idle_tx.send(()).unwrap();
+        tick_tx.send(Instant::now()).unwrap();
+
+        let mut ran = false;
+        run_if_due(&tick_rx, || ran = true);
+        assert!(ran);
+
+        // The tick is consumed, so `select()` can only pick the idle channel
+        let mut select = crossbeam::channel::Select::new();
+        let tick_index = select.recv(&tick_rx);
+        let idle_index = select.recv(&idle_rx);
+        let oper = select.select();
+        assert_eq!(oper.index(), idle_index);
+        assert_ne!(oper.index(), tick_index);
+        oper.recv(&idle_rx).unwrap();
+
+        // Nothing runs when no tick is due
+        let mut ran = false;
+        run_if_due(&tick_rx, || ran = true);
+        assert!(!ran);
+    }
+
+    #[test]
+    fn test_execution_count_restarts_at_one_after_restart() {
+        r_task(|| {
+            let console = Console::get_mut();
+
+            let (_, first) = console.init_execute_request(&execute_request("1"));
+            let (_, second) = console.init_execute_request(&execute_request("2"));
+            assert_eq!(second, first + 1);
+
+            console.prepare_shutdown(true);
+
+            let (_, count) = console.init_execute_request(&execute_request("3"));
+            assert_eq!(count, 1);
+        })
+    }
+}
@@ -58,6 +58,16 @@ impl Control {
 }
 
 impl ControlHandler for Control {
+    /// Shut down R, either for good or for a restart.
+    ///
+    /// Both cases exit the kernel process the same way, with status 0, once
+    /// R has returned to the top-level prompt. R can't be reinitialised within
+    /// a process, so on `restart: true` it is up to the frontend to launch a
+    /// new kernel, as the Jupyter protocol specifies. Nothing of the R session
+    /// is preserved: the global environment, loaded packages, options, and
+    /// open comms are all lost. `.Last()` runs but the workspace is not saved.
+    /// Before exiting, all comms are closed on IOPub and, on restart, the
+    /// execution counter is reset.
     fn handle_shutdown_request(&self, msg: &ShutdownRequest) -> Result<ShutdownReply, Exception> {
         log::info!("Received shutdown request: {msg:?}");
 
@@ -170,7 +170,7 @@ impl DapJupyterHandler {
             DapConsoleEvent::Interrupt => {
                 crate::sys::control::handle_interrupt_request();
             },
-            DapConsoleEvent::Restart => {
+            DapConsoleEvent::Restart | DapConsoleEvent::RestartFunction(_) => {
                 log::warn!("Jupyter DAP: Restart requested but not supported");
             },
         }
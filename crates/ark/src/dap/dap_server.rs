@@ -10,6 +10,7 @@ use std::io::BufReader;
 use std::io::BufWriter;
 use std::io::Read;
 use std::io::Write;
+use std::net::SocketAddr;
 use std::net::TcpListener;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -33,6 +34,9 @@ use dap::requests::*;
 use dap::responses::*;
 use dap::server::ServerOutput;
 use dap::types::*;
+use harp::environment::R_ENVS;
+use harp::exec::RFunction;
+use harp::exec::RFunctionExt;
 use stdext::result::ResultExt;
 use stdext::spawn;
 
@@ -47,7 +51,10 @@ use crate::console::ConsoleOutputCapture;
 use crate::console::FrameInfo;
 use crate::console::FrameSource;
 use crate::dap::dap_variables::object_variables;
+use crate::dap::dap_variables::set_variable;
 use crate::dap::dap_variables::RVariable;
+use crate::modules::ARK_ENVS;
+use crate::network::server_ip_address;
 use crate::r_task;
 use crate::r_task::RTask;
 use crate::request::debug_request_command;
@@ -69,6 +76,9 @@ pub enum DapConsoleEvent {
     Interrupt,
     /// Request a session restart via the frontend.
     Restart,
+    /// Quit the debugger and evaluate the given code via the frontend, to
+    /// re-run the debugged function from the top.
+    RestartFunction(String),
 }
 
 /// The result of handling a single DAP request. The transport layer (TCP or
@@ -140,10 +150,13 @@ impl DapHandler {
             Command::Threads => self.handle_threads(),
             Command::SetBreakpoints(args) => self.handle_set_breakpoints(args),
             Command::SetExceptionBreakpoints(args) => self.handle_set_exception_breakpoints(args),
+            Command::DataBreakpointInfo(args) => self.handle_data_breakpoint_info(args),
+            Command::SetDataBreakpoints(args) => self.handle_set_data_breakpoints(args),
             Command::StackTrace(args) => self.handle_stacktrace(args),
             Command::Source(args) => self.handle_source(args),
             Command::Scopes(args) => self.handle_scopes(args),
             Command::Variables(args) => self.handle_variables(args),
+            Command::SetVariable(args) => self.handle_set_variable(args),
             Command::Continue(args) => {
                 let resp = ResponseBody::Continue(ContinueResponse {
                     all_threads_continued: Some(true),
@@ -210,6 +223,8 @@ impl DapHandler {
             supports_hit_conditional_breakpoints: Some(true),
             supports_log_points: Some(true),
             supports_configuration_done_request: Some(true),
+            supports_data_breakpoints: Some(true),
+            supports_set_variable: Some(true),
             ..Default::default()
         });
         Ok(DapHandlerOutput {
@@ -461,9 +476,17 @@ impl DapHandler {
     fn handle_disconnect(&self, _args: DisconnectArguments) -> anyhow::Result<DapHandlerOutput> {
         let mut state = self.state.lock().unwrap();
         let is_debugging = state.is_debugging;
+        let data_breakpoints = std::mem::take(&mut state.data_breakpoints);
         state.is_connected = false;
         drop(state);
 
+        // Restore watched variables once R is idle, we don't need to wait
+        if !data_breakpoints.is_empty() {
+            r_task::spawn(RTask::send_idle_any_prompt(async move |_capture| {
+                unwatch_variables(&data_breakpoints);
+            }));
+        }
+
         let console_events = if is_debugging {
             log::trace!("DAP: Disconnect while debugging, injecting `Quit` to exit the browser");
             vec![DapConsoleEvent::DebugCommand(DebugRequest::Quit)]
@@ -479,11 +502,24 @@ impl DapHandler {
         })
     }
 
+    // Restarts the debugged function when stopped in the debugger, and the
+    // whole session otherwise
     fn handle_restart<T>(&self, _args: T) -> anyhow::Result<DapHandlerOutput> {
+        let is_debugging = self.state.lock().unwrap().is_debugging;
+
+        let console_event = if is_debugging {
+            let command = r_task(|| Console::get().debug_restart_command())
+                .map_err(|err| anyhow::anyhow!("Restart not supported here: {err}"))?;
+            log::trace!("DAP: Restarting debugged function with `{command}`");
+            DapConsoleEvent::RestartFunction(command)
+        } else {
+            DapConsoleEvent::Restart
+        };
+
         Ok(DapHandlerOutput {
             body: ResponseBody::Restart,
             dap_events: vec![],
-            console_events: vec![DapConsoleEvent::Restart],
+            console_events: vec![console_event],
         })
     }
 
@@ -522,6 +558,108 @@ impl DapHandler {
         })
     }
 
+    /// Data breakpoints watch a variable and stop in the debugger whenever it
+    /// is assigned a different value. They are implemented with active
+    /// bindings (see `debug_watch()` in `debug.R`), which comes with
+    /// limitations:
+    ///
+    /// - Only existing variables of the global environment can be watched.
+    /// - Locked bindings and bindings that are already active can't be watched.
+    /// - Only assignments are detected, not modifications of the object made
+    ///   in place from C code.
+    fn handle_data_breakpoint_info(
+        &self,
+        args: DataBreakpointInfoArguments,
+    ) -> anyhow::Result<DapHandlerOutput> {
+        let name = args.name;
+
+        let is_global = match args.variables_reference {
+            None | Some(0) => true,
+            Some(variables_reference) => {
+                let state = self.state.clone();
+                r_task(move || {
+                    let state = state.lock().unwrap();
+                    state
+                        .variables_reference_to_r_object
+                        .get(&variables_reference)
+                        .is_some_and(|env| env.get().sexp == R_ENVS.global)
+                })
+            },
+        };
+
+        let body = if is_global {
+            DataBreakpointInfoResponse {
+                description: format!("Break when `{name}` changes"),
+                data_id: Some(name),
+                access_types: None,
+                can_persist: Some(false),
+            }
+        } else {
+            DataBreakpointInfoResponse {
+                description: String::from("Only global variables can be watched"),
+                data_id: None,
+                access_types: None,
+                can_persist: Some(false),
+            }
+        };
+
+        Ok(DapHandlerOutput {
+            body: ResponseBody::DataBreakpointInfo(body),
+            dap_events: vec![],
+            console_events: vec![],
+        })
+    }
+
+    fn handle_set_data_breakpoints(
+        &self,
+        args: SetDataBreakpointsArguments,
+    ) -> anyhow::Result<DapHandlerOutput> {
+        let names: Vec<String> = args.breakpoints.into_iter().map(|bp| bp.data_id).collect();
+
+        // The request replaces all existing data breakpoints. Lock inside the
+        // `r_task()`, see `collect_r_variables()`.
+        let state = self.state.clone();
+        let watched = r_task({
+            let names = names.clone();
+            move || {
+                let mut state = state.lock().unwrap();
+                let old = std::mem::take(&mut state.data_breakpoints);
+                unwatch_variables(&old);
+
+                let watched = watch_variables(&names);
+                state.data_breakpoints = names
+                    .iter()
+                    .zip(watched.iter())
+                    .filter(|(_, watched)| **watched)
+                    .map(|(name, _)| name.clone())
+                    .collect();
+
+                watched
+            }
+        });
+
+        let mut state = self.state.lock().unwrap();
+        let breakpoints = names
+            .iter()
+            .zip(watched)
+            .map(|(name, verified)| {
+                let message = (!verified).then(|| format!("Can't watch `{name}`"));
+                dap::types::Breakpoint {
+                    id: Some(state.next_breakpoint_id()),
+                    verified,
+                    message,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Ok(DapHandlerOutput {
+            body: ResponseBody::SetDataBreakpoints(SetDataBreakpointsResponse { breakpoints }),
+            dap_events: vec![],
+            console_events: vec![],
+        })
+    }
+
     fn handle_stacktrace(&self, args: StackTraceArguments) -> anyhow::Result<DapHandlerOutput> {
         let stack = {
             let state = self.state.lock().unwrap();
@@ -643,6 +781,44 @@ impl DapHandler {
         variables
     }
 
+    fn handle_set_variable(&self, args: SetVariableArguments) -> anyhow::Result<DapHandlerOutput> {
+        // Wait until we're in the `r_task()` to lock, as in `collect_r_variables()`
+        let state = self.state.clone();
+
+        let variable = r_task(move || -> anyhow::Result<RVariable> {
+            let state = state.lock().unwrap();
+            let variables_reference = args.variables_reference;
+
+            let Some(object) = state
+                .variables_reference_to_r_object
+                .get(&variables_reference)
+            else {
+                return Err(anyhow::anyhow!(
+                    "Failed to locate R object for `variables_reference` {variables_reference}."
+                ));
+            };
+
+            let env = object.get();
+            Ok(set_variable(env.sexp, &args.name, &args.value)?)
+        })?;
+
+        let Some(variable) = self.make_variables(vec![variable]).pop() else {
+            return Err(anyhow::anyhow!("Can't make variable `{}`", args.name));
+        };
+
+        Ok(DapHandlerOutput {
+            body: ResponseBody::SetVariable(SetVariableResponse {
+                value: variable.value,
+                type_field: variable.type_field,
+                variables_reference: Some(variable.variables_reference),
+                named_variables: None,
+                indexed_variables: None,
+            }),
+            dap_events: vec![],
+            console_events: vec![],
+        })
+    }
+
     // `make_variables()` doesn't require R, so locking outside of an
     // `r_task()` is fine here, unlike in `collect_r_variables()`.
     fn make_variables(&self, variables: Vec<RVariable>) -> Vec<Variable> {
@@ -737,10 +913,16 @@ pub fn start_dap(
     r_request_tx: Sender<RRequest>,
     comm_tx: CommOutgoingTx,
 ) {
-    let ip_address = server_start.ip_address();
+    let ip_address = match server_ip_address(server_start.ip_address()) {
+        Ok(ip_address) => ip_address,
+        Err(err) => {
+            log::error!("DAP: Can't start server: {err:?}");
+            return;
+        },
+    };
 
     // Binding to port `0` to allow the OS to allocate a port for us to bind to
-    let listener = TcpListener::bind(format!("{ip_address}:0",)).unwrap();
+    let listener = TcpListener::bind(SocketAddr::new(ip_address, 0)).unwrap();
 
     let address = match listener.local_addr() {
         Ok(address) => address,
@@ -753,7 +935,7 @@ pub fn start_dap(
     // Get the OS allocated port
     let port = address.port();
 
-    log::trace!("DAP: Thread starting at address {ip_address}:{port}.");
+    log::info!("DAP: Thread starting at address {address}.");
 
     // Send the port back to `Shell` and eventually out to the frontend so it can connect
     server_started_tx
@@ -982,6 +1164,19 @@ impl<R: Read, W: Write> DapServer<R, W> {
                     tx.send(msg).log_err();
                 }
             },
+            DapConsoleEvent::RestartFunction(command) => {
+                // Needs the frontend to evaluate the call at top level once the
+                // browser has been quit. Both go in a single input: the call
+                // stays pending while `Q` jumps back to top level.
+                let Some(tx) = &self.comm_tx else {
+                    log::warn!("DAP: Can't restart function without a frontend comm");
+                    return;
+                };
+                let quit = debug_request_command(DebugRequest::Quit);
+                let command = format!("{quit}\n{command}");
+                tx.send(amalthea::comm_rpc_message!("execute", command = command))
+                    .log_err();
+            },
         }
     }
 
@@ -1077,3 +1272,32 @@ fn into_dap_frame(frame: &FrameInfo, fallback_sources: &HashMap<String, String>)
         presentation_hint: None,
     }
 }
+
+/// Install data breakpoints on global variables. Returns whether each
+/// variable could be watched. Must be called on the R thread.
+fn watch_variables(names: &[String]) -> Vec<bool> {
+    names
+        .iter()
+        .map(|name| {
+            RFunction::new("", "debug_watch")
+                .add(name.as_str())
+                .add(R_ENVS.global)
+                .call_in(ARK_ENVS.positron_ns)
+                .and_then(bool::try_from)
+                .log_err()
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// Restore the plain bindings of watched global variables. Must be called on
+/// the R thread.
+fn unwatch_variables(names: &[String]) {
+    for name in names {
+        RFunction::new("", "debug_unwatch")
+            .add(name.as_str())
+            .add(R_ENVS.global)
+            .call_in(ARK_ENVS.positron_ns)
+            .log_err();
+    }
+}
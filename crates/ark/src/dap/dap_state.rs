@@ -28,6 +28,7 @@ use dap::responses::EvaluateResponse;
 use dap::types;
 use dap::types::Variable;
 use harp::environment::R_ENVS;
+use harp::exec::with_restart;
 use harp::object::RObject;
 use stdext::result::ResultExt;
 use stdext::spawn;
@@ -155,6 +156,9 @@ pub enum DapBackendEvent {
 
     /// Event sent when an exception/error occurs
     Exception(DapExceptionEvent),
+
+    /// Event sent when a watched variable is assigned a new value
+    DataBreakpoint { name: String },
 }
 
 #[derive(Debug, Clone)]
@@ -194,6 +198,16 @@ impl DapBackendEvent {
                 })
             },
 
+            DapBackendEvent::DataBreakpoint { name } => Event::Stopped(StoppedEventBody {
+                reason: types::StoppedEventReason::DataBreakpoint,
+                description: Some(format!("`{name}` changed")),
+                thread_id: Some(THREAD_ID),
+                preserve_focus_hint: Some(false),
+                text: None,
+                all_threads_stopped: Some(true),
+                hit_breakpoint_ids: None,
+            }),
+
             DapBackendEvent::Invalidated => Event::Invalidated(InvalidatedEventBody {
                 areas: Some(vec![types::InvalidatedAreas::Variables]),
                 thread_id: Some(THREAD_ID),
@@ -347,6 +361,9 @@ pub struct Dap {
     /// Filters for enabled condition breakpoints
     pub exception_breakpoint_filters: Vec<String>,
 
+    /// Names of the global variables watched by data breakpoints
+    pub data_breakpoints: Vec<String>,
+
     /// Map of `source` -> `source_reference` used for frames that don't have
     /// associated files (i.e. no `srcref` attribute). The `source` is the key to
     /// ensure that we don't insert the same function multiple times, which would result
@@ -408,6 +425,7 @@ impl Dap {
             stack: None,
             breakpoints: BreakpointMap::default(),
             exception_breakpoint_filters: Vec::new(),
+            data_breakpoints: Vec::new(),
             fallback_sources: HashMap::new(),
             frame_id_to_variables_reference: HashMap::new(),
             variables_reference_to_r_object: HashMap::new(),
@@ -463,6 +481,7 @@ impl Dap {
             DebugStoppedReason::Condition { class, message } => {
                 DapBackendEvent::Exception(DapExceptionEvent { class, message })
             },
+            DebugStoppedReason::DataBreakpoint { name } => DapBackendEvent::DataBreakpoint { name },
         };
         self.send_backend_event(event);
     }
@@ -894,7 +913,16 @@ impl Dap {
         env: libr::SEXP,
         capture: Option<&mut ConsoleOutputCapture>,
     ) -> anyhow::Result<RVariable> {
-        match harp::parse_eval0(expression, harp::RObject::view(env)) {
+        // Establish an `abort` restart around the evaluation so that code
+        // aborting back to top level, e.g. via `invokeRestart("abort")`,
+        // unwinds to here instead of through the debugger's R frames
+        let result = harp::parse_exprs(expression).and_then(|exprs| {
+            with_restart("abort", exprs.sexp, env, |_| {
+                Err(harp::anyhow!("Evaluation was aborted"))
+            })
+        });
+
+        match result {
             Ok(value) => {
                 if let Some(capture) = capture {
                     harp::utils::r_print(&value)
@@ -11,10 +11,12 @@ use harp::symbol::RSymbol;
 use harp::utils::*;
 use harp::vec_format::vec_format;
 use libr::*;
-use stdext::unwrap;
 
 use crate::thread::RThreadSafe;
 
+/// Maximum length of the deparsed value of a call variable
+const CALL_VALUE_MAX_LENGTH: usize = 500;
+
 pub struct RVariable {
     pub name: String,
     pub value: String,
@@ -101,6 +103,34 @@ pub(super) fn object_variables(x: SEXP) -> Vec<RVariable> {
     }
 }
 
+/// Assign the result of evaluating `value` in `env` to the variable `name` of
+/// `env`, for the `SetVariable` DAP request. Locked bindings, and new
+/// bindings in locked environments, can't be assigned to.
+pub(super) fn set_variable(env: SEXP, name: &str, value: &str) -> harp::Result<RVariable> {
+    if r_typeof(env) != ENVSXP {
+        return Err(harp::anyhow!(
+            "Can't set `{name}`: only variables of environments can be set"
+        ));
+    }
+
+    let sym = r_symbol!(name);
+
+    if r_env_has(env, sym) {
+        if r_binding_is_locked(env, sym)? {
+            return Err(harp::Error::LockedBindingError {
+                name: name.to_string(),
+            });
+        }
+    } else if r_env_is_locked(env) {
+        return Err(harp::Error::LockedEnvironmentError);
+    }
+
+    let value = harp::parse_eval0(value, RObject::view(env))?;
+    unsafe { Rf_defineVar(sym, value.sexp, env) };
+
+    Ok(object_variable(name.to_string(), value.sexp))
+}
+
 fn env_variables(x: SEXP) -> Vec<RVariable> {
     let names = RObject::from(r_env_names(x));
     let names = Vec::<String>::try_from(names).unwrap_or(Vec::new());
@@ -226,7 +256,7 @@ fn list_variable_impl(name: String, x: SEXP, value: String, type_field: String)
 }
 
 fn symbol_variable(name: String, x: SEXP) -> RVariable {
-    let value = RSymbol::new_unchecked(x).to_string();
+    let value = RSymbol::new_unchecked(x).to_backticked_string();
     let type_field = String::from("<symbol>");
 
     RVariableBuilder::new(name)
@@ -238,10 +268,7 @@ fn symbol_variable(name: String, x: SEXP) -> RVariable {
 // TODO: It might be nice to treat the call like a pairlist with children,
 // since the debugger is mostly a development tool where introspection is valuable.
 fn call_variable(name: String, x: SEXP) -> RVariable {
-    let value = unwrap!(harp::call::expr_deparse_collapse(x), Err(err) => {
-        log::error!("Failed to format call value: {err:?}");
-        String::from("<call>")
-    });
+    let value = r_deparse_truncated(x, CALL_VALUE_MAX_LENGTH);
 
     let type_field = String::from("<call>");
 
@@ -431,6 +458,7 @@ mod tests {
     use libr::*;
 
     use crate::dap::dap_variables::env_binding_variable;
+    use crate::dap::dap_variables::set_variable;
     use crate::r_task;
 
     #[test]
@@ -498,4 +526,48 @@ mod tests {
             assert_eq!(variable.type_field, Some(String::from("<active binding>")));
         })
     }
+
+    #[test]
+    fn test_set_variable() {
+        r_task(|| {
+            let env = RFunction::new("base", "new.env")
+                .param("parent", R_ENVS.base)
+                .call()
+                .unwrap();
+
+            let variable = set_variable(env.sexp, "a", "1L + 1L").unwrap();
+            assert_eq!(variable.name, String::from("a"));
+            assert_eq!(variable.value, String::from("2L"));
+
+            RFunction::new("base", "lockBinding")
+                .add("a")
+                .add(env.sexp)
+                .call()
+                .unwrap();
+
+            let err = set_variable(env.sexp, "a", "3L").unwrap_err();
+            assert!(matches!(err, harp::Error::LockedBindingError { .. }));
+            assert_eq!(err.to_string(), "Can't modify `a`: binding is locked");
+
+            // The locked binding keeps its value
+            let variable = env_binding_variable(String::from("a"), env.sexp).unwrap();
+            assert_eq!(variable.value, String::from("2L"));
+
+            // Existing bindings of locked environments can be set, but new
+            // ones can't be added
+            RFunction::new("base", "unlockBinding")
+                .add("a")
+                .add(env.sexp)
+                .call()
+                .unwrap();
+            RFunction::new("base", "lockEnvironment")
+                .add(env.sexp)
+                .call()
+                .unwrap();
+
+            assert!(set_variable(env.sexp, "a", "3L").is_ok());
+            let err = set_variable(env.sexp, "b", "1L").unwrap_err();
+            assert!(matches!(err, harp::Error::LockedEnvironmentError));
+        })
+    }
 }
@@ -7,6 +7,7 @@
 
 pub mod column_profile;
 pub mod convert_to_code;
+pub mod data_explorer_ext_comm;
 pub mod export_selection;
 pub mod format;
 pub mod histogram;
@@ -277,7 +277,7 @@ fn profile_null_count(column: RObject) -> anyhow::Result<i64> {
     // Compute the number of nulls in the column
     let result: i32 = RFunction::new("", ".ps.null_count")
         .param("column", column)
-        .call_in(ARK_ENVS.positron_ns)?
+        .call_in_sandbox(ARK_ENVS.positron_ns)?
         .try_into()?;
 
     // Return the count of nulls and NA values
@@ -0,0 +1,89 @@
+//
+// data_explorer_ext_comm.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+//! Data explorer comm methods supported by Ark on top of the ones generated
+//! from the Positron comm schema in `amalthea::comm::data_explorer_comm`. The
+//! generated file can't be edited by hand, so these live here until they are
+//! added to the schema.
+
+use amalthea::comm::data_explorer_comm::DataExplorerBackendReply;
+use amalthea::comm::data_explorer_comm::DataExplorerBackendRequest;
+use amalthea::comm::data_explorer_comm::TableSelection;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A request on the data explorer comm, either from the schema or Ark specific
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum DataExplorerRequest {
+    Comm(DataExplorerBackendRequest),
+    Ext(DataExplorerExtBackendRequest),
+}
+
+/// A reply on the data explorer comm, either from the schema or Ark specific
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum DataExplorerReply {
+    Comm(DataExplorerBackendReply),
+    Ext(DataExplorerExtBackendReply),
+}
+
+/// The result of exporting a data selection to a file
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ExportedFile {
+    /// The path of the written file
+    pub path: String,
+}
+
+/// Parameters for the ExportDataSelectionRds method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ExportDataSelectionRdsParams {
+    /// The data selection
+    pub selection: TableSelection,
+
+    /// The path of the `.rds` file to write the selection to
+    pub path: String,
+}
+
+/// Parameters for the SetDataObject method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SetDataObjectParams {
+    /// The name of the variable in the global environment to show
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", content = "params")]
+pub enum DataExplorerExtBackendRequest {
+    /// Export a data selection to an `.rds` file
+    ///
+    /// Writes the selected region of the data with `saveRDS()`, preserving
+    /// column types and attributes so it can be read back losslessly. Unlike
+    /// `export_data_selection`, the binary result is written to a file rather
+    /// than returned as text for the clipboard.
+    #[serde(rename = "export_data_selection_rds")]
+    ExportDataSelectionRds(ExportDataSelectionRdsParams),
+
+    /// Swap the data object backing the data explorer
+    ///
+    /// Shows another variable in this data explorer rather than opening a new
+    /// comm for it. The data explorer then follows the new variable. The
+    /// frontend is sent a schema update if the columns changed, or a data
+    /// update otherwise, in which case sorts and filters are kept.
+    #[serde(rename = "set_data_object")]
+    SetDataObject(SetDataObjectParams),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", content = "result")]
+pub enum DataExplorerExtBackendReply {
+    /// The result of exporting the selection
+    ExportDataSelectionRdsReply(ExportedFile),
+
+    /// Reply for the set_data_object method (no result)
+    SetDataObjectReply(),
+}
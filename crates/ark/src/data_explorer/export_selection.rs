@@ -59,6 +59,26 @@ pub fn export_selection(
         .try_into()?)
 }
 
+// Writes the selected region of the data frame to an `.rds` file at `path`
+//
+// Unlike the text formats of `export_selection()`, this preserves column types
+// (factors, dates, etc) and attributes so the selection can be read back
+// losslessly with `readRDS()`. The arguments are the same as for
+// `export_selection()`.
+pub fn export_selection_rds(
+    data: SEXP,
+    view_indices: &Option<Vec<i32>>,
+    selection: TableSelection,
+    path: &str,
+) -> anyhow::Result<()> {
+    let region = get_selection(data, view_indices, selection)?;
+    RFunction::from("export_selection_rds")
+        .param("x", region)
+        .param("path", path)
+        .call_in(ARK_ENVS.positron_ns)?;
+    Ok(())
+}
+
 fn get_selection(
     data: SEXP,
     view_indices: &Option<Vec<i32>>,
@@ -595,4 +615,32 @@ mod tests {
             assert_eq!(result, "col_0\n10\n11");
         });
     }
+
+    #[test]
+    fn test_export_rds_round_trip() {
+        r_task(|| {
+            let data = harp::parse_eval_global(
+                "data.frame(x = 1:3, f = factor(c('a', 'b', 'a')), d = as.Date('2024-01-01') + 0:2)",
+            )
+            .unwrap();
+
+            let file = tempfile::NamedTempFile::new().unwrap();
+            let path = file.path().to_string_lossy().replace('\\', "/");
+
+            export_selection_rds(data.sexp, &None, row_indices_selection(vec![0, 2]), &path)
+                .unwrap();
+
+            // Types and attributes survive the round trip
+            let code = format!(
+                r#"local({{
+                    x <- readRDS("{path}")
+                    identical(x$x, c(1L, 3L)) &&
+                        identical(x$f, factor(c("a", "a"), levels = c("a", "b"))) &&
+                        identical(x$d, as.Date("2024-01-01") + c(0, 2))
+                }})"#
+            );
+            let identical = harp::parse_eval_global(&code).unwrap();
+            assert!(bool::try_from(identical).unwrap());
+        });
+    }
 }
@@ -20,10 +20,12 @@ use harp::utils::r_is_null;
 use harp::utils::r_typeof;
 use harp::vector::CharacterVector;
 use harp::vector::ComplexVector;
+use harp::vector::Integer64Vector;
 use harp::vector::IntegerVector;
 use harp::vector::LogicalVector;
 use harp::vector::NumericVector;
 use harp::vector::Vector;
+use itertools::Itertools;
 use libr::SEXP;
 use libr::*;
 use stdext::unwrap;
@@ -61,6 +63,51 @@ fn format(x: SEXP, format_options: &FormatOptions) -> Vec<FormattedValue> {
     formatted
 }
 
+/// Format table cells as tab-separated values, e.g. to paste in a spreadsheet.
+///
+/// `cells` is a named list of character columns, as returned by
+/// `.ps.environment.clipboardCells()`. The names form the header row. Missing
+/// values are written as empty cells when `na_as_empty` is set, and as `NA`
+/// otherwise. Tabs and newlines within cells are replaced by spaces so they
+/// don't break the table layout.
+pub fn format_tsv(cells: &RObject, na_as_empty: bool) -> anyhow::Result<String> {
+    let n_cols = r_length(cells.sexp);
+
+    let mut columns = Vec::with_capacity(n_cols as usize);
+    for i in 0..n_cols {
+        let column = CharacterVector::new(harp::list_get(cells.sexp, i))?;
+        columns.push(column.iter().collect::<Vec<Option<String>>>());
+    }
+
+    let n_rows = columns.iter().map(|column| column.len()).max().unwrap_or(0);
+
+    let escape = |cell: &str| cell.replace(['\t', '\n', '\r'], " ");
+
+    let header = cells
+        .names()
+        .unwrap_or_else(|| vec![None; n_cols as usize])
+        .into_iter()
+        .map(|name| escape(&name.unwrap_or_default()))
+        .join("\t");
+
+    let mut lines = Vec::with_capacity(n_rows + 1);
+    lines.push(header);
+
+    for row in 0..n_rows {
+        let line = columns
+            .iter()
+            .map(|column| match column.get(row) {
+                Some(Some(cell)) => escape(cell),
+                Some(None) if !na_as_empty => String::from("NA"),
+                _ => String::new(),
+            })
+            .join("\t");
+        lines.push(line);
+    }
+
+    Ok(lines.join("\n"))
+}
+
 // Truncating strings in Rust is more complicated that one would imagine.
 // If you index using eg s[..6] that would take 6 bytes, which is not necessarily 6 characters.
 // We need to iterate over the characters and truncate the string at the right character.
@@ -83,6 +130,15 @@ fn unknown_format(x: SEXP) -> Vec<FormattedValue> {
 
 // Format a column of data for display in the data explorer.
 fn format_values(x: SEXP, format_options: &FormatOptions) -> anyhow::Result<Vec<FormattedValue>> {
+    // `integer64` values are formatted like integers rather than through
+    // `format()`, so they get the same thousands separators
+    if Integer64Vector::is_integer64(x) {
+        return Ok(format_int64(
+            unsafe { Integer64Vector::new_unchecked(x) },
+            format_options,
+        ));
+    }
+
     if r_classes(x).is_some() {
         return Ok(format_object(x));
     }
@@ -98,7 +154,7 @@ fn format_values(x: SEXP, format_options: &FormatOptions) -> anyhow::Result<Vec<
         )),
         STRSXP => Ok(format_chr(unsafe { CharacterVector::new_unchecked(x) })),
         LGLSXP => Ok(format_lgl(unsafe { LogicalVector::new_unchecked(x) })),
-        CPLXSXP => Ok(format_cpl(unsafe { ComplexVector::new_unchecked(x) })),
+        CPLXSXP => format_cpl(unsafe { ComplexVector::new_unchecked(x) }),
         VECSXP => Ok(format_list(x)),
         _ => Err(anyhow::anyhow!("Unsupported column type")),
     }
@@ -218,13 +274,21 @@ fn format_list_elt(x: SEXP) -> String {
     format!("<{} [{}]>", class_str, dim_str)
 }
 
-fn format_cpl(x: ComplexVector) -> Vec<FormattedValue> {
-    x.iter()
-        .map(|x| match x {
-            Some(v) => FormattedValue::Value(format!("{}+{}i", v.r, v.i)),
-            None => FormattedValue::Na,
+fn format_cpl(x: ComplexVector) -> anyhow::Result<Vec<FormattedValue>> {
+    // Defer to R's `format()` so that values look like they do when printed,
+    // e.g. `1e+10+1i`, including scientific notation and signs
+    let formatted: Vec<Option<String>> = RFunction::from("format_complex_elements")
+        .add(x.data())
+        .call_in(ARK_ENVS.positron_ns)?
+        .try_into()?;
+
+    Ok(x.iter()
+        .zip(formatted)
+        .map(|(x, formatted)| match (x, formatted) {
+            (Some(_), Some(v)) => FormattedValue::Value(v),
+            _ => FormattedValue::Na,
         })
-        .collect()
+        .collect())
 }
 
 fn format_lgl(x: LogicalVector) -> Vec<FormattedValue> {
@@ -262,6 +326,18 @@ fn format_int_elt(x: Option<i32>, options: &FormatOptions) -> FormattedValue {
     }
 }
 
+fn format_int64(x: Integer64Vector, options: &FormatOptions) -> Vec<FormattedValue> {
+    x.iter()
+        .map(|x| match x {
+            None => FormattedValue::Na,
+            Some(v) => FormattedValue::Value(apply_thousands_sep(
+                v.to_string(),
+                options.thousands_sep.clone(),
+            )),
+        })
+        .collect()
+}
+
 fn format_dbl(x: NumericVector, options: &FormatOptions) -> Vec<FormattedValue> {
     x.iter().map(|x| format_dbl_elt(x, options)).collect()
 }
@@ -697,10 +773,6 @@ mod tests {
 
     #[test]
     fn test_cpl_formatting() {
-        // TODO: In the future we might want to use scientific notation for complex numbers
-        // although I'm not sure it's really helpful:
-        // > 1000000000+1000000000i
-        // [1] 1e+09+1e+09i
         r_task(|| {
             let data =
                 harp::parse_eval_global("c(1+1i, 2+2i, 3+3i, NA, 1000000000+1000000000i, 5+5i)")
@@ -711,12 +783,52 @@ mod tests {
                 ColumnValue::FormattedValue("2+2i".to_string()),
                 ColumnValue::FormattedValue("3+3i".to_string()),
                 FormattedValue::Na.into(),
-                ColumnValue::FormattedValue("1000000000+1000000000i".to_string()),
+                ColumnValue::FormattedValue("1e+09+1e+09i".to_string()),
                 ColumnValue::FormattedValue("5+5i".to_string())
             ]);
         })
     }
 
+    #[test]
+    fn test_cpl_formatting_signs_and_special_values() {
+        r_task(|| {
+            let data = harp::parse_eval_global(
+                "c(1-2i, -1.5+0.25i, 1/3+0i, complex(real = Inf, imaginary = -Inf), complex(real = NaN, imaginary = 1))",
+            )
+            .unwrap();
+            let formatted = format_column(data.sexp, &default_options());
+            assert_eq!(formatted, vec![
+                ColumnValue::FormattedValue("1-2i".to_string()),
+                ColumnValue::FormattedValue("-1.5+0.25i".to_string()),
+                ColumnValue::FormattedValue("0.3333333+0i".to_string()),
+                ColumnValue::FormattedValue("Inf-Infi".to_string()),
+                ColumnValue::FormattedValue("NaN+1i".to_string())
+            ]);
+        })
+    }
+
+    #[test]
+    fn test_cpl_formatting_magnitudes() {
+        r_task(|| {
+            let data = harp::parse_eval_global("c(1e10+1e10i, 1e-10+1e-10i, -1e15-1e15i)").unwrap();
+            let formatted = format_column(data.sexp, &default_options());
+            assert_eq!(formatted, vec![
+                ColumnValue::FormattedValue("1e+10+1e+10i".to_string()),
+                ColumnValue::FormattedValue("1e-10+1e-10i".to_string()),
+                ColumnValue::FormattedValue("-1e+15-1e+15i".to_string()),
+            ]);
+
+            // Mixed magnitudes are formatted exactly as R prints them
+            let data = harp::parse_eval_global("1e10+1i").unwrap();
+            let expected: String = harp::parse_eval_global("format(1e10+1i)")
+                .unwrap()
+                .try_into()
+                .unwrap();
+            let formatted = format_column(data.sexp, &default_options());
+            assert_eq!(formatted, vec![ColumnValue::FormattedValue(expected)]);
+        })
+    }
+
     #[test]
     fn test_lgl_formatting() {
         r_task(|| {
@@ -786,4 +898,45 @@ mod tests {
             ),]);
         })
     }
+
+    #[test]
+    fn test_format_tsv() {
+        r_task(|| {
+            let data = harp::parse_eval_base(
+                "data.frame(x = c(1.5, NA), y = c('a\tb', 'c'), f = factor(c(NA, 'lvl')))",
+            )
+            .unwrap();
+            let cells = RFunction::from(".ps.environment.clipboardCells")
+                .add(data)
+                .add(100)
+                .add(100)
+                .call()
+                .unwrap();
+
+            let tsv = format_tsv(&cells, false).unwrap();
+            let lines: Vec<&str> = tsv.lines().collect();
+            assert_eq!(lines, vec!["x\ty\tf", "1.5\ta b\tNA", "NA\tc\tlvl"]);
+
+            // Missing values as empty cells
+            let tsv = format_tsv(&cells, true).unwrap();
+            let lines: Vec<&str> = tsv.lines().collect();
+            assert_eq!(lines[1], "1.5\ta b\t");
+            assert_eq!(lines[2], "\tc\tlvl");
+
+            // Row names and row/column caps
+            let data = harp::parse_eval_base(
+                "matrix(1:6, nrow = 3, dimnames = list(c('r1', 'r2', 'r3'), c('a', 'b')))",
+            )
+            .unwrap();
+            let cells = RFunction::from(".ps.environment.clipboardCells")
+                .add(data)
+                .add(2)
+                .add(1)
+                .call()
+                .unwrap();
+
+            let tsv = format_tsv(&cells, false).unwrap();
+            assert_eq!(tsv, "\ta\nr1\t1\nr2\t2");
+        })
+    }
 }
@@ -16,6 +16,7 @@ use harp::r_null;
 use harp::utils::r_classes;
 use harp::utils::r_inherits;
 use harp::utils::r_typeof;
+use harp::vector::Integer64Vector;
 use libr::INTSXP;
 use libr::REALSXP;
 use libr::SEXP;
@@ -37,9 +38,13 @@ pub fn profile_histogram(
     // Checks for supported objects:
     // - Atomic integers and doubles
     // - Dates and POSIXct objects
+    // - `bit64::integer64` vectors
     match r_classes(column) {
         Some(v) => {
-            if !r_inherits(column, "Date") && !r_inherits(column, "POSIXct") {
+            if !r_inherits(column, "Date") &&
+                !r_inherits(column, "POSIXct") &&
+                !Integer64Vector::is_integer64(column)
+            {
                 return Err(anyhow!("Object with class '{:?}' unsupported.", v));
             }
         },
@@ -63,7 +68,7 @@ pub fn profile_histogram(
         .add(method)
         .add(num_bins)
         .add(quantiles)
-        .call_in(ARK_ENVS.positron_ns)?
+        .call_in_sandbox(ARK_ENVS.positron_ns)?
         .try_into()?;
 
     // Bin edges are expected to be objects that can be formatted, such as integers vectors,
@@ -124,7 +129,7 @@ pub fn profile_frequency_table(
     let results: HashMap<String, RObject> = RFunction::from("profile_frequency_table")
         .add(column)
         .add(params.limit as i32)
-        .call_in(ARK_ENVS.positron_ns)?
+        .call_in_sandbox(ARK_ENVS.positron_ns)?
         .try_into()?;
 
     let values = unwrap!(results.get("values"), None => {
@@ -566,6 +571,34 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_frequency_table_ordered_factors() {
+        r_task(|| {
+            // Categories of ordered factors are listed in level order
+            let code = "factor(
+                rep(c('low', 'medium', 'high'), c(100, 200, 150)),
+                levels = c('low', 'medium', 'high'),
+                ordered = TRUE
+            )";
+            test_frequency_table(
+                code,
+                10,
+                harp::parse_eval_global("c('low', 'medium', 'high')").unwrap(),
+                vec![100, 200, 150],
+                None,
+            );
+
+            // The limit still selects the most frequent categories
+            test_frequency_table(
+                code,
+                2,
+                harp::parse_eval_global("c('medium', 'high')").unwrap(),
+                vec![200, 150],
+                Some(100),
+            );
+        })
+    }
+
     #[test]
     fn test_frequency_table_numerics_and_dates() {
         r_task(|| {
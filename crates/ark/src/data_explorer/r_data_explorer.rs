@@ -65,10 +65,12 @@ use amalthea::socket::comm::CommOutgoingTx;
 use anyhow::anyhow;
 use anyhow::bail;
 use anyhow::Context;
+use harp::environment::R_ENVS;
 use harp::exec::RFunction;
 use harp::exec::RFunctionExt;
 use harp::object::RObject;
 use harp::r_symbol;
+use harp::table_info;
 use harp::table_kind;
 use harp::tbl_get_column;
 use harp::utils::r_is_promise;
@@ -76,7 +78,7 @@ use harp::utils::r_promise_is_forced;
 use harp::utils::r_promise_value;
 use harp::vector::CharacterVector;
 use harp::vector::Vector;
-use harp::ColumnNames;
+use harp::TableInfo;
 use harp::TableKind;
 use itertools::Itertools;
 use libr::*;
@@ -92,6 +94,13 @@ use crate::console::Console;
 use crate::data_explorer::column_profile::handle_columns_profiles_requests;
 use crate::data_explorer::column_profile::ProcessColumnsProfilesParams;
 use crate::data_explorer::convert_to_code;
+use crate::data_explorer::data_explorer_ext_comm::DataExplorerExtBackendReply;
+use crate::data_explorer::data_explorer_ext_comm::DataExplorerExtBackendRequest;
+use crate::data_explorer::data_explorer_ext_comm::DataExplorerReply;
+use crate::data_explorer::data_explorer_ext_comm::DataExplorerRequest;
+use crate::data_explorer::data_explorer_ext_comm::ExportDataSelectionRdsParams;
+use crate::data_explorer::data_explorer_ext_comm::ExportedFile;
+use crate::data_explorer::data_explorer_ext_comm::SetDataObjectParams;
 use crate::data_explorer::export_selection;
 use crate::data_explorer::format;
 use crate::data_explorer::format::format_string;
@@ -212,6 +221,7 @@ impl RDataExplorer {
     ) -> anyhow::Result<Self> {
         let table = Table::new(data);
         let shape = Self::get_shape(table.get().clone())?;
+
         Ok(Self {
             title,
             table,
@@ -269,17 +279,29 @@ impl RDataExplorer {
             new
         };
 
-        let changed = if new == self.table.get().sexp {
-            false
-        } else {
-            self.table.set(RObject::new(new));
-            true
-        };
+        self.set_data(RObject::new(new), ctx)
+    }
 
+    /// Swap the data object, notifying the frontend of a schema update if the
+    /// columns changed or of a data update otherwise. Sorts and filters are
+    /// preserved when the schema is unchanged.
+    ///
+    /// Returns false if the new object can't be displayed and the data viewer
+    /// should be closed.
+    fn set_data(&mut self, new: RObject, ctx: &CommHandlerContext) -> anyhow::Result<bool> {
         // No change to the value, so we're done
-        if !changed {
+        if new.same_address(self.table.source()) {
+            return Ok(true);
+        }
+
+        // The binding was reassigned to an equal value, e.g. after reloading
+        // the same data. Keep the new object so the next check takes the fast
+        // path, but there is nothing to tell the frontend.
+        if new.identical(self.table.source()) {
+            self.table.set(new);
             return Ok(true);
         }
+        self.table.set(new);
 
         // Now we need to check to see if the schema has changed or just a data
         // value. Regenerate the schema.
@@ -379,6 +401,21 @@ impl RDataExplorer {
     }
 
     fn handle_rpc(
+        &mut self,
+        req: DataExplorerRequest,
+        ctx: &CommHandlerContext,
+    ) -> anyhow::Result<DataExplorerReply> {
+        match req {
+            DataExplorerRequest::Comm(req) => {
+                Ok(DataExplorerReply::Comm(self.handle_comm_rpc(req, ctx)?))
+            },
+            DataExplorerRequest::Ext(req) => {
+                Ok(DataExplorerReply::Ext(self.handle_ext_rpc(req, ctx)?))
+            },
+        }
+    }
+
+    fn handle_comm_rpc(
         &mut self,
         req: DataExplorerBackendRequest,
         ctx: &CommHandlerContext,
@@ -496,6 +533,74 @@ impl RDataExplorer {
             },
         }
     }
+
+    fn handle_ext_rpc(
+        &mut self,
+        req: DataExplorerExtBackendRequest,
+        ctx: &CommHandlerContext,
+    ) -> anyhow::Result<DataExplorerExtBackendReply> {
+        match req {
+            DataExplorerExtBackendRequest::ExportDataSelectionRds(
+                ExportDataSelectionRdsParams { selection, path },
+            ) => {
+                export_selection::export_selection_rds(
+                    self.table.get().sexp,
+                    &self.view_indices,
+                    selection,
+                    &path,
+                )?;
+                Ok(DataExplorerExtBackendReply::ExportDataSelectionRdsReply(
+                    ExportedFile { path },
+                ))
+            },
+
+            DataExplorerExtBackendRequest::SetDataObject(SetDataObjectParams { name }) => {
+                self.set_data_object(name, ctx)?;
+                Ok(DataExplorerExtBackendReply::SetDataObjectReply())
+            },
+        }
+    }
+
+    /// Show the object bound to `name` in the global environment instead of
+    /// the current one, and follow that binding from now on
+    fn set_data_object(&mut self, name: String, ctx: &CommHandlerContext) -> anyhow::Result<()> {
+        let env = RObject::new(R_ENVS.global);
+
+        let data = unsafe {
+            let data = Rf_findVarInFrame(env.sexp, r_symbol!(name));
+            if data == R_UnboundValue {
+                return Err(anyhow!("Can't find object `{name}`"));
+            }
+            data
+        };
+
+        // As in `update()`, never force a promise from a comm request
+        let data = if r_is_promise(data) {
+            if !r_promise_is_forced(data) {
+                return Err(anyhow!("Can't show `{name}`: it hasn't been evaluated yet"));
+            }
+            r_promise_value(data)
+        } else {
+            data
+        };
+
+        // Check the object up front so the current one stays on display if
+        // the new one can't be shown
+        if table_kind(data).is_none() {
+            return Err(anyhow!(
+                "Can't show `{name}`: unsupported type for the data viewer"
+            ));
+        }
+
+        if !self.set_data(RObject::new(data), ctx)? {
+            return Err(anyhow!("Can't show `{name}` in the data viewer"));
+        }
+
+        self.title = name.clone();
+        self.binding = Some(DataObjectEnvInfo { name, env });
+
+        Ok(())
+    }
 }
 
 impl CommHandler for RDataExplorer {
@@ -529,23 +634,18 @@ impl RDataExplorer {
         unsafe {
             let table = table.clone();
 
-            let Some(kind) = table_kind(table.sexp) else {
+            if table_kind(table.sexp).is_none() {
                 return Err(anyhow!("Unsupported type for the data viewer"));
-            };
+            }
 
             // `DataFrame::n_row()` will materialize duckplyr compact row names, but we
             // are ok with that for the data explorer and don't provide a hook to opt out.
-            let (n_row, n_col, column_names) = match kind {
-                TableKind::Dataframe => (
-                    harp::DataFrame::n_row(table.sexp)?,
-                    harp::DataFrame::n_col(table.sexp)?,
-                    ColumnNames::from_data_frame(table.sexp)?,
-                ),
-                TableKind::Matrix => {
-                    let (n_row, n_col) = harp::Matrix::dim(table.sexp)?;
-                    (n_row, n_col, ColumnNames::from_matrix(table.sexp)?)
-                },
-            };
+            let TableInfo {
+                kind,
+                n_row,
+                n_col,
+                col_names: column_names,
+            } = table_info(table.sexp)?;
 
             let mut column_schemas = Vec::<ColumnSchema>::new();
             for i in 0..(n_col as isize) {
@@ -23,6 +23,7 @@ use harp::exec::RFunctionExt;
 use harp::object::RObject;
 use harp::utils::r_names2;
 use harp::vector::CharacterVector;
+use harp::vector::LogicalVector;
 use harp::vector::Vector;
 use libr::SEXP;
 use stdext::unwrap;
@@ -55,6 +56,9 @@ pub fn summary_stats(
         },
         ColumnDisplayType::Date => stats.date_stats = Some(summary_stats_date(column)?),
         ColumnDisplayType::Datetime => stats.datetime_stats = Some(summary_stats_datetime(column)?),
+        // Complex and list-columns only report counts, which are computed by
+        // the null count profile
+        ColumnDisplayType::Object => {},
         _ => {
             return Err(anyhow::anyhow!("Unkown type"));
         },
@@ -129,12 +133,11 @@ fn summary_stats_string(column: SEXP) -> anyhow::Result<SummaryStatsString> {
 }
 
 fn summary_stats_boolean(column: SEXP) -> anyhow::Result<SummaryStatsBoolean> {
-    let stats = call_summary_fn("summary_stats_boolean", column)?;
-    let r_stats: HashMap<String, i32> = stats.try_into()?;
+    let column = LogicalVector::new(column)?;
 
     Ok(SummaryStatsBoolean {
-        true_count: get_stat(&r_stats, "true_count")?,
-        false_count: get_stat(&r_stats, "false_count")?,
+        true_count: column.count_true() as i64,
+        false_count: column.count_false() as i64,
     })
 }
 
@@ -167,7 +170,7 @@ fn summary_stats_datetime(column: SEXP) -> anyhow::Result<SummaryStatsDatetime>
 
     let timezone: Option<String> = RFunction::from("summary_stats_get_timezone")
         .add(column)
-        .call_in(ARK_ENVS.positron_ns)?
+        .call_in_sandbox(ARK_ENVS.positron_ns)?
         .try_into()?;
 
     Ok(SummaryStatsDatetime {
@@ -183,7 +186,7 @@ fn summary_stats_datetime(column: SEXP) -> anyhow::Result<SummaryStatsDatetime>
 fn call_summary_fn(function: &str, column: SEXP) -> anyhow::Result<RObject> {
     Ok(RFunction::from(function)
         .add(column)
-        .call_in(ARK_ENVS.positron_ns)?)
+        .call_in_sandbox(ARK_ENVS.positron_ns)?)
 }
 
 fn empty_column_summary_stats() -> data_explorer_comm::ColumnSummaryStats {
@@ -409,4 +412,35 @@ mod tests {
             assert_eq!(stats, stats_factor);
         })
     }
+
+    #[test]
+    fn test_integer64_summary() {
+        crate::r_task(|| {
+            if !package_is_installed("bit64") {
+                return;
+            }
+
+            let column =
+                harp::parse_eval_base("bit64::as.integer64(c(1, 2, 3, 4, 10000000, NA))").unwrap();
+
+            // Recognised as integers once bit64 is loaded
+            let display_type = crate::data_explorer::utils::display_type(column.sexp);
+            assert_eq!(display_type, ColumnDisplayType::Integer);
+
+            // Values are formatted as integers rather than as the doubles
+            // that hold their bits
+            let formatted = format_string(column.sexp, &default_options());
+            assert_eq!(formatted, vec!["1", "2", "3", "4", "10,000,000", "NA"]);
+
+            let stats = summary_stats(column.sexp, display_type, &default_options()).unwrap();
+            let expected = SummaryStatsNumber {
+                min_value: Some("1".to_string()),
+                max_value: Some("10000000".to_string()),
+                mean: Some("2,000,002.00".to_string()),
+                median: Some("3".to_string()),
+                stdev: Some("4,472,134.84".to_string()),
+            };
+            assert_eq!(stats.number_stats, Some(expected));
+        })
+    }
 }
@@ -5,20 +5,35 @@
 //
 //
 
+use harp::tbl_from_vector;
 use harp::RObject;
+use stdext::result::ResultExt;
 
-pub struct Table(RObject);
+pub struct Table {
+    /// The object as supplied, used to detect changes to the binding
+    source: RObject,
+    /// The object as a table. Named vectors and 1D arrays are converted to a
+    /// single column matrix.
+    data: RObject,
+}
 
 impl Table {
-    pub fn new(data: RObject) -> Self {
-        Self(data)
+    pub fn new(source: RObject) -> Self {
+        let data = tbl_from_vector(source.clone())
+            .log_err()
+            .unwrap_or_else(|| source.clone());
+        Self { source, data }
     }
 
     pub fn get(&self) -> &RObject {
-        &self.0
+        &self.data
+    }
+
+    pub fn source(&self) -> &RObject {
+        &self.source
     }
 
-    pub fn set(&mut self, data: RObject) {
-        self.0 = data;
+    pub fn set(&mut self, source: RObject) {
+        *self = Self::new(source);
     }
 }
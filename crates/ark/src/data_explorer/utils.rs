@@ -1,11 +1,13 @@
 use amalthea::comm::data_explorer_comm::ColumnDisplayType;
+use harp::attrib::class_of;
 use harp::exec::RFunction;
 use harp::exec::RFunctionExt;
 use harp::object::RObject;
-use harp::utils::r_inherits;
+use harp::utils::r_is_data_frame;
 use harp::utils::r_is_object;
 use harp::utils::r_is_s4;
 use harp::utils::r_typeof;
+use harp::vector::Integer64Vector;
 use libr::*;
 
 use crate::modules::ARK_ENVS;
@@ -47,53 +49,62 @@ pub fn display_type(x: SEXP) -> ColumnDisplayType {
         return ColumnDisplayType::Unknown;
     }
 
+    // List-columns don't support summary statistics or filters beyond counts
+    if is_list_column(x) {
+        return ColumnDisplayType::Object;
+    }
+
     if r_is_object(x) {
+        // Read the class vector once rather than walking it for each check
+        let class = class_of(x);
+        let inherits = |name: &str| class.iter().any(|cls| cls == name);
+
         // `haven_labelled` objects inherit from their internal data type
         // such as integer or character. We special case them here before
         // checking the internal types below.
-        if r_inherits(x, "haven_labelled") {
+        if inherits("haven_labelled") {
             return ColumnDisplayType::String;
         }
 
-        if r_inherits(x, "logical") {
+        if inherits("logical") {
             return ColumnDisplayType::Boolean;
         }
 
-        if r_inherits(x, "integer") {
+        // `bit64::integer64` is stored as a double but holds integers
+        if Integer64Vector::is_integer64(x) {
             return ColumnDisplayType::Integer;
         }
-        if r_inherits(x, "double") {
+
+        if inherits("integer") {
+            return ColumnDisplayType::Integer;
+        }
+        if inherits("double") {
             return ColumnDisplayType::Floating;
         }
-        if r_inherits(x, "complex") {
+        if inherits("complex") {
             return ColumnDisplayType::Floating;
         }
-        if r_inherits(x, "numeric") {
+        if inherits("numeric") {
             return ColumnDisplayType::Floating;
         }
 
-        if r_inherits(x, "character") {
+        if inherits("character") {
             return ColumnDisplayType::String;
         }
-        if r_inherits(x, "factor") {
+        if inherits("factor") {
             return ColumnDisplayType::String;
         }
 
-        if r_inherits(x, "Date") {
+        if inherits("Date") {
             return ColumnDisplayType::Date;
         }
-        if r_inherits(x, "POSIXct") {
+        if inherits("POSIXct") {
             return ColumnDisplayType::Datetime;
         }
-        if r_inherits(x, "POSIXlt") {
+        if inherits("POSIXlt") {
             return ColumnDisplayType::Datetime;
         }
 
-        // TODO: vctrs's list_of
-        if r_inherits(x, "list") {
-            return ColumnDisplayType::Unknown;
-        }
-
         // Catch-all, including for data frame
         return ColumnDisplayType::Unknown;
     }
@@ -102,9 +113,26 @@ pub fn display_type(x: SEXP) -> ColumnDisplayType {
         LGLSXP => ColumnDisplayType::Boolean,
         INTSXP => ColumnDisplayType::Integer,
         REALSXP => ColumnDisplayType::Floating,
-        CPLXSXP => ColumnDisplayType::Floating,
+        // Complex numbers don't support numeric summaries, histograms, or filters
+        CPLXSXP => ColumnDisplayType::Object,
         STRSXP => ColumnDisplayType::String,
-        VECSXP => ColumnDisplayType::Unknown,
         _ => ColumnDisplayType::Unknown,
     }
 }
+
+/// Is `x` a list-column, e.g. from `tidyr::nest()`?
+///
+/// This includes bare lists and list subclasses such as vctrs's `list_of` or
+/// lists wrapped in `I()`, but not data frame columns or list-based classes
+/// like `vctrs_rcrd`.
+fn is_list_column(x: SEXP) -> bool {
+    if r_typeof(x) != VECSXP || r_is_data_frame(x) {
+        return false;
+    }
+
+    if !r_is_object(x) {
+        return true;
+    }
+
+    class_of(x).iter().any(|cls| cls == "list" || cls == "AsIs")
+}
@@ -0,0 +1,104 @@
+//
+// display.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use amalthea::socket::iopub::IOPubMessage;
+use amalthea::wire::clear_output::ClearOutput;
+use amalthea::wire::display_data::DisplayData;
+use amalthea::wire::update_display_data::TransientValue;
+use amalthea::wire::update_display_data::UpdateDisplayData;
+use harp::object::RObject;
+use harp::utils::r_is_null;
+use libr::R_NilValue;
+use libr::SEXP;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::console::Console;
+
+/// Emit rich output from R.
+///
+/// `data` is a named list mapping MIME types to their contents, and
+/// `metadata` an optional named list of metadata. When `display_id` is
+/// supplied, the display can later be updated in place (e.g. a progress bar)
+/// by emitting an `update_display_data` message with the same `display_id`
+/// and `update = TRUE`.
+#[harp::register]
+pub unsafe extern "C-unwind" fn ps_display_data(
+    data: SEXP,
+    metadata: SEXP,
+    display_id: SEXP,
+    update: SEXP,
+) -> anyhow::Result<SEXP> {
+    let data = Value::try_from(RObject::view(data))?;
+
+    let metadata = if r_is_null(metadata) {
+        json!({})
+    } else {
+        Value::try_from(RObject::view(metadata))?
+    };
+
+    let display_id: Option<String> = if r_is_null(display_id) {
+        None
+    } else {
+        RObject::view(display_id).try_into()?
+    };
+
+    let update: bool = RObject::view(update).try_into()?;
+
+    let message = match (update, display_id) {
+        (true, Some(display_id)) => IOPubMessage::UpdateDisplayData(UpdateDisplayData {
+            data,
+            metadata,
+            transient: TransientValue {
+                display_id,
+                data: None,
+            },
+        }),
+        (true, None) => {
+            return Err(anyhow::anyhow!(
+                "A `display_id` is required to update a display"
+            ));
+        },
+        (false, display_id) => {
+            let transient = match display_id {
+                Some(display_id) => serde_json::to_value(TransientValue {
+                    display_id,
+                    data: None,
+                })?,
+                None => json!({}),
+            };
+            IOPubMessage::DisplayData(DisplayData {
+                data,
+                metadata,
+                transient,
+            })
+        },
+    };
+
+    Console::get()
+        .iopub_tx()
+        .send(message)
+        .map_err(|err| anyhow::anyhow!("Failed to emit display data: {err}"))?;
+
+    Ok(R_NilValue)
+}
+
+/// Clear the output of the current cell.
+///
+/// With `wait = TRUE`, the output is only cleared once new output replaces
+/// it, which avoids flickering when redrawing e.g. a progress display.
+#[harp::register]
+pub unsafe extern "C-unwind" fn ps_clear_output(wait: SEXP) -> anyhow::Result<SEXP> {
+    let wait: bool = RObject::view(wait).try_into()?;
+
+    Console::get()
+        .iopub_tx()
+        .send(IOPubMessage::ClearOutput(ClearOutput { wait }))
+        .map_err(|err| anyhow::anyhow!("Failed to emit clear output: {err}"))?;
+
+    Ok(R_NilValue)
+}
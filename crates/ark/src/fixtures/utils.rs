@@ -12,7 +12,6 @@ use tree_sitter::Point;
 #[cfg(feature = "testing")]
 use crate::console::Console;
 use crate::modules;
-use crate::modules::ARK_ENVS;
 
 static INIT: Once = Once::new();
 
@@ -65,13 +64,7 @@ pub fn point_and_offset_from_cursor(x: &str, cursor: u8) -> (String, Point, usiz
 }
 
 pub fn package_is_installed(package: &str) -> bool {
-    harp::parse_eval0(
-        format!(".ps.is_installed('{package}')").as_str(),
-        ARK_ENVS.positron_ns,
-    )
-    .unwrap()
-    .try_into()
-    .unwrap()
+    harp::utils::r_is_installed(package)
 }
 
 #[cfg(test)]
@@ -5,5 +5,6 @@
 //
 //
 
+pub(crate) mod help_links;
 pub mod message;
 pub mod r_help;
@@ -0,0 +1,29 @@
+//
+// help_links.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use amalthea::wire::help_link::HelpLink;
+use harp::exec::RFunction;
+use harp::exec::RFunctionExt;
+use harp::object::RObject;
+
+use crate::modules::ARK_ENVS;
+
+/// Links shown in the Help menu of Jupyter frontends: the R manuals, CRAN,
+/// and the websites of attached packages. Computed once at startup and sent
+/// with the kernel info reply.
+pub(crate) fn kernel_help_links() -> anyhow::Result<Vec<HelpLink>> {
+    let links = RFunction::from("kernel_help_links").call_in(ARK_ENVS.positron_ns)?;
+
+    let text: Vec<String> = RObject::view(harp::list_get(links.sexp, 0)).try_into()?;
+    let url: Vec<String> = RObject::view(harp::list_get(links.sexp, 1)).try_into()?;
+
+    Ok(text
+        .into_iter()
+        .zip(url)
+        .map(|(text, url)| HelpLink { text, url })
+        .collect())
+}
@@ -61,7 +61,7 @@ impl RHelp {
         url.starts_with(prefix.as_str())
     }
 
-    fn help_url_prefix(port: u16) -> String {
+    pub(crate) fn help_url_prefix(port: u16) -> String {
         format!("http://127.0.0.1:{port}/")
     }
 
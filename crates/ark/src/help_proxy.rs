@@ -5,6 +5,7 @@
 //
 //
 
+use std::net::SocketAddr;
 use std::time::Duration;
 
 use actix_web::get;
@@ -28,6 +29,7 @@ use stdext::spawn;
 use stdext::unwrap;
 use url::Url;
 
+use crate::network::server_ip_address;
 use crate::r_task;
 
 // Embed `resources/help/` which is where replacement resources can be found.
@@ -62,7 +64,7 @@ pub fn start(target_port: u16) -> anyhow::Result<(u16, ProxyHandle)> {
 
     spawn!("ark-help-proxy", move || -> anyhow::Result<()> {
         // Bind to port `0` to allow the OS to assign the port, avoiding any race conditions
-        let address = "127.0.0.1:0";
+        let address = SocketAddr::new(server_ip_address("127.0.0.1")?, 0);
 
         let server = HttpServer::new(move || {
             App::new()
@@ -84,7 +86,7 @@ pub fn start(target_port: u16) -> anyhow::Result<(u16, ProxyHandle)> {
 
         // Send back the finalized port address
         let port = address.port();
-        log::info!("Help proxy server listening on port {port}");
+        log::info!("Help proxy server listening on {address}");
         port_tx.send(port)?;
 
         // Create a single-threaded Tokio runtime to spare stack memory. The
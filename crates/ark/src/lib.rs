@@ -20,6 +20,7 @@ pub mod coordinates;
 pub mod dap;
 pub mod data_explorer;
 pub mod debug;
+pub mod display;
 
 pub mod fixtures;
 pub mod help;
@@ -31,6 +32,7 @@ pub mod lsp;
 pub mod methods;
 pub mod modules;
 pub mod modules_utils;
+pub mod network;
 pub mod plots;
 pub mod r_task;
 pub mod repos;
@@ -52,6 +54,7 @@ pub mod variables;
 pub mod version;
 pub mod view;
 pub mod viewer;
+pub mod watchdog;
 
 pub(crate) use r_task::r_task;
 
@@ -6,9 +6,12 @@
 //
 
 pub mod backend;
+pub mod call_hierarchy;
 pub mod capabilities;
 pub mod code_action;
+pub mod code_lens;
 pub mod comm;
+pub mod commands;
 pub mod completions;
 mod config;
 mod content_changes;
@@ -19,6 +22,7 @@ pub mod diagnostics_syntax;
 pub mod document_context;
 pub mod events;
 pub mod folding_range;
+pub mod formatting;
 pub mod goto_definition;
 pub mod handler;
 pub mod handlers;
@@ -36,12 +40,14 @@ pub mod sources;
 pub mod find_references;
 pub mod rename;
 pub mod selection_range;
+pub mod semantic_tokens;
 pub mod signature_help;
 pub mod state;
 pub mod state_handlers;
 pub mod statement_range;
 pub mod symbols;
 pub mod traits;
+pub mod type_definition;
 pub mod util;
 
 #[cfg(test)]
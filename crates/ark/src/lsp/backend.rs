@@ -7,6 +7,7 @@
 
 #![allow(deprecated)]
 
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -26,6 +27,8 @@ use tower_lsp::jsonrpc;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::request::GotoImplementationParams;
 use tower_lsp::lsp_types::request::GotoImplementationResponse;
+use tower_lsp::lsp_types::request::GotoTypeDefinitionParams;
+use tower_lsp::lsp_types::request::GotoTypeDefinitionResponse;
 use tower_lsp::lsp_types::FoldingRange;
 use tower_lsp::lsp_types::SelectionRange;
 use tower_lsp::lsp_types::*;
@@ -53,7 +56,9 @@ use crate::lsp::main_loop::TokioUnboundedSender;
 use crate::lsp::statement_range;
 use crate::lsp::statement_range::StatementRangeParams;
 use crate::lsp::statement_range::StatementRangeResponse;
+use crate::network::server_ip_address;
 use crate::r_task;
+use crate::request::RRequest;
 
 // This enum is useful for two things. First it allows us to distinguish a
 // normal request failure from a crash. In the latter case we send a
@@ -167,12 +172,19 @@ pub(crate) enum LspRequest {
     SignatureHelp(SignatureHelpParams),
     GotoDefinition(GotoDefinitionParams),
     GotoImplementation(GotoImplementationParams),
+    GotoTypeDefinition(GotoTypeDefinitionParams),
+    PrepareCallHierarchy(CallHierarchyPrepareParams),
+    IncomingCalls(CallHierarchyIncomingCallsParams),
+    OutgoingCalls(CallHierarchyOutgoingCallsParams),
     SelectionRange(SelectionRangeParams),
+    SemanticTokensFull(SemanticTokensParams),
+    CodeLens(CodeLensParams),
     References(ReferenceParams),
     PrepareRename(TextDocumentPositionParams),
     Rename(RenameParams),
     StatementRange(StatementRangeParams),
     HelpTopic(HelpTopicParams),
+    Formatting(DocumentFormattingParams),
     OnTypeFormatting(DocumentOnTypeFormattingParams),
     CodeAction(CodeActionParams),
     VirtualDocument(VirtualDocumentParams),
@@ -193,12 +205,19 @@ pub(crate) enum LspResponse {
     SignatureHelp(Option<SignatureHelp>),
     GotoDefinition(Option<GotoDefinitionResponse>),
     GotoImplementation(Option<GotoImplementationResponse>),
+    GotoTypeDefinition(Option<GotoTypeDefinitionResponse>),
+    PrepareCallHierarchy(Option<Vec<CallHierarchyItem>>),
+    IncomingCalls(Option<Vec<CallHierarchyIncomingCall>>),
+    OutgoingCalls(Option<Vec<CallHierarchyOutgoingCall>>),
     SelectionRange(Option<Vec<SelectionRange>>),
+    SemanticTokensFull(Option<SemanticTokensResult>),
+    CodeLens(Option<Vec<CodeLens>>),
     References(Option<Vec<Location>>),
     PrepareRename(Option<PrepareRenameResponse>),
     Rename(Option<WorkspaceEdit>),
     StatementRange(Option<StatementRangeResponse>),
     HelpTopic(Option<HelpTopicResponse>),
+    Formatting(Option<Vec<TextEdit>>),
     OnTypeFormatting(Option<Vec<TextEdit>>),
     CodeAction(Option<CodeActionResponse>),
     VirtualDocument(VirtualDocumentResponse),
@@ -427,6 +446,50 @@ impl LanguageServer for Backend {
         )
     }
 
+    async fn goto_type_definition(
+        &self,
+        params: GotoTypeDefinitionParams,
+    ) -> Result<Option<GotoTypeDefinitionResponse>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::GotoTypeDefinition(params)).await,
+            LspResponse::GotoTypeDefinition
+        )
+    }
+
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<CallHierarchyItem>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::PrepareCallHierarchy(params)).await,
+            LspResponse::PrepareCallHierarchy
+        )
+    }
+
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::IncomingCalls(params)).await,
+            LspResponse::IncomingCalls
+        )
+    }
+
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::OutgoingCalls(params)).await,
+            LspResponse::OutgoingCalls
+        )
+    }
+
     async fn selection_range(
         &self,
         params: SelectionRangeParams,
@@ -438,6 +501,25 @@ impl LanguageServer for Backend {
         )
     }
 
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::SemanticTokensFull(params)).await,
+            LspResponse::SemanticTokensFull
+        )
+    }
+
+    async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::CodeLens(params)).await,
+            LspResponse::CodeLens
+        )
+    }
+
     async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
         cast_response!(
             self,
@@ -465,6 +547,14 @@ impl LanguageServer for Backend {
         )
     }
 
+    async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        cast_response!(
+            self,
+            self.request(LspRequest::Formatting(params)).await,
+            LspResponse::Formatting
+        )
+    }
+
     async fn on_type_formatting(
         &self,
         params: DocumentOnTypeFormattingParams,
@@ -556,12 +646,21 @@ pub(crate) fn start_lsp(
     server_start: ServerStartMessage,
     server_started_tx: Sender<ServerStartedMessage>,
     console_notification_tx: AsyncUnboundedSender<ConsoleNotification>,
+    r_request_tx: Sender<RRequest>,
 ) {
     runtime.block_on(async {
-        let ip_address = server_start.ip_address();
+        let ip_address = match server_ip_address(server_start.ip_address()) {
+            Ok(ip_address) => ip_address,
+            Err(err) => {
+                log::error!("LSP: Can't start server: {err:?}");
+                return;
+            },
+        };
 
         // Binding to port `0` to allow the OS to allocate a port for us to bind to
-        let listener = TcpListener::bind(format!("{ip_address}:0")).await.unwrap();
+        let listener = TcpListener::bind(SocketAddr::new(ip_address, 0))
+            .await
+            .unwrap();
 
         let address = match listener.local_addr() {
             Ok(address) => address,
@@ -574,7 +673,7 @@ pub(crate) fn start_lsp(
         // Get the OS allocated port
         let port = address.port();
 
-        log::trace!("LSP: Thread starting at address {ip_address}:{port}.");
+        log::info!("LSP: Thread starting at address {address}.");
 
         // Send the port back to `Shell` and eventually out to the frontend so it can connect
         server_started_tx
@@ -590,7 +689,12 @@ pub(crate) fn start_lsp(
         let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
 
         let init = |client: Client| {
-            let state = GlobalState::new(client.clone(), r_home, console_notification_tx);
+            let state = GlobalState::new(
+                client.clone(),
+                r_home,
+                console_notification_tx,
+                r_request_tx,
+            );
             let events_tx = state.events_tx();
 
             // Start main loop and hold onto the handle that keeps it alive
@@ -0,0 +1,202 @@
+//
+// call_hierarchy.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use aether_path::FilePath;
+use oak_db::Db;
+use oak_db::File;
+use tower_lsp::lsp_types::CallHierarchyIncomingCall;
+use tower_lsp::lsp_types::CallHierarchyIncomingCallsParams;
+use tower_lsp::lsp_types::CallHierarchyItem;
+use tower_lsp::lsp_types::CallHierarchyOutgoingCall;
+use tower_lsp::lsp_types::CallHierarchyOutgoingCallsParams;
+use tower_lsp::lsp_types::CallHierarchyPrepareParams;
+use tower_lsp::lsp_types::Range;
+use tower_lsp::lsp_types::SymbolKind;
+use tower_lsp::lsp_types::Url;
+
+use crate::lsp::db::FileArkExt;
+use crate::lsp::indexer;
+use crate::lsp::indexer::IndexEntry;
+use crate::lsp::indexer::IndexRange;
+use crate::lsp::open_file::tree_sitter_point_from_lsp_position;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::NodeTypeExt;
+
+/// Resolve the function named at the cursor, either at its definition or at
+/// one of its call sites, to a workspace function.
+pub(crate) fn prepare_call_hierarchy(
+    params: CallHierarchyPrepareParams,
+    state: &WorldState,
+) -> anyhow::Result<Option<Vec<CallHierarchyItem>>> {
+    let uri = &params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let db = &state.db;
+    let encoding = state.config.position_encoding;
+
+    let Some(file) = db.file_by_path(&FilePath::from_url(uri)) else {
+        return Ok(None);
+    };
+
+    let contents = file.source_text(db);
+    let point = tree_sitter_point_from_lsp_position(position, file.line_index(db), encoding)?;
+    let Some(node) = file
+        .tree_sitter(db)
+        .root_node()
+        .find_smallest_spanning_node(point)
+    else {
+        return Ok(None);
+    };
+
+    if !node.is_identifier() {
+        return Ok(None);
+    }
+    let name = node.node_as_str(contents.as_str())?;
+
+    let Some((def_file, entry)) = indexer::find_function(db, name) else {
+        return Ok(None);
+    };
+
+    Ok(function_item(state, def_file, &entry).map(|item| vec![item]))
+}
+
+/// Find the workspace functions that call `params.item`. Calls made at top
+/// level aren't reported since they have no enclosing function.
+pub(crate) fn incoming_calls(
+    params: CallHierarchyIncomingCallsParams,
+    state: &WorldState,
+) -> anyhow::Result<Option<Vec<CallHierarchyIncomingCall>>> {
+    let name = params.item.name.as_str();
+    let db = &state.db;
+
+    // Call sites grouped by caller, in workspace order
+    let mut callers: Vec<(File, String, Vec<IndexRange>)> = Vec::new();
+
+    indexer::map_calls(db, |file, call| {
+        if call.callee != name {
+            return;
+        }
+        let Some(caller) = &call.caller else {
+            return;
+        };
+        match callers
+            .iter_mut()
+            .find(|(caller_file, caller_name, _)| *caller_file == file && caller_name == caller)
+        {
+            Some((_, _, ranges)) => ranges.push(call.range),
+            None => callers.push((file, caller.clone(), vec![call.range])),
+        }
+    });
+
+    let calls = callers
+        .into_iter()
+        .filter_map(|(file, caller, ranges)| {
+            let entry = indexer::file_function(db, file, &caller)?;
+            Some(CallHierarchyIncomingCall {
+                from: function_item(state, file, &entry)?,
+                from_ranges: lsp_ranges(state, file, &ranges),
+            })
+        })
+        .collect();
+
+    Ok(Some(calls))
+}
+
+/// Find the functions called in the body of `params.item`. Functions that
+/// aren't defined in the workspace, such as library functions, are reported
+/// as leaves located at their first call site.
+pub(crate) fn outgoing_calls(
+    params: CallHierarchyOutgoingCallsParams,
+    state: &WorldState,
+) -> anyhow::Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+    let item = &params.item;
+    let db = &state.db;
+
+    let Some(file) = db.file_by_path(&FilePath::from_url(&item.uri)) else {
+        return Ok(None);
+    };
+
+    // Call sites grouped by callee, in document order
+    let mut callees: Vec<(&str, Vec<IndexRange>)> = Vec::new();
+
+    for call in indexer::file_calls(db, file) {
+        if call.caller.as_deref() != Some(item.name.as_str()) {
+            continue;
+        }
+        match callees
+            .iter_mut()
+            .find(|(callee, _)| *callee == call.callee)
+        {
+            Some((_, ranges)) => ranges.push(call.range),
+            None => callees.push((&call.callee, vec![call.range])),
+        }
+    }
+
+    let calls = callees
+        .into_iter()
+        .filter_map(|(callee, ranges)| {
+            let from_ranges = lsp_ranges(state, file, &ranges);
+
+            let to = match indexer::find_function(db, callee) {
+                Some((callee_file, entry)) => function_item(state, callee_file, &entry)?,
+                None => leaf_item(callee, item.uri.clone(), *from_ranges.first()?),
+            };
+
+            Some(CallHierarchyOutgoingCall { to, from_ranges })
+        })
+        .collect();
+
+    Ok(Some(calls))
+}
+
+fn function_item(state: &WorldState, file: File, entry: &IndexEntry) -> Option<CallHierarchyItem> {
+    let range = indexer::index_range_to_lsp_range(
+        &state.db,
+        file,
+        entry.range,
+        state.config.position_encoding,
+    )?;
+
+    Some(CallHierarchyItem {
+        name: entry.key.clone(),
+        kind: SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: state.wire_url(file),
+        range,
+        selection_range: range,
+        data: None,
+    })
+}
+
+fn leaf_item(name: &str, uri: Url, range: Range) -> CallHierarchyItem {
+    CallHierarchyItem {
+        name: name.to_string(),
+        kind: SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri,
+        range,
+        selection_range: range,
+        data: None,
+    }
+}
+
+fn lsp_ranges(state: &WorldState, file: File, ranges: &[IndexRange]) -> Vec<Range> {
+    ranges
+        .iter()
+        .filter_map(|range| {
+            indexer::index_range_to_lsp_range(
+                &state.db,
+                file,
+                *range,
+                state.config.position_encoding,
+            )
+        })
+        .collect()
+}
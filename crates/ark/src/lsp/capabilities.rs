@@ -17,6 +17,7 @@ pub(crate) struct Capabilities {
     dynamic_registration_for_did_change_configuration: bool,
     code_action_literal_support: bool,
     workspace_edit_document_changes: bool,
+    completion_snippet_support: bool,
 }
 
 impl Capabilities {
@@ -46,10 +47,19 @@ impl Capabilities {
             .and_then(|workspace_edit| workspace_edit.document_changes)
             .is_some_and(|document_changes| document_changes);
 
+        let completion_snippet_support = client_capabilities
+            .text_document
+            .as_ref()
+            .and_then(|text_document| text_document.completion.as_ref())
+            .and_then(|completion| completion.completion_item.as_ref())
+            .and_then(|completion_item| completion_item.snippet_support)
+            .unwrap_or(false);
+
         Self {
             dynamic_registration_for_did_change_configuration,
             code_action_literal_support,
             workspace_edit_document_changes,
+            completion_snippet_support,
         }
     }
 
@@ -85,16 +95,21 @@ impl Capabilities {
         self
     }
 
+    pub(crate) fn completion_snippet_support(&self) -> bool {
+        self.completion_snippet_support
+    }
+
     pub(crate) fn code_action_provider_capability(&self) -> Option<CodeActionProviderCapability> {
         if !self.code_action_literal_support() {
             return None;
         }
 
-        // Currently we only support documentation generating code actions, which don't
-        // map to an existing kind. rust-analyzer maps them to `EMPTY`, so we follow suit.
-        // Currently no code actions require delayed resolution.
+        // Documentation generating code actions don't map to an existing kind.
+        // rust-analyzer maps them to `EMPTY`, so we follow suit. Quick fixes
+        // repair problems reported by diagnostics. Currently no code actions
+        // require delayed resolution.
         Some(CodeActionProviderCapability::Options(CodeActionOptions {
-            code_action_kinds: Some(vec![CodeActionKind::EMPTY]),
+            code_action_kinds: Some(vec![CodeActionKind::EMPTY, CodeActionKind::QUICKFIX]),
             work_done_progress_options: WorkDoneProgressOptions::default(),
             resolve_provider: Some(false),
         }))
@@ -22,6 +22,7 @@ use crate::lsp::db::ArkDb;
 use crate::lsp::open_file::lsp_position_from_tree_sitter_point;
 use crate::lsp::open_file::OpenFile;
 
+mod assignment_in_conditional;
 mod roxygen;
 
 /// A code action computed from analysis, in tree-sitter coordinates and without
@@ -59,6 +60,15 @@ impl CodeActionTextEdit {
             new_text,
         }
     }
+
+    /// A replacement of the text between `start` and `end` with `new_text`.
+    pub(crate) fn replacement(start: Point, end: Point, new_text: String) -> Self {
+        Self {
+            start,
+            end,
+            new_text,
+        }
+    }
 }
 
 /// Accumulates the code actions for a request. Holds analysis-layer edits in
@@ -81,6 +91,10 @@ pub(crate) fn code_actions(
         actions.add_action(action);
     }
 
+    if let Some(action) = assignment_in_conditional::to_code_action(db, file, range, capabilities) {
+        actions.add_action(action);
+    }
+
     actions
 }
 
@@ -0,0 +1,135 @@
+//
+// assignment_in_conditional.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use oak_db::File;
+use tower_lsp::lsp_types;
+
+use crate::lsp::capabilities::Capabilities;
+use crate::lsp::code_action::CodeActionEdit;
+use crate::lsp::code_action::CodeActionTextEdit;
+use crate::lsp::db::ArkDb;
+use crate::lsp::db::FileArkExt;
+use crate::lsp::diagnostics::is_assignment_in_conditional;
+
+/// Build the "Replace with `==`" quick fix for `range`, if the cursor is
+/// within an assignment used as the condition of an `if` or `while`
+/// statement, e.g. `if (x = 1)`.
+pub(crate) fn to_code_action(
+    db: &dyn ArkDb,
+    file: File,
+    range: tree_sitter::Range,
+    capabilities: &Capabilities,
+) -> Option<CodeActionEdit> {
+    if !capabilities.code_action_literal_support() {
+        return None;
+    }
+
+    let start = range.start_point;
+
+    let mut node = file
+        .tree_sitter(db)
+        .root_node()
+        .descendant_for_point_range(start, start)?;
+
+    // Find the nearest enclosing assignment used as a condition
+    while !is_assignment_in_conditional(&node) {
+        node = node.parent()?;
+    }
+
+    let operator = node.child_by_field_name("operator")?;
+
+    Some(CodeActionEdit::new(
+        "Replace with `==`".to_string(),
+        lsp_types::CodeActionKind::QUICKFIX,
+        vec![CodeActionTextEdit::replacement(
+            operator.start_position(),
+            operator.end_position(),
+            "==".to_string(),
+        )],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use aether_lsp_utils::proto::PositionEncoding;
+    use tower_lsp::lsp_types;
+    use tower_lsp::lsp_types::CodeActionOrCommand;
+    use tree_sitter::Range;
+
+    use crate::fixtures::point_and_offset_from_cursor;
+    use crate::lsp::capabilities::Capabilities;
+    use crate::lsp::code_action::code_actions;
+    use crate::lsp::open_file::test_open_file;
+
+    const ENCODING: PositionEncoding =
+        PositionEncoding::Wide(biome_line_index::WideEncoding::Utf16);
+
+    fn quick_fixes(text: &str) -> Vec<lsp_types::TextEdit> {
+        let capabilities = Capabilities::default()
+            .with_code_action_literal_support(true)
+            .with_workspace_edit_document_changes(false);
+
+        let (text, point, offset) = point_and_offset_from_cursor(text, b'@');
+        let (db, open_file) = test_open_file(&text);
+
+        let range = Range {
+            start_byte: offset,
+            end_byte: offset,
+            start_point: point,
+            end_point: point,
+        };
+
+        code_actions(&db, open_file.file(), range, &capabilities)
+            .into_response(&db, &open_file, ENCODING, &capabilities)
+            .into_iter()
+            .filter_map(|action| match action {
+                CodeActionOrCommand::CodeAction(action)
+                    if action.kind == Some(lsp_types::CodeActionKind::QUICKFIX) =>
+                {
+                    Some(action)
+                },
+                _ => None,
+            })
+            .flat_map(|action| {
+                action
+                    .edit
+                    .unwrap()
+                    .changes
+                    .unwrap()
+                    .into_values()
+                    .flatten()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replaces_assignment_in_if_condition() {
+        let edits = quick_fixes("if (x <- @1) {}");
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].new_text, "==");
+        assert_eq!(edits[0].range.start, lsp_types::Position::new(0, 6));
+        assert_eq!(edits[0].range.end, lsp_types::Position::new(0, 8));
+
+        let edits = quick_fixes("while (@x = 1) {}");
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range.start, lsp_types::Position::new(0, 9));
+        assert_eq!(edits[0].range.end, lsp_types::Position::new(0, 10));
+    }
+
+    #[test]
+    fn test_no_quick_fix_outside_conditions() {
+        assert!(quick_fixes("if (x == @1) {}").is_empty());
+        assert!(quick_fixes("x <- @1").is_empty());
+        assert!(quick_fixes("if (x) { y <- @1 }").is_empty());
+    }
+
+    #[test]
+    fn test_no_quick_fix_for_nested_assignments() {
+        // Only assignments at the root of the condition are flagged
+        assert!(quick_fixes("if (f(x <- @1)) {}").is_empty());
+    }
+}
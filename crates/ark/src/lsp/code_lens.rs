@@ -0,0 +1,197 @@
+//
+// code_lens.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use aether_lsp_utils::proto::PositionEncoding;
+use oak_db::File;
+use serde::Deserialize;
+use serde::Serialize;
+use tower_lsp::lsp_types;
+use tower_lsp::lsp_types::CodeLens;
+use tower_lsp::lsp_types::Command;
+use tower_lsp::lsp_types::Url;
+use tree_sitter::Node;
+
+use crate::lsp::db::ArkDb;
+use crate::lsp::db::FileArkExt;
+use crate::lsp::open_file::lsp_range_from_tree_sitter_range;
+use crate::lsp::statement_range::expand_range_across_semicolons;
+use crate::treesitter::node_has_error_or_missing;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+
+/// Client command invoked by the "Run" lens above a top-level expression.
+/// Takes a single [CodeLensRunArgs] argument.
+pub static ARK_CODE_LENS_RUN_COMMAND: &str = "ark.codeLens.run";
+
+/// Client command invoked by the "Source function" lens above a top-level
+/// function definition. Takes a single [CodeLensRunArgs] argument.
+pub static ARK_CODE_LENS_SOURCE_FUNCTION_COMMAND: &str = "ark.codeLens.sourceFunction";
+
+/// ID of the dynamic registration of the code lens provider. Registered when
+/// `positron.r.codeLens.enable` is set, since only Positron implements the
+/// commands above.
+pub(crate) static CODE_LENS_REGISTRATION_ID: &str = "ark-code-lens";
+
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeLensRunArgs {
+    /// The document containing the expression.
+    pub uri: Url,
+    /// The document range the expression covers.
+    pub range: lsp_types::Range,
+    /// The code to execute in the console.
+    pub code: String,
+}
+
+/// Code lenses offering to run each top-level expression of a document
+///
+/// Runnable regions follow the statement range logic: expressions separated by
+/// `;` on the same line form a single region, and nothing is offered past the
+/// first top-level parse error.
+pub(crate) fn code_lenses(
+    db: &dyn ArkDb,
+    file: File,
+    uri: &Url,
+    encoding: PositionEncoding,
+) -> anyhow::Result<Vec<CodeLens>> {
+    let root = file.tree_sitter(db).root_node();
+    let contents = file.source_text(db).as_str();
+    let line_index = file.line_index(db);
+
+    let mut lenses = Vec::new();
+    let mut covered_until = 0;
+
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        // Past the first parse error the tree is unreliable, so we stop
+        // offering anything to run, as with statement ranges
+        if node_has_error_or_missing(&child) {
+            break;
+        }
+
+        // Skip comments, `;` separators, and expressions already covered by
+        // the previous region
+        if !child.is_named() || child.is_comment() || child.start_byte() < covered_until {
+            continue;
+        }
+
+        let range = expand_range_across_semicolons(child);
+        covered_until = range.end_byte;
+
+        let (title, command) = if is_function_assignment(&child) {
+            ("Source function", ARK_CODE_LENS_SOURCE_FUNCTION_COMMAND)
+        } else {
+            ("Run", ARK_CODE_LENS_RUN_COMMAND)
+        };
+
+        let code = contents[range.start_byte..range.end_byte].to_string();
+        let range = lsp_range_from_tree_sitter_range(range, line_index, encoding)?;
+
+        let args = CodeLensRunArgs {
+            uri: uri.clone(),
+            range,
+            code,
+        };
+
+        lenses.push(CodeLens {
+            range,
+            command: Some(Command {
+                title: String::from(title),
+                command: String::from(command),
+                arguments: Some(vec![serde_json::to_value(args)?]),
+            }),
+            data: None,
+        });
+    }
+
+    Ok(lenses)
+}
+
+fn is_function_assignment(node: &Node) -> bool {
+    let (NodeType::BinaryOperator(BinaryOperatorType::LeftAssignment) |
+    NodeType::BinaryOperator(BinaryOperatorType::EqualsAssignment)) = node.node_type()
+    else {
+        return false;
+    };
+
+    let (Some(lhs), Some(rhs)) = (
+        node.child_by_field_name("lhs"),
+        node.child_by_field_name("rhs"),
+    ) else {
+        return false;
+    };
+
+    lhs.is_identifier_or_string() && rhs.is_function_definition()
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp::lsp_types::Url;
+
+    use crate::lsp::code_lens::code_lenses;
+    use crate::lsp::code_lens::CodeLensRunArgs;
+    use crate::lsp::code_lens::ARK_CODE_LENS_RUN_COMMAND;
+    use crate::lsp::code_lens::ARK_CODE_LENS_SOURCE_FUNCTION_COMMAND;
+    use crate::lsp::open_file::test_open_file;
+
+    fn test_code_lenses(code: &str) -> Vec<(u32, String, CodeLensRunArgs)> {
+        let (db, file) = test_open_file(code);
+        let uri = Url::parse("file:///test.R").unwrap();
+        let encoding = crate::lsp::config::LspConfig::default().position_encoding;
+
+        code_lenses(&db, file.file(), &uri, encoding)
+            .unwrap()
+            .into_iter()
+            .map(|lens| {
+                let command = lens.command.unwrap();
+                let args = command.arguments.unwrap().pop().unwrap();
+                let args: CodeLensRunArgs = serde_json::from_value(args).unwrap();
+                (lens.range.start.line, command.command, args)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_code_lens_top_level_expressions() {
+        let code = "
+# A comment
+x <- 1
+
+f <- function(a) {
+  a + x
+}
+";
+        let lenses = test_code_lenses(code);
+        assert_eq!(lenses.len(), 2);
+
+        let (line, command, args) = &lenses[0];
+        assert_eq!(*line, 2);
+        assert_eq!(command, ARK_CODE_LENS_RUN_COMMAND);
+        assert_eq!(args.code, "x <- 1");
+
+        let (line, command, args) = &lenses[1];
+        assert_eq!(*line, 4);
+        assert_eq!(command, ARK_CODE_LENS_SOURCE_FUNCTION_COMMAND);
+        assert_eq!(args.code, "f <- function(a) {\n  a + x\n}");
+        assert_eq!(args.range.end.line, 6);
+    }
+
+    #[test]
+    fn test_code_lens_semicolons_and_parse_errors() {
+        // Expressions separated by `;` are run together
+        let lenses = test_code_lenses("1; 2\n3");
+        assert_eq!(lenses.len(), 2);
+        assert_eq!(lenses[0].2.code, "1; 2");
+        assert_eq!(lenses[1].0, 1);
+
+        // Nothing is offered past the first parse error
+        let lenses = test_code_lenses("1\nfoo(\n2");
+        assert_eq!(lenses.len(), 1);
+        assert_eq!(lenses[0].0, 0);
+    }
+}
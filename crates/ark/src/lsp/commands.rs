@@ -0,0 +1,104 @@
+//
+// commands.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use anyhow::anyhow;
+use crossbeam::channel::bounded;
+use crossbeam::channel::Sender;
+use harp::environment::R_ENVS;
+use harp::exec::RFunction;
+use harp::exec::RFunctionExt;
+use tower_lsp::jsonrpc;
+use tower_lsp::lsp_types::ExecuteCommandParams;
+
+use crate::lsp::backend::LspError;
+use crate::lsp::backend::LspResult;
+use crate::lsp::events::EVENTS;
+use crate::r_task;
+use crate::r_task::RTask;
+use crate::r_task::RTaskPriority;
+use crate::request::RRequest;
+
+/// Server command restarting the R session. Takes no arguments.
+///
+/// Takes the same path as a `shutdown_request` with `restart: true`: R exits
+/// once idle and it is up to the client to start a new kernel.
+pub static ARK_RESTART_R_COMMAND: &str = "ark.restartR";
+
+/// Server command removing all objects from the global environment, as with
+/// `rm(list = ls())`. Takes no arguments.
+pub static ARK_CLEAR_ENVIRONMENT_COMMAND: &str = "ark.clearEnvironment";
+
+/// The commands advertised in the `executeCommandProvider` capability
+pub(crate) fn server_commands() -> Vec<String> {
+    vec![
+        String::from(ARK_RESTART_R_COMMAND),
+        String::from(ARK_CLEAR_ENVIRONMENT_COMMAND),
+    ]
+}
+
+/// Runs a server command. Blocks until the command is done, so must be called
+/// off the LSP main loop.
+pub(crate) fn execute_command(
+    params: &ExecuteCommandParams,
+    r_request_tx: &Sender<RRequest>,
+) -> LspResult<()> {
+    let command = params.command.as_str();
+
+    if command == ARK_RESTART_R_COMMAND {
+        Ok(restart_r(r_request_tx)?)
+    } else if command == ARK_CLEAR_ENVIRONMENT_COMMAND {
+        Ok(clear_environment()?)
+    } else {
+        Err(LspError::JsonRpc(jsonrpc::Error::invalid_params(format!(
+            "Unknown command '{command}'"
+        ))))
+    }
+}
+
+fn restart_r(r_request_tx: &Sender<RRequest>) -> anyhow::Result<()> {
+    // Interrupt any ongoing computation so R becomes idle and handles the
+    // request, as on `shutdown_request`
+    crate::sys::control::handle_interrupt_request();
+
+    // Blocks until R is idle, which is fine as we're off the main loop
+    r_request_tx
+        .send(RRequest::Shutdown(true))
+        .map_err(|err| anyhow!("Can't send restart request: {err:?}"))
+}
+
+fn clear_environment() -> anyhow::Result<()> {
+    let (done_tx, done_rx) = bounded::<anyhow::Result<()>>(1);
+
+    // Only clear at top level. At a debugger prompt, the user is looking at a
+    // frame whose code might refer to global objects.
+    r_task::spawn(RTask::send_with_priority(
+        RTaskPriority::TopLevelIdle,
+        async move |_capture| {
+            let result = (|| -> anyhow::Result<()> {
+                let names = RFunction::new("base", "ls")
+                    .param("envir", R_ENVS.global)
+                    .call()?;
+
+                RFunction::new("base", "rm")
+                    .param("list", names)
+                    .param("envir", R_ENVS.global)
+                    .call()?;
+
+                Ok(())
+            })();
+
+            // Let the variables pane know it should refresh
+            EVENTS.environment_changed.emit(());
+
+            done_tx.send(result).ok();
+        },
+    ));
+
+    done_rx
+        .recv()
+        .map_err(|err| anyhow!("Clear environment task was dropped: {err:?}"))?
+}
@@ -18,6 +18,8 @@ use crate::treesitter::node_find_containing_call;
 pub(crate) struct CompletionContext<'a> {
     pub(crate) document_context: &'a DocumentContext<'a>,
     pub(crate) state: &'a WorldState,
+    /// Whether the client accepts placeholders in snippet completions
+    pub(crate) snippet_support: bool,
     pipe_root_cell: OnceCell<Option<PipeRoot>>,
     containing_call_cell: OnceCell<Option<Node<'a>>>,
     function_context_cell: OnceCell<anyhow::Result<FunctionContext>>,
@@ -28,12 +30,18 @@ impl<'a> CompletionContext<'a> {
         Self {
             document_context,
             state,
+            snippet_support: false,
             pipe_root_cell: OnceCell::new(),
             containing_call_cell: OnceCell::new(),
             function_context_cell: OnceCell::new(),
         }
     }
 
+    pub fn with_snippet_support(mut self, snippet_support: bool) -> Self {
+        self.snippet_support = snippet_support;
+        self
+    }
+
     pub fn pipe_root(&self) -> Option<PipeRoot> {
         let call_node = self.containing_call_node();
 
@@ -56,7 +64,12 @@ impl<'a> CompletionContext<'a> {
 
     pub fn function_context(&self) -> anyhow::Result<&FunctionContext> {
         self.function_context_cell
-            .get_or_init(|| FunctionContext::new(self.document_context))
+            .get_or_init(|| {
+                FunctionContext::new(self.document_context).map(|mut function_context| {
+                    function_context.argument_placeholders = self.snippet_support;
+                    function_context
+                })
+            })
             .as_ref()
             .map_err(|err| anyhow::anyhow!("{err:?}"))
     }
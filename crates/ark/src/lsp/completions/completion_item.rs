@@ -12,6 +12,7 @@ use harp::syntax::is_valid_symbol;
 use harp::syntax::sym_quote;
 use harp::syntax::sym_quote_invalid;
 use harp::utils::r_env_binding_is_active;
+use harp::utils::r_formals;
 use harp::utils::r_promise_force_with_rollback;
 use harp::utils::r_promise_is_forced;
 use harp::utils::r_promise_is_lazy_load_binding;
@@ -166,6 +167,7 @@ pub(super) unsafe fn completion_item_from_package(
 pub(super) fn completion_item_from_function(
     name: &str,
     package: Option<&str>,
+    required_arguments: &[String],
     function_context: &FunctionContext,
 ) -> anyhow::Result<CompletionItem> {
     let label = name.to_string();
@@ -218,7 +220,11 @@ pub(super) fn completion_item_from_function(
         match function_context.usage {
             FunctionRefUsage::Call => {
                 item.insert_text_format = Some(InsertTextFormat::SNIPPET);
-                item.insert_text = Some(format!("{insert_text}($0)"));
+                item.insert_text = if function_context.argument_placeholders {
+                    Some(call_snippet(&insert_text, required_arguments))
+                } else {
+                    Some(format!("{insert_text}($0)"))
+                };
                 item.command = Some(Command {
                     title: "Trigger Parameter Hints".to_string(),
                     command: "editor.action.triggerParameterHints".to_string(),
@@ -288,6 +294,30 @@ pub(super) fn completion_item_from_function(
     Ok(item)
 }
 
+/// Completion item for an export of a package that isn't attached. Accepting
+/// it inserts the `pkg::` qualified name. We can't tell the type of the
+/// object without loading the namespace, so we don't add parentheses.
+pub(super) fn completion_item_from_package_export(
+    name: &str,
+    package: &str,
+) -> anyhow::Result<CompletionItem> {
+    let mut item = completion_item(name, CompletionData::Function {
+        name: name.to_string(),
+        package: Some(package.to_string()),
+    })?;
+
+    item.kind = Some(CompletionItemKind::FUNCTION);
+    item.label_details = Some(item_details(Some(package)));
+
+    item.insert_text_format = Some(InsertTextFormat::PLAIN_TEXT);
+    item.insert_text = Some(format!("{package}::{}", sym_quote_invalid(name)));
+
+    // Filter on the bare name, as typed by the user
+    item.filter_text = Some(name.to_string());
+
+    Ok(item)
+}
+
 fn item_details(package: Option<&str>) -> CompletionItemLabelDetails {
     let description = package.map(|p| {
         // Environments from the search path often have a "package:" prefix.
@@ -357,7 +387,12 @@ pub(super) unsafe fn completion_item_from_object(
     // In other words, when creating a completion item for these functions,
     // we should also figure out where we can receive the help from.
     if Rf_isFunction(object) != 0 {
-        return completion_item_from_function(name, package, function_context);
+        let required_arguments = if function_context.argument_placeholders {
+            required_arguments(object)
+        } else {
+            Vec::new()
+        };
+        return completion_item_from_function(name, package, &required_arguments, function_context);
     }
 
     let mut item = completion_item(name, CompletionData::Object {
@@ -374,6 +409,53 @@ pub(super) unsafe fn completion_item_from_object(
     Ok(item)
 }
 
+/// Names of the arguments of `object` that have no default value, excluding
+/// `...`. Returns an empty vector when the formals can't be determined.
+fn required_arguments(object: SEXP) -> Vec<String> {
+    let arguments = match r_formals(object) {
+        Ok(arguments) => arguments,
+        Err(err) => {
+            log::trace!("Can't get formals for completion item: {err:?}");
+            return Vec::new();
+        },
+    };
+
+    arguments
+        .into_iter()
+        .filter(|argument| argument.name != "..." && argument.value.sexp == harp::missing())
+        .map(|argument| argument.name)
+        .collect()
+}
+
+/// Snippet for a call with a tab stop for each of `arguments`, e.g.
+/// `fun(${1:x}, ${2:y})$0`, ending after the call. Without arguments the
+/// cursor is placed inside the parentheses.
+fn call_snippet(insert_text: &str, arguments: &[String]) -> String {
+    if arguments.is_empty() {
+        return format!("{insert_text}($0)");
+    }
+
+    let placeholders: Vec<String> = arguments
+        .iter()
+        .enumerate()
+        .map(|(i, argument)| format!("${{{}:{}}}", i + 1, snippet_escape(argument)))
+        .collect();
+
+    format!("{insert_text}({})$0", placeholders.join(", "))
+}
+
+/// Escape the characters that have a meaning inside a snippet placeholder
+fn snippet_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '$' | '}' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
 pub(super) fn completion_item_from_variable(name: &str) -> anyhow::Result<CompletionItem> {
     let mut item = completion_item(String::from(name), CompletionData::Object {
         name: String::from(name),
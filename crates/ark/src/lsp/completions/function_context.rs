@@ -41,6 +41,9 @@ pub(crate) struct FunctionContext {
     pub arguments_status: ArgumentsStatus,
     /// Whether the cursor is at the end of the effective function node
     pub cursor_is_at_end: bool,
+    /// Whether calls should be completed with placeholders for their
+    /// required arguments, which requires client snippet support
+    pub argument_placeholders: bool,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -75,6 +78,7 @@ impl FunctionContext {
                 usage: FunctionRefUsage::Call,
                 arguments_status: ArgumentsStatus::Absent,
                 cursor_is_at_end: true,
+                argument_placeholders: false,
             });
         };
 
@@ -130,6 +134,7 @@ impl FunctionContext {
             usage,
             arguments_status,
             cursor_is_at_end: is_cursor_at_end,
+            argument_placeholders: false,
         })
     }
 }
@@ -20,6 +20,7 @@ use crate::treesitter::NodeTypeExt;
 pub(crate) fn provide_completions(
     document_context: &DocumentContext,
     state: &WorldState,
+    snippet_support: bool,
 ) -> anyhow::Result<Vec<CompletionItem>> {
     log::info!(
         "provide_completions() - Completion node text: '{node_text}', Node type: '{node_type:?}'",
@@ -30,7 +31,8 @@ pub(crate) fn provide_completions(
         node_type = document_context.node.node_type()
     );
 
-    let completion_context = CompletionContext::new(document_context, state);
+    let completion_context =
+        CompletionContext::new(document_context, state).with_snippet_support(snippet_support);
 
     // Try unique sources first
     if let Some(completions) = unique::get_completions(&completion_context)? {
@@ -1,11 +1,14 @@
 //
 // resolve.rs
 //
-// Copyright (C) 2023 Posit Software, PBC. All rights reserved.
+// Copyright (C) 2023-2026 Posit Software, PBC. All rights reserved.
 //
 //
 
 use anyhow::bail;
+use harp::exec::RFunction;
+use harp::exec::RFunctionExt;
+use oak_package_metadata::dcf::Dcf;
 use stdext::*;
 use tower_lsp::lsp_types::CompletionItem;
 use tower_lsp::lsp_types::Documentation;
@@ -14,7 +17,15 @@ use tower_lsp::lsp_types::MarkupKind;
 
 use crate::lsp::completions::types::CompletionData;
 use crate::lsp::help::RHtmlHelp;
-
+use crate::lsp::markdown::md_bold;
+use crate::lsp::markdown::md_newline;
+
+/// Fill in the documentation of a completion item when the client requests
+/// it, typically when the item gets selected in the completion menu.
+///
+/// Fetching R help is expensive, so completion sources return lightweight
+/// items and only record in `data` the key needed to look up the help topic
+/// here. Returns whether the item was amended.
 pub fn resolve_completion(item: &mut CompletionItem) -> anyhow::Result<bool> {
     let Some(data) = item.data.clone() else {
         bail!("Completion '{}' has no associated data", item.label);
@@ -49,15 +60,22 @@ fn resolve_package_completion_item(
     item: &mut CompletionItem,
     package: &str,
 ) -> anyhow::Result<bool> {
-    let topic = join!(package, "-package");
-    let help = unwrap!(RHtmlHelp::from_topic(topic.as_str(), Some(package))?, None => {
-        return Ok(false);
-    });
+    // Prefer the `DESCRIPTION` summary, which all installed packages have,
+    // and fall back to the `{package}-package` help topic
+    let markup = match package_description_markdown(package)? {
+        Some(markup) => markup,
+        None => {
+            let topic = join!(package, "-package");
+            let help = unwrap!(RHtmlHelp::from_topic(topic.as_str(), Some(package))?, None => {
+                return Ok(false);
+            });
+            help.markdown()?
+        },
+    };
 
-    let markup = help.markdown()?;
     let markup = MarkupContent {
         kind: MarkupKind::Markdown,
-        value: markup.to_string(),
+        value: markup,
     };
 
     item.detail = None;
@@ -66,6 +84,39 @@ fn resolve_package_completion_item(
     Ok(true)
 }
 
+/// The `Title` and `Description` fields of an installed package's
+/// `DESCRIPTION` file, as markdown.
+fn package_description_markdown(package: &str) -> anyhow::Result<Option<String>> {
+    let path = RFunction::new("base", "system.file")
+        .add("DESCRIPTION")
+        .param("package", package)
+        .call()?;
+    let path = String::try_from(path)?;
+    if path.is_empty() {
+        return Ok(None);
+    }
+
+    let contents = std::fs::read_to_string(&path)?;
+    let dcf = Dcf::parse(&contents);
+
+    // Fields may be wrapped over several lines
+    let field = |name: &str| {
+        dcf.get(name)
+            .map(|value| value.split_whitespace().collect::<Vec<_>>().join(" "))
+    };
+
+    let Some(title) = field("Title") else {
+        return Ok(None);
+    };
+
+    let mut markdown = md_bold(&title);
+    if let Some(description) = field("Description") {
+        push!(markdown, md_newline(), description);
+    }
+
+    Ok(Some(markdown))
+}
+
 fn resolve_function_completion_item(
     item: &mut CompletionItem,
     name: &str,
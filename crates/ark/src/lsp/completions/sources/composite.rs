@@ -8,6 +8,7 @@
 mod call;
 mod document;
 mod keyword;
+mod package_exports;
 pub(crate) mod pipe;
 mod search_path;
 mod subset;
@@ -52,6 +53,11 @@ struct CompletionItemWithSource {
 }
 
 /// Gets completions from all composite sources, with deduplication and sorting
+///
+/// Sources are pushed in order of priority. When several sources provide an
+/// item with the same label and kind, the item of the first source is kept,
+/// so that e.g. a function defined in the document shadows a function of the
+/// same name from an attached package.
 pub(crate) fn get_completions(
     completion_context: &CompletionContext,
 ) -> anyhow::Result<Option<Vec<CompletionItem>>> {
@@ -80,14 +86,16 @@ pub(crate) fn get_completions(
     {
         push_completions(keyword::KeywordSource, completion_context, &mut completions)?;
 
+        // In-scope definitions take precedence over objects on the search
+        // path, which includes attached packages
         push_completions(
-            search_path::SearchPathSource,
+            document::DocumentSource,
             completion_context,
             &mut completions,
         )?;
 
         push_completions(
-            document::DocumentSource,
+            search_path::SearchPathSource,
             completion_context,
             &mut completions,
         )?;
@@ -97,6 +105,12 @@ pub(crate) fn get_completions(
             completion_context,
             &mut completions,
         )?;
+
+        push_completions(
+            package_exports::PackageExportsSource,
+            completion_context,
+            &mut completions,
+        )?;
     }
 
     // Simplify to plain old CompletionItems and sort them
@@ -118,7 +132,7 @@ where
     if let Some(source_completions) = collect_completions(source, completion_context)? {
         for item in source_completions {
             let key = CompletionItemKey::new(&item);
-            if let Some(existing) = completions.get(&key) {
+            if let Some(existing) = completions.get_mut(&key) {
                 log::trace!(
                     "Completion with label '{}' and kind '{:?}' already exists (first contributed by source: {}, now also from: {})",
                     key.label,
@@ -126,6 +140,7 @@ where
                     existing.source,
                     source_name
                 );
+                merge_completion_item(&mut existing.item, item);
             } else {
                 completions.insert(key, CompletionItemWithSource {
                     item,
@@ -138,6 +153,17 @@ where
     Ok(())
 }
 
+/// Fill in the details that the higher priority item lacks from a duplicate
+/// item provided by a lower priority source
+fn merge_completion_item(item: &mut CompletionItem, duplicate: CompletionItem) {
+    if item.detail.is_none() {
+        item.detail = duplicate.detail;
+    }
+    if item.documentation.is_none() {
+        item.documentation = duplicate.documentation;
+    }
+}
+
 /// Produce plain old CompletionItems and sort them
 fn finalize_completions(
     completions: HashMap<CompletionItemKey, CompletionItemWithSource>,
@@ -216,6 +242,9 @@ fn is_identifier_like(x: Node) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use tower_lsp::lsp_types::CompletionItemKind;
+    use tower_lsp::lsp_types::Documentation;
+
     use crate::fixtures::point_from_cursor;
     use crate::lsp::completions::completion_context::CompletionContext;
     use crate::lsp::completions::sources::composite::get_completions;
@@ -280,4 +309,33 @@ mod tests {
             assert!(!completions.unwrap().is_empty());
         });
     }
+
+    #[test]
+    fn test_get_completions_deduplicates_across_sources() {
+        r_task(|| {
+            // `paste()` is both defined in the document and exported by base
+            let code = "paste <- function(x) x\npas@";
+            let (text, point) = point_from_cursor(code);
+            let doc = TestDocument::new(&text);
+            let document_context = doc.context(point);
+            let state = WorldState::default();
+            let context = CompletionContext::new(&document_context, &state);
+
+            let completions = get_completions(&context).unwrap().unwrap();
+            let matches: Vec<_> = completions
+                .iter()
+                .filter(|item| item.label == "paste")
+                .collect();
+
+            // The in-scope definition is the one that's kept
+            assert_eq!(matches.len(), 1);
+            let item = matches[0];
+            assert_eq!(item.kind, Some(CompletionItemKind::FUNCTION));
+            assert_eq!(item.detail.as_deref(), Some("paste(x)"));
+            let Some(Documentation::MarkupContent(markup)) = &item.documentation else {
+                panic!("Expected markup documentation");
+            };
+            assert!(markup.value.contains("Defined in this document"));
+        });
+    }
 }
@@ -266,6 +266,7 @@ fn completions_from_workspace_arguments(
         },
         indexer::IndexEntryData::Variable { .. } => return Ok(None),
         indexer::IndexEntryData::Method { .. } => return Ok(None),
+        indexer::IndexEntryData::Class { .. } => return Ok(None),
     }
 
     // Only 1 call worth of arguments are added to the completion set.
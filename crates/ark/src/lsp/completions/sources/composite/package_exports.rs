@@ -0,0 +1,241 @@
+//
+// package_exports.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::collections::HashSet;
+
+use oak_db::DbInputs;
+use tower_lsp::lsp_types::CompletionItem;
+
+use crate::lsp::completions::completion_context::CompletionContext;
+use crate::lsp::completions::completion_item::completion_item_from_package_export;
+use crate::lsp::completions::sources::utils::filter_out_dot_prefixes;
+use crate::lsp::completions::sources::CompletionSource;
+use crate::lsp::db::ArkDb;
+use crate::lsp::traits::node::NodeExt;
+use crate::lsp::traits::string::StringExt;
+use crate::treesitter::NodeTypeExt;
+
+/// Number of characters to type before exports are matched. Shorter prefixes
+/// match too much of the library to be useful.
+const MIN_PREFIX_LENGTH: usize = 3;
+
+/// Upper bound on the number of exports offered in one completion request
+const MAX_COMPLETIONS: usize = 200;
+
+/// Exports of installed packages, inserted as `pkg::name`. Opt-in with
+/// `positron.r.completions.packageExports` since the library can be large.
+pub(super) struct PackageExportsSource;
+
+impl CompletionSource for PackageExportsSource {
+    fn name(&self) -> &'static str {
+        "package_exports"
+    }
+
+    fn provide_completions(
+        &self,
+        completion_context: &CompletionContext,
+    ) -> anyhow::Result<Option<Vec<CompletionItem>>> {
+        completions_from_package_exports(completion_context)
+    }
+}
+
+fn completions_from_package_exports(
+    completion_context: &CompletionContext,
+) -> anyhow::Result<Option<Vec<CompletionItem>>> {
+    let context = completion_context.document_context;
+    let state = completion_context.state;
+    let node = context.node;
+
+    if !state.config.completions.package_exports {
+        return Ok(None);
+    }
+
+    // Matching every export of every installed package would flood the
+    // completion list, so require the user to type a few characters first
+    if !node.is_identifier() {
+        return Ok(None);
+    }
+    let token = node.node_as_str(context.contents)?;
+    if token.chars().count() < MIN_PREFIX_LENGTH {
+        return Ok(None);
+    }
+
+    // Names on the search path are already completed unqualified by the
+    // search path source
+    let attached: HashSet<&str> = state
+        .console_scopes
+        .iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+
+    let mut completions = vec![];
+
+    for (name, package) in library_exports(&state.db) {
+        if !name.fuzzy_matches(token) || attached.contains(name.as_str()) {
+            continue;
+        }
+        completions.push(completion_item_from_package_export(name, package)?);
+        if completions.len() >= MAX_COMPLETIONS {
+            break;
+        }
+    }
+
+    filter_out_dot_prefixes(context, &mut completions);
+
+    Ok(Some(completions))
+}
+
+/// Exports of all packages in the library roots, as `(name, package)` pairs.
+///
+/// Cached by salsa and recomputed when the library roots or a package's
+/// `NAMESPACE` change. When a package is installed in several libraries, the
+/// first one wins, like in `.libPaths()`.
+#[salsa::tracked(returns(ref))]
+fn library_exports(db: &dyn ArkDb) -> Vec<(String, String)> {
+    let mut seen = HashSet::new();
+    let mut exports = Vec::new();
+
+    for root in db.library_roots().roots(db) {
+        for &package in root.packages(db) {
+            let name = package.name(db);
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            for export in package.namespace(db).exports.iter() {
+                exports.push((export.clone(), name.clone()));
+            }
+        }
+    }
+
+    exports
+}
+
+#[cfg(test)]
+mod tests {
+    use aether_path::FilePath;
+    use oak_db::DbInputs;
+    use oak_db::FileRevision;
+    use oak_db::Package;
+    use oak_db::Root;
+    use oak_db::RootKind;
+    use oak_package_metadata::namespace::Namespace;
+    use salsa::Setter;
+    use stdext::SortedVec;
+
+    use crate::fixtures::point_from_cursor;
+    use crate::lsp::completions::completion_context::CompletionContext;
+    use crate::lsp::completions::sources::composite::package_exports::completions_from_package_exports;
+    use crate::lsp::completions::sources::composite::package_exports::MAX_COMPLETIONS;
+    use crate::lsp::config::CompletionsConfig;
+    use crate::lsp::config::LspConfig;
+    use crate::lsp::document_context::TestDocument;
+    use crate::lsp::state::WorldState;
+
+    fn make_state(package_exports: bool) -> WorldState {
+        make_state_with_exports(package_exports, vec![
+            String::from("str_detect"),
+            String::from("str_c"),
+        ])
+    }
+
+    fn make_state_with_exports(package_exports: bool, exports: Vec<String>) -> WorldState {
+        let mut state = WorldState {
+            config: LspConfig {
+                completions: CompletionsConfig { package_exports },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let library = std::env::temp_dir().join("library");
+        let namespace = Namespace {
+            exports: SortedVec::from_vec(exports),
+            ..Default::default()
+        };
+        let package = Package::new(
+            &state.db,
+            FilePath::from_path_buf(library.join("stringr").join("DESCRIPTION")).unwrap(),
+            "stringr".to_string(),
+            FileRevision::zero(),
+            FileRevision::zero(),
+            None,
+            Some(namespace),
+            vec![],
+            vec![],
+        );
+        let root = Root::new(
+            &state.db,
+            FilePath::from_path_buf(library).unwrap(),
+            RootKind::Library,
+            vec![],
+            vec![package],
+        );
+        state
+            .db
+            .library_roots()
+            .set_roots(&mut state.db)
+            .to(vec![root]);
+
+        state
+    }
+
+    fn complete(state: &WorldState, code: &str) -> Option<Vec<(String, Option<String>)>> {
+        let (text, point) = point_from_cursor(code);
+        let doc = TestDocument::new(&text);
+        let document_context = doc.context(point);
+        let context = CompletionContext::new(&document_context, state);
+
+        completions_from_package_exports(&context)
+            .unwrap()
+            .map(|items| {
+                items
+                    .into_iter()
+                    .map(|item| (item.label, item.insert_text))
+                    .collect()
+            })
+    }
+
+    #[test]
+    fn test_package_exports_insert_qualified_name() {
+        let state = make_state(true);
+
+        assert_eq!(
+            complete(&state, "str_det@"),
+            Some(vec![(
+                String::from("str_detect"),
+                Some(String::from("stringr::str_detect"))
+            )])
+        );
+
+        // Not enough typed yet
+        assert_eq!(complete(&state, "@"), None);
+        assert_eq!(complete(&state, "st@"), None);
+    }
+
+    #[test]
+    fn test_package_exports_capped() {
+        let exports = (0..500).map(|i| format!("str_{i}")).collect();
+        let state = make_state_with_exports(true, exports);
+
+        assert_eq!(complete(&state, "str@").unwrap().len(), MAX_COMPLETIONS);
+    }
+
+    #[test]
+    fn test_package_exports_skip_attached_names() {
+        let mut state = make_state(true);
+        state.console_scopes = vec![vec![String::from("str_detect")]];
+
+        assert_eq!(complete(&state, "str_det@"), Some(vec![]));
+    }
+
+    #[test]
+    fn test_package_exports_disabled_by_default() {
+        let state = make_state(false);
+        assert_eq!(complete(&state, "str_det@"), None);
+    }
+}
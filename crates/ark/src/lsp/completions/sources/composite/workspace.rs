@@ -86,13 +86,16 @@ fn completions_from_workspace(
                     },
                 };
 
-                let mut completion = match completion_item_from_function(name, None, fun_context) {
-                    Ok(completion) => completion,
-                    Err(err) => {
-                        log::error!("{:?}", err);
-                        return;
-                    },
-                };
+                // The index doesn't record argument defaults, so we don't know
+                // which arguments are required
+                let mut completion =
+                    match completion_item_from_function(name, None, &[], fun_context) {
+                        Ok(completion) => completion,
+                        Err(err) => {
+                            log::error!("{:?}", err);
+                            return;
+                        },
+                    };
 
                 // Add some metadata about where the completion was found
                 let mut path = uri.as_str().to_owned();
@@ -137,8 +140,10 @@ fn completions_from_workspace(
                 completions.push(completion);
             },
 
-            // Methods are currently only indexed for workspace symbols
+            // Methods and classes are currently only indexed for workspace
+            // symbols
             indexer::IndexEntryData::Method { .. } => {},
+            indexer::IndexEntryData::Class { .. } => {},
         }
     });
 
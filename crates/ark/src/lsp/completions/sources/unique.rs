@@ -13,6 +13,7 @@ mod file_path;
 mod namespace;
 mod string;
 mod subset;
+mod switch;
 
 use tower_lsp::lsp_types::CompletionItem;
 
@@ -5,15 +5,24 @@
 //
 //
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+
 use harp::eval::RParseEvalOptions;
 use harp::exec::RFunction;
 use harp::exec::RFunctionExt;
+use harp::object::RObject;
 use harp::r_symbol;
 use harp::utils::r_env_has;
+use harp::utils::r_is_function;
+use harp::utils::r_is_s4;
 use harp::utils::r_typeof;
+use harp::vector::names::Names;
 use harp::Error;
 use libr::STRSXP;
+use libr::VECSXP;
 use tower_lsp::lsp_types::CompletionItem;
+use tower_lsp::lsp_types::CompletionItemKind;
 use tree_sitter::Node;
 
 use crate::console;
@@ -22,7 +31,11 @@ use crate::lsp::completions::completion_item::completion_item_from_data_variable
 use crate::lsp::completions::sources::utils::set_sort_text_by_first_appearance;
 use crate::lsp::completions::sources::CompletionSource;
 use crate::lsp::document_context::DocumentContext;
+use crate::lsp::indexer;
+use crate::lsp::indexer::IndexEntryData;
+use crate::lsp::state::WorldState;
 use crate::lsp::traits::node::NodeExt;
+use crate::lsp::type_definition::class_of_expr;
 use crate::treesitter::ExtractOperatorType;
 use crate::treesitter::NodeType;
 use crate::treesitter::NodeTypeExt;
@@ -53,7 +66,7 @@ impl CompletionSource for AtSource {
         &self,
         builder: &CompletionContext,
     ) -> anyhow::Result<Option<Vec<CompletionItem>>> {
-        completions_from_at(builder.document_context)
+        completions_from_at(builder)
     }
 }
 
@@ -67,12 +80,110 @@ fn completions_from_dollar(
     )
 }
 
-fn completions_from_at(context: &DocumentContext) -> anyhow::Result<Option<Vec<CompletionItem>>> {
-    completions_from_extractor(
-        context,
-        NodeType::ExtractOperator(ExtractOperatorType::At),
-        ".AtNames",
-    )
+fn completions_from_at(context: &CompletionContext) -> anyhow::Result<Option<Vec<CompletionItem>>> {
+    let document_context = context.document_context;
+    let node_type = NodeType::ExtractOperator(ExtractOperatorType::At);
+
+    // Objects created with `new("Foo")` or with a class generator `Foo()`
+    // can't be evaluated, but we know their class so we can complete its slots
+    if let Some(node) = locate_extractor_node(document_context.node, node_type) {
+        if let Some(lhs) = node.child_by_field_name("lhs") {
+            let contents = document_context.contents;
+
+            if let Some(class) = class_of_expr(&context.state.db, &lhs, contents) {
+                let text = lhs.node_as_str(contents)?;
+                let slots = class_slots(context.state, &class);
+                return Ok(Some(completions_from_slots(&slots, text)));
+            }
+        }
+    }
+
+    completions_from_extractor(document_context, node_type, ".AtNames")
+}
+
+/// Slot names and classes of the S4 class `class`, including inherited
+/// slots. Classes defined in the workspace are looked up in the index, walking
+/// up their superclasses. Other classes are queried from R.
+fn class_slots(state: &WorldState, class: &str) -> Vec<(String, String)> {
+    let mut slots = Vec::new();
+    let mut visited = HashSet::new();
+    collect_class_slots(state, class, &mut slots, &mut visited);
+    slots
+}
+
+fn collect_class_slots(
+    state: &WorldState,
+    class: &str,
+    slots: &mut Vec<(String, String)>,
+    visited: &mut HashSet<String>,
+) {
+    // Guard against cycles in `contains` chains of code being edited
+    if !visited.insert(class.to_string()) {
+        return;
+    }
+
+    if let Some((_file, entry)) = indexer::find_class(&state.db, class) {
+        if let IndexEntryData::Class {
+            slots: own_slots,
+            contains,
+            ..
+        } = entry.data
+        {
+            push_slots(slots, own_slots);
+            for superclass in &contains {
+                collect_class_slots(state, superclass, slots, visited);
+            }
+            return;
+        }
+    }
+
+    match r_class_slots(RObject::from(class)) {
+        Ok(r_slots) => push_slots(slots, r_slots),
+        Err(err) => log::trace!("Can't get slots of class `{class}`: {err:?}"),
+    }
+}
+
+/// Slots redefined by a subclass take precedence over inherited ones
+fn push_slots(slots: &mut Vec<(String, String)>, new: Vec<(String, String)>) {
+    for slot in new {
+        if !slots.iter().any(|(name, _)| *name == slot.0) {
+            slots.push(slot);
+        }
+    }
+}
+
+/// Query the slots of a class with `methods::getSlots()`. `class` is either
+/// a class name or a class representation.
+fn r_class_slots(class: RObject) -> harp::Result<Vec<(String, String)>> {
+    let slots = RFunction::new("methods", "getSlots").add(class).call()?;
+    let classes: Vec<String> = slots.clone().try_into()?;
+    let names = Names::new(*slots, |_i| String::from(""));
+
+    Ok(classes
+        .into_iter()
+        .enumerate()
+        .map(|(i, class)| (names.get_unchecked(i as isize), class))
+        .collect())
+}
+
+fn completions_from_slots(slots: &[(String, String)], text: &str) -> Vec<CompletionItem> {
+    const ENQUOTE: bool = false;
+
+    let mut completions = vec![];
+
+    for (name, class) in slots {
+        match unsafe { completion_item_from_data_variable(name, text, ENQUOTE) } {
+            Ok(mut item) => {
+                item.detail = Some(class.clone());
+                completions.push(item);
+            },
+            Err(err) => log::error!("{err:?}"),
+        }
+    }
+
+    set_sort_text_by_first_appearance(&mut completions);
+
+    completions
 }
 
 fn completions_from_extractor(
@@ -185,9 +296,38 @@ fn completions_from_extractor_object(text: &str, fun: &str) -> anyhow::Result<Ve
 
         let names = names.to::<Vec<String>>()?;
 
+        // For lists, look up each element by name so we can tell functions
+        // apart from data
+        let index = if r_typeof(*object) == VECSXP {
+            Names::new(*object, |_i| String::from("")).index()
+        } else {
+            HashMap::new()
+        };
+
+        // For S4 objects, report the class of each slot
+        let slot_classes: HashMap<String, String> = if fun == ".AtNames" && r_is_s4(*object) {
+            let class = RFunction::new("base", "class").add(object.clone()).call()?;
+            r_class_slots(class)
+                .unwrap_or_default()
+                .into_iter()
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
         for name in names {
             match completion_item_from_data_variable(&name, text, ENQUOTE) {
-                Ok(item) => completions.push(item),
+                Ok(mut item) => {
+                    if let Some(i) = index.get(&name) {
+                        if r_is_function(harp::list_get(*object, *i as isize)) {
+                            item.kind = Some(CompletionItemKind::FUNCTION);
+                        }
+                    }
+                    if let Some(class) = slot_classes.get(&name) {
+                        item.detail = Some(class.clone());
+                    }
+                    completions.push(item)
+                },
                 Err(err) => log::error!("{err:?}"),
             }
         }
@@ -202,13 +342,20 @@ fn completions_from_extractor_object(text: &str, fun: &str) -> anyhow::Result<Ve
 
 #[cfg(test)]
 mod tests {
+    use aether_path::FilePath;
     use harp::eval::RParseEvalOptions;
     use harp::object::r_lgl_get;
+    use tower_lsp::lsp_types::CompletionItemKind;
 
     use crate::fixtures::package_is_installed;
     use crate::fixtures::point_from_cursor;
+    use crate::lsp::completions::completion_context::CompletionContext;
+    use crate::lsp::completions::sources::unique::extractor::completions_from_at;
     use crate::lsp::completions::sources::unique::extractor::completions_from_dollar;
+    use crate::lsp::completions::tests::utils::find_completion_by_label;
     use crate::lsp::document_context::TestDocument;
+    use crate::lsp::state::WorldState;
+    use crate::lsp::util::test_path;
     use crate::r_task;
 
     #[test]
@@ -247,6 +394,40 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_dollar_completions_function_elements() {
+        r_task(|| {
+            let options = RParseEvalOptions {
+                forbid_function_calls: false,
+                ..Default::default()
+            };
+
+            // The first `a` is a function, so R's `foo$a` is too
+            harp::parse_eval(
+                "foo <- list(a = function() 1, b = 2, a = 3)",
+                options.clone(),
+            )
+            .unwrap();
+
+            let (text, point) = point_from_cursor("foo$@");
+            let doc = TestDocument::new(&text);
+            let context = doc.context(point);
+
+            let completions = completions_from_dollar(&context).unwrap().unwrap();
+            assert_eq!(completions.len(), 3);
+
+            let kinds: Vec<_> = completions.iter().map(|item| item.kind).collect();
+            assert_eq!(kinds, vec![
+                Some(CompletionItemKind::FUNCTION),
+                Some(CompletionItemKind::VARIABLE),
+                Some(CompletionItemKind::FUNCTION),
+            ]);
+
+            // Clean up
+            harp::parse_eval("remove(foo)", options.clone()).unwrap();
+        })
+    }
+
     #[test]
     fn test_dollar_completions_on_nonexistent_object() {
         r_task(|| {
@@ -359,6 +540,117 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_at_completions_on_new() {
+        r_task(|| {
+            let uri = test_path("class.R");
+            let mut state = WorldState::default();
+            let file = state.db.upsert_editor(
+                FilePath::from_url(&uri),
+                String::from(
+                    r#"
+setClass("Foo", slots = c(a = "numeric", b = "character"))
+setClass("Bar", slots = c(c = "logical"), contains = "Foo")
+setClass("Baz", contains = c("Bar", "genericFunction"))
+"#,
+                ),
+            );
+            state.insert_open_file(uri, file, None);
+
+            let (text, point) = point_from_cursor(r#"new("Foo")@@"#);
+            let doc = TestDocument::new(&text);
+            let document_context = doc.context(point);
+            let context = CompletionContext::new(&document_context, &state);
+
+            let completions = completions_from_at(&context).unwrap().unwrap();
+            let slots: Vec<_> = completions
+                .iter()
+                .map(|item| (item.label.as_str(), item.detail.as_deref()))
+                .collect();
+            assert_eq!(slots, vec![
+                ("a", Some("numeric")),
+                ("b", Some("character"))
+            ]);
+
+            // Inherited slots come after the slots of the class itself
+            let (text, point) = point_from_cursor(r#"new("Bar")@@"#);
+            let doc = TestDocument::new(&text);
+            let document_context = doc.context(point);
+            let context = CompletionContext::new(&document_context, &state);
+
+            let completions = completions_from_at(&context).unwrap().unwrap();
+            let slots: Vec<_> = completions
+                .iter()
+                .map(|item| (item.label.as_str(), item.detail.as_deref()))
+                .collect();
+            assert_eq!(slots, vec![
+                ("c", Some("logical")),
+                ("a", Some("numeric")),
+                ("b", Some("character"))
+            ]);
+
+            // Superclasses that aren't defined in the workspace are queried
+            // from R
+            let (text, point) = point_from_cursor(r#"new("Baz")@@"#);
+            let doc = TestDocument::new(&text);
+            let document_context = doc.context(point);
+            let context = CompletionContext::new(&document_context, &state);
+
+            let completions = completions_from_at(&context).unwrap().unwrap();
+            assert_eq!(completions[0].label, "c");
+            assert!(find_completion_by_label(&completions, "signature").is_some());
+
+            // Classes that aren't defined in the workspace are queried from R
+            let (text, point) = point_from_cursor(r#"new("genericFunction")@@"#);
+            let doc = TestDocument::new(&text);
+            let document_context = doc.context(point);
+            let context = CompletionContext::new(&document_context, &state);
+
+            let completions = completions_from_at(&context).unwrap().unwrap();
+            assert!(find_completion_by_label(&completions, "signature").is_some());
+        })
+    }
+
+    #[test]
+    fn test_at_completions_on_s4_object() {
+        r_task(|| {
+            let options = RParseEvalOptions {
+                forbid_function_calls: false,
+                ..Default::default()
+            };
+
+            harp::parse_eval(
+                "setClass('ArkAtFoo', slots = c(a = 'numeric', b = 'character'), where = globalenv())
+                 foo <- new('ArkAtFoo', a = 1, b = 'x')",
+                options.clone(),
+            )
+            .unwrap();
+
+            let (text, point) = point_from_cursor("foo@@");
+            let doc = TestDocument::new(&text);
+            let document_context = doc.context(point);
+            let state = WorldState::default();
+            let context = CompletionContext::new(&document_context, &state);
+
+            let completions = completions_from_at(&context).unwrap().unwrap();
+            let slots: Vec<_> = completions
+                .iter()
+                .map(|item| (item.label.as_str(), item.detail.as_deref()))
+                .collect();
+            assert_eq!(slots, vec![
+                ("a", Some("numeric")),
+                ("b", Some("character"))
+            ]);
+
+            // Clean up
+            harp::parse_eval(
+                "removeClass('ArkAtFoo', where = globalenv()); remove(foo)",
+                options.clone(),
+            )
+            .unwrap();
+        })
+    }
+
     #[test]
     fn test_dollar_completions_on_r6() {
         r_task(|| {
@@ -8,8 +8,13 @@
 use std::env::current_dir;
 use std::path::PathBuf;
 
+use harp::environment::Environment;
+use harp::environment::R_ENVS;
+use harp::exec::RFunction;
+use harp::exec::RFunctionExt;
 use harp::utils::r_is_string;
 use harp::utils::r_normalize_path;
+use harp::RObject;
 use stdext::unwrap;
 use tower_lsp::lsp_types::CompletionItem;
 use tree_sitter::Node;
@@ -17,7 +22,11 @@ use tree_sitter::Node;
 use crate::lsp::completions::completion_item::completion_item_from_direntry;
 use crate::lsp::completions::sources::utils::set_sort_text_by_words_first;
 use crate::lsp::document_context::DocumentContext;
+use crate::lsp::indexer::s4_call_arg;
 use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::node_find_parent_call;
+use crate::treesitter::node_is_call;
+use crate::treesitter::NodeTypeExt;
 
 pub(super) fn completions_from_string_file_path(
     node: &Node,
@@ -47,18 +56,23 @@ pub(super) fn completions_from_string_file_path(
         return Ok(completions);
     }
 
-    // Use R to normalize the path.
-    let path = r_normalize_path(contents)?;
+    let mut path = match path_base_directory(node, context) {
+        // Strings in `file.path()` or `system.file()` are relative to the
+        // preceding path components
+        Some(base) => {
+            let contents = String::try_from(contents)?;
+            base.join(contents)
+        },
 
-    // parse the file path and get the directory component
-    let mut path = PathBuf::from(path.as_str());
-    log::trace!("Normalized path: {}", path.display());
-
-    // if this path doesn't have a root, add it on
-    if !path.has_root() {
-        let root = current_dir()?;
-        path = root.join(path);
-    }
+        None => {
+            // Use R to normalize the path, so that `~` and relative paths
+            // are resolved like R would
+            let contents = String::try_from(contents)?;
+            let path = PathBuf::from(r_normalize_path(&contents, false)?);
+            log::trace!("Normalized path: {}", path.display());
+            path
+        },
+    };
 
     // if this isn't a directory, get the parent path
     if !path.is_dir() {
@@ -92,8 +106,111 @@ pub(super) fn completions_from_string_file_path(
     Ok(completions)
 }
 
+/// The directory that a path component of `file.path()` or `system.file()`
+/// is relative to, like `file.path(dir, "<tab>")` or
+/// `system.file("extdata", "<tab>", package = "pkg")`
+///
+/// Returns `None` when `node` is not one of these components, or when the
+/// preceding components can't be resolved statically.
+fn path_base_directory(node: &Node, context: &DocumentContext) -> Option<PathBuf> {
+    let contents = context.contents;
+    let call = node_find_parent_call(node)?;
+
+    let is_file_path = node_is_call(&call, "file.path", contents);
+    let is_system_file = node_is_call(&call, "system.file", contents);
+    if !is_file_path && !is_system_file {
+        return None;
+    }
+
+    // Collect the path components that precede the string. Named arguments
+    // like `package` or `fsep` are not path components.
+    let mut components: Vec<String> = vec![];
+    for (name, value) in call.arguments() {
+        let value = value?;
+        if name.is_some() {
+            if value == *node {
+                return None;
+            }
+            continue;
+        }
+        if value == *node {
+            break;
+        }
+
+        let component = if value.is_string() {
+            value
+                .get_identifier_or_string_text(contents)
+                .ok()?
+                .to_string()
+        } else if is_file_path && value.is_identifier() {
+            global_string_variable(value.node_as_str(contents).ok()?)?
+        } else {
+            return None;
+        };
+        components.push(component);
+    }
+
+    let mut base = if is_system_file {
+        // `package` follows `...` so it can only be supplied by name
+        let package = match s4_call_arg(&call, "package", usize::MAX, contents) {
+            Some(package) if package.is_string() => package
+                .get_identifier_or_string_text(contents)
+                .ok()?
+                .to_string(),
+            Some(_) => return None,
+            None => String::from("base"),
+        };
+        PathBuf::from(package_directory(&package)?)
+    } else {
+        // `file.path("<tab>")` has no base directory
+        if components.is_empty() {
+            return None;
+        }
+        current_dir().ok()?
+    };
+
+    for component in components {
+        base.push(component);
+    }
+
+    Some(base)
+}
+
+/// The installation directory of `package`, if installed
+fn package_directory(package: &str) -> Option<String> {
+    let path = RFunction::new("base", "system.file")
+        .param("package", package)
+        .call()
+        .ok()?;
+    let path = String::try_from(path).ok()?;
+
+    if path.is_empty() {
+        None
+    } else {
+        Some(path)
+    }
+}
+
+/// The value of a string variable of the global environment. Promises and
+/// active bindings are not evaluated.
+fn global_string_variable(name: &str) -> Option<String> {
+    let env = Environment::view(R_ENVS.global);
+    if env.is_active(name.into()).ok()? {
+        return None;
+    }
+
+    let value = env.find(name).ok()?;
+    if !r_is_string(value) {
+        return None;
+    }
+
+    String::try_from(RObject::view(value)).ok()
+}
+
 #[cfg(test)]
 mod tests {
+    use harp::environment::R_ENVS;
+
     use crate::fixtures::point_from_cursor;
     use crate::lsp::completions::sources::unique::file_path::completions_from_string_file_path;
     use crate::lsp::document_context::TestDocument;
@@ -114,4 +231,58 @@ mod tests {
             assert_eq!(completions.len(), 0);
         })
     }
+
+    fn file_path_labels(code: &str) -> Vec<String> {
+        let (text, point) = point_from_cursor(code);
+        let doc = TestDocument::new(&text);
+        let context = doc.context(point);
+        let node = node_find_string(&context.node).unwrap();
+
+        completions_from_string_file_path(&node, &context)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    }
+
+    #[test]
+    fn test_system_file_completions() {
+        r_task(|| {
+            // Files of the installation directory of base
+            let labels = file_path_labels(r#"system.file("@", package = "base")"#);
+            assert!(labels.contains(&String::from("DESCRIPTION")));
+            assert!(labels.contains(&String::from("R/")));
+
+            // `base` is the default package
+            let labels = file_path_labels(r#"system.file("DESC@")"#);
+            assert!(labels.contains(&String::from("DESCRIPTION")));
+
+            // Preceding components are resolved
+            let labels = file_path_labels(r#"system.file("R", "@", package = "base")"#);
+            assert!(labels.contains(&String::from("base")));
+        })
+    }
+
+    #[test]
+    fn test_file_path_completions() {
+        r_task(|| {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(dir.path().join("data.csv"), "").unwrap();
+
+            let labels = file_path_labels(&format!(
+                r#"file.path("{}", "@")"#,
+                dir.path().to_string_lossy().replace('\\', "/")
+            ));
+            assert_eq!(labels, vec![String::from("data.csv")]);
+
+            // Directory stored in a global variable
+            let path = harp::RObject::from(dir.path().to_string_lossy().to_string());
+            harp::environment::Environment::view(R_ENVS.global).bind("ark_test_dir".into(), &path);
+
+            let labels = file_path_labels(r#"file.path(ark_test_dir, "@")"#);
+            assert_eq!(labels, vec![String::from("data.csv")]);
+
+            harp::parse_eval_global("rm(ark_test_dir)").unwrap();
+        })
+    }
 }
@@ -10,6 +10,7 @@ use tower_lsp::lsp_types::CompletionItem;
 use super::file_path::completions_from_string_file_path;
 use crate::lsp::completions::completion_context::CompletionContext;
 use crate::lsp::completions::sources::unique::subset::completions_from_string_subset;
+use crate::lsp::completions::sources::unique::switch::completions_from_string_switch;
 use crate::lsp::completions::sources::CompletionSource;
 use crate::lsp::document_context::DocumentContext;
 use crate::treesitter::node_find_string;
@@ -63,6 +64,13 @@ fn completions_from_string(
         return Ok(Some(completions));
     }
 
+    // Check if we are writing a `switch()` case label, like
+    // `switch(match.arg(type), "<tab>")`
+    if let Some(mut candidates) = completions_from_string_switch(&node, context)? {
+        completions.append(&mut candidates);
+        return Ok(Some(completions));
+    }
+
     // If no special string cases are hit, we show file path completions
     completions.append(&mut completions_from_string_file_path(&node, context)?);
 
@@ -0,0 +1,171 @@
+//
+// switch.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use tower_lsp::lsp_types::CompletionItem;
+use tower_lsp::lsp_types::CompletionItemKind;
+use tree_sitter::Node;
+
+use crate::lsp::completions::completion_item::completion_item;
+use crate::lsp::completions::types::CompletionData;
+use crate::lsp::document_context::DocumentContext;
+use crate::lsp::indexer::s4_call_arg;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::node_find_parent_call;
+use crate::treesitter::node_is_call;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+
+/// Checks for `switch()` case labels when the user is inside a `""`, like
+/// `switch(x, "<tab>")` or `switch(x, "<tab>" = )`
+///
+/// The labels are inferred from the `switch()` subject. Currently only
+/// `match.arg()` subjects are supported, whose choices are either supplied
+/// explicitly or taken from the default value of the matched parameter of
+/// the enclosing function, as in:
+///
+/// ```r
+/// f <- function(type = c("a", "b")) switch(match.arg(type), "<tab>")
+/// ```
+pub(super) fn completions_from_string_switch(
+    node: &Node,
+    context: &DocumentContext,
+) -> anyhow::Result<Option<Vec<CompletionItem>>> {
+    log::trace!("completions_from_string_switch()");
+
+    let Some(choices) = switch_case_choices(node, context.contents) else {
+        return Ok(None);
+    };
+
+    let mut completions: Vec<CompletionItem> = vec![];
+
+    for choice in choices {
+        let mut item = completion_item(&choice, CompletionData::Unknown)?;
+        item.kind = Some(CompletionItemKind::ENUM_MEMBER);
+        item.detail = Some(String::from("switch() case"));
+        completions.push(item);
+    }
+
+    Ok(Some(completions))
+}
+
+fn switch_case_choices(node: &Node, contents: &str) -> Option<Vec<String>> {
+    // The string must be the name or the value of a `switch()` argument
+    let call = node_find_parent_call(node)?;
+    if !node_is_call(&call, "switch", contents) {
+        return None;
+    }
+
+    // The subject is the first argument, which can't be a case label
+    let (_, subject) = call.arguments().next()?;
+    let subject = subject?;
+    if subject == *node {
+        return None;
+    }
+
+    if !node_is_call(&subject, "match.arg", contents) {
+        return None;
+    }
+
+    if let Some(choices) = s4_call_arg(&subject, "choices", 1, contents) {
+        return string_vector_values(&choices, contents);
+    }
+
+    // No explicit choices, use the default of the matched parameter
+    let arg = s4_call_arg(&subject, "arg", 0, contents)?;
+    if !arg.is_identifier() {
+        return None;
+    }
+    let arg = arg.node_as_str(contents).ok()?;
+
+    let function = call
+        .ancestors()
+        .find(|node| node.is_function_definition())?;
+    let parameters = function.child_by_field_name("parameters")?;
+
+    let mut cursor = parameters.walk();
+    let default = parameters.children(&mut cursor).find_map(|parameter| {
+        if parameter.node_type() != NodeType::Parameter {
+            return None;
+        }
+        let name = parameter.child_by_field_name("name")?;
+        if name.node_as_str(contents).ok()? != arg {
+            return None;
+        }
+        parameter.child_by_field_name("default")
+    })?;
+
+    string_vector_values(&default, contents)
+}
+
+/// The values of a literal character vector like `c("a", "b")` or `"a"`
+fn string_vector_values(node: &Node, contents: &str) -> Option<Vec<String>> {
+    if node.is_string() {
+        let value = node.get_identifier_or_string_text(contents).ok()?;
+        return Some(vec![value.to_string()]);
+    }
+
+    if !node_is_call(node, "c", contents) {
+        return None;
+    }
+
+    let values: Vec<String> = node
+        .arguments_values()
+        .flatten()
+        .filter(|value| value.is_string())
+        .filter_map(|value| value.get_identifier_or_string_text(contents).ok())
+        .map(|value| value.to_string())
+        .collect();
+
+    Some(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::point_from_cursor;
+    use crate::lsp::completions::sources::unique::switch::completions_from_string_switch;
+    use crate::lsp::document_context::TestDocument;
+    use crate::treesitter::node_find_string;
+
+    fn switch_labels(code: &str) -> Option<Vec<String>> {
+        let (text, point) = point_from_cursor(code);
+        let doc = TestDocument::new(&text);
+        let context = doc.context(point);
+        let node = node_find_string(&context.node).unwrap();
+
+        completions_from_string_switch(&node, &context)
+            .unwrap()
+            .map(|items| items.into_iter().map(|item| item.label).collect())
+    }
+
+    #[test]
+    fn test_switch_match_arg_default() {
+        let labels =
+            switch_labels(r#"f <- function(type = c("a", "b")) switch(match.arg(type), "@")"#);
+        assert_eq!(labels, Some(vec![String::from("a"), String::from("b")]));
+
+        // Case labels supplied as argument names
+        let labels = switch_labels(
+            r#"f <- function(x, type = c("a", "b")) {
+  switch(match.arg(type), a = 1, "@" = 2)
+}"#,
+        );
+        assert_eq!(labels, Some(vec![String::from("a"), String::from("b")]));
+    }
+
+    #[test]
+    fn test_switch_match_arg_choices() {
+        let labels = switch_labels(r#"switch(match.arg(type, c("x", "y")), "@")"#);
+        assert_eq!(labels, Some(vec![String::from("x"), String::from("y")]));
+    }
+
+    #[test]
+    fn test_switch_unknown_subject() {
+        assert_eq!(switch_labels(r#"switch(type, "@")"#), None);
+        assert_eq!(switch_labels(r#"switch("@", "a")"#), None);
+        assert_eq!(switch_labels(r#"f(match.arg(type), "@")"#), None);
+    }
+}
@@ -5,6 +5,7 @@
 //
 //
 
+use harp::environment::Environment;
 use harp::error::Error;
 use harp::eval::RParseEvalOptions;
 use harp::exec::RFunction;
@@ -194,9 +195,17 @@ pub(super) fn completions_from_evaluated_object_names(
 ) -> anyhow::Result<Option<Vec<CompletionItem>>> {
     log::trace!("completions_from_evaluated_object_names({name:?})");
 
+    let env = console::selected_env();
+
+    // Cheap check for the common case of a plain symbol that isn't bound
+    // (e.g. pseudocode), saves a parse and an R error round trip
+    if harp::syntax::is_valid_symbol(name) && !Environment::view(env.sexp).exists_inherited(name) {
+        return Ok(None);
+    }
+
     let options = RParseEvalOptions {
         forbid_function_calls: true,
-        env: console::selected_env(),
+        env,
     };
 
     // Try to evaluate the object
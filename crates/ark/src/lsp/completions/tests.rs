@@ -5,4 +5,5 @@
 //
 
 mod function_completions;
+mod resolve_completions;
 pub(crate) mod utils;
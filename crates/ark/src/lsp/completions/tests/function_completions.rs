@@ -12,6 +12,7 @@ mod function_call_tests {
     use crate::lsp::completions::tests::utils::assert_has_parameter_hints;
     use crate::lsp::completions::tests::utils::find_completion_by_label;
     use crate::lsp::completions::tests::utils::get_completions_at_cursor;
+    use crate::lsp::completions::tests::utils::get_snippet_completions_at_cursor;
     use crate::r_task;
 
     #[test]
@@ -288,6 +289,30 @@ mod empty_args_tests {
             assert_text_edit(item, "adist(");
         });
     }
+
+    #[test]
+    fn test_call_with_required_argument_placeholders() {
+        r_task(|| {
+            harp::parse_eval_global("my_snippet_fun <- function(x, y = 1, ...) NULL").unwrap();
+
+            // Only the argument without a default gets a placeholder
+            let completions = get_snippet_completions_at_cursor("my_snippet_f@").unwrap();
+            let item = find_completion_by_label(&completions, "my_snippet_fun").unwrap();
+            assert_eq!(
+                item.insert_text,
+                Some("my_snippet_fun(${1:x})$0".to_string())
+            );
+            assert_eq!(item.insert_text_format, Some(InsertTextFormat::SNIPPET));
+            assert_has_parameter_hints(item);
+
+            // Clients without snippet support get the plain call
+            let completions = get_completions_at_cursor("my_snippet_f@").unwrap();
+            let item = find_completion_by_label(&completions, "my_snippet_fun").unwrap();
+            assert_eq!(item.insert_text, Some("my_snippet_fun($0)".to_string()));
+
+            harp::parse_eval_global("rm(my_snippet_fun)").unwrap();
+        });
+    }
 }
 
 #[cfg(test)]
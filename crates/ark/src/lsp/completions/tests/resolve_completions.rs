@@ -0,0 +1,65 @@
+//
+// resolve_completions.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+
+#[cfg(test)]
+mod resolve_tests {
+    use tower_lsp::lsp_types::CompletionItem;
+    use tower_lsp::lsp_types::Documentation;
+
+    use crate::lsp::completions::resolve_completion;
+    use crate::lsp::completions::tests::utils::find_completion_by_label;
+    use crate::lsp::completions::tests::utils::get_completions_at_cursor;
+    use crate::r_task;
+
+    fn completion(cursor_text: &str, label: &str) -> CompletionItem {
+        let completions = get_completions_at_cursor(cursor_text).unwrap();
+        find_completion_by_label(&completions, label)
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn test_function_documentation_is_resolved_lazily() {
+        r_task(|| {
+            let mut item = completion("abbrev@", "abbreviate");
+
+            // Help is only fetched on resolve, but the key to find it is there
+            assert!(item.documentation.is_none());
+            assert!(item.data.is_some());
+
+            assert!(resolve_completion(&mut item).unwrap());
+            assert!(item.documentation.is_some());
+        });
+    }
+
+    #[test]
+    fn test_parameter_documentation_is_resolved_lazily() {
+        r_task(|| {
+            let mut item = completion("abbreviate(minl@)", "minlength = ");
+
+            assert!(item.documentation.is_none());
+            assert!(item.detail.is_none());
+
+            assert!(resolve_completion(&mut item).unwrap());
+            assert!(item.documentation.is_some());
+            assert_eq!(item.detail, Some(String::from("abbreviate()")));
+        });
+    }
+
+    #[test]
+    fn test_package_description_is_resolved_lazily() {
+        r_task(|| {
+            let mut item = completion("library(uti@)", "utils");
+            assert!(item.documentation.is_none());
+
+            assert!(resolve_completion(&mut item).unwrap());
+            let Some(Documentation::MarkupContent(markup)) = item.documentation else {
+                panic!("Expected markup documentation");
+            };
+            assert!(markup.value.starts_with("**The R Utils Package**"));
+        });
+    }
+}
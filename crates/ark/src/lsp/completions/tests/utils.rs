@@ -15,12 +15,26 @@ use crate::lsp::document_context::TestDocument;
 use crate::lsp::state::WorldState;
 
 pub(crate) fn get_completions_at_cursor(cursor_text: &str) -> anyhow::Result<Vec<CompletionItem>> {
+    get_completions_at_cursor_impl(cursor_text, false)
+}
+
+/// Like [get_completions_at_cursor()] for a client that supports snippets
+pub(crate) fn get_snippet_completions_at_cursor(
+    cursor_text: &str,
+) -> anyhow::Result<Vec<CompletionItem>> {
+    get_completions_at_cursor_impl(cursor_text, true)
+}
+
+fn get_completions_at_cursor_impl(
+    cursor_text: &str,
+    snippet_support: bool,
+) -> anyhow::Result<Vec<CompletionItem>> {
     let (text, point) = point_from_cursor(cursor_text);
     let doc = TestDocument::new(&text);
     let document_context = doc.context(point);
     let state = WorldState::default();
 
-    match provide_completions(&document_context, &state) {
+    match provide_completions(&document_context, &state, snippet_support) {
         Ok(completions) => Ok(completions),
         Err(err) => Err(anyhow::anyhow!("Failed to get completions: {err}")),
     }
@@ -10,6 +10,8 @@ use std::path::PathBuf;
 use serde::Deserialize;
 use serde::Serialize;
 
+/// Stored in the `data` field of completion items so we can identify them in
+/// `completionItem/resolve`, see [super::resolve::resolve_completion()]
 #[derive(Serialize, Deserialize, Debug)]
 pub(super) enum CompletionData {
     DataVariable {
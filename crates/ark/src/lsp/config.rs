@@ -43,6 +43,30 @@ pub static GLOBAL_SETTINGS: &[Setting<LspConfig>] = &[
                 .unwrap_or_else(|| WorkspaceSymbolsConfig::default().include_comment_sections)
         },
     },
+    Setting {
+        key: "positron.r.codeLens.enable",
+        set: |cfg, v| {
+            cfg.code_lens.enable = v
+                .as_bool()
+                .unwrap_or_else(|| CodeLensConfig::default().enable)
+        },
+    },
+    Setting {
+        key: "positron.r.semanticHighlighting.enable",
+        set: |cfg, v| {
+            cfg.semantic_tokens.enable = v
+                .as_bool()
+                .unwrap_or_else(|| SemanticTokensConfig::default().enable)
+        },
+    },
+    Setting {
+        key: "positron.r.completions.packageExports",
+        set: |cfg, v| {
+            cfg.completions.package_exports = v
+                .as_bool()
+                .unwrap_or_else(|| CompletionsConfig::default().package_exports)
+        },
+    },
 ];
 
 /// These document settings are updated on a URI basis. Each document has its
@@ -88,6 +112,9 @@ pub(crate) struct LspConfig {
     pub(crate) diagnostics: DiagnosticsConfig,
     pub(crate) symbols: SymbolsConfig,
     pub(crate) workspace_symbols: WorkspaceSymbolsConfig,
+    pub(crate) completions: CompletionsConfig,
+    pub(crate) code_lens: CodeLensConfig,
+    pub(crate) semantic_tokens: SemanticTokensConfig,
 
     /// Session-wide position encoding for offset <-> LSP-position conversion.
     /// One value for the whole session, not per document. Hard-coded to UTF-16,
@@ -101,6 +128,9 @@ impl Default for LspConfig {
             diagnostics: DiagnosticsConfig::default(),
             symbols: SymbolsConfig::default(),
             workspace_symbols: WorkspaceSymbolsConfig::default(),
+            completions: CompletionsConfig::default(),
+            code_lens: CodeLensConfig::default(),
+            semantic_tokens: SemanticTokensConfig::default(),
             position_encoding: PositionEncoding::Wide(WideEncoding::Utf16),
         }
     }
@@ -118,6 +148,25 @@ pub struct WorkspaceSymbolsConfig {
     pub include_comment_sections: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CompletionsConfig {
+    /// Whether to complete exports of installed packages that aren't attached,
+    /// inserting them as `pkg::name`.
+    pub package_exports: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CodeLensConfig {
+    /// Whether to show "Run" lenses above top-level expressions.
+    pub enable: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SemanticTokensConfig {
+    /// Whether to provide semantic tokens for syntax highlighting.
+    pub enable: bool,
+}
+
 /// Configuration of a document.
 ///
 /// The naming follows <https://editorconfig.org/> where possible.
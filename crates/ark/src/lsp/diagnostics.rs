@@ -19,6 +19,7 @@ use oak_db::File;
 use stdext::*;
 use tower_lsp::lsp_types::Diagnostic;
 use tower_lsp::lsp_types::DiagnosticSeverity;
+use tower_lsp::lsp_types::DiagnosticTag;
 use tree_sitter::Node;
 use tree_sitter::Point;
 use tree_sitter::Range;
@@ -33,6 +34,7 @@ use crate::lsp::open_file::lsp_range_from_tree_sitter_range;
 use crate::lsp::state::WorldState;
 use crate::lsp::traits::node::NodeExt;
 use crate::treesitter::node_has_error_or_missing;
+use crate::treesitter::node_is_call;
 use crate::treesitter::BinaryOperatorType;
 use crate::treesitter::NodeType;
 use crate::treesitter::NodeTypeExt;
@@ -752,14 +754,67 @@ fn recurse_braced_expression(
 ) -> Result<()> {
     // Recurse into body statements.
     let mut cursor = node.walk();
+    let body: Vec<Node> = node.children_by_field_name("body", &mut cursor).collect();
 
-    for child in node.children_by_field_name("body", &mut cursor) {
+    for &child in &body {
         recurse(child, context, diagnostics)?;
     }
 
+    check_unreachable_code(&body, context, diagnostics)?;
+
+    ().ok()
+}
+
+/// Flag the statements of a block that follow an unconditional `return()`,
+/// `stop()`, or `q()`. Only calls that are statements of the block itself
+/// are considered, so e.g. a `return()` inside an `if` branch never makes
+/// the rest of the enclosing block unreachable.
+fn check_unreachable_code(
+    body: &[Node],
+    context: &mut DiagnosticContext,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<()> {
+    let contents = context.contents();
+
+    let Some(terminal) = body
+        .iter()
+        .position(|statement| is_terminal_call(statement, contents))
+    else {
+        return ().ok();
+    };
+
+    let unreachable = &body[terminal + 1..];
+    let (Some(first), Some(last)) = (unreachable.first(), unreachable.last()) else {
+        return ().ok();
+    };
+
+    let range = Range {
+        start_byte: first.start_byte(),
+        end_byte: last.end_byte(),
+        start_point: first.start_position(),
+        end_point: last.end_position(),
+    };
+    let range = lsp_range_from_tree_sitter_range(
+        range,
+        context.file.line_index(context.db),
+        context.encoding,
+    )?;
+
+    let mut diagnostic = Diagnostic::new_simple(range, "Unreachable code.".into());
+    diagnostic.severity = Some(DiagnosticSeverity::HINT);
+    diagnostic.tags = Some(vec![DiagnosticTag::UNNECESSARY]);
+    diagnostics.push(diagnostic);
+
     ().ok()
 }
 
+/// Is `node` a call that never returns to the caller?
+fn is_terminal_call(node: &Node, contents: &str) -> bool {
+    ["return", "stop", "q", "quit"]
+        .iter()
+        .any(|name| node_is_call(node, name, contents))
+}
+
 fn recurse_parenthesized_expression(
     node: Node,
     context: &mut DiagnosticContext,
@@ -1027,7 +1082,7 @@ fn dispatch(node: Node, context: &mut DiagnosticContext, diagnostics: &mut Vec<D
     let result: Result<bool> = local! {
         check_invalid_na_comparison(node, context, diagnostics)?;
         check_symbol_in_scope(node, context, diagnostics)?;
-        check_unexpected_assignment_in_if_conditional(node, context, diagnostics)?;
+        check_unexpected_assignment_in_conditional(node, context, diagnostics)?;
         true.ok()
     };
 
@@ -1077,8 +1132,8 @@ fn check_invalid_na_comparison(
     true.ok()
 }
 
-// TODO: Move this to `recurse_if()` and get it out of `dispatch()`
-fn check_unexpected_assignment_in_if_conditional(
+// TODO: Move this to `recurse_if()` and `recurse_while()` and get it out of `dispatch()`
+fn check_unexpected_assignment_in_conditional(
     node: Node,
     context: &mut DiagnosticContext,
     diagnostics: &mut Vec<Diagnostic>,
@@ -1088,7 +1143,10 @@ fn check_unexpected_assignment_in_if_conditional(
         return false.ok();
     }
 
-    if node.node_type() != NodeType::IfStatement {
+    if !matches!(
+        node.node_type(),
+        NodeType::IfStatement | NodeType::WhileStatement
+    ) {
         return false.ok();
     }
 
@@ -1096,23 +1154,53 @@ fn check_unexpected_assignment_in_if_conditional(
         return false.ok();
     });
 
-    if condition.node_type() != NodeType::BinaryOperator(BinaryOperatorType::EqualsAssignment) {
+    if !is_assignment_in_conditional(&condition) {
         return false.ok();
     }
 
+    let operator = unwrap!(condition.child_by_field_name("operator"), None => {
+        return false.ok();
+    });
+    let operator = operator.node_as_str(context.contents())?;
+
     let range = condition.range();
     let range = lsp_range_from_tree_sitter_range(
         range,
         context.file.line_index(context.db),
         context.encoding,
     )?;
-    let message = "Unexpected '='; use '==' to compare values for equality.";
-    let diagnostic = Diagnostic::new_simple(range, message.into());
+    let message = format!("Unexpected '{operator}'; use '==' to compare values for equality.");
+    let diagnostic = Diagnostic::new_simple(range, message);
     diagnostics.push(diagnostic);
 
     true.ok()
 }
 
+/// Is `node` an `=` or `<-` assignment that is the condition of an `if` or
+/// `while` statement? These are usually typos for `==`.
+pub(crate) fn is_assignment_in_conditional(node: &Node) -> bool {
+    if !node.is_binary_operator_of_kind(BinaryOperatorType::EqualsAssignment) &&
+        !node.is_binary_operator_of_kind(BinaryOperatorType::LeftAssignment)
+    {
+        return false;
+    }
+
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+
+    if !matches!(
+        parent.node_type(),
+        NodeType::IfStatement | NodeType::WhileStatement
+    ) {
+        return false;
+    }
+
+    parent
+        .child_by_field_name("condition")
+        .is_some_and(|condition| condition == *node)
+}
+
 // TODO: Move this to `recurse_identifier()` and get it out of `dispatch()`
 fn check_symbol_in_scope(
     node: Node,
@@ -1786,4 +1874,80 @@ foo
         let diagnostics = generate_diagnostics(code, state);
         assert_eq!(diagnostics.len(), 3);
     }
+
+    #[test]
+    fn test_assignment_in_conditional() {
+        r_task(|| {
+            let text = "
+x <- 1
+if (x <- 1) {}
+while (x = 2) {}
+";
+            let diagnostics = generate_diagnostics(text, current_state());
+            assert_eq!(diagnostics.len(), 2);
+
+            let diagnostic = diagnostics.first().unwrap();
+            assert_eq!(
+                diagnostic.message,
+                "Unexpected '<-'; use '==' to compare values for equality."
+            );
+            assert_eq!(diagnostic.range.start, Position::new(2, 4));
+            assert_eq!(diagnostic.range.end, Position::new(2, 10));
+
+            let diagnostic = diagnostics.get(1).unwrap();
+            assert_eq!(
+                diagnostic.message,
+                "Unexpected '='; use '==' to compare values for equality."
+            );
+
+            // Comparisons and nested assignments are fine
+            let text = "
+x <- 1
+if (x == 1) {}
+while (isTRUE(y <- x)) {}
+";
+            let diagnostics = generate_diagnostics(text, current_state());
+            assert!(diagnostics.is_empty());
+        })
+    }
+
+    #[test]
+    fn test_unreachable_code() {
+        r_task(|| {
+            let text = "
+function(x) {
+  return(x)
+  x <- 1
+  x
+}
+";
+            let diagnostics = generate_diagnostics(text, current_state());
+            assert_eq!(diagnostics.len(), 1);
+
+            let diagnostic = diagnostics.first().unwrap();
+            assert_eq!(diagnostic.message, "Unreachable code.");
+            assert_eq!(
+                diagnostic.severity,
+                Some(lsp_types::DiagnosticSeverity::HINT)
+            );
+            assert_eq!(diagnostic.range.start, Position::new(3, 2));
+            assert_eq!(diagnostic.range.end, Position::new(4, 3));
+
+            // Terminal calls in branches, or as the last statement, are fine
+            let text = "
+function(x) {
+  if (x) return(x)
+  stop(\"nope\")
+}
+function(x) {
+  if (x) {
+    return(x)
+  }
+  x
+}
+";
+            let diagnostics = generate_diagnostics(text, current_state());
+            assert!(diagnostics.is_empty());
+        })
+    }
 }
@@ -0,0 +1,535 @@
+//
+// formatting.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::collections::HashMap;
+
+use oak_db::File;
+use tree_sitter::Node;
+use tree_sitter::Tree;
+
+use crate::lsp::config::IndentationConfig;
+use crate::lsp::db::ArkDb;
+use crate::lsp::db::FileArkExt;
+use crate::lsp::indent::line_text_indent;
+use crate::lsp::indent::new_line_indent;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::node_has_error_or_missing;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+
+/// Format a whole document
+///
+/// This is a conservative formatter based on the tree-sitter tree. It never
+/// reflows code, i.e. line breaks are never added or removed. It only
+/// normalises whitespace between tokens that live on the same line and
+/// reindents lines:
+///
+/// - One space around binary operators and assignments, except for `^` and
+///   `:`. No space around `::`, `:::`, `$`, and `@`, nor after unary
+///   operators.
+/// - No space before commas and one space after.
+/// - No space inside `()` and `[]` delimiters, nor between a function and its
+///   arguments.
+/// - One space between `if`/`for`/`while` and their condition, and before an
+///   opening brace that follows `)`, `else`, or `repeat`.
+/// - Lines are indented by one level within braces, hanging delimiters,
+///   multiline binary operations (e.g. pipelines), and unbraced bodies.
+///   Arguments aligned with their opening delimiter keep their alignment.
+///
+/// Strings and comments are never modified, and neither is the whitespace
+/// preceding a comment.
+///
+/// Returns `None` if the document contains syntax errors or if formatting
+/// wouldn't change it.
+pub(crate) fn format_document(
+    db: &dyn ArkDb,
+    file: File,
+    config: &IndentationConfig,
+) -> Option<String> {
+    let text = file.source_text(db);
+    let formatted = format_text(&text, file.tree_sitter(db), config)?;
+    (formatted != text).then_some(formatted)
+}
+
+pub(crate) fn format_text(text: &str, tree: &Tree, config: &IndentationConfig) -> Option<String> {
+    let root = tree.root_node();
+
+    // Don't try to make sense of incomplete code
+    if node_has_error_or_missing(&root) {
+        return None;
+    }
+
+    let mut tokens = Vec::new();
+    collect_tokens(root, &mut tokens);
+
+    let lines: Vec<&str> = text.split('\n').collect();
+    let n_lines = lines.len();
+
+    let mut line_tokens: Vec<Vec<Node>> = vec![Vec::new(); n_lines];
+    let mut starts_in_token = vec![false; n_lines];
+
+    for token in tokens {
+        let start = token.start_position().row;
+        let end = token.end_position().row;
+
+        // Only strings can span multiple lines. Lines starting inside a
+        // string are left untouched.
+        for row in (start + 1)..=end.min(n_lines - 1) {
+            starts_in_token[row] = true;
+        }
+
+        if let Some(line_tokens) = line_tokens.get_mut(start) {
+            line_tokens.push(token);
+        }
+    }
+
+    let mut formatter = Formatter {
+        config,
+        lines: &lines,
+        old_indents: vec![0; n_lines],
+        new_indents: vec![0; n_lines],
+        new_columns: HashMap::new(),
+    };
+
+    let mut out = Vec::with_capacity(n_lines);
+
+    for (line, text) in lines.iter().enumerate() {
+        // Preserve Windows line endings
+        let (text, eol) = match text.strip_suffix('\r') {
+            Some(text) => (text, "\r"),
+            None => (*text, ""),
+        };
+
+        let old_indent = line_text_indent(text, config).0;
+        formatter.old_indents[line] = old_indent;
+
+        if starts_in_token[line] {
+            formatter.new_indents[line] = old_indent;
+            out.push(format!("{text}{eol}"));
+            continue;
+        }
+
+        // Whitespace-only lines are emptied
+        let Some(first) = line_tokens[line].first() else {
+            out.push(String::from(eol));
+            continue;
+        };
+
+        let new_indent = formatter.indent(line, *first);
+        formatter.new_indents[line] = new_indent;
+
+        let content = formatter.line_content(line, text, &line_tokens[line], new_indent);
+        let indent = new_line_indent(config, new_indent);
+
+        out.push(format!("{indent}{content}{eol}"));
+    }
+
+    Some(out.join("\n"))
+}
+
+struct Formatter<'a> {
+    config: &'a IndentationConfig,
+    lines: &'a [&'a str],
+
+    /// Indentation of each line, before and after formatting
+    old_indents: Vec<usize>,
+    new_indents: Vec<usize>,
+
+    /// Column of each token after formatting, keyed by start byte
+    new_columns: HashMap<usize, usize>,
+}
+
+impl Formatter<'_> {
+    /// Compute the indentation of `line`, whose first token is `token`.
+    ///
+    /// Indentation is anchored on the formatted indentation of a previous
+    /// line, which is why lines must be formatted in order.
+    fn indent(&self, line: usize, token: Node) -> usize {
+        let size = self.config.indent_size;
+
+        for node in token.ancestors().skip(1) {
+            if node.start_position().row >= line {
+                continue;
+            }
+
+            match node.node_type() {
+                NodeType::Program => return 0,
+
+                NodeType::BracedExpression => {
+                    let (Some(open), Some(close)) = delimiters(node) else {
+                        continue;
+                    };
+                    let anchor = self.new_indents[open.start_position().row];
+
+                    if token == close {
+                        return anchor;
+                    }
+                    return anchor + size;
+                },
+
+                NodeType::Arguments | NodeType::Parameters | NodeType::ParenthesizedExpression => {
+                    let (Some(open), Some(close)) = delimiters(node) else {
+                        continue;
+                    };
+                    let open_row = open.start_position().row;
+
+                    let hanging = open
+                        .next_sibling()
+                        .is_none_or(|next| next.start_position().row > open_row);
+
+                    if hanging {
+                        let anchor = self.new_indents[open_row];
+
+                        if token == close {
+                            return anchor;
+                        }
+                        return anchor + size;
+                    }
+
+                    // Arguments are aligned with the opening delimiter. Keep
+                    // them aligned by shifting them as much as the delimiter
+                    // was moved.
+                    let Some(new_column) = self.new_columns.get(&open.start_byte()) else {
+                        return self.old_indents[line];
+                    };
+                    let old_column = self.old_column(open);
+                    let indent = self.old_indents[line] + new_column;
+                    return indent.saturating_sub(old_column);
+                },
+
+                NodeType::BinaryOperator(_) => {
+                    // Indent relative to the start of the whole chain of
+                    // operators to avoid a staircase effect in pipelines
+                    let top = node
+                        .ancestors()
+                        .take_while(|node| node.is_binary_operator())
+                        .last()
+                        .unwrap_or(node);
+
+                    return self.new_indents[top.start_position().row] + size;
+                },
+
+                NodeType::FunctionDefinition |
+                NodeType::IfStatement |
+                NodeType::ForStatement |
+                NodeType::WhileStatement |
+                NodeType::RepeatStatement => {
+                    let anchor = self.new_indents[node.start_position().row];
+
+                    // Opening braces on their own line and `else` keywords
+                    // line up with the start of the statement
+                    if matches!(token.kind(), "{" | "else") {
+                        return anchor;
+                    }
+                    return anchor + size;
+                },
+
+                _ => continue,
+            }
+        }
+
+        0
+    }
+
+    /// Rebuild the text of `line` (without indentation) from its tokens,
+    /// normalising the whitespace between them
+    fn line_content(
+        &mut self,
+        line: usize,
+        text: &str,
+        tokens: &[Node],
+        new_indent: usize,
+    ) -> String {
+        let mut content = String::new();
+        let mut prev: Option<Node> = None;
+
+        for token in tokens.iter().copied() {
+            if let Some(prev) = prev {
+                let gap = &text[prev.end_position().column..token.start_position().column];
+
+                if gap.chars().all(char::is_whitespace) {
+                    content.push_str(token_spacing(prev, token).unwrap_or(gap));
+                } else {
+                    content.push_str(gap);
+                }
+            }
+
+            self.new_columns
+                .insert(token.start_byte(), new_indent + content.chars().count());
+
+            let start = token.start_position().column;
+            let token_text = if token.end_position().row == line {
+                &text[start..token.end_position().column]
+            } else {
+                // Multiline string, take the rest of the line
+                &text[start..]
+            };
+            content.push_str(token_text);
+
+            prev = Some(token);
+        }
+
+        content
+    }
+
+    fn old_column(&self, node: Node) -> usize {
+        let point = node.start_position();
+        let text = self.lines[point.row];
+        let text = &text[..point.column.min(text.len())];
+
+        text.chars()
+            .map(|c| if c == '\t' { self.config.tab_width } else { 1 })
+            .sum()
+    }
+}
+
+/// Collect leaf tokens in document order. Strings and comments are
+/// collected as single tokens since we never modify them.
+fn collect_tokens<'tree>(node: Node<'tree>, tokens: &mut Vec<Node<'tree>>) {
+    if node.child_count() == 0 || node.is_string() || node.is_comment() {
+        // Skip zero-width tokens
+        if node.end_byte() > node.start_byte() {
+            tokens.push(node);
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_tokens(child, tokens);
+    }
+}
+
+fn delimiters(node: Node) -> (Option<Node>, Option<Node>) {
+    let n = node.child_count();
+    if n < 2 {
+        return (None, None);
+    }
+    (node.child(0), node.child(n - 1))
+}
+
+fn is_comma(node: Node) -> bool {
+    matches!(node.kind(), "comma" | ",")
+}
+
+fn is_open_delimiter(node: Node) -> bool {
+    matches!(node.kind(), "(" | "[" | "[[")
+}
+
+fn is_close_delimiter(node: Node) -> bool {
+    matches!(node.kind(), ")" | "]" | "]]")
+}
+
+/// Is `node` the operator token of its parent?
+fn operator_parent(node: Node) -> Option<Node> {
+    let parent = node.parent()?;
+    (parent.child_by_field_name("operator") == Some(node)).then_some(parent)
+}
+
+/// Whitespace to insert between two tokens on the same line, or `None` to
+/// keep the existing whitespace
+fn token_spacing(prev: Node, next: Node) -> Option<&'static str> {
+    // Don't touch the space before comments as it is often used to align them
+    if next.is_comment() {
+        return None;
+    }
+
+    if is_comma(next) {
+        return Some("");
+    }
+    if is_comma(prev) {
+        return Some(" ");
+    }
+
+    if is_open_delimiter(prev) || is_close_delimiter(next) {
+        return Some("");
+    }
+
+    if is_open_delimiter(next) {
+        // `if (`, `for (`, `while (`
+        if matches!(prev.kind(), "if" | "for" | "while") {
+            return Some(" ");
+        }
+
+        // `fn(`, `x[`, `function(`, `\(`
+        if next
+            .parent()
+            .is_some_and(|parent| matches!(parent.kind(), "arguments" | "parameters"))
+        {
+            return Some("");
+        }
+    }
+
+    if next.kind() == "{" && matches!(prev.kind(), ")" | "else" | "repeat") {
+        return Some(" ");
+    }
+    if prev.kind() == "}" && next.kind() == "else" {
+        return Some(" ");
+    }
+    if prev.kind() == "else" {
+        return Some(" ");
+    }
+
+    if let Some(spacing) = operator_spacing(prev, next) {
+        return Some(spacing);
+    }
+
+    // `fn(x = 1)` and `function(x = 1)`
+    let is_argument_equals = |node: Node| {
+        node.kind() == "=" &&
+            node.parent()
+                .is_some_and(|parent| matches!(parent.kind(), "argument" | "parameter"))
+    };
+    if is_argument_equals(prev) || is_argument_equals(next) {
+        return Some(" ");
+    }
+
+    None
+}
+
+fn operator_spacing(prev: Node, next: Node) -> Option<&'static str> {
+    // Spacing after an operator
+    if let Some(parent) = operator_parent(prev) {
+        match parent.node_type() {
+            NodeType::UnaryOperator(_) => return Some(""),
+            NodeType::BinaryOperator(_) => return Some(binary_operator_spacing(prev)),
+            NodeType::ExtractOperator(_) | NodeType::NamespaceOperator(_) => return Some(""),
+            _ => {},
+        }
+    }
+
+    // Spacing before an operator. Unary operators are spaced according to
+    // their previous token.
+    if let Some(parent) = operator_parent(next) {
+        match parent.node_type() {
+            NodeType::BinaryOperator(_) => return Some(binary_operator_spacing(next)),
+            NodeType::ExtractOperator(_) | NodeType::NamespaceOperator(_) => return Some(""),
+            _ => {},
+        }
+    }
+
+    None
+}
+
+fn binary_operator_spacing(operator: Node) -> &'static str {
+    match operator.kind() {
+        "^" | ":" => "",
+        _ => " ",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lsp::config::IndentStyle;
+    use crate::lsp::config::IndentationConfig;
+    use crate::lsp::db::parse_tree_sitter;
+    use crate::lsp::formatting::format_text;
+
+    fn format(text: &str) -> String {
+        format_with(text, &IndentationConfig::default())
+    }
+
+    fn format_with(text: &str, config: &IndentationConfig) -> String {
+        let tree = parse_tree_sitter(text);
+        format_text(text, &tree, config).unwrap()
+    }
+
+    #[test]
+    fn test_format_operators() {
+        assert_eq!(format("x<-1+2*3"), "x <- 1 + 2 * 3");
+        assert_eq!(format("y   =   x|>f()"), "y = x |> f()");
+        assert_eq!(format("x^2 + 1 : 10"), "x^2 + 1:10");
+        assert_eq!(format("- x + ! y"), "-x + !y");
+        assert_eq!(format("x $ y + pkg :: fn"), "x$y + pkg::fn");
+        assert_eq!(format("x%>%f"), "x %>% f");
+    }
+
+    #[test]
+    fn test_format_commas_and_delimiters() {
+        assert_eq!(format("f ( a,b ,c )"), "f(a, b, c)");
+        assert_eq!(format("x[ 1,]"), "x[1, ]");
+        assert_eq!(format("x[,1]"), "x[, 1]");
+        assert_eq!(format("f(x=1, y  =  2)"), "f(x = 1, y = 2)");
+        assert_eq!(format("function (x=1){ x }"), "function(x = 1) { x }");
+    }
+
+    #[test]
+    fn test_format_control_flow() {
+        assert_eq!(
+            format("if(x){\ny\n}else{\nz\n}"),
+            "if (x) {\n  y\n} else {\n  z\n}"
+        );
+        assert_eq!(
+            format("for(i in 1:10){\nf(i)\n}"),
+            "for (i in 1:10) {\n  f(i)\n}"
+        );
+        assert_eq!(format("while(TRUE)\nf()"), "while (TRUE)\n  f()");
+    }
+
+    #[test]
+    fn test_format_indentation() {
+        assert_eq!(
+            format("f <- function() {\n      x\n    if (y) {\nz\n        }\n}"),
+            "f <- function() {\n  x\n  if (y) {\n    z\n  }\n}"
+        );
+
+        // Pipelines are indented once relative to the start of the chain
+        assert_eq!(format("x |>\nf() |>\n        g()"), "x |>\n  f() |>\n  g()");
+
+        // Hanging arguments
+        assert_eq!(format("f(\na,\n b\n    )"), "f(\n  a,\n  b\n)");
+
+        // Aligned arguments stay aligned when the delimiter moves
+        assert_eq!(format("x<-foo(a,\n       b)"), "x <- foo(a,\n         b)");
+
+        // Opening brace on its own line
+        assert_eq!(
+            format("f <- function()\n{\nbody\n}"),
+            "f <- function()\n{\n  body\n}"
+        );
+    }
+
+    #[test]
+    fn test_format_preserves_strings_and_comments() {
+        assert_eq!(
+            format("x<-'a  ,b'  # a  ,  comment"),
+            "x <- 'a  ,b'  # a  ,  comment"
+        );
+
+        // Multiline strings are left untouched, including trailing whitespace
+        assert_eq!(
+            format("{\nx <- \"a  \n    b\"\n}"),
+            "{\n  x <- \"a  \n    b\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_format_whitespace_lines() {
+        assert_eq!(format("x <- 1   \n   \ny"), "x <- 1\n\ny");
+        assert_eq!(format("x\r\n  y\r\n"), "x\r\ny\r\n");
+    }
+
+    #[test]
+    fn test_format_tabs() {
+        let config = IndentationConfig {
+            indent_style: IndentStyle::Tab,
+            indent_size: 4,
+            tab_width: 4,
+        };
+        assert_eq!(
+            format_with("{\n  x\n{\ny\n}\n}", &config),
+            "{\n\tx\n\t{\n\t\ty\n\t}\n}"
+        );
+    }
+
+    #[test]
+    fn test_format_syntax_error() {
+        let text = "f(x<-1";
+        let tree = parse_tree_sitter(text);
+        assert!(format_text(text, &tree, &IndentationConfig::default()).is_none());
+    }
+}
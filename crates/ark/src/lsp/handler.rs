@@ -22,6 +22,7 @@ use tokio::sync::mpsc::UnboundedSender as AsyncUnboundedSender;
 use super::backend;
 use crate::console::ConsoleNotification;
 use crate::console::KernelInfo;
+use crate::request::RRequest;
 
 pub(crate) struct Lsp {
     r_home: PathBuf,
@@ -29,6 +30,7 @@ pub(crate) struct Lsp {
     kernel_init_rx: BusReader<KernelInfo>,
     kernel_initialized: bool,
     console_notification_tx: AsyncUnboundedSender<ConsoleNotification>,
+    r_request_tx: Sender<RRequest>,
 }
 
 impl Lsp {
@@ -36,6 +38,7 @@ impl Lsp {
         r_home: PathBuf,
         kernel_init_rx: BusReader<KernelInfo>,
         console_notification_tx: AsyncUnboundedSender<ConsoleNotification>,
+        r_request_tx: Sender<RRequest>,
     ) -> Self {
         let rt = Builder::new_multi_thread()
             .enable_all()
@@ -53,6 +56,7 @@ impl Lsp {
             kernel_init_rx,
             kernel_initialized: false,
             console_notification_tx,
+            r_request_tx,
         }
     }
 }
@@ -82,6 +86,7 @@ impl ServerHandler for Lsp {
 
         let r_home = self.r_home.clone();
         let console_notification_tx = self.console_notification_tx.clone();
+        let r_request_tx = self.r_request_tx.clone();
         spawn!("ark-lsp", move || {
             backend::start_lsp(
                 r_home,
@@ -89,6 +94,7 @@ impl ServerHandler for Lsp {
                 server_start,
                 server_started_tx,
                 console_notification_tx,
+                r_request_tx,
             )
         });
         Ok(())
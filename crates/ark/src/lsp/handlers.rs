@@ -6,18 +6,31 @@
 //
 
 use anyhow::anyhow;
+use crossbeam::channel::Sender;
 use serde_json::Value;
 use stdext::result::ResultExt;
 use stdext::unwrap;
+use tower_lsp::lsp_types::request::GotoTypeDefinitionParams;
+use tower_lsp::lsp_types::request::GotoTypeDefinitionResponse;
+use tower_lsp::lsp_types::CallHierarchyIncomingCall;
+use tower_lsp::lsp_types::CallHierarchyIncomingCallsParams;
+use tower_lsp::lsp_types::CallHierarchyItem;
+use tower_lsp::lsp_types::CallHierarchyOutgoingCall;
+use tower_lsp::lsp_types::CallHierarchyOutgoingCallsParams;
+use tower_lsp::lsp_types::CallHierarchyPrepareParams;
 use tower_lsp::lsp_types::CodeActionParams;
 use tower_lsp::lsp_types::CodeActionResponse;
+use tower_lsp::lsp_types::CodeLens;
+use tower_lsp::lsp_types::CodeLensParams;
 use tower_lsp::lsp_types::CompletionItem;
 use tower_lsp::lsp_types::CompletionParams;
 use tower_lsp::lsp_types::CompletionResponse;
 use tower_lsp::lsp_types::DidChangeWatchedFilesRegistrationOptions;
+use tower_lsp::lsp_types::DocumentFormattingParams;
 use tower_lsp::lsp_types::DocumentOnTypeFormattingParams;
 use tower_lsp::lsp_types::DocumentSymbolParams;
 use tower_lsp::lsp_types::DocumentSymbolResponse;
+use tower_lsp::lsp_types::ExecuteCommandParams;
 use tower_lsp::lsp_types::FileSystemWatcher;
 use tower_lsp::lsp_types::FoldingRange;
 use tower_lsp::lsp_types::FoldingRangeParams;
@@ -28,13 +41,15 @@ use tower_lsp::lsp_types::Hover;
 use tower_lsp::lsp_types::HoverContents;
 use tower_lsp::lsp_types::HoverParams;
 use tower_lsp::lsp_types::Location;
-use tower_lsp::lsp_types::MessageType;
 use tower_lsp::lsp_types::PrepareRenameResponse;
 use tower_lsp::lsp_types::ReferenceParams;
 use tower_lsp::lsp_types::Registration;
 use tower_lsp::lsp_types::RenameParams;
 use tower_lsp::lsp_types::SelectionRange;
 use tower_lsp::lsp_types::SelectionRangeParams;
+use tower_lsp::lsp_types::SemanticTokens;
+use tower_lsp::lsp_types::SemanticTokensParams;
+use tower_lsp::lsp_types::SemanticTokensResult;
 use tower_lsp::lsp_types::SignatureHelp;
 use tower_lsp::lsp_types::SignatureHelpParams;
 use tower_lsp::lsp_types::SymbolInformation;
@@ -49,19 +64,23 @@ use crate::analysis::input_boundaries::input_boundaries;
 use crate::lsp;
 use crate::lsp::backend::LspError;
 use crate::lsp::backend::LspResult;
+use crate::lsp::call_hierarchy;
 use crate::lsp::code_action::code_actions;
+use crate::lsp::code_lens::code_lenses;
+use crate::lsp::commands::execute_command;
 use crate::lsp::completions::provide_completions;
 use crate::lsp::completions::resolve_completion;
 use crate::lsp::db::FileArkExt;
 use crate::lsp::document_context::DocumentContext;
 use crate::lsp::find_references::find_references;
 use crate::lsp::folding_range::folding_range;
+use crate::lsp::formatting::format_document;
 use crate::lsp::goto_definition::goto_definition;
 use crate::lsp::help_topic::help_topic;
 use crate::lsp::help_topic::HelpTopicParams;
 use crate::lsp::help_topic::HelpTopicResponse;
 use crate::lsp::hover::r_hover;
-use crate::lsp::indent::indent_edit;
+use crate::lsp::indent::on_type_indent_edit;
 use crate::lsp::input_boundaries::InputBoundariesParams;
 use crate::lsp::input_boundaries::InputBoundariesResponse;
 use crate::lsp::main_loop::LspState;
@@ -71,13 +90,16 @@ use crate::lsp::open_file::tree_sitter_range_from_lsp_range;
 use crate::lsp::rename;
 use crate::lsp::selection_range::convert_selection_range_from_tree_sitter_to_lsp;
 use crate::lsp::selection_range::selection_range;
+use crate::lsp::semantic_tokens::semantic_tokens;
 use crate::lsp::signature_help::r_signature_help;
 use crate::lsp::state::WorldState;
 use crate::lsp::statement_range::statement_range;
 use crate::lsp::statement_range::StatementRangeParams;
 use crate::lsp::statement_range::StatementRangeResponse;
 use crate::lsp::symbols;
+use crate::lsp::type_definition::type_definition;
 use crate::r_task;
+use crate::request::RRequest;
 
 pub static ARK_VDOC_REQUEST: &str = "ark/internal/virtualDocument";
 
@@ -199,18 +221,68 @@ pub(crate) fn handle_folding_range(
     }
 }
 
-pub(crate) async fn handle_execute_command(client: &Client) -> LspResult<Option<Value>> {
-    match client.apply_edit(WorkspaceEdit::default()).await {
-        Ok(res) if res.applied => client.log_message(MessageType::INFO, "applied").await,
-        Ok(_) => client.log_message(MessageType::INFO, "rejected").await,
-        Err(err) => client.log_message(MessageType::ERROR, err).await,
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_semantic_tokens_full(
+    params: SemanticTokensParams,
+    state: &WorldState,
+) -> LspResult<Option<SemanticTokensResult>> {
+    if !state.config.semantic_tokens.enable {
+        return Ok(None);
+    }
+
+    let uri = &params.text_document.uri;
+    let file = state.open_file(uri)?.file();
+    let db = &state.db;
+    let encoding = state.config.position_encoding;
+
+    match semantic_tokens(db, file, encoding) {
+        Ok(data) => Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+            result_id: None,
+            data,
+        }))),
+        Err(err) => {
+            lsp::log_error!("{err:?}");
+            Ok(None)
+        },
+    }
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_code_lens(
+    params: CodeLensParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<CodeLens>>> {
+    if !state.config.code_lens.enable {
+        return Ok(None);
     }
+
+    let uri = &params.text_document.uri;
+    let file = state.open_file(uri)?.file();
+    let db = &state.db;
+    let encoding = state.config.position_encoding;
+
+    match code_lenses(db, file, uri, encoding) {
+        Ok(lenses) => Ok(Some(lenses)),
+        Err(err) => {
+            lsp::log_error!("{err:?}");
+            Ok(None)
+        },
+    }
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_execute_command(
+    params: ExecuteCommandParams,
+    r_request_tx: &Sender<RRequest>,
+) -> LspResult<Option<Value>> {
+    execute_command(&params, r_request_tx)?;
     Ok(None)
 }
 
 #[tracing::instrument(level = "info", skip_all)]
 pub(crate) fn handle_completion(
     params: CompletionParams,
+    lsp_state: &LspState,
     state: &WorldState,
 ) -> LspResult<Option<CompletionResponse>> {
     let uri = params.text_document_position.text_document.uri;
@@ -237,7 +309,8 @@ pub(crate) fn handle_completion(
     // sends the closure across threads, and `&WorldState` isn't `Send` because
     // `OakDatabase`'s salsa storage keeps thread-local query state.
     let state = state.clone();
-    let completions = r_task(move || provide_completions(&context, &state))?;
+    let snippet_support = lsp_state.capabilities.completion_snippet_support();
+    let completions = r_task(move || provide_completions(&context, &state, snippet_support))?;
 
     if !completions.is_empty() {
         Ok(Some(CompletionResponse::Array(completions)))
@@ -339,6 +412,44 @@ pub(crate) fn handle_goto_definition(
     Ok(goto_definition(params, state).log_err().flatten())
 }
 
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_type_definition(
+    params: GotoTypeDefinitionParams,
+    state: &WorldState,
+) -> LspResult<Option<GotoTypeDefinitionResponse>> {
+    Ok(type_definition(params, state).log_err().flatten())
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_prepare_call_hierarchy(
+    params: CallHierarchyPrepareParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<CallHierarchyItem>>> {
+    Ok(call_hierarchy::prepare_call_hierarchy(params, state)
+        .log_err()
+        .flatten())
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_incoming_calls(
+    params: CallHierarchyIncomingCallsParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<CallHierarchyIncomingCall>>> {
+    Ok(call_hierarchy::incoming_calls(params, state)
+        .log_err()
+        .flatten())
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_outgoing_calls(
+    params: CallHierarchyOutgoingCallsParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<CallHierarchyOutgoingCall>>> {
+    Ok(call_hierarchy::outgoing_calls(params, state)
+        .log_err()
+        .flatten())
+}
+
 #[tracing::instrument(level = "info", skip_all)]
 pub(crate) fn handle_selection_range(
     params: SelectionRangeParams,
@@ -438,6 +549,37 @@ pub(crate) fn handle_help_topic(
     help_topic(db, file, point)
 }
 
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn handle_formatting(
+    params: DocumentFormattingParams,
+    state: &WorldState,
+) -> LspResult<Option<Vec<TextEdit>>> {
+    let uri = &params.text_document.uri;
+    let open_file = state.open_file(uri)?;
+    let encoding = state.config.position_encoding;
+
+    let db = &state.db;
+    let Some(new_text) = format_document(db, open_file.file(), &open_file.config().indent) else {
+        return Ok(None);
+    };
+
+    // Replace the whole document
+    let text = open_file.source_text(db);
+    let last_line_start = text.rfind('\n').map_or(0, |i| i + 1);
+    let range = tree_sitter::Range {
+        start_byte: 0,
+        end_byte: text.len(),
+        start_point: tree_sitter::Point::new(0, 0),
+        end_point: tree_sitter::Point::new(
+            text.matches('\n').count(),
+            text.len() - last_line_start,
+        ),
+    };
+    let range = lsp_range_from_tree_sitter_range(range, open_file.line_index(db), encoding)?;
+
+    Ok(Some(vec![TextEdit { range, new_text }]))
+}
+
 #[tracing::instrument(level = "info", skip_all)]
 pub(crate) fn handle_indent(
     params: DocumentOnTypeFormattingParams,
@@ -452,7 +594,13 @@ pub(crate) fn handle_indent(
     let line_index = open_file.line_index(db);
     let point = tree_sitter_point_from_lsp_position(ctxt.position, line_index, encoding)?;
 
-    let Some(edits) = indent_edit(db, open_file.file(), &open_file.config().indent, point.row)?
+    let Some(edits) = on_type_indent_edit(
+        db,
+        open_file.file(),
+        &open_file.config().indent,
+        point.row,
+        &params.ch,
+    )?
     else {
         return Ok(None);
     };
@@ -23,6 +23,7 @@ use tower_lsp::lsp_types::MarkupKind;
 use crate::lsp::markdown::*;
 
 static RE_COMMA_SEPARATOR: Lazy<Regex> = Lazy::new(|| Regex::new("\\s*,\\s*").unwrap());
+static RE_TOPIC_PACKAGE: Lazy<Regex> = Lazy::new(|| Regex::new("\\{([^}]+)\\}").unwrap());
 
 pub struct RHtmlHelp {
     html: Html,
@@ -118,6 +119,35 @@ impl RHtmlHelp {
         Some(preamble)
     }
 
+    /// The package documenting this topic, from the `topic {package}` preamble
+    pub fn package(&self) -> Option<String> {
+        let topic = self.topic()?;
+        let captures = RE_TOPIC_PACKAGE.captures(&topic)?;
+        Some(captures[1].trim().to_string())
+    }
+
+    /// Topics linked from the "See Also" section, in order of appearance
+    pub fn see_also(&self) -> Vec<String> {
+        let mut topics: Vec<String> = Vec::new();
+        let links = Selector::parse("a").unwrap();
+
+        for_each_section(&self.html, |header, elements| {
+            if elt_text(header).trim() != "See Also" {
+                return;
+            }
+            for elt in elements {
+                for link in elt.select(&links) {
+                    let topic = elt_text(link).trim().to_string();
+                    if !topic.is_empty() && !topics.contains(&topic) {
+                        topics.push(topic);
+                    }
+                }
+            }
+        });
+
+        topics
+    }
+
     pub fn title(&self) -> Option<String> {
         let selector = Selector::parse("head > title").unwrap();
         let title = self.html.select(&selector).next()?;
@@ -273,6 +303,11 @@ impl RHtmlHelp {
     }
 
     pub fn markdown(&self) -> anyhow::Result<String> {
+        self.markdown_without(&[])
+    }
+
+    /// Like `markdown()`, but leaves out the sections named in `skip`
+    pub fn markdown_without(&self, skip: &[&str]) -> anyhow::Result<String> {
         let mut markdown = String::new();
 
         // add topic
@@ -288,6 +323,9 @@ impl RHtmlHelp {
         for_each_section(&self.html, |header, elements| {
             // add a title
             let header = elt_text(header);
+            if skip.contains(&header.trim()) {
+                return;
+            }
             markdown.push_str(md_h3(header.as_str()).as_str());
             markdown.push_str(md_newline().as_str());
 
@@ -1,22 +1,36 @@
 //
 // hover.rs
 //
-// Copyright (C) 2022 Posit Software, PBC. All rights reserved.
+// Copyright (C) 2022-2026 Posit Software, PBC. All rights reserved.
 //
 //
 
 use anyhow::*;
+use harp::exec::RFunction;
+use harp::exec::RFunctionExt;
+use harp::utils::r_defining_package;
+use harp::utils::r_typeof;
+use libr::CLOSXP;
+use stdext::push;
 use stdext::unwrap;
 use stdext::unwrap::IntoResult;
 use tower_lsp::lsp_types::MarkupContent;
 use tower_lsp::lsp_types::MarkupKind;
 use tree_sitter::Node;
 
+use crate::console;
+use crate::console::Console;
 use crate::lsp::document_context::DocumentContext;
 use crate::lsp::help::RHtmlHelp;
+use crate::lsp::markdown::md_bold;
+use crate::lsp::markdown::md_codeblock;
+use crate::lsp::markdown::md_newline;
 use crate::lsp::traits::node::NodeExt;
 use crate::treesitter::NodeTypeExt;
 
+/// Maximum number of lines of function source shown in hovers
+const MAX_SOURCE_LINES: usize = 20;
+
 enum HoverContext {
     Topic { topic: String },
     QualifiedTopic { package: String, topic: String },
@@ -82,7 +96,7 @@ pub(crate) fn r_hover(context: &DocumentContext) -> anyhow::Result<Option<Markup
 
     // Currently, `hover_context()` restricts to only showing hover docs for functions,
     // so we also use `RHtmlHelp::from_function()` here
-    let help = match ctx {
+    let help = match &ctx {
         HoverContext::QualifiedTopic { package, topic } => {
             RHtmlHelp::from_function(topic.as_str(), Some(package.as_str()))?
         },
@@ -90,13 +104,173 @@ pub(crate) fn r_hover(context: &DocumentContext) -> anyhow::Result<Option<Markup
         HoverContext::Topic { topic } => RHtmlHelp::from_function(topic.as_str(), None)?,
     };
 
+    // Show the package the function comes from, e.g. `{dplyr}`
+    let mut markdown = match hover_package(&ctx) {
+        Some(package) => format!("`{{{package}}}`{}", md_newline()),
+        None => String::new(),
+    };
+
     let help = unwrap!(help, None => {
-        return Ok(None);
+        // User functions typically don't have help, show their source instead
+        let HoverContext::Topic { topic } = ctx else {
+            return Ok(None);
+        };
+        let Some(source) = function_source(&topic)? else {
+            return Ok(None);
+        };
+        push!(markdown, md_codeblock("r", &source));
+        return Ok(Some(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: markdown,
+        }));
     });
 
-    let markdown = help.markdown()?;
+    // Summarise the "See Also" section on a single line to keep hovers short
+    push!(markdown, help.markdown_without(&["See Also"])?);
+
+    let see_also = help.see_also();
+    if !see_also.is_empty() {
+        let topics: Vec<String> = see_also.iter().map(|topic| format!("`{topic}`")).collect();
+        push!(
+            markdown,
+            md_bold("See also:"),
+            " ",
+            topics.join(", "),
+            md_newline()
+        );
+    }
+
+    if let Some(url) = help.package().and_then(|package| vignettes_url(&package)) {
+        push!(
+            markdown,
+            format!("[Package vignettes]({url})"),
+            md_newline()
+        );
+    }
+
     Ok(Some(MarkupContent {
         kind: MarkupKind::Markdown,
         value: markdown,
     }))
 }
+
+/// The package the hovered function comes from. Unqualified functions are
+/// looked up from the console's current environment.
+fn hover_package(ctx: &HoverContext) -> Option<String> {
+    match ctx {
+        HoverContext::QualifiedTopic { package, .. } => Some(package.clone()),
+        HoverContext::Topic { topic } => {
+            let env = console::selected_env();
+            r_defining_package(topic, env.sexp)
+        },
+    }
+}
+
+/// Source of the function bound to `name` in the console's current
+/// environment, from its srcref if available and deparsed otherwise. Long
+/// functions are truncated to [MAX_SOURCE_LINES] lines.
+fn function_source(name: &str) -> anyhow::Result<Option<String>> {
+    let fun = RFunction::new("base", "get0")
+        .param("x", name)
+        .param("envir", console::selected_env())
+        .param("mode", "function")
+        .call()?;
+
+    if r_typeof(fun.sexp) != CLOSXP {
+        return Ok(None);
+    }
+
+    let srcref = RFunction::new("utils", "getSrcref")
+        .add(fun.clone())
+        .call()?;
+    let lines = if srcref.is_null() {
+        RFunction::new("base", "deparse").add(fun).call()?
+    } else {
+        RFunction::new("base", "as.character").add(srcref).call()?
+    };
+    let mut lines: Vec<String> = lines.try_into()?;
+
+    if lines.len() > MAX_SOURCE_LINES {
+        lines.truncate(MAX_SOURCE_LINES);
+        lines.push(String::from("..."));
+    }
+
+    Ok(Some(lines.join("\n")))
+}
+
+/// Link to the vignettes index of `package` through the help proxy, if the
+/// package has any vignettes and the help server is running.
+fn vignettes_url(package: &str) -> Option<String> {
+    if !Console::is_initialized() {
+        return None;
+    }
+
+    let has_vignettes = RFunction::from(".ps.help.hasVignettes")
+        .param("package", package)
+        .call()
+        .and_then(bool::try_from);
+
+    match has_vignettes {
+        Result::Ok(true) => {},
+        Result::Ok(false) => return None,
+        Err(err) => {
+            log::error!("Can't look up vignettes of package '{package}': {err:?}");
+            return None;
+        },
+    }
+
+    Console::get().help_proxy_url(&format!("library/{package}/doc/index.html"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::point_from_cursor;
+    use crate::lsp::document_context::TestDocument;
+    use crate::lsp::hover::r_hover;
+    use crate::r_task;
+
+    #[test]
+    fn test_hover_see_also() {
+        r_task(|| {
+            let (text, point) = point_from_cursor("ma@tch(1, 2)");
+            let doc = TestDocument::new(&text);
+            let context = doc.context(point);
+
+            let hover = r_hover(&context).unwrap().unwrap();
+            assert!(hover.value.starts_with("`{base}`"));
+            assert!(hover.value.contains("**See also:**"));
+            assert!(hover.value.contains("`pmatch`"));
+
+            // The full section is replaced by the one-line summary
+            assert!(!hover.value.contains("### See Also"));
+        })
+    }
+
+    #[test]
+    fn test_hover_function_source() {
+        r_task(|| {
+            harp::parse_eval_global("hover_fn <- function(x) {\n  x + 1\n}").unwrap();
+
+            let (text, point) = point_from_cursor("hover_f@n(1)");
+            let doc = TestDocument::new(&text);
+            let context = doc.context(point);
+
+            let hover = r_hover(&context).unwrap().unwrap();
+            assert!(hover.value.starts_with("``` r\n"));
+            assert!(hover.value.contains("x + 1"));
+
+            // Long functions are truncated
+            harp::parse_eval_global(
+                "hover_fn <- eval(parse(text = paste0('function() {', paste0('x', 1:30, collapse = '\n'), '}'), keep.source = FALSE))",
+            )
+            .unwrap();
+
+            let hover = r_hover(&context).unwrap().unwrap();
+            assert!(hover.value.contains("x1\n"));
+            assert!(!hover.value.contains("x30"));
+            assert!(hover.value.contains("..."));
+
+            harp::parse_eval_global("rm(hover_fn)").unwrap();
+        })
+    }
+}
@@ -17,9 +17,39 @@ pub(crate) struct IndentEdit {
     pub(crate) new_text: String,
 }
 
+/// Provide indentation corrections after the user typed `ch`
+///
+/// Hooked up to format-on-type. After a newline we indent the new line. After
+/// a `}` we dedent it to match its opener, but only when the brace is the
+/// first character of the line so we don't reindent lines like `x <- 1 }`.
+pub(crate) fn on_type_indent_edit(
+    db: &dyn ArkDb,
+    file: File,
+    config: &IndentationConfig,
+    line: usize,
+    ch: &str,
+) -> LspResult<Option<Vec<IndentEdit>>> {
+    match ch {
+        "\n" => indent_edit(db, file, config, line),
+        "}" => {
+            let text = file.source_text(db).as_str();
+            let starts_with_brace = text
+                .lines()
+                .nth(line)
+                .is_some_and(|line_text| line_text.trim_start().starts_with('}'));
+
+            if !starts_with_brace {
+                return Ok(None);
+            }
+            indent_edit(db, file, config, line)
+        },
+        _ => Ok(None),
+    }
+}
+
 /// Provide indentation corrections
 ///
-/// Hooked up to format-on-type for newline characters.
+/// Hooked up to format-on-type for newline and `}` characters.
 ///
 /// This is not a full indenter yet. We only provide corrections for the
 /// Positron frontend when the VS Code regexp-based indenting rules are not able
@@ -227,12 +257,16 @@ fn brace_parent(node: tree_sitter::Node) -> tree_sitter::Node {
 
 /// Returns indent as a pair of space size and byte size
 pub fn line_indent(text: &str, line: usize, config: &IndentationConfig) -> (usize, usize) {
-    let mut byte_indent = 0;
-    let mut indent = 0;
-
     let Some(line_text) = text.lines().nth(line) else {
         return (0, 0);
     };
+    line_text_indent(line_text, config)
+}
+
+/// Returns indent of a single line of text as a pair of space size and byte size
+pub fn line_text_indent(line_text: &str, config: &IndentationConfig) -> (usize, usize) {
+    let mut byte_indent = 0;
+    let mut indent = 0;
 
     for next_char in line_text.chars() {
         if next_char == ' ' {
@@ -284,6 +318,7 @@ mod tests {
     use crate::lsp::config::IndentationConfig;
     use crate::lsp::indent::indent_edit;
     use crate::lsp::indent::new_line_indent;
+    use crate::lsp::indent::on_type_indent_edit;
     use crate::lsp::indent::IndentEdit;
     use crate::lsp::open_file::lsp_range_from_tree_sitter_range;
     use crate::lsp::open_file::OpenFile;
@@ -526,6 +561,47 @@ mod tests {
         assert_eq!(file.source_text(&db), "function(\n        ) {\n  \n}");
     }
 
+    #[test]
+    fn test_on_type_indent_newline_in_function_body() {
+        let (mut db, file) =
+            crate::lsp::open_file::test_open_file("f <- function(x) {\n  y <- x\n\n}");
+
+        let edit = on_type_indent_edit(&db, file.file(), &file.config().indent, 2, "\n")
+            .unwrap()
+            .unwrap();
+        apply_text_edits(edit, &mut db, &file, ENCODING);
+        assert_eq!(file.source_text(&db), "f <- function(x) {\n  y <- x\n  \n}");
+    }
+
+    #[test]
+    fn test_on_type_indent_closing_brace() {
+        let (mut db, file) = crate::lsp::open_file::test_open_file(
+            "f <- function(x) {\n  if (x) {\n    x\n    }\n}",
+        );
+
+        let edit = on_type_indent_edit(&db, file.file(), &file.config().indent, 3, "}")
+            .unwrap()
+            .unwrap();
+        apply_text_edits(edit, &mut db, &file, ENCODING);
+        assert_eq!(
+            file.source_text(&db),
+            "f <- function(x) {\n  if (x) {\n    x\n  }\n}"
+        );
+
+        // A brace typed after other code doesn't reindent the line
+        let (db, file) = crate::lsp::open_file::test_open_file("{\nfoo }");
+        assert_match!(
+            on_type_indent_edit(&db, file.file(), &file.config().indent, 1, "}"),
+            Ok(None)
+        );
+
+        // Unknown trigger characters are ignored
+        assert_match!(
+            on_type_indent_edit(&db, file.file(), &file.config().indent, 1, ")"),
+            Ok(None)
+        );
+    }
+
     #[test]
     fn test_line_indent_minimum() {
         // https://github.com/posit-dev/positron/issues/1683
@@ -41,6 +41,15 @@ pub enum IndexEntryData {
     Method {
         name: String,
     },
+    // S4 class defined with `setClass()`
+    Class {
+        name: String,
+        /// Slot names and classes declared in the definition, excluding
+        /// inherited slots
+        slots: Vec<(String, String)>,
+        /// Names of the superclasses declared in the definition
+        contains: Vec<String>,
+    },
     Section {
         level: usize,
         title: String,
@@ -79,6 +88,18 @@ pub struct IndexEntry {
     pub data: IndexEntryData,
 }
 
+/// A call to a function by name, as in `foo()`.
+#[derive(Clone, Debug, PartialEq, Eq, salsa::Update)]
+pub struct IndexCall {
+    /// Name of the called function.
+    pub callee: String,
+    /// Name of the top-level function whose body contains the call, or `None`
+    /// for calls made at top level.
+    pub caller: Option<String>,
+    /// Range of the function name at the call site.
+    pub range: IndexRange,
+}
+
 /// Convert an index entry's tree-sitter point range to an LSP range, resolving
 /// the file's line index from the db. Returns `None` if the points fall outside
 /// the line index, in which case the symbol is dropped from the results.
@@ -115,6 +136,12 @@ pub static RE_COMMENT_SECTION: LazyLock<Regex> =
 #[derive(Clone, Debug, Default, PartialEq, Eq, salsa::Update)]
 pub(crate) struct FileIndex {
     pub(crate) symbols: rustc_hash::FxHashMap<String, IndexEntry>,
+    /// S4 classes, keyed by class name. Kept separately from `symbols`
+    /// because classes live in their own namespace: `Foo <- setClass("Foo")`
+    /// defines both a `Foo` variable and a `Foo` class.
+    pub(crate) classes: rustc_hash::FxHashMap<String, IndexEntry>,
+    /// Calls made in the file, in document order.
+    pub(crate) calls: Vec<IndexCall>,
 }
 
 /// Find the first workspace symbol matching `symbol`, scanning files in
@@ -132,6 +159,40 @@ pub(crate) fn find(db: &dyn ArkDb, symbol: &str) -> Option<IndexEntry> {
     None
 }
 
+/// Find the first workspace function named `name`, scanning files in
+/// `workspace_files` order.
+pub(crate) fn find_function(db: &dyn ArkDb, name: &str) -> Option<(File, IndexEntry)> {
+    for &file in oak_db::workspace_files(db) {
+        if let Some(entry) = file_function(db, file, name) {
+            return Some((file, entry));
+        }
+    }
+    None
+}
+
+/// Find the function named `name` defined at top level in `file`.
+pub(crate) fn file_function(db: &dyn ArkDb, file: File, name: &str) -> Option<IndexEntry> {
+    if !is_indexable(db, file) {
+        return None;
+    }
+    let entry = file_index(db, file).symbols.get(name)?;
+    matches!(entry.data, IndexEntryData::Function { .. }).then(|| entry.clone())
+}
+
+/// Find the first S4 class definition named `name`, scanning files in
+/// `workspace_files` order.
+pub(crate) fn find_class(db: &dyn ArkDb, name: &str) -> Option<(File, IndexEntry)> {
+    for &file in oak_db::workspace_files(db) {
+        if !is_indexable(db, file) {
+            continue;
+        }
+        if let Some(entry) = file_index(db, file).classes.get(name) {
+            return Some((file, entry.clone()));
+        }
+    }
+    None
+}
+
 /// Extract a file's workspace symbols.
 #[salsa::tracked(returns(ref))]
 fn file_index(db: &dyn ArkDb, file: File) -> FileIndex {
@@ -141,19 +202,31 @@ fn file_index(db: &dyn ArkDb, file: File) -> FileIndex {
     let root = tree.root_node();
     let mut cursor = root.walk();
     let mut entries = Vec::new();
+    let mut calls = Vec::new();
 
     for node in root.children(&mut cursor) {
         if let Err(err) = index_node(contents, &node, &mut entries) {
             lsp::log_error!("Can't index document: {err:?}");
         }
+        if let Err(err) = index_calls(contents, &node, &mut calls) {
+            lsp::log_error!("Can't index calls: {err:?}");
+        }
     }
 
     let mut symbols = rustc_hash::FxHashMap::default();
+    let mut classes = rustc_hash::FxHashMap::default();
     for entry in entries {
-        index_insert(&mut symbols, entry);
+        match entry.data {
+            IndexEntryData::Class { .. } => index_insert(&mut classes, entry),
+            _ => index_insert(&mut symbols, entry),
+        }
     }
 
-    FileIndex { symbols }
+    FileIndex {
+        symbols,
+        classes,
+        calls,
+    }
 }
 
 /// Visit every workspace symbol across all indexable files. Callers that need a
@@ -164,12 +237,33 @@ pub(crate) fn map(db: &dyn ArkDb, mut callback: impl FnMut(File, &str, &IndexEnt
         if !is_indexable(db, file) {
             continue;
         }
-        for (symbol, entry) in file_index(db, file).symbols.iter() {
+        let index = file_index(db, file);
+        for (symbol, entry) in index.symbols.iter().chain(index.classes.iter()) {
             callback(file, symbol, entry);
         }
     }
 }
 
+/// Visit every call site across all indexable files.
+pub(crate) fn map_calls(db: &dyn ArkDb, mut callback: impl FnMut(File, &IndexCall)) {
+    for &file in oak_db::workspace_files(db) {
+        if !is_indexable(db, file) {
+            continue;
+        }
+        for call in file_index(db, file).calls.iter() {
+            callback(file, call);
+        }
+    }
+}
+
+/// Call sites of `file`, in document order.
+pub(crate) fn file_calls(db: &dyn ArkDb, file: File) -> &[IndexCall] {
+    if !is_indexable(db, file) {
+        return &[];
+    }
+    &file_index(db, file).calls
+}
+
 /// Call [`file_index()`] for every workspace file. This ensures workspace
 /// symbols are loaded before the user needs to read them (e.g. by looking up a
 /// workspace symbol without any file opened).
@@ -204,10 +298,64 @@ fn index_insert(index: &mut rustc_hash::FxHashMap<String, IndexEntry>, entry: In
 
 fn index_node(contents: &str, node: &Node, entries: &mut Vec<IndexEntry>) -> anyhow::Result<()> {
     index_assignment(contents, node, entries)?;
+    index_s4(contents, node, entries)?;
     index_comment(contents, node, entries)?;
     Ok(())
 }
 
+/// Index the calls made in a top-level expression. Calls in the body of a
+/// function assigned at top level are attributed to that function, including
+/// calls made from nested functions.
+fn index_calls(contents: &str, node: &Node, calls: &mut Vec<IndexCall>) -> anyhow::Result<()> {
+    let mut caller = None;
+
+    if matches!(
+        node.node_type(),
+        NodeType::BinaryOperator(BinaryOperatorType::LeftAssignment) |
+            NodeType::BinaryOperator(BinaryOperatorType::EqualsAssignment)
+    ) {
+        if let (Some(lhs), Some(rhs)) = (
+            node.child_by_field_name("lhs"),
+            node.child_by_field_name("rhs"),
+        ) {
+            if lhs.is_identifier() && rhs.is_function_definition() {
+                caller = Some(lhs.node_to_string(contents)?);
+            }
+        }
+    }
+
+    index_calls_in(contents, node, &caller, calls)
+}
+
+fn index_calls_in(
+    contents: &str,
+    node: &Node,
+    caller: &Option<String>,
+    calls: &mut Vec<IndexCall>,
+) -> anyhow::Result<()> {
+    if node.is_call() {
+        if let Some(function) = node.child_by_field_name("function") {
+            if function.is_identifier() {
+                calls.push(IndexCall {
+                    callee: function.node_to_string(contents)?,
+                    caller: caller.clone(),
+                    range: IndexRange {
+                        start: function.start_position().into(),
+                        end: function.end_position().into(),
+                    },
+                });
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        index_calls_in(contents, &child, caller, calls)?;
+    }
+
+    Ok(())
+}
+
 fn index_assignment(
     contents: &str,
     node: &Node,
@@ -335,6 +483,226 @@ fn index_r6_class_methods(
     Ok(())
 }
 
+/// Index S4 definitions made with `setClass()`, `setGeneric()`, and
+/// `setMethod()`, either at top level or on the RHS of an assignment (as in
+/// `Foo <- setClass("Foo")`).
+fn index_s4(contents: &str, node: &Node, entries: &mut Vec<IndexEntry>) -> anyhow::Result<()> {
+    let node = match node.node_type() {
+        NodeType::BinaryOperator(BinaryOperatorType::LeftAssignment) |
+        NodeType::BinaryOperator(BinaryOperatorType::EqualsAssignment) => {
+            let Some(rhs) = node.child_by_field_name("rhs") else {
+                return Ok(());
+            };
+            rhs
+        },
+        _ => *node,
+    };
+
+    if node_is_methods_call(&node, "setClass", contents) {
+        let Some(class) = s4_call_arg(&node, "Class", 0, contents) else {
+            return Ok(());
+        };
+        let Some(name) = s4_string(&class, contents) else {
+            return Ok(());
+        };
+
+        let slots = s4_class_slots(&node, contents);
+        let contains = s4_class_contains(&node, contents);
+
+        entries.push(IndexEntry {
+            key: name.clone(),
+            range: IndexRange {
+                start: class.start_position().into(),
+                end: class.end_position().into(),
+            },
+            data: IndexEntryData::Class {
+                name,
+                slots,
+                contains,
+            },
+        });
+    } else if node_is_methods_call(&node, "setGeneric", contents) {
+        let Some(generic) = s4_call_arg(&node, "name", 0, contents) else {
+            return Ok(());
+        };
+        let Some(name) = s4_string(&generic, contents) else {
+            return Ok(());
+        };
+
+        let mut arguments = Vec::new();
+        if let Some(def) = s4_call_arg(&node, "def", 1, contents) {
+            if let Some(parameters) = def.child_by_field_name("parameters") {
+                let mut cursor = parameters.walk();
+                for child in parameters.children(&mut cursor) {
+                    let name = unwrap!(child.child_by_field_name("name"), None => continue);
+                    if name.is_identifier() {
+                        arguments.push(name.node_to_string(contents)?);
+                    }
+                }
+            }
+        }
+
+        entries.push(IndexEntry {
+            key: name.clone(),
+            range: IndexRange {
+                start: generic.start_position().into(),
+                end: generic.end_position().into(),
+            },
+            data: IndexEntryData::Function { name, arguments },
+        });
+    } else if node_is_methods_call(&node, "setMethod", contents) {
+        let Some(generic) = s4_call_arg(&node, "f", 0, contents) else {
+            return Ok(());
+        };
+        let Some(generic_name) = s4_string(&generic, contents) else {
+            return Ok(());
+        };
+
+        // The signature is either a single class name or a call like
+        // `signature("A", "B")` or `c("A", "B")`
+        let signature = s4_call_arg(&node, "signature", 1, contents)
+            .map(|signature| {
+                if signature.is_call() {
+                    signature
+                        .arguments_values()
+                        .flatten()
+                        .filter_map(|value| s4_string(&value, contents))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                } else {
+                    s4_string(&signature, contents).unwrap_or_default()
+                }
+            })
+            .unwrap_or_default();
+
+        // Follows the `generic,Class-method` convention of S4 method aliases
+        let name = format!("{generic_name},{signature}-method");
+
+        entries.push(IndexEntry {
+            key: name.clone(),
+            range: IndexRange {
+                start: generic.start_position().into(),
+                end: generic.end_position().into(),
+            },
+            data: IndexEntryData::Method { name },
+        });
+    }
+
+    Ok(())
+}
+
+/// Collect the slots declared in a `setClass()` call, either with
+/// `representation(a = "numeric")` or with `slots = c(a = "numeric")`.
+/// Unnamed entries of `slots`, as in `slots = c("a", "b")`, are slots of class
+/// `ANY`. Unnamed entries of `representation()` are superclasses and skipped.
+fn s4_class_slots(node: &Node, contents: &str) -> Vec<(String, String)> {
+    let mut slots = Vec::new();
+
+    if let Some(representation) = s4_call_arg(node, "representation", 1, contents) {
+        if node_is_methods_call(&representation, "representation", contents) {
+            for (name, value) in representation.arguments() {
+                let (Some(name), Some(value)) = (name, value) else {
+                    continue;
+                };
+                let (Ok(name), Some(class)) =
+                    (name.node_to_string(contents), s4_string(&value, contents))
+                else {
+                    continue;
+                };
+                slots.push((name, class));
+            }
+        }
+    }
+
+    // `slots` comes after `...` in the signature of `setClass()` so can only
+    // be matched by name
+    if let Some(value) = s4_call_arg(node, "slots", usize::MAX, contents) {
+        if crate::treesitter::node_is_call(&value, "c", contents) ||
+            crate::treesitter::node_is_call(&value, "list", contents)
+        {
+            for (name, value) in value.arguments() {
+                let Some(value) = value.and_then(|value| s4_string(&value, contents)) else {
+                    continue;
+                };
+                let slot = match name.and_then(|name| name.node_to_string(contents).ok()) {
+                    Some(name) => (name, value),
+                    None => (value, String::from("ANY")),
+                };
+                slots.push(slot);
+            }
+        }
+    }
+
+    slots
+}
+
+/// Collect the superclasses declared in a `setClass()` call, either with
+/// `contains = c("A", "B")` or as unnamed entries of `representation("A")`.
+fn s4_class_contains(node: &Node, contents: &str) -> Vec<String> {
+    let mut contains = Vec::new();
+
+    if let Some(representation) = s4_call_arg(node, "representation", 1, contents) {
+        if node_is_methods_call(&representation, "representation", contents) {
+            for (name, value) in representation.arguments() {
+                if name.is_some() {
+                    continue;
+                }
+                contains.extend(value.and_then(|value| s4_string(&value, contents)));
+            }
+        }
+    }
+
+    if let Some(value) = s4_call_arg(node, "contains", 3, contents) {
+        if crate::treesitter::node_is_call(&value, "c", contents) {
+            for (_name, value) in value.arguments() {
+                contains.extend(value.and_then(|value| s4_string(&value, contents)));
+            }
+        } else {
+            contains.extend(s4_string(&value, contents));
+        }
+    }
+
+    contains
+}
+
+pub(crate) fn node_is_methods_call(node: &Node, name: &str, contents: &str) -> bool {
+    crate::treesitter::node_is_call(node, name, contents) ||
+        crate::treesitter::node_is_namespaced_call(node, "methods", name, contents)
+}
+
+/// Find the argument of an S4 call by name, or else by position among the
+/// unnamed arguments. This is a simplified version of R's argument matching
+/// that covers the way these calls are typically written.
+pub(crate) fn s4_call_arg<'tree>(
+    node: &Node<'tree>,
+    name: &str,
+    position: usize,
+    contents: &str,
+) -> Option<Node<'tree>> {
+    let mut positional = Vec::new();
+
+    for (arg_name, arg_value) in node.arguments() {
+        match arg_name {
+            Some(arg_name) => {
+                if arg_name.node_as_str(contents).ok()? == name {
+                    return arg_value;
+                }
+            },
+            None => positional.extend(arg_value),
+        }
+    }
+
+    positional.get(position).copied()
+}
+
+pub(crate) fn s4_string(node: &Node, contents: &str) -> Option<String> {
+    if !node.is_string() {
+        return None;
+    }
+    let text = node.get_identifier_or_string_text(contents).ok()?;
+    Some(text.to_string())
+}
+
 fn index_comment(contents: &str, node: &Node, entries: &mut Vec<IndexEntry>) -> anyhow::Result<()> {
     // check for comment
     if !node.is_comment() {
@@ -510,6 +878,119 @@ class <- R6::R6Class(
         );
     }
 
+    #[test]
+    fn test_index_s4() {
+        test_index!(
+            r#"
+setClass("Person", representation(name = "character"))
+Student <- methods::setClass("Student", contains = "Person")
+setGeneric("greet", function(x, ...) standardGeneric("greet"))
+setMethod("greet", "Person", function(x, ...) NULL)
+setMethod("greet", signature("Student"), function(x, ...) NULL)
+"#
+        );
+    }
+
+    #[test]
+    fn test_index_s4_class_alongside_variable() {
+        use aether_path::FilePath;
+        let mut db = oak_db::OakDatabase::new();
+        let url = Url::parse("file:///test.R").unwrap();
+        let file = db.upsert_editor(
+            FilePath::from_url(&url),
+            r#"Foo <- setClass("Foo")"#.to_string(),
+        );
+        let index = file_index(&db, file);
+
+        assert_matches!(
+            &index.symbols.get("Foo").unwrap().data,
+            IndexEntryData::Variable { name } => assert_eq!(name, "Foo")
+        );
+        assert_matches!(
+            &index.classes.get("Foo").unwrap().data,
+            IndexEntryData::Class { name, slots, contains } => {
+                assert_eq!(name, "Foo");
+                assert!(slots.is_empty());
+                assert!(contains.is_empty());
+            }
+        );
+
+        let (found, entry) = find_class(&db, "Foo").unwrap();
+        assert_eq!(found, file);
+        assert_eq!(entry.range.start, IndexPoint { row: 0, column: 16 });
+        assert!(find_class(&db, "Bar").is_none());
+    }
+
+    #[test]
+    fn test_index_s4_class_slots() {
+        use aether_path::FilePath;
+        let mut db = oak_db::OakDatabase::new();
+        let url = Url::parse("file:///test.R").unwrap();
+        let file = db.upsert_editor(
+            FilePath::from_url(&url),
+            r#"
+setClass("Foo", slots = c(a = "numeric", b = "character"))
+setClass("Bar", slots = c("x"), contains = "Foo")
+setClass("Baz", representation("Foo", y = "logical"))
+"#
+            .to_string(),
+        );
+
+        let slots = |class: &str| {
+            let (_, entry) = find_class(&db, class).unwrap();
+            match entry.data {
+                IndexEntryData::Class { slots, .. } => slots,
+                _ => panic!("Expected a class"),
+            }
+        };
+
+        assert_eq!(slots("Foo"), vec![
+            (String::from("a"), String::from("numeric")),
+            (String::from("b"), String::from("character")),
+        ]);
+        assert_eq!(slots("Bar"), vec![(String::from("x"), String::from("ANY"))]);
+        assert_eq!(slots("Baz"), vec![(
+            String::from("y"),
+            String::from("logical")
+        )]);
+
+        let contains = |class: &str| {
+            let (_, entry) = find_class(&db, class).unwrap();
+            match entry.data {
+                IndexEntryData::Class { contains, .. } => contains,
+                _ => panic!("Expected a class"),
+            }
+        };
+
+        assert!(contains("Foo").is_empty());
+        assert_eq!(contains("Bar"), vec![String::from("Foo")]);
+        assert_eq!(contains("Baz"), vec![String::from("Foo")]);
+    }
+
+    #[test]
+    fn test_index_calls() {
+        use aether_path::FilePath;
+        let mut db = oak_db::OakDatabase::new();
+        let url = Url::parse("file:///test.R").unwrap();
+        let file = db.upsert_editor(
+            FilePath::from_url(&url),
+            "a <- function() {\n  b(c())\n  pkg::d()\n}\ne()\n".to_string(),
+        );
+
+        let calls: Vec<(&str, Option<&str>)> = file_calls(&db, file)
+            .iter()
+            .map(|call| (call.callee.as_str(), call.caller.as_deref()))
+            .collect();
+        assert_eq!(calls, vec![("b", Some("a")), ("c", Some("a")), ("e", None)]);
+
+        let b = &file_calls(&db, file)[0];
+        assert_eq!(b.range.start, IndexPoint { row: 1, column: 2 });
+        assert_eq!(b.range.end, IndexPoint { row: 1, column: 3 });
+
+        assert!(file_function(&db, file, "a").is_some());
+        assert!(file_function(&db, file, "b").is_none());
+    }
+
     #[test]
     fn test_index_insert_priority() {
         let mut index = rustc_hash::FxHashMap::default();
@@ -19,6 +19,7 @@ use std::sync::RwLock;
 
 use aether_path::FilePath;
 use anyhow::anyhow;
+use crossbeam::channel::Sender;
 use futures::StreamExt;
 use oak_db::OakDatabase;
 use oak_scan::DbScan;
@@ -60,6 +61,7 @@ use crate::lsp::sources::SourceScheduler;
 use crate::lsp::state::WorldState;
 use crate::lsp::state_handlers;
 use crate::lsp::state_handlers::ConsoleInputs;
+use crate::request::RRequest;
 use crate::url::ExtUrl;
 
 pub(crate) type TokioUnboundedSender<T> = tokio::sync::mpsc::UnboundedSender<T>;
@@ -190,6 +192,10 @@ pub(crate) struct LspState {
     /// Channel for sending notifications to Console (e.g., document changes for DAP)
     pub(crate) console_notification_tx: TokioUnboundedSender<ConsoleNotification>,
 
+    /// Channel for sending requests to the R thread, e.g. to restart R from
+    /// `workspace/executeCommand`
+    pub(crate) r_request_tx: Sender<RRequest>,
+
     /// Coordinator for asynchronous workspace scans. Mutated only from
     /// main-loop handlers. Must be out of [`WorldState`] because the scheduler
     /// is not clonable.
@@ -203,11 +209,13 @@ pub(crate) struct LspState {
 impl LspState {
     pub(crate) fn new(
         console_notification_tx: TokioUnboundedSender<ConsoleNotification>,
+        r_request_tx: Sender<RRequest>,
         source_scheduler: SourceScheduler,
     ) -> Self {
         Self {
             capabilities: Capabilities::default(),
             console_notification_tx,
+            r_request_tx,
             oak_scheduler: ScanScheduler::new(),
             source_scheduler,
         }
@@ -244,6 +252,7 @@ impl GlobalState {
         client: Client,
         r_home: PathBuf,
         console_notification_tx: TokioUnboundedSender<ConsoleNotification>,
+        r_request_tx: Sender<RRequest>,
     ) -> Self {
         // FIXME: We shouldn't call R code in the kernel to figure this out
         let library_paths = crate::r_task(|| -> anyhow::Result<Vec<String>> {
@@ -270,6 +279,7 @@ impl GlobalState {
             WorldState::new(db),
             LspState::new(
                 console_notification_tx,
+                r_request_tx,
                 SourceScheduler::new(source_handler(&r_home)),
             ),
         )
@@ -415,8 +425,8 @@ impl GlobalState {
                         LspNotification::DidChangeTextDocument(params) => {
                             state_handlers::did_change(params, &mut self.lsp_state, &mut self.world)?;
                         },
-                        LspNotification::DidSaveTextDocument(_params) => {
-                            // Currently ignored
+                        LspNotification::DidSaveTextDocument(params) => {
+                            state_handlers::did_save(params, &mut self.world, &mut self.lsp_state, &self.events_tx)?;
                         },
                         LspNotification::DidCloseTextDocument(params) => {
                             state_handlers::did_close(params, &mut self.world)?;
@@ -440,12 +450,13 @@ impl GlobalState {
                         LspRequest::FoldingRange(params) => {
                             respond(tx, || handlers::handle_folding_range(params, &self.world), LspResponse::FoldingRange)?;
                         },
-                        LspRequest::ExecuteCommand(_params) => {
-                            let response = handlers::handle_execute_command(&self.client).await;
-                            respond(tx, || response, LspResponse::ExecuteCommand)?;
+                        LspRequest::ExecuteCommand(params) => {
+                            // Runs R code once R is idle, don't block the main loop meanwhile
+                            let r_request_tx = self.lsp_state.r_request_tx.clone();
+                            Self::spawn_handler(tx, move || handlers::handle_execute_command(params, &r_request_tx), LspResponse::ExecuteCommand);
                         },
                         LspRequest::Completion(params) => {
-                            respond(tx, || handlers::handle_completion(params, &self.world), LspResponse::Completion)?;
+                            respond(tx, || handlers::handle_completion(params, &self.lsp_state, &self.world), LspResponse::Completion)?;
                         },
                         LspRequest::CompletionResolve(params) => {
                             respond(tx, || handlers::handle_completion_resolve(params), LspResponse::CompletionResolve)?;
@@ -463,9 +474,27 @@ impl GlobalState {
                             // TODO
                             respond(tx, || Ok(None), LspResponse::GotoImplementation)?;
                         },
+                        LspRequest::GotoTypeDefinition(params) => {
+                            respond(tx, || handlers::handle_type_definition(params, &self.world), LspResponse::GotoTypeDefinition)?;
+                        },
+                        LspRequest::PrepareCallHierarchy(params) => {
+                            respond(tx, || handlers::handle_prepare_call_hierarchy(params, &self.world), LspResponse::PrepareCallHierarchy)?;
+                        },
+                        LspRequest::IncomingCalls(params) => {
+                            respond(tx, || handlers::handle_incoming_calls(params, &self.world), LspResponse::IncomingCalls)?;
+                        },
+                        LspRequest::OutgoingCalls(params) => {
+                            respond(tx, || handlers::handle_outgoing_calls(params, &self.world), LspResponse::OutgoingCalls)?;
+                        },
                         LspRequest::SelectionRange(params) => {
                             respond(tx, || handlers::handle_selection_range(params, &self.world), LspResponse::SelectionRange)?;
                         },
+                        LspRequest::SemanticTokensFull(params) => {
+                            respond(tx, || handlers::handle_semantic_tokens_full(params, &self.world), LspResponse::SemanticTokensFull)?;
+                        },
+                        LspRequest::CodeLens(params) => {
+                            respond(tx, || handlers::handle_code_lens(params, &self.world), LspResponse::CodeLens)?;
+                        },
                         LspRequest::References(params) => {
                             respond(tx, || handlers::handle_references(params, &self.world), LspResponse::References)?;
                         },
@@ -481,6 +510,10 @@ impl GlobalState {
                         LspRequest::HelpTopic(params) => {
                             respond(tx, || handlers::handle_help_topic(params, &self.world), LspResponse::HelpTopic)?;
                         },
+                        LspRequest::Formatting(params) => {
+                            state_handlers::did_change_formatting_options(&params.text_document.uri, &params.options, &mut self.world);
+                            respond(tx, || handlers::handle_formatting(params, &self.world), LspResponse::Formatting)?;
+                        },
                         LspRequest::OnTypeFormatting(params) => {
                             state_handlers::did_change_formatting_options(&params.text_document_position.text_document.uri, &params.options, &mut self.world);
                             respond(tx, || handlers::handle_indent(params, &self.world), LspResponse::OnTypeFormatting)?;
@@ -584,7 +617,6 @@ impl GlobalState {
         Ok(())
     }
 
-    #[allow(dead_code)] // Currently unused
     /// Spawn blocking thread for LSP request handler
     ///
     /// Use this for handlers that might take too long to handle on the main
@@ -0,0 +1,492 @@
+//
+// semantic_tokens.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::sync::LazyLock;
+
+use aether_lsp_utils::proto::PositionEncoding;
+use oak_db::File;
+use regex::Regex;
+use tower_lsp::lsp_types::SemanticToken;
+use tower_lsp::lsp_types::SemanticTokenModifier;
+use tower_lsp::lsp_types::SemanticTokenType;
+use tower_lsp::lsp_types::SemanticTokensLegend;
+use tree_sitter::Node;
+use tree_sitter::Point;
+
+use crate::lsp::db::ArkDb;
+use crate::lsp::db::FileArkExt;
+use crate::lsp::open_file::get_line;
+use crate::lsp::open_file::lsp_position_from_tree_sitter_point;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+
+/// ID of the dynamic registration of the semantic tokens provider. Registered
+/// when `positron.r.semanticHighlighting.enable` is set, so that clients keep
+/// their own highlighting by default.
+pub(crate) static SEMANTIC_TOKENS_REGISTRATION_ID: &str = "ark-semantic-tokens";
+
+/// Matches roxygen tags like `@param` in `#'` comments
+static RE_ROXYGEN_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"@[a-zA-Z]+").unwrap());
+
+/// Anonymous nodes that are R keywords. Keyword constants like `TRUE` or
+/// `NULL` have their own named node types.
+const KEYWORDS: &[&str] = &[
+    "function", "\\", "if", "else", "for", "in", "while", "repeat",
+];
+
+/// Token types, in the order of the legend we send to the client when
+/// registering the provider. Tokens refer to their type by index in the legend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenType {
+    Namespace,
+    Function,
+    Parameter,
+    Variable,
+    Property,
+    String,
+    Number,
+    Comment,
+    Keyword,
+    Operator,
+    Decorator,
+}
+
+impl TokenType {
+    const ALL: [TokenType; 11] = [
+        TokenType::Namespace,
+        TokenType::Function,
+        TokenType::Parameter,
+        TokenType::Variable,
+        TokenType::Property,
+        TokenType::String,
+        TokenType::Number,
+        TokenType::Comment,
+        TokenType::Keyword,
+        TokenType::Operator,
+        TokenType::Decorator,
+    ];
+
+    fn lsp_type(self) -> SemanticTokenType {
+        match self {
+            TokenType::Namespace => SemanticTokenType::NAMESPACE,
+            TokenType::Function => SemanticTokenType::FUNCTION,
+            TokenType::Parameter => SemanticTokenType::PARAMETER,
+            TokenType::Variable => SemanticTokenType::VARIABLE,
+            TokenType::Property => SemanticTokenType::PROPERTY,
+            TokenType::String => SemanticTokenType::STRING,
+            TokenType::Number => SemanticTokenType::NUMBER,
+            TokenType::Comment => SemanticTokenType::COMMENT,
+            TokenType::Keyword => SemanticTokenType::KEYWORD,
+            TokenType::Operator => SemanticTokenType::OPERATOR,
+            TokenType::Decorator => SemanticTokenType::DECORATOR,
+        }
+    }
+}
+
+/// Token modifiers, as bit flags in the order of the legend
+pub(crate) const MODIFIER_DECLARATION: u32 = 1 << 0;
+
+pub(crate) fn semantic_tokens_legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TokenType::ALL.iter().map(|x| x.lsp_type()).collect(),
+        token_modifiers: vec![SemanticTokenModifier::DECLARATION],
+    }
+}
+
+/// A token spanning a single line, in tree-sitter coordinates
+#[derive(Debug)]
+struct Token {
+    start: Point,
+    end: Point,
+    token_type: TokenType,
+    modifiers: u32,
+}
+
+/// Compute the semantic tokens of a whole document, encoded relative to each
+/// other as required by the LSP protocol
+pub(crate) fn semantic_tokens(
+    db: &dyn ArkDb,
+    file: File,
+    encoding: PositionEncoding,
+) -> anyhow::Result<Vec<SemanticToken>> {
+    let contents = file.source_text(db).as_str();
+    let line_index = file.line_index(db);
+
+    let mut tokens: Vec<Token> = Vec::new();
+    collect_tokens(
+        file.tree_sitter(db).root_node(),
+        contents,
+        line_index,
+        &mut tokens,
+    );
+
+    let mut out: Vec<SemanticToken> = Vec::with_capacity(tokens.len());
+    let mut prev_line = 0;
+    let mut prev_start = 0;
+
+    for token in tokens {
+        let start = lsp_position_from_tree_sitter_point(token.start, line_index, encoding)?;
+        let end = lsp_position_from_tree_sitter_point(token.end, line_index, encoding)?;
+
+        let length = end.character.saturating_sub(start.character);
+        if length == 0 {
+            continue;
+        }
+
+        let delta_line = start.line - prev_line;
+        let delta_start = if delta_line == 0 {
+            start.character - prev_start
+        } else {
+            start.character
+        };
+
+        out.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type: token.token_type as u32,
+            token_modifiers_bitset: token.modifiers,
+        });
+
+        prev_line = start.line;
+        prev_start = start.character;
+    }
+
+    Ok(out)
+}
+
+fn collect_tokens(
+    node: Node,
+    contents: &str,
+    line_index: &biome_line_index::LineIndex,
+    tokens: &mut Vec<Token>,
+) {
+    match node.node_type() {
+        NodeType::Comment => {
+            push_comment(&node, contents, tokens);
+            return;
+        },
+        NodeType::String => {
+            push_node(&node, TokenType::String, 0, contents, line_index, tokens);
+            return;
+        },
+        NodeType::Integer | NodeType::Float | NodeType::Complex => {
+            push_node(&node, TokenType::Number, 0, contents, line_index, tokens);
+            return;
+        },
+        NodeType::Identifier => {
+            let (token_type, modifiers) = classify_identifier(&node);
+            push_node(&node, token_type, modifiers, contents, line_index, tokens);
+            return;
+        },
+        NodeType::Dots | NodeType::DotDotI => {
+            push_node(&node, TokenType::Variable, 0, contents, line_index, tokens);
+            return;
+        },
+        _ if node.is_keyword() => {
+            push_node(&node, TokenType::Keyword, 0, contents, line_index, tokens);
+            return;
+        },
+        NodeType::Anonymous(kind) if KEYWORDS.contains(&kind.as_str()) => {
+            push_node(&node, TokenType::Keyword, 0, contents, line_index, tokens);
+            return;
+        },
+        _ => {},
+    }
+
+    // The operator of unary and binary operators is an anonymous child
+    // reachable through the `operator` field
+    let operator = if node.is_unary_operator() ||
+        node.is_binary_operator() ||
+        node.is_namespace_operator() ||
+        matches!(node.node_type(), NodeType::ExtractOperator(_))
+    {
+        node.child_by_field_name("operator")
+    } else {
+        None
+    };
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if Some(child) == operator {
+            push_node(&child, TokenType::Operator, 0, contents, line_index, tokens);
+        } else {
+            collect_tokens(child, contents, line_index, tokens);
+        }
+    }
+}
+
+fn classify_identifier(node: &Node) -> (TokenType, u32) {
+    let Some(parent) = node.parent() else {
+        return (TokenType::Variable, 0);
+    };
+    let is_field = |field: &str| parent.child_by_field_name(field) == Some(*node);
+
+    match parent.node_type() {
+        NodeType::NamespaceOperator(_) if is_field("lhs") => (TokenType::Namespace, 0),
+        NodeType::NamespaceOperator(_) => {
+            // `pkg::fn()`
+            let is_called = parent.parent().is_some_and(|call| {
+                call.is_call() && call.child_by_field_name("function") == Some(parent)
+            });
+            if is_called {
+                (TokenType::Function, 0)
+            } else {
+                (TokenType::Variable, 0)
+            }
+        },
+        NodeType::Call if is_field("function") => (TokenType::Function, 0),
+        NodeType::Parameter if is_field("name") => (TokenType::Parameter, MODIFIER_DECLARATION),
+        NodeType::Argument if is_field("name") => (TokenType::Parameter, 0),
+        NodeType::ExtractOperator(_) if is_field("rhs") => (TokenType::Property, 0),
+        NodeType::BinaryOperator(kind) => {
+            let target = match kind {
+                BinaryOperatorType::LeftAssignment |
+                BinaryOperatorType::LeftSuperAssignment |
+                BinaryOperatorType::EqualsAssignment => "lhs",
+                BinaryOperatorType::RightAssignment | BinaryOperatorType::RightSuperAssignment => {
+                    "rhs"
+                },
+                _ => return (TokenType::Variable, 0),
+            };
+            if !is_field(target) {
+                return (TokenType::Variable, 0);
+            }
+
+            // Assigned value is on the other side
+            let value = if target == "lhs" { "rhs" } else { "lhs" };
+            let is_function = parent
+                .child_by_field_name(value)
+                .is_some_and(|value| value.is_function_definition());
+
+            if is_function {
+                (TokenType::Function, MODIFIER_DECLARATION)
+            } else {
+                (TokenType::Variable, MODIFIER_DECLARATION)
+            }
+        },
+        _ => (TokenType::Variable, 0),
+    }
+}
+
+/// Push a token per line covered by `node`, since clients don't necessarily
+/// support multiline tokens
+fn push_node(
+    node: &Node,
+    token_type: TokenType,
+    modifiers: u32,
+    contents: &str,
+    line_index: &biome_line_index::LineIndex,
+    tokens: &mut Vec<Token>,
+) {
+    let start = node.start_position();
+    let end = node.end_position();
+
+    for row in start.row..=end.row {
+        let start_column = if row == start.row { start.column } else { 0 };
+
+        let end_column = if row == end.row {
+            end.column
+        } else {
+            let Some(line) = get_line(contents, line_index, row) else {
+                return;
+            };
+            line.trim_end_matches(['\n', '\r']).len()
+        };
+
+        tokens.push(Token {
+            start: Point::new(row, start_column),
+            end: Point::new(row, end_column),
+            token_type,
+            modifiers,
+        });
+    }
+}
+
+/// Push a comment, highlighting the tags of roxygen comments separately
+fn push_comment(node: &Node, contents: &str, tokens: &mut Vec<Token>) {
+    let start = node.start_position();
+    let end = node.end_position();
+
+    let text = &contents[node.byte_range()];
+
+    if !text.starts_with("#'") {
+        tokens.push(Token {
+            start,
+            end,
+            token_type: TokenType::Comment,
+            modifiers: 0,
+        });
+        return;
+    }
+
+    let row = start.row;
+    let mut column = start.column;
+
+    for tag in RE_ROXYGEN_TAG.find_iter(text) {
+        let tag_start = start.column + tag.start();
+        let tag_end = start.column + tag.end();
+
+        // Skip email addresses and other `@` within words
+        if text[..tag.start()]
+            .chars()
+            .last()
+            .is_some_and(|c| !c.is_whitespace())
+        {
+            continue;
+        }
+
+        tokens.push(Token {
+            start: Point::new(row, column),
+            end: Point::new(row, tag_start),
+            token_type: TokenType::Comment,
+            modifiers: 0,
+        });
+        tokens.push(Token {
+            start: Point::new(row, tag_start),
+            end: Point::new(row, tag_end),
+            token_type: TokenType::Decorator,
+            modifiers: 0,
+        });
+        column = tag_end;
+    }
+
+    tokens.push(Token {
+        start: Point::new(row, column),
+        end,
+        token_type: TokenType::Comment,
+        modifiers: 0,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use aether_lsp_utils::proto::PositionEncoding;
+    use tower_lsp::lsp_types::SemanticTokenType;
+
+    use crate::lsp::open_file::test_open_file;
+    use crate::lsp::semantic_tokens::semantic_tokens;
+    use crate::lsp::semantic_tokens::semantic_tokens_legend;
+    use crate::lsp::semantic_tokens::MODIFIER_DECLARATION;
+
+    const ENCODING: PositionEncoding =
+        PositionEncoding::Wide(biome_line_index::WideEncoding::Utf16);
+
+    /// Decode tokens into absolute `(line, start, text, type, modifiers)`
+    fn tokens(code: &str) -> Vec<(u32, u32, String, SemanticTokenType, u32)> {
+        let (db, file) = test_open_file(code);
+        let legend = semantic_tokens_legend();
+        let lines: Vec<&str> = code.lines().collect();
+
+        let mut line = 0;
+        let mut start = 0;
+
+        semantic_tokens(&db, file.file(), ENCODING)
+            .unwrap()
+            .into_iter()
+            .map(|token| {
+                if token.delta_line > 0 {
+                    start = 0;
+                }
+                line += token.delta_line;
+                start += token.delta_start;
+
+                let text: String = lines[line as usize]
+                    .chars()
+                    .skip(start as usize)
+                    .take(token.length as usize)
+                    .collect();
+                let token_type = legend.token_types[token.token_type as usize].clone();
+
+                (line, start, text, token_type, token.token_modifiers_bitset)
+            })
+            .collect()
+    }
+
+    fn find<'a>(
+        tokens: &'a [(u32, u32, String, SemanticTokenType, u32)],
+        text: &str,
+    ) -> Vec<&'a (u32, u32, String, SemanticTokenType, u32)> {
+        tokens.iter().filter(|token| token.2 == text).collect()
+    }
+
+    #[test]
+    fn test_semantic_tokens_call_vs_variable() {
+        let tokens = tokens("x <- 1\nprint(x)\nbase::paste0(x, sep = \"\")");
+
+        // `x` is declared, then referenced twice
+        let x = find(&tokens, "x");
+        assert_eq!(x.len(), 3);
+        assert_eq!(x[0].3, SemanticTokenType::VARIABLE);
+        assert_eq!(x[0].4, MODIFIER_DECLARATION);
+        assert_eq!(x[1].3, SemanticTokenType::VARIABLE);
+        assert_eq!(x[1].4, 0);
+        assert_eq!(x[2].3, SemanticTokenType::VARIABLE);
+
+        assert_eq!(find(&tokens, "print")[0].3, SemanticTokenType::FUNCTION);
+        assert_eq!(find(&tokens, "base")[0].3, SemanticTokenType::NAMESPACE);
+        assert_eq!(find(&tokens, "::")[0].3, SemanticTokenType::OPERATOR);
+        assert_eq!(find(&tokens, "paste0")[0].3, SemanticTokenType::FUNCTION);
+        assert_eq!(find(&tokens, "sep")[0].3, SemanticTokenType::PARAMETER);
+        assert_eq!(find(&tokens, "\"\"")[0].3, SemanticTokenType::STRING);
+        assert_eq!(find(&tokens, "<-")[0].3, SemanticTokenType::OPERATOR);
+        assert_eq!(find(&tokens, "1")[0].3, SemanticTokenType::NUMBER);
+    }
+
+    #[test]
+    fn test_semantic_tokens_function_definition() {
+        let tokens = tokens("f <- function(a, b = TRUE) a$b");
+
+        let f = find(&tokens, "f");
+        assert_eq!(f[0].3, SemanticTokenType::FUNCTION);
+        assert_eq!(f[0].4, MODIFIER_DECLARATION);
+
+        assert_eq!(find(&tokens, "function")[0].3, SemanticTokenType::KEYWORD);
+        assert_eq!(find(&tokens, "TRUE")[0].3, SemanticTokenType::KEYWORD);
+
+        let a = find(&tokens, "a");
+        assert_eq!(a[0].3, SemanticTokenType::PARAMETER);
+        assert_eq!(a[0].4, MODIFIER_DECLARATION);
+        assert_eq!(a[1].3, SemanticTokenType::VARIABLE);
+
+        let b = find(&tokens, "b");
+        assert_eq!(b[0].3, SemanticTokenType::PARAMETER);
+        assert_eq!(b[1].3, SemanticTokenType::PROPERTY);
+    }
+
+    #[test]
+    fn test_semantic_tokens_comments() {
+        let tokens = tokens("#' Title\n#' @param x A value, see <a@b.c>\n# @param");
+
+        assert_eq!(tokens[0].2, "#' Title");
+        assert_eq!(tokens[0].3, SemanticTokenType::COMMENT);
+
+        assert_eq!(tokens[1].2, "#' ");
+        assert_eq!(tokens[2].2, "@param");
+        assert_eq!(tokens[2].3, SemanticTokenType::DECORATOR);
+        assert_eq!(tokens[3].2, " x A value, see <a@b.c>");
+        assert_eq!(tokens[3].3, SemanticTokenType::COMMENT);
+
+        // Not a roxygen comment
+        assert_eq!(tokens[4].2, "# @param");
+        assert_eq!(tokens[4].3, SemanticTokenType::COMMENT);
+    }
+
+    #[test]
+    fn test_semantic_tokens_multiline_string() {
+        let tokens = tokens("x <- \"a\nbc\"");
+
+        let strings: Vec<_> = tokens
+            .iter()
+            .filter(|token| token.3 == SemanticTokenType::STRING)
+            .collect();
+        assert_eq!(strings.len(), 2);
+        assert_eq!((strings[0].0, strings[0].2.as_str()), (0, "\"a"));
+        assert_eq!((strings[1].0, strings[1].2.as_str()), (1, "bc\""));
+    }
+}
@@ -8,12 +8,11 @@
 use harp::eval::RParseEvalOptions;
 use harp::object::*;
 use harp::r_null;
-use harp::syntax::sym_quote_invalid;
 use harp::utils::r_formals;
 use harp::utils::r_is_function;
 use harp::utils::r_is_object;
-use harp::utils::r_type2char;
 use harp::utils::r_typeof;
+use harp::utils::r_typeof_name;
 use harp::RSymbol;
 use libr::*;
 use log::info;
@@ -398,7 +397,7 @@ fn obj_label(x: SEXP) -> String {
 }
 
 fn type_label(x: SEXP) -> String {
-    let out = r_type2char(r_typeof(x));
+    let out = r_typeof_name(x);
     let out = String::from("<") + out.as_str() + ">";
     out
 }
@@ -480,11 +479,7 @@ fn sym_label(x: SEXP) -> String {
         panic!("`R_MissingArg` should have been handled earlier.");
     }
 
-    let x = RSymbol::new_unchecked(x);
-    let x = String::from(x);
-    let x = sym_quote_invalid(x.as_str());
-
-    x
+    RSymbol::new_unchecked(x).to_backticked_string()
 }
 
 fn call_label(x: SEXP) -> String {
@@ -15,6 +15,7 @@ use oak_scan::FileEvent;
 use oak_scan::FileEventKind;
 use stdext::result::ResultExt;
 use tower_lsp::lsp_types;
+use tower_lsp::lsp_types::CallHierarchyServerCapability;
 use tower_lsp::lsp_types::CompletionOptions;
 use tower_lsp::lsp_types::CompletionOptionsCompletionItem;
 use tower_lsp::lsp_types::DidChangeConfigurationParams;
@@ -23,6 +24,7 @@ use tower_lsp::lsp_types::DidChangeWatchedFilesParams;
 use tower_lsp::lsp_types::DidChangeWorkspaceFoldersParams;
 use tower_lsp::lsp_types::DidCloseTextDocumentParams;
 use tower_lsp::lsp_types::DidOpenTextDocumentParams;
+use tower_lsp::lsp_types::DidSaveTextDocumentParams;
 use tower_lsp::lsp_types::DocumentOnTypeFormattingOptions;
 use tower_lsp::lsp_types::ExecuteCommandOptions;
 use tower_lsp::lsp_types::FileChangeType;
@@ -33,6 +35,7 @@ use tower_lsp::lsp_types::ImplementationProviderCapability;
 use tower_lsp::lsp_types::InitializeParams;
 use tower_lsp::lsp_types::InitializeResult;
 use tower_lsp::lsp_types::OneOf;
+use tower_lsp::lsp_types::Registration;
 use tower_lsp::lsp_types::RenameOptions;
 use tower_lsp::lsp_types::SelectionRangeProviderCapability;
 use tower_lsp::lsp_types::ServerCapabilities;
@@ -40,6 +43,10 @@ use tower_lsp::lsp_types::ServerInfo;
 use tower_lsp::lsp_types::SignatureHelpOptions;
 use tower_lsp::lsp_types::TextDocumentSyncCapability;
 use tower_lsp::lsp_types::TextDocumentSyncKind;
+use tower_lsp::lsp_types::TextDocumentSyncOptions;
+use tower_lsp::lsp_types::TextDocumentSyncSaveOptions;
+use tower_lsp::lsp_types::TypeDefinitionProviderCapability;
+use tower_lsp::lsp_types::Unregistration;
 use tower_lsp::lsp_types::WorkDoneProgressOptions;
 use tower_lsp::lsp_types::WorkspaceFoldersServerCapabilities;
 use tower_lsp::lsp_types::WorkspaceServerCapabilities;
@@ -50,6 +57,8 @@ use crate::console::ConsoleNotification;
 use crate::lsp;
 use crate::lsp::backend::LspResult;
 use crate::lsp::capabilities::Capabilities;
+use crate::lsp::code_lens::CODE_LENS_REGISTRATION_ID;
+use crate::lsp::commands::server_commands;
 use crate::lsp::config::indent_style_from_lsp;
 use crate::lsp::config::DOCUMENT_SETTINGS;
 use crate::lsp::config::GLOBAL_SETTINGS;
@@ -60,6 +69,8 @@ use crate::lsp::main_loop::DidOpenVirtualDocumentParams;
 use crate::lsp::main_loop::Event;
 use crate::lsp::main_loop::LspState;
 use crate::lsp::main_loop::TokioUnboundedSender;
+use crate::lsp::semantic_tokens::semantic_tokens_legend;
+use crate::lsp::semantic_tokens::SEMANTIC_TOKENS_REGISTRATION_ID;
 use crate::lsp::state::open_file_wire_urls;
 use crate::lsp::state::WorldState;
 
@@ -119,8 +130,13 @@ pub(crate) fn initialize(
             // Currently hard-coded to UTF-16, but we might want to allow UTF-8 frontends
             // once/if Ark becomes an independent LSP
             position_encoding: Some(lsp_types::PositionEncodingKind::UTF16),
-            text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                TextDocumentSyncKind::INCREMENTAL,
+            text_document_sync: Some(TextDocumentSyncCapability::Options(
+                TextDocumentSyncOptions {
+                    open_close: Some(true),
+                    change: Some(TextDocumentSyncKind::INCREMENTAL),
+                    save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                    ..Default::default()
+                },
             )),
             selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
             hover_provider: Some(HoverProviderCapability::from(true)),
@@ -141,8 +157,9 @@ pub(crate) fn initialize(
                 },
             }),
             definition_provider: Some(OneOf::Left(true)),
-            type_definition_provider: None,
+            type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
             implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
+            call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
             references_provider: Some(OneOf::Left(true)),
             rename_provider: Some(OneOf::Right(RenameOptions {
                 prepare_provider: Some(true),
@@ -154,7 +171,7 @@ pub(crate) fn initialize(
             folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
             workspace_symbol_provider: Some(OneOf::Left(true)),
             execute_command_provider: Some(ExecuteCommandOptions {
-                commands: vec![],
+                commands: server_commands(),
                 work_done_progress_options: Default::default(),
             }),
             code_action_provider: lsp_state.capabilities.code_action_provider_capability(),
@@ -169,9 +186,10 @@ pub(crate) fn initialize(
                 // current. A rename arrives there as delete + create.
                 file_operations: None,
             }),
+            document_formatting_provider: Some(OneOf::Left(true)),
             document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
                 first_trigger_character: String::from("\n"),
-                more_trigger_character: None,
+                more_trigger_character: Some(vec![String::from("}")]),
             }),
             ..ServerCapabilities::default()
         },
@@ -256,6 +274,37 @@ pub(crate) fn did_change(
     Ok(())
 }
 
+#[tracing::instrument(level = "info", skip_all)]
+pub(crate) fn did_save(
+    params: DidSaveTextDocumentParams,
+    state: &mut WorldState,
+    lsp_state: &mut LspState,
+    events_tx: &TokioUnboundedSender<Event>,
+) -> anyhow::Result<()> {
+    let path = FilePath::from_url(&params.text_document.uri);
+
+    // The editor's buffer stays the source of truth for the file's contents,
+    // but the saved file on disk is the one the workspace tracks. Route the
+    // save through the watcher path (without the usual editor-owned skip) so
+    // the file is registered with its workspace root and its disk revision is
+    // current. If the file vanished between the edit and the save, drop it
+    // from the index instead.
+    let kind = match path.as_path() {
+        Some(fs_path) if fs_path.exists() => FileEventKind::Changed,
+        Some(_) => FileEventKind::Deleted,
+        None => return Ok(()),
+    };
+
+    let requests = lsp_state.oak_scheduler.apply_watcher_events(
+        &mut state.db,
+        vec![FileEvent { path, kind }],
+        &HashSet::new(),
+    );
+    dispatch_scan_requests(events_tx, requests);
+
+    Ok(())
+}
+
 #[tracing::instrument(level = "info", skip_all)]
 pub(crate) fn did_close(
     params: DidCloseTextDocumentParams,
@@ -410,6 +459,8 @@ async fn update_config(
 ) -> anyhow::Result<()> {
     // Keep track of existing config to detect whether it was changed
     let diagnostics_config = state.config.diagnostics.clone();
+    let code_lens_enable = state.config.code_lens.enable;
+    let semantic_tokens_enable = state.config.semantic_tokens.enable;
 
     // Build the configuration request for global and document settings
     let mut items: Vec<_> = vec![];
@@ -483,9 +534,74 @@ async fn update_config(
         lsp::main_loop::diagnostics_refresh_all(state);
     }
 
+    if state.config.code_lens.enable != code_lens_enable {
+        update_code_lens_registration(client, state.config.code_lens.enable).await;
+    }
+
+    if state.config.semantic_tokens.enable != semantic_tokens_enable {
+        update_semantic_tokens_registration(client, state.config.semantic_tokens.enable).await;
+    }
+
     Ok(())
 }
 
+/// Register the code lens provider when the user enables lenses, and
+/// unregister it when they're disabled again
+async fn update_code_lens_registration(client: &tower_lsp::Client, enable: bool) {
+    let id = String::from(CODE_LENS_REGISTRATION_ID);
+    let method = String::from("textDocument/codeLens");
+
+    let result = if enable {
+        client
+            .register_capability(vec![Registration {
+                id,
+                method,
+                register_options: Some(serde_json::json!({
+                    "documentSelector": null,
+                    "resolveProvider": false,
+                })),
+            }])
+            .await
+    } else {
+        client
+            .unregister_capability(vec![Unregistration { id, method }])
+            .await
+    };
+
+    if let Err(err) = result {
+        log::error!("Can't update code lens registration: {err:?}");
+    }
+}
+
+/// Register the semantic tokens provider when the user enables semantic
+/// highlighting, and unregister it when it's disabled again
+async fn update_semantic_tokens_registration(client: &tower_lsp::Client, enable: bool) {
+    let id = String::from(SEMANTIC_TOKENS_REGISTRATION_ID);
+    let method = String::from("textDocument/semanticTokens");
+
+    let result = if enable {
+        client
+            .register_capability(vec![Registration {
+                id,
+                method,
+                register_options: Some(serde_json::json!({
+                    "documentSelector": null,
+                    "legend": semantic_tokens_legend(),
+                    "full": true,
+                })),
+            }])
+            .await
+    } else {
+        client
+            .unregister_capability(vec![Unregistration { id, method }])
+            .await
+    };
+
+    if let Err(err) = result {
+        log::error!("Can't update semantic tokens registration: {err:?}");
+    }
+}
+
 #[tracing::instrument(level = "info", skip_all)]
 pub(crate) fn did_change_console_inputs(
     inputs: ConsoleInputs,
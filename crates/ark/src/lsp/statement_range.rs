@@ -466,7 +466,7 @@ fn adjust_roxygen_examples_rejection(
 /// share its line number. If there are, that means the nodes are separated by
 /// a `;`, and that we should expand the range to also include the node after
 /// the `;`.
-fn expand_range_across_semicolons(mut node: Node) -> tree_sitter::Range {
+pub(crate) fn expand_range_across_semicolons(mut node: Node) -> tree_sitter::Range {
     let start_byte = node.start_byte();
     let start_point = node.start_position();
 
@@ -124,6 +124,17 @@ pub(crate) fn symbols(
                     container_name: None,
                 });
             },
+
+            IndexEntryData::Class { name, .. } => {
+                info.push(SymbolInformation {
+                    name: name.clone(),
+                    kind: SymbolKind::CLASS,
+                    location: Location { uri, range },
+                    tags: None,
+                    deprecated: None,
+                    container_name: None,
+                });
+            },
         };
     });
 
@@ -1,3 +1,4 @@
+mod call_hierarchy;
 mod db;
 mod diagnostics;
 mod find_references;
@@ -8,4 +9,5 @@ mod source_handler;
 mod sources;
 mod state;
 mod state_handlers;
+mod type_definition;
 mod utils;
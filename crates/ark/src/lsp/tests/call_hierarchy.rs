@@ -0,0 +1,107 @@
+use tower_lsp::lsp_types;
+use tower_lsp::lsp_types::CallHierarchyIncomingCallsParams;
+use tower_lsp::lsp_types::CallHierarchyItem;
+use tower_lsp::lsp_types::CallHierarchyOutgoingCallsParams;
+use tower_lsp::lsp_types::CallHierarchyPrepareParams;
+use url::Url;
+
+use super::utils::make_state_with;
+use super::utils::range;
+use crate::lsp::call_hierarchy::incoming_calls;
+use crate::lsp::call_hierarchy::outgoing_calls;
+use crate::lsp::call_hierarchy::prepare_call_hierarchy;
+use crate::lsp::state::WorldState;
+use crate::lsp::util::test_path;
+
+fn prepare(state: &WorldState, uri: &Url, line: u32, character: u32) -> Option<CallHierarchyItem> {
+    let params = CallHierarchyPrepareParams {
+        text_document_position_params: lsp_types::TextDocumentPositionParams {
+            text_document: lsp_types::TextDocumentIdentifier { uri: uri.clone() },
+            position: lsp_types::Position::new(line, character),
+        },
+        work_done_progress_params: Default::default(),
+    };
+    let mut items = prepare_call_hierarchy(params, state).unwrap()?;
+    assert_eq!(items.len(), 1);
+    items.pop()
+}
+
+#[test]
+fn test_call_hierarchy_across_files() {
+    let a_uri = test_path("a.R");
+    let b_uri = test_path("b.R");
+    let state = make_state_with(&[
+        (&a_uri, "a <- function() {\n  b()\n  print(b())\n}\n"),
+        (&b_uri, "b <- function() {\n  1\n}\n"),
+    ]);
+
+    // Prepare from the call site of `b()` in `a.R`
+    let b = prepare(&state, &a_uri, 1, 2).unwrap();
+    assert_eq!(b.name, "b");
+    assert_eq!(b.uri, b_uri);
+    assert_eq!(b.selection_range, range((0, 0), (0, 1)));
+
+    // `b()` is called twice by `a()`
+    let incoming = incoming_calls(
+        CallHierarchyIncomingCallsParams {
+            item: b.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        },
+        &state,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(incoming.len(), 1);
+    assert_eq!(incoming[0].from.name, "a");
+    assert_eq!(incoming[0].from.uri, a_uri);
+    assert_eq!(incoming[0].from_ranges, vec![
+        range((1, 2), (1, 3)),
+        range((2, 8), (2, 9))
+    ]);
+
+    // `a()` calls `b()` and the library function `print()`
+    let a = prepare(&state, &a_uri, 0, 0).unwrap();
+    let outgoing = outgoing_calls(
+        CallHierarchyOutgoingCallsParams {
+            item: a,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        },
+        &state,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(outgoing.len(), 2);
+
+    assert_eq!(outgoing[0].to.name, "b");
+    assert_eq!(outgoing[0].to.uri, b_uri);
+    assert_eq!(outgoing[0].from_ranges.len(), 2);
+
+    // Library calls are leaves located at their call site
+    assert_eq!(outgoing[1].to.name, "print");
+    assert_eq!(outgoing[1].to.uri, a_uri);
+    assert_eq!(outgoing[1].to.range, range((2, 2), (2, 7)));
+
+    // `b()` calls nothing
+    let outgoing = outgoing_calls(
+        CallHierarchyOutgoingCallsParams {
+            item: b,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        },
+        &state,
+    )
+    .unwrap()
+    .unwrap();
+    assert!(outgoing.is_empty());
+}
+
+#[test]
+fn test_call_hierarchy_prepare_non_function() {
+    let uri = test_path("test.R");
+    let state = make_state_with(&[(&uri, "x <- 1\nx\nprint(x)\n")]);
+
+    assert!(prepare(&state, &uri, 1, 0).is_none());
+    assert!(prepare(&state, &uri, 2, 0).is_none());
+}
@@ -14,8 +14,8 @@ use url::Url;
 use super::source_handler::TestBehavior;
 use super::source_handler::TestSourceHandler;
 use super::utils::did_change_workspace_folders;
-use super::utils::insert_file;
 use super::utils::make_state;
+use super::utils::make_state_with;
 use super::utils::range;
 use super::utils::test_client;
 use super::utils::write_sources;
@@ -40,16 +40,6 @@ fn make_params(uri: lsp_types::Url, line: u32, character: u32) -> GotoDefinition
     }
 }
 
-/// A state with several open files, each mirrored into `oak` like `did_open`
-/// does, so `source()` targets resolve through `file_by_path`.
-fn make_state_with(files: &[(&Url, &str)]) -> WorldState {
-    let mut state = WorldState::default();
-    for (uri, code) in files {
-        insert_file(&mut state, uri, code);
-    }
-    state
-}
-
 #[test]
 fn test_goto_definition() {
     let uri = test_path("test.R");
@@ -262,6 +252,7 @@ async fn test_goto_definition_resolves_unqualified_import_into_package() {
         WorldState::new(db),
         LspState::new(
             tokio::sync::mpsc::unbounded_channel().0,
+            crossbeam::channel::unbounded().0,
             SourceScheduler::new(Some(handler)),
         ),
     );
@@ -328,6 +319,7 @@ async fn test_goto_definition_resolves_unqualified_import_from_into_package() {
         WorldState::new(db),
         LspState::new(
             tokio::sync::mpsc::unbounded_channel().0,
+            crossbeam::channel::unbounded().0,
             SourceScheduler::new(Some(handler)),
         ),
     );
@@ -398,6 +390,7 @@ async fn test_goto_definition_resolves_namespace_accesses() {
         WorldState::new(db),
         LspState::new(
             tokio::sync::mpsc::unbounded_channel().0,
+            crossbeam::channel::unbounded().0,
             SourceScheduler::new(Some(handler)),
         ),
     );
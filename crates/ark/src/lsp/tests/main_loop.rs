@@ -37,6 +37,7 @@ async fn test_workspace_folder_scan_drives_through_main_loop() {
         WorldState::new(OakDatabase::new()),
         LspState::new(
             tokio::sync::mpsc::unbounded_channel().0,
+            crossbeam::channel::unbounded().0,
             SourceScheduler::new(None),
         ),
     );
@@ -85,6 +85,7 @@ async fn test_source_pipeline_ingests_package_sources() {
         WorldState::new(db),
         LspState::new(
             tokio::sync::mpsc::unbounded_channel().0,
+            crossbeam::channel::unbounded().0,
             SourceScheduler::new(Some(handler.clone())),
         ),
     );
@@ -146,6 +147,7 @@ async fn test_failed_source_is_not_retried() {
         WorldState::new(db),
         LspState::new(
             tokio::sync::mpsc::unbounded_channel().0,
+            crossbeam::channel::unbounded().0,
             SourceScheduler::new(Some(handler.clone())),
         ),
     );
@@ -221,6 +223,7 @@ async fn test_source_pipeline_ingests_real_srcref_sources() {
         WorldState::new(db),
         LspState::new(
             tokio::sync::mpsc::unbounded_channel().0,
+            crossbeam::channel::unbounded().0,
             SourceScheduler::new(Some(handler)),
         ),
     );
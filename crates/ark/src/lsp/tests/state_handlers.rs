@@ -18,12 +18,14 @@ use oak_scan::ScanScheduler;
 use tower_lsp::lsp_types::DidChangeWatchedFilesParams;
 use tower_lsp::lsp_types::DidChangeWorkspaceFoldersParams;
 use tower_lsp::lsp_types::DidCloseTextDocumentParams;
+use tower_lsp::lsp_types::DidSaveTextDocumentParams;
 use tower_lsp::lsp_types::FileChangeType;
 use tower_lsp::lsp_types::FileEvent;
 use tower_lsp::lsp_types::InitializeParams;
 use tower_lsp::lsp_types::TextDocumentIdentifier;
 use tower_lsp::lsp_types::WorkspaceFolder;
 use tower_lsp::lsp_types::WorkspaceFoldersChangeEvent;
+use tower_lsp::lsp_types::WorkspaceSymbolParams;
 use url::Url;
 
 use crate::lsp::main_loop::dispatch_scan_requests;
@@ -36,6 +38,7 @@ use crate::lsp::sources::SourceScheduler;
 use crate::lsp::state::WorldState;
 use crate::lsp::state_handlers::did_close;
 use crate::lsp::state_handlers::effective_workspace_uris;
+use crate::lsp::symbols;
 
 /// Local sync wrappers around the async-shaped scheduler API. Tests
 /// don't need the timing flexibility, so each operation kicks off
@@ -87,6 +90,17 @@ fn did_change_workspace_folders(
     })
 }
 
+fn did_save(url: &Url, state: &mut WorldState) -> anyhow::Result<()> {
+    let params = DidSaveTextDocumentParams {
+        text_document: TextDocumentIdentifier { uri: url.clone() },
+        text: None,
+    };
+    let mut lsp_state = test_lsp_state();
+    run_handler_to_quiescence(state, &mut lsp_state, |state, lsp_state, events_tx| {
+        crate::lsp::state_handlers::did_save(params, state, lsp_state, events_tx)
+    })
+}
+
 /// Drive a production handler that dispatches its scans through `events_tx`,
 /// then pump the resulting `OakScanCompleted` events to quiescence on a local
 /// runtime. Production does this pumping in the main loop's event handler;
@@ -127,6 +141,7 @@ where
 fn test_lsp_state() -> LspState {
     LspState::new(
         tokio::sync::mpsc::unbounded_channel().0,
+        crossbeam::channel::unbounded().0,
         SourceScheduler::new(None),
     )
 }
@@ -661,3 +676,67 @@ fn test_did_close_releases_orphan_file_to_stale() {
         AuxiliaryEvent::PublishDiagnostics(u, diags, _) if u == url && diags.is_empty()
     ));
 }
+
+fn workspace_symbol_names(state: &WorldState, query: &str) -> Vec<String> {
+    let params = WorkspaceSymbolParams {
+        query: query.to_string(),
+        ..Default::default()
+    };
+    symbols::symbols(&params, state)
+        .unwrap()
+        .into_iter()
+        .map(|symbol| symbol.name)
+        .collect()
+}
+
+#[test]
+fn test_did_save_indexes_new_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("a.R"), "x <- 1\n").unwrap();
+    let mut state = workspace_state(tmp.path());
+
+    // Open a file that doesn't exist on disk yet, define a function, and
+    // save it
+    let path = tmp.path().join("b.R");
+    let url = Url::from_file_path(&path).unwrap();
+    let contents = "my_fun <- function(x) x\n";
+    let file = state
+        .db
+        .upsert_editor(FilePath::from_url(&url), contents.to_string());
+    state.insert_open_file(url.clone(), file, None);
+
+    fs::write(&path, contents).unwrap();
+    did_save(&url, &mut state).unwrap();
+
+    // The saved file now belongs to the workspace root
+    let root = state.db.workspace_roots().roots(&state.db)[0];
+    assert!(root.scripts(&state.db).contains(&file));
+    assert!(!state.db.orphan_root().files(&state.db).contains(&file));
+
+    assert_eq!(workspace_symbol_names(&state, "my_fun"), vec![
+        String::from("my_fun")
+    ]);
+}
+
+#[test]
+fn test_did_save_removes_deleted_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("a.R");
+    fs::write(&path, "my_fun <- function(x) x\n").unwrap();
+    let mut state = workspace_state(tmp.path());
+
+    let url = Url::from_file_path(&path).unwrap();
+    let file_path = FilePath::from_url(&url);
+    let file = state
+        .db
+        .upsert_editor(file_path.clone(), "my_fun <- function(x) x\n".to_string());
+    state.insert_open_file(url.clone(), file, None);
+    assert_eq!(workspace_symbol_names(&state, "my_fun").len(), 1);
+
+    // The file is deleted between the edit and the save
+    fs::remove_file(&path).unwrap();
+    did_save(&url, &mut state).unwrap();
+
+    assert!(state.db.file_by_path(&file_path).is_none());
+    assert!(workspace_symbol_names(&state, "my_fun").is_empty());
+}
@@ -0,0 +1,103 @@
+use assert_matches::assert_matches;
+use tower_lsp::lsp_types;
+use tower_lsp::lsp_types::request::GotoTypeDefinitionParams;
+use tower_lsp::lsp_types::request::GotoTypeDefinitionResponse;
+
+use super::utils::make_state_with;
+use super::utils::range;
+use crate::lsp::type_definition::type_definition;
+use crate::lsp::util::test_path;
+
+fn make_params(uri: lsp_types::Url, line: u32, character: u32) -> GotoTypeDefinitionParams {
+    GotoTypeDefinitionParams {
+        text_document_position_params: lsp_types::TextDocumentPositionParams {
+            text_document: lsp_types::TextDocumentIdentifier { uri },
+            position: lsp_types::Position::new(line, character),
+        },
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    }
+}
+
+#[test]
+fn test_type_definition_from_new() {
+    let class_uri = test_path("class.R");
+    let usage_uri = test_path("usage.R");
+    let state = make_state_with(&[
+        (
+            &class_uri,
+            "setClass(\"Foo\", representation(x = \"numeric\"))\n",
+        ),
+        (&usage_uri, "obj <- new(\"Foo\", x = 1)\nobj\n"),
+    ]);
+
+    // On a variable assigned from `new()`
+    assert_matches!(
+        type_definition(make_params(usage_uri.clone(), 1, 1), &state).unwrap(),
+        Some(GotoTypeDefinitionResponse::Scalar(location)) => {
+            assert_eq!(location.uri, class_uri);
+            assert_eq!(location.range, range((0, 9), (0, 14)));
+        }
+    );
+
+    // On the class name passed to `new()`
+    assert_matches!(
+        type_definition(make_params(usage_uri.clone(), 0, 13), &state).unwrap(),
+        Some(GotoTypeDefinitionResponse::Scalar(location)) => {
+            assert_eq!(location.uri, class_uri);
+        }
+    );
+
+    // On `new` itself
+    assert_matches!(
+        type_definition(make_params(usage_uri, 0, 8), &state).unwrap(),
+        Some(GotoTypeDefinitionResponse::Scalar(location)) => {
+            assert_eq!(location.uri, class_uri);
+        }
+    );
+}
+
+#[test]
+fn test_type_definition_from_generator() {
+    let uri = test_path("test.R");
+    let state = make_state_with(&[(&uri, "Foo <- setClass(\"Foo\")\nobj <- Foo()\nobj\n")]);
+
+    assert_matches!(
+        type_definition(make_params(uri, 2, 0), &state).unwrap(),
+        Some(GotoTypeDefinitionResponse::Scalar(location)) => {
+            assert_eq!(location.range, range((0, 16), (0, 21)));
+        }
+    );
+}
+
+#[test]
+fn test_type_definition_without_s4_class_returns_none() {
+    let uri = test_path("test.R");
+    let state = make_state_with(&[(&uri, "x <- c(1, 2)\nx\nlist()\n")]);
+
+    assert_eq!(
+        type_definition(make_params(uri.clone(), 1, 0), &state).unwrap(),
+        None
+    );
+    assert_eq!(
+        type_definition(make_params(uri, 2, 1), &state).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_type_definition_outside_workspace_returns_none() {
+    // The class isn't defined in the indexed files, so there is no location
+    // to report even if the class exists in the R session
+    let uri = test_path("test.R");
+    let state = make_state_with(&[(&uri, "obj <- new(\"dgCMatrix\")\nobj\n")]);
+
+    assert_eq!(
+        type_definition(make_params(uri.clone(), 1, 0), &state).unwrap(),
+        None
+    );
+    assert_eq!(
+        type_definition(make_params(uri, 0, 13), &state).unwrap(),
+        None
+    );
+}
@@ -63,6 +63,16 @@ pub(super) fn make_state(uri: &lsp_types::Url, contents: &str) -> WorldState {
     state
 }
 
+/// A state with several open files, each mirrored into `oak` like `did_open`
+/// does, so cross-file lookups resolve through `file_by_path`.
+pub(super) fn make_state_with(files: &[(&lsp_types::Url, &str)]) -> WorldState {
+    let mut state = WorldState::default();
+    for (uri, contents) in files {
+        insert_file(&mut state, uri, contents);
+    }
+    state
+}
+
 /// Insert an editor buffer, the same as `did_open` performs, so handlers
 /// reading either `state.documents` or `state.db` (via `file_by_path`) see a
 /// consistent file.
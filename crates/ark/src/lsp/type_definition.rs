@@ -0,0 +1,158 @@
+//
+// type_definition.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use aether_lsp_utils::proto::from_proto;
+use aether_path::FilePath;
+use biome_rowan::TextRange;
+use oak_db::Db;
+use oak_db::File;
+use tower_lsp::lsp_types::request::GotoTypeDefinitionParams;
+use tower_lsp::lsp_types::request::GotoTypeDefinitionResponse;
+use tower_lsp::lsp_types::Location;
+use tree_sitter::Node;
+
+use crate::lsp::db::ArkDb;
+use crate::lsp::db::FileArkExt;
+use crate::lsp::indexer;
+use crate::lsp::open_file::tree_sitter_point_from_lsp_position;
+use crate::lsp::state::WorldState;
+use crate::lsp::traits::node::NodeExt;
+use crate::treesitter::node_find_parent_call;
+use crate::treesitter::node_find_string;
+use crate::treesitter::BinaryOperatorType;
+use crate::treesitter::NodeType;
+use crate::treesitter::NodeTypeExt;
+
+/// Jump to the `setClass()` definition of the S4 class of the object at the
+/// cursor.
+///
+/// The class is inferred statically from `new("Foo")` calls, calls to a class
+/// generator `Foo()` created with `Foo <- setClass("Foo")`, or variables
+/// assigned from one of these. The definition is looked up in the indexed
+/// workspace files only, so classes from installed packages, S3 classes, and
+/// base types have no type definition.
+pub(crate) fn type_definition(
+    params: GotoTypeDefinitionParams,
+    state: &WorldState,
+) -> anyhow::Result<Option<GotoTypeDefinitionResponse>> {
+    let uri = &params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let db = &state.db;
+    let encoding = state.config.position_encoding;
+
+    let Some(file) = db.file_by_path(&FilePath::from_url(uri)) else {
+        return Ok(None);
+    };
+
+    let contents = file.source_text(db);
+    let point = tree_sitter_point_from_lsp_position(position, file.line_index(db), encoding)?;
+    let Some(node) = file
+        .tree_sitter(db)
+        .root_node()
+        .find_smallest_spanning_node(point)
+    else {
+        return Ok(None);
+    };
+
+    let class = match class_of_node(db, &node, contents.as_str()) {
+        Some(class) => class,
+        None => {
+            // Resolve a variable to its binding and infer the class from
+            // the assigned value
+            let offset = from_proto::offset_from_position(position, file.line_index(db), encoding)?;
+            let Some(target) = oak_ide::goto_definition(db, file, offset)
+                .into_iter()
+                .next()
+            else {
+                return Ok(None);
+            };
+            let Some(class) = class_of_binding(db, target.file, target.focus_range) else {
+                return Ok(None);
+            };
+            class
+        },
+    };
+
+    let Some((class_file, entry)) = indexer::find_class(db, &class) else {
+        return Ok(None);
+    };
+    let Some(range) = indexer::index_range_to_lsp_range(db, class_file, entry.range, encoding)
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(GotoTypeDefinitionResponse::Scalar(Location {
+        uri: state.wire_url(class_file),
+        range,
+    })))
+}
+
+/// Infer the class from the node at the cursor, for `new("Foo")` and
+/// `Foo()` calls where the cursor is on the function or the class name.
+fn class_of_node(db: &dyn ArkDb, node: &Node, contents: &str) -> Option<String> {
+    // Cursor on the class name in `new("Foo")`
+    if let Some(string) = node_find_string(node) {
+        let call = node_find_parent_call(&string)?;
+        return class_of_expr(db, &call, contents);
+    }
+
+    // Cursor on the function of a call
+    let parent = node.parent()?;
+    if parent.is_call() && parent.child_by_field_name("function") == Some(*node) {
+        return class_of_expr(db, &parent, contents);
+    }
+
+    None
+}
+
+/// Infer the class of the value bound at `range` in `file`, e.g. `x` in
+/// `x <- new("Foo")`.
+fn class_of_binding(db: &dyn ArkDb, file: File, range: TextRange) -> Option<String> {
+    let contents = file.source_text(db);
+    let tree = file.tree_sitter(db);
+
+    let lhs = tree
+        .root_node()
+        .descendant_for_byte_range(range.start().into(), range.end().into())?;
+
+    let assignment = lhs.parent()?;
+    if !matches!(
+        assignment.node_type(),
+        NodeType::BinaryOperator(BinaryOperatorType::LeftAssignment) |
+            NodeType::BinaryOperator(BinaryOperatorType::EqualsAssignment)
+    ) {
+        return None;
+    }
+    if assignment.child_by_field_name("lhs") != Some(lhs) {
+        return None;
+    }
+
+    let rhs = assignment.child_by_field_name("rhs")?;
+    class_of_expr(db, &rhs, contents.as_str())
+}
+
+/// Infer the class of the object created by `expr`.
+pub(crate) fn class_of_expr(db: &dyn ArkDb, expr: &Node, contents: &str) -> Option<String> {
+    if !expr.is_call() {
+        return None;
+    }
+
+    if indexer::node_is_methods_call(expr, "new", contents) {
+        let class = indexer::s4_call_arg(expr, "Class", 0, contents)?;
+        return indexer::s4_string(&class, contents);
+    }
+
+    // A class generator, as in `Foo <- setClass("Foo")`. We assume the
+    // generator is named after its class.
+    let fun = expr.child_by_field_name("function")?;
+    if !fun.is_identifier() {
+        return None;
+    }
+    let name = fun.node_as_str(contents).ok()?;
+    indexer::find_class(db, name).map(|_| name.to_string())
+}
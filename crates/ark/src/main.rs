@@ -9,19 +9,27 @@
 
 use std::cell::Cell;
 use std::env;
+use std::path::Path;
+use std::path::PathBuf;
 
 use amalthea::kernel;
+use amalthea::kernel_spec::InterruptMode;
 use amalthea::kernel_spec::KernelSpec;
 use anyhow::Context;
 use ark::console::catching_panics;
+use ark::console::BannerMode;
 use ark::console::SessionMode;
 use ark::logger;
+use ark::network::set_allow_external_connections;
+use ark::plots::graphics_device::set_max_plot_size;
+use ark::plots::graphics_device::MaxPlotSize;
 use ark::repos::DefaultRepos;
 use ark::signals::initialize_signal_block;
 use ark::start::start_kernel;
 use ark::traps::register_trap_handlers;
 use crossbeam::channel::unbounded;
 use harp::command::r_home_setup;
+use harp::command::r_home_validate;
 use notify::Watcher;
 use stdext::unwrap;
 
@@ -41,9 +49,23 @@ Available options:
                              (see the Jupyter kernel documentation for details)
 -- arg1 arg2 ...             Set the argument list to pass to R; defaults to
                              --interactive
+--r-home DIR                 Use the R installation in DIR instead of detecting
+                             one from `R_HOME` or the `PATH`
+--r-args-file FILE           Read arguments to pass to R from FILE, one per line.
+                             Blank lines and lines starting with `#` are skipped.
+                             Arguments after `--` are passed after these, so they
+                             take precedence when R options conflict
 --startup-file FILE          An R file to run on session startup
 --session-mode MODE          The mode in which the session is running (console, notebook, background)
 --no-capture-streams         Do not capture stdout/stderr from R
+--capture-log FILE           Also write the raw stdout/stderr captured from R to
+                             FILE, for debugging output issues
+--quiet                      Discard the R startup banner instead of sending it to
+                             the frontend (the default in background sessions)
+--verbose                    Send the R startup banner to the frontend and also
+                             log it
+--env KEY=VALUE              Set an environment variable before R starts (can be
+                             repeated). Variables are set before `.Rprofile` runs
 --default-repos              Set the default repositories to use, by name:
                              "rstudio" ('cran.rstudio.com', the default), or
                              "posit-ppm" ('packagemanager.posit.co', subject to availability), or
@@ -53,9 +75,36 @@ Available options:
 --default-ppm-repo           Set the default repositories to a custom Posit Package Manager URL.
 --default-cran-repo          Set the default CRAN repository to a custom URL.
 --version                    Print the version of Ark
+--list-r                     Print the R installations found in common locations,
+                             with their version and `R_HOME`, and exit
 --log FILE                   Log to the given file (if not specified, stdout/stderr
                              will be used)
---install                    Install the kernel spec for Ark"#
+--profile-startup            Log the time spent in each phase of kernel startup
+--interrupt-on-idle-timeout SECONDS
+                             Interrupt R when it runs for more than SECONDS
+                             without returning to the prompt (disabled by
+                             default). Useful for unattended sessions
+--max-plot-size WxH          Clamp rendered plots to at most W by H pixels
+                             (default 16384x16384)
+--allow-external-connections Let the LSP, DAP, and help servers accept
+                             connections from other machines by listening on
+                             all interfaces. By default they only listen on
+                             loopback addresses
+--install                    Install the kernel spec for Ark
+--prefix DIR                 With `--install`, install the kernel spec under
+                             DIR/share/jupyter/kernels rather than in Jupyter's
+                             default location (useful for conda/venv prefixes)
+--dump-kernelspec            Print the kernel spec JSON that `--install` would
+                             write, without installing it
+--interrupt-mode MODE        With `--install` or `--dump-kernelspec`, how the
+                             frontend should interrupt the kernel: "signal" (the
+                             default) or "message" (for environments where
+                             signals can't be delivered)
+--kernel-name NAME           With `--install`, the name of the kernel spec
+                             directory (default "ark"). Use distinct names to
+                             install kernels for several R versions side by side
+--display-name NAME          With `--install` or `--dump-kernelspec`, the name
+                             shown by the frontend (default "Ark R Kernel")"#
     );
 
     // Windows-specific options
@@ -93,11 +142,24 @@ fn main() -> anyhow::Result<()> {
     let mut session_mode = SessionMode::Console;
     let mut log_file: Option<String> = None;
     let mut profile_file: Option<String> = None;
+    let mut profile_startup = false;
     let mut startup_notifier_file: Option<String> = None;
     let mut startup_delay: Option<std::time::Duration> = None;
+    let mut interrupt_timeout: Option<std::time::Duration> = None;
+    let mut max_plot_size: Option<MaxPlotSize> = None;
+    let mut allow_external_connections = false;
     let mut r_args: Vec<String> = Vec::new();
     let mut has_action = false;
+    let mut install = false;
+    let mut dump_kernelspec = false;
+    let mut install_prefix: Option<PathBuf> = None;
+    let mut interrupt_mode: Option<InterruptMode> = None;
+    let mut kernel_name: Option<String> = None;
+    let mut display_name: Option<String> = None;
     let mut capture_streams = true;
+    let mut capture_log: Option<PathBuf> = None;
+    let mut banner_mode: Option<BannerMode> = None;
+    let mut env_vars: Vec<(String, String)> = Vec::new();
     let mut default_repos = DefaultRepos::Auto;
     #[cfg(target_os = "windows")]
     let mut use_windows_dll_search_path = false;
@@ -146,15 +208,82 @@ fn main() -> anyhow::Result<()> {
                 println!("Ark {}", ark::BUILD_VERSION);
                 return Ok(());
             },
-            "--install" => {
-                install_kernel_spec()?;
+            "--list-r" => {
+                list_r_installations();
                 return Ok(());
             },
+            "--install" => install = true,
+            "--dump-kernelspec" => dump_kernelspec = true,
+            "--prefix" => {
+                if let Some(dir) = argv.next() {
+                    install_prefix = Some(PathBuf::from(dir));
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "A directory must be specified when using the `--prefix` argument."
+                    ));
+                }
+            },
+            "--interrupt-mode" => {
+                if let Some(mode) = argv.next() {
+                    interrupt_mode = Some(match mode.as_str() {
+                        "signal" => InterruptMode::Signal,
+                        "message" => InterruptMode::Message,
+                        _ => {
+                            return Err(anyhow::anyhow!(
+                                "Invalid interrupt mode: '{mode}'. Expected `signal` or `message`."
+                            ));
+                        },
+                    });
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "An interrupt mode must be specified when using the `--interrupt-mode` argument."
+                    ));
+                }
+            },
+            "--kernel-name" => {
+                if let Some(name) = argv.next() {
+                    validate_kernel_name(&name)?;
+                    kernel_name = Some(name);
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "A name must be specified when using the `--kernel-name` argument."
+                    ));
+                }
+            },
+            "--display-name" => {
+                if let Some(name) = argv.next() {
+                    display_name = Some(name);
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "A name must be specified when using the `--display-name` argument."
+                    ));
+                }
+            },
             "--help" => {
                 print_usage();
                 return Ok(());
             },
             "--no-capture-streams" => capture_streams = false,
+            "--capture-log" => {
+                if let Some(file) = argv.next() {
+                    capture_log = Some(PathBuf::from(file));
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "A log file must be specified when using the `--capture-log` argument."
+                    ));
+                }
+            },
+            "--quiet" => banner_mode = Some(BannerMode::Quiet),
+            "--verbose" => banner_mode = Some(BannerMode::Verbose),
+            "--env" => {
+                if let Some(var) = argv.next() {
+                    env_vars.push(parse_env_var(&var)?);
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "A `KEY=VALUE` pair must be specified when using the `--env` argument."
+                    ));
+                }
+            },
             #[cfg(target_os = "windows")]
             "--standard-dll-search-order" => use_windows_dll_search_path = true,
             "--default-repos" => {
@@ -264,6 +393,7 @@ fn main() -> anyhow::Result<()> {
                     ));
                 }
             },
+            "--profile-startup" => profile_startup = true,
             "--startup-notifier-file" => {
                 if let Some(file) = argv.next() {
                     startup_notifier_file = Some(file);
@@ -286,6 +416,56 @@ fn main() -> anyhow::Result<()> {
                     ));
                 }
             },
+            "--interrupt-on-idle-timeout" => {
+                if let Some(timeout_arg) = argv.next() {
+                    match timeout_arg.parse::<u64>() {
+                        Ok(timeout) if timeout > 0 => {
+                            interrupt_timeout = Some(std::time::Duration::from_secs(timeout));
+                        },
+                        _ => {
+                            return Err(anyhow::anyhow!(
+                                "Can't parse timeout in seconds: {timeout_arg}"
+                            ));
+                        },
+                    }
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "A timeout in seconds must be specified when using the `--interrupt-on-idle-timeout` argument."
+                    ));
+                }
+            },
+            "--max-plot-size" => {
+                if let Some(size) = argv.next() {
+                    max_plot_size = Some(size.parse::<MaxPlotSize>()?);
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "A size of the form `WxH` must be specified when using the `--max-plot-size` argument."
+                    ));
+                }
+            },
+            "--allow-external-connections" => allow_external_connections = true,
+            "--r-home" => {
+                if let Some(dir) = argv.next() {
+                    // Set `R_HOME` right away so that it takes precedence over
+                    // auto-detection, including for `--list-r`
+                    let dir = PathBuf::from(dir);
+                    r_home_validate(&dir)?;
+                    env::set_var("R_HOME", &dir);
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "A directory must be specified when using the `--r-home` argument."
+                    ));
+                }
+            },
+            "--r-args-file" => {
+                if let Some(file) = argv.next() {
+                    r_args.extend(read_r_args_file(&file)?);
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "A file must be specified when using the `--r-args-file` argument."
+                    ));
+                }
+            },
             "--" => {
                 // Consume the rest of the arguments for passthrough delivery to R
                 for arg in argv.by_ref() {
@@ -299,6 +479,53 @@ fn main() -> anyhow::Result<()> {
         }
     }
 
+    let interrupt_mode_arg = interrupt_mode.is_some();
+    let interrupt_mode = interrupt_mode.unwrap_or_default();
+
+    let display_name_arg = display_name.is_some();
+    let display_name = display_name.unwrap_or_else(|| String::from("Ark R Kernel"));
+
+    if dump_kernelspec {
+        let spec = kernel_spec(interrupt_mode, display_name)?;
+        println!("{}", serde_json::to_string_pretty(&spec)?);
+        return Ok(());
+    }
+
+    if install {
+        let kernel_name = kernel_name.unwrap_or_else(|| String::from("ark"));
+        install_kernel_spec(
+            install_prefix.as_deref(),
+            kernel_name,
+            display_name,
+            interrupt_mode,
+        )?;
+        return Ok(());
+    }
+
+    if interrupt_mode_arg {
+        return Err(anyhow::anyhow!(
+            "The `--interrupt-mode` argument can only be used together with `--install` or `--dump-kernelspec`."
+        ));
+    }
+
+    if display_name_arg {
+        return Err(anyhow::anyhow!(
+            "The `--display-name` argument can only be used together with `--install` or `--dump-kernelspec`."
+        ));
+    }
+
+    if kernel_name.is_some() {
+        return Err(anyhow::anyhow!(
+            "The `--kernel-name` argument can only be used together with `--install`."
+        ));
+    }
+
+    if install_prefix.is_some() {
+        return Err(anyhow::anyhow!(
+            "The `--prefix` argument can only be used together with `--install`."
+        ));
+    }
+
     // Initialize the logger.
     logger::init(log_file.as_deref(), profile_file.as_deref());
 
@@ -453,6 +680,25 @@ fn main() -> anyhow::Result<()> {
         harp::sys::library::set_use_standard_dll_search_path(true);
     }
 
+    // Set environment variables requested on the command line. This happens
+    // before R starts so they are visible to R at startup, including in
+    // `.Rprofile`.
+    for (key, value) in env_vars {
+        env::set_var(key, value);
+    }
+
+    if let Some(timeout) = interrupt_timeout {
+        ark::watchdog::start(timeout);
+    }
+
+    if let Some(size) = max_plot_size {
+        set_max_plot_size(size);
+    }
+
+    if allow_external_connections {
+        set_allow_external_connections(true);
+    }
+
     // Connect the Jupyter kernel and start R.
     // Does not return!
     start_kernel(
@@ -461,16 +707,121 @@ fn main() -> anyhow::Result<()> {
         r_args,
         startup_file,
         session_mode,
+        banner_mode.unwrap_or_else(|| BannerMode::default_for(session_mode)),
         capture_streams,
+        capture_log,
         default_repos,
+        profile_startup,
     );
 
     // Just to please Rust
     Ok(())
 }
 
-// Install the kernelspec JSON file into one of Jupyter's search paths.
-fn install_kernel_spec() -> anyhow::Result<()> {
+// Parse a `KEY=VALUE` pair supplied with `--env`. The value may be empty
+// and may contain `=`, but the key must not be empty.
+fn parse_env_var(var: &str) -> anyhow::Result<(String, String)> {
+    match var.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((String::from(key), String::from(value))),
+        _ => Err(anyhow::anyhow!(
+            "Invalid `--env` argument '{var}'. Expected the form `KEY=VALUE`."
+        )),
+    }
+}
+
+// Read R arguments supplied with `--r-args-file`, one per line. Lines are
+// trimmed, and blank lines and `#` comments are skipped.
+fn read_r_args_file(file: &str) -> anyhow::Result<Vec<String>> {
+    let path = Path::new(file);
+    if !path.exists() {
+        return Err(anyhow::anyhow!(
+            "The specified R arguments file {file:?} does not exist."
+        ));
+    }
+
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read R arguments file {file:?}"))?;
+
+    let args = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+
+    Ok(args)
+}
+
+fn list_r_installations() {
+    let installations = ark::version::detect_r_installations();
+
+    if installations.is_empty() {
+        println!("No R installations found.");
+        println!(
+            "Install R from https://cloud.r-project.org, or point Ark to an existing \
+             installation by setting the `R_HOME` environment variable or by adding \
+             the `R` executable to the `PATH`."
+        );
+        return;
+    }
+
+    for installation in installations {
+        let supported = if installation.version.is_supported() {
+            ""
+        } else {
+            " (unsupported)"
+        };
+        println!(
+            "R {}{supported}\t{}",
+            installation.version,
+            installation.r_home.display()
+        );
+    }
+}
+
+// Jupyter identifies kernels by the name of their kernelspec directory, which
+// may only contain ASCII letters, digits, `.`, `_`, and `-`.
+fn validate_kernel_name(name: &str) -> anyhow::Result<()> {
+    let valid = !name.is_empty() &&
+        name.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+
+    if !valid {
+        return Err(anyhow::anyhow!(
+            "Invalid kernel name: '{name}'. Kernel names may only contain ASCII letters, digits, `.`, `_`, and `-`."
+        ));
+    }
+
+    Ok(())
+}
+
+// Install the kernelspec JSON file into one of Jupyter's search paths, or
+// under `PREFIX/share/jupyter/kernels` if a prefix is supplied.
+fn install_kernel_spec(
+    prefix: Option<&Path>,
+    kernel_name: String,
+    display_name: String,
+    interrupt_mode: InterruptMode,
+) -> anyhow::Result<()> {
+    let spec = kernel_spec(interrupt_mode, display_name)?;
+
+    let dest = unwrap!(spec.install(kernel_name, prefix), Err(err) => {
+        return Err(anyhow::anyhow!("Failed to install Ark's Jupyter kernelspec. {err}"))
+    });
+
+    println!(
+        "Successfully installed Ark Jupyter kernelspec.
+
+    Kernel: {}
+    ",
+        dest.to_string_lossy()
+    );
+
+    Ok(())
+}
+
+// Create the kernelspec for the current Ark executable and R installation
+fn kernel_spec(interrupt_mode: InterruptMode, display_name: String) -> anyhow::Result<KernelSpec> {
     // Create the environment set for the kernel spec
     let mut env = serde_json::Map::new();
 
@@ -503,21 +854,27 @@ fn install_kernel_spec() -> anyhow::Result<()> {
             String::from("notebook"),
         ],
         language: String::from("R"),
-        display_name: String::from("Ark R Kernel"),
+        display_name,
         env,
+        interrupt_mode,
+        metadata: None,
     };
 
-    let dest = unwrap!(spec.install(String::from("ark")), Err(err) => {
-        return Err(anyhow::anyhow!("Failed to install Ark's Jupyter kernelspec. {err}"))
-    });
+    Ok(spec)
+}
 
-    println!(
-        "Successfully installed Ark Jupyter kernelspec.
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Kernel: {}
-    ",
-        dest.to_string_lossy()
-    );
+    #[test]
+    fn test_validate_kernel_name() {
+        assert!(validate_kernel_name("ark").is_ok());
+        assert!(validate_kernel_name("r-4.3_dev.1").is_ok());
 
-    Ok(())
+        assert!(validate_kernel_name("").is_err());
+        assert!(validate_kernel_name("my kernel").is_err());
+        assert!(validate_kernel_name("r/4.3").is_err());
+        assert!(validate_kernel_name("café").is_err());
+    }
 }
@@ -0,0 +1,106 @@
+//
+// network.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::sync::OnceLock;
+
+use anyhow::anyhow;
+
+/// Whether the LSP, DAP, and help proxy servers may accept connections from
+/// other machines, set with `--allow-external-connections`
+static ALLOW_EXTERNAL_CONNECTIONS: OnceLock<bool> = OnceLock::new();
+
+/// Allow servers to bind to all interfaces. Must be called before any server
+/// starts.
+pub fn set_allow_external_connections(allow: bool) {
+    if ALLOW_EXTERNAL_CONNECTIONS.set(allow).is_err() {
+        log::error!("External connections are already configured");
+    }
+}
+
+fn allow_external_connections() -> bool {
+    ALLOW_EXTERNAL_CONNECTIONS.get().copied().unwrap_or(false)
+}
+
+/// The IP address a server should bind to, given the address requested by
+/// the frontend. Servers only bind to loopback addresses, and we fall back to
+/// loopback when the request isn't a valid IP address. Non-loopback requests
+/// are refused unless external connections are allowed, in which case we bind
+/// to all interfaces.
+pub(crate) fn server_ip_address(requested: &str) -> anyhow::Result<IpAddr> {
+    bind_ip_address(requested, allow_external_connections())
+}
+
+fn bind_ip_address(requested: &str, allow_external: bool) -> anyhow::Result<IpAddr> {
+    let requested_ip = requested.parse::<IpAddr>();
+
+    if allow_external {
+        return Ok(match requested_ip {
+            Ok(IpAddr::V6(_)) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+            _ => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        });
+    }
+
+    let Ok(ip) = requested_ip else {
+        log::warn!("Binding to 127.0.0.1 instead of invalid requested address '{requested}'.");
+        return Ok(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    };
+
+    if !ip.is_loopback() {
+        return Err(anyhow!(
+            "Refusing to bind to non-loopback address '{ip}'. \
+             Use `--allow-external-connections` to accept external connections."
+        ));
+    }
+
+    Ok(ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use std::net::Ipv4Addr;
+    use std::net::Ipv6Addr;
+
+    use crate::network::bind_ip_address;
+    use crate::network::server_ip_address;
+
+    #[test]
+    fn test_default_bind_address_is_loopback() {
+        for requested in ["127.0.0.1", "::1", "localhost", ""] {
+            let ip = server_ip_address(requested).unwrap();
+            assert!(ip.is_loopback());
+        }
+
+        // Loopback addresses requested by the frontend are kept
+        assert_eq!(
+            bind_ip_address("::1", false).unwrap(),
+            IpAddr::V6(Ipv6Addr::LOCALHOST)
+        );
+    }
+
+    #[test]
+    fn test_requested_non_loopback_address_is_refused() {
+        assert!(bind_ip_address("192.168.1.10", false).is_err());
+        assert!(bind_ip_address("0.0.0.0", false).is_err());
+        assert!(bind_ip_address("::", false).is_err());
+    }
+
+    #[test]
+    fn test_external_connections_bind_all_interfaces() {
+        let all_v4 = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+        assert_eq!(bind_ip_address("127.0.0.1", true).unwrap(), all_v4);
+        assert_eq!(bind_ip_address("192.168.1.10", true).unwrap(), all_v4);
+        assert_eq!(bind_ip_address("localhost", true).unwrap(), all_v4);
+
+        let all_v6 = IpAddr::V6(Ipv6Addr::UNSPECIFIED);
+        assert_eq!(bind_ip_address("::1", true).unwrap(), all_v6);
+        assert_eq!(bind_ip_address("fe80::1", true).unwrap(), all_v6);
+    }
+}
@@ -6,3 +6,4 @@
 //
 
 pub mod graphics_device;
+pub mod plot_ext_comm;
@@ -13,7 +13,10 @@ use std::fmt::Display;
 use std::fs::File;
 use std::io::BufReader;
 use std::io::Read;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
 use amalthea::comm::comm_channel::CommMsg;
 use amalthea::comm::plot_comm::IntrinsicSize;
@@ -38,11 +41,11 @@ use amalthea::wire::update_display_data::TransientValue;
 use amalthea::wire::update_display_data::UpdateDisplayData;
 use anyhow::anyhow;
 use anyhow::Context;
-use base64::prelude::*;
 use crossbeam::channel::Sender;
 use harp::exec::RFunction;
 use harp::exec::RFunctionExt;
 use harp::object::RObject;
+use harp::vector::RawVector;
 use libr::pDevDesc;
 use libr::pGEcontext;
 use libr::R_NilValue;
@@ -58,10 +61,95 @@ use crate::comm_handler::CommHandlerContext;
 use crate::console::Console;
 use crate::console::SessionMode;
 use crate::modules::ARK_ENVS;
+use crate::plots::plot_ext_comm::ExportResult;
+use crate::plots::plot_ext_comm::PlotExtBackendReply;
+use crate::plots::plot_ext_comm::PlotExtBackendRequest;
+use crate::plots::plot_ext_comm::PlotReply;
+use crate::plots::plot_ext_comm::PlotRequest;
 use crate::r_task;
 
 pub const PLOT_COMM_NAME: &str = "positron.plot";
 
+/// Upper bound on the size of rendered images, set with `--max-plot-size`
+static MAX_PLOT_SIZE: OnceLock<MaxPlotSize> = OnceLock::new();
+
+/// Maximum physical size of a rendered plot, in pixels. Render requests are
+/// clamped to this size so that a malformed resize request can't make the
+/// device allocate a huge image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaxPlotSize {
+    pub width: i64,
+    pub height: i64,
+}
+
+impl Default for MaxPlotSize {
+    fn default() -> Self {
+        Self {
+            width: 16384,
+            height: 16384,
+        }
+    }
+}
+
+impl FromStr for MaxPlotSize {
+    type Err = anyhow::Error;
+
+    /// Parse a size in the form `WxH`, e.g. `4000x3000`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || anyhow!("Invalid plot size '{s}'. Expected the form `WxH`, e.g. `4000x3000`.");
+
+        let (width, height) = s.split_once('x').ok_or_else(invalid)?;
+        let width: i64 = width.parse().map_err(|_| invalid())?;
+        let height: i64 = height.parse().map_err(|_| invalid())?;
+
+        if width <= 0 || height <= 0 {
+            return Err(invalid());
+        }
+
+        Ok(Self { width, height })
+    }
+}
+
+impl MaxPlotSize {
+    /// Clamp render settings so that the rendered image, in physical pixels,
+    /// fits within this size. Both dimensions are scaled by the same factor so
+    /// the aspect ratio of the plot is preserved.
+    fn clamp(&self, settings: &PlotRenderSettings) -> PlotRenderSettings {
+        let pixel_ratio = settings.pixel_ratio.max(1.);
+        let width = settings.size.width.max(1) as f64;
+        let height = settings.size.height.max(1) as f64;
+
+        let scale = (self.width as f64 / (width * pixel_ratio))
+            .min(self.height as f64 / (height * pixel_ratio))
+            .min(1.);
+
+        if scale >= 1. {
+            return *settings;
+        }
+
+        PlotRenderSettings {
+            size: PlotSize {
+                width: ((width * scale).floor() as i64).max(1),
+                height: ((height * scale).floor() as i64).max(1),
+            },
+            pixel_ratio: settings.pixel_ratio,
+            format: settings.format,
+        }
+    }
+}
+
+/// Set the maximum size of rendered plots. Must be called before R starts.
+pub fn set_max_plot_size(size: MaxPlotSize) {
+    if MAX_PLOT_SIZE.set(size).is_err() {
+        log::error!("The maximum plot size is already set");
+    }
+}
+
+fn max_plot_size() -> MaxPlotSize {
+    MAX_PLOT_SIZE.get().copied().unwrap_or_default()
+}
+
 /// Perform R-side initialization of the graphics device.
 /// Must be called from the main R thread after Console is initialized.
 pub(crate) fn init_graphics_device() {
@@ -103,6 +191,9 @@ struct ExecutionContext {
 struct PlotContext {
     metadata: PlotMetadata,
     intrinsic_size: Option<IntrinsicSize>,
+    /// Whether the plot keeps its aspect ratio when resized, e.g. a ggplot
+    /// with `coord_fixed()`. The frontend uses this to size the plot pane.
+    fixed_aspect_ratio: bool,
 }
 
 /// Graphics device state: plot recording, rendering, and comm management.
@@ -432,6 +523,23 @@ impl DeviceContext {
         }
     }
 
+    /// Whether the last plot has a fixed aspect ratio. Defaults to `false`
+    /// when this can't be determined.
+    fn detect_fixed_aspect_ratio(&self) -> bool {
+        let result = RFunction::from(".ps.graphics.has_fixed_aspect_ratio").call();
+
+        match result {
+            Ok(fixed) => fixed.to::<bool>().unwrap_or_else(|err| {
+                log::warn!("Failed to convert aspect ratio flag to bool: {err:?}");
+                false
+            }),
+            Err(err) => {
+                log::warn!("Failed to detect fixed aspect ratio: {err:?}");
+                false
+            },
+        }
+    }
+
     /// Generate a unique name for a plot of the given kind
     fn generate_plot_name(&self, kind: &str) -> String {
         let mut counters = self.kind_counters.borrow_mut();
@@ -440,8 +548,15 @@ impl DeviceContext {
         format!("{} {}", kind, counter)
     }
 
+    fn handle_rpc(&self, message: PlotRequest, id: &PlotId) -> anyhow::Result<PlotReply> {
+        match message {
+            PlotRequest::Comm(message) => Ok(PlotReply::Comm(self.handle_comm_rpc(message, id)?)),
+            PlotRequest::Ext(message) => Ok(PlotReply::Ext(self.handle_ext_rpc(message, id)?)),
+        }
+    }
+
     #[tracing::instrument(level = "trace", skip_all, fields(id = %id))]
-    fn handle_rpc(
+    fn handle_comm_rpc(
         &self,
         message: PlotBackendRequest,
         id: &PlotId,
@@ -481,34 +596,12 @@ impl DeviceContext {
             PlotBackendRequest::Render(plot_meta) => {
                 log::trace!("PlotBackendRequest::Render");
 
-                let size = match plot_meta.size {
-                    Some(size) => size,
-                    None => {
-                        // No explicit size requested — use intrinsic size if available
-                        let intrinsic = self
-                            .plot_contexts
-                            .borrow()
-                            .get(id)
-                            .and_then(|ctx| ctx.intrinsic_size.clone());
-                        match intrinsic {
-                            Some(intrinsic) => intrinsic.to_plot_size(),
-                            None => {
-                                return Err(anyhow!(
-                                    "No size provided for plot {id} and no intrinsic size available"
-                                ));
-                            },
-                        }
-                    },
-                };
-
-                let settings = PlotRenderSettings {
-                    size: PlotSize {
-                        width: size.width,
-                        height: size.height,
-                    },
-                    pixel_ratio: plot_meta.pixel_ratio,
-                    format: plot_meta.format,
-                };
+                let settings = self.render_settings(
+                    id,
+                    plot_meta.size,
+                    plot_meta.pixel_ratio,
+                    plot_meta.format,
+                )?;
 
                 let data = self.render_plot(id, &settings)?;
                 let mime_type = Self::get_mime_type(&plot_meta.format);
@@ -522,6 +615,116 @@ impl DeviceContext {
         }
     }
 
+    #[tracing::instrument(level = "trace", skip_all, fields(id = %id))]
+    fn handle_ext_rpc(
+        &self,
+        message: PlotExtBackendRequest,
+        id: &PlotId,
+    ) -> anyhow::Result<PlotExtBackendReply> {
+        match message {
+            PlotExtBackendRequest::Export(params) => {
+                log::trace!("PlotExtBackendRequest::Export");
+
+                let path = Self::validate_export_path(&params.path, &params.format)?;
+                let settings =
+                    self.render_settings(id, params.size, params.pixel_ratio, params.format)?;
+
+                let image_path = self.render_plot_to_file(id, &settings)?;
+                std::fs::copy(&image_path, &path).with_context(|| {
+                    format!("Failed to write plot {id} to '{}'", path.display())
+                })?;
+
+                Ok(PlotExtBackendReply::ExportReply(ExportResult {
+                    path: path.to_string_lossy().to_string(),
+                }))
+            },
+        }
+    }
+
+    /// Resolve the settings for rendering a plot, falling back to the plot's
+    /// intrinsic size when no size is requested
+    fn render_settings(
+        &self,
+        id: &PlotId,
+        size: Option<PlotSize>,
+        pixel_ratio: f64,
+        format: PlotRenderFormat,
+    ) -> anyhow::Result<PlotRenderSettings> {
+        let size = match size {
+            Some(size) => size,
+            None => {
+                // No explicit size requested — use intrinsic size if available
+                let intrinsic = self
+                    .plot_contexts
+                    .borrow()
+                    .get(id)
+                    .and_then(|ctx| ctx.intrinsic_size.clone());
+                match intrinsic {
+                    Some(intrinsic) => intrinsic.to_plot_size(),
+                    None => {
+                        return Err(anyhow!(
+                            "No size provided for plot {id} and no intrinsic size available"
+                        ));
+                    },
+                }
+            },
+        };
+
+        Ok(PlotRenderSettings {
+            size: PlotSize {
+                width: size.width,
+                height: size.height,
+            },
+            pixel_ratio,
+            format,
+        })
+    }
+
+    /// Check that a plot can be exported to `path` in `format`. The path must
+    /// be absolute, its directory must exist, and its extension (if any) must
+    /// match the format.
+    fn validate_export_path(path: &str, format: &PlotRenderFormat) -> anyhow::Result<PathBuf> {
+        let path = PathBuf::from(path);
+
+        if !path.is_absolute() {
+            return Err(anyhow!(
+                "Can't export plot to '{}': path must be absolute",
+                path.display()
+            ));
+        }
+        if path.is_dir() {
+            return Err(anyhow!(
+                "Can't export plot to '{}': path is a directory",
+                path.display()
+            ));
+        }
+        if !path.parent().is_some_and(|parent| parent.is_dir()) {
+            return Err(anyhow!(
+                "Can't export plot to '{}': directory doesn't exist",
+                path.display()
+            ));
+        }
+
+        if let Some(ext) = path.extension() {
+            let ext = ext.to_string_lossy().to_lowercase();
+            let valid = match format {
+                PlotRenderFormat::Png => &["png"][..],
+                PlotRenderFormat::Jpeg => &["jpeg", "jpg"][..],
+                PlotRenderFormat::Svg => &["svg"][..],
+                PlotRenderFormat::Pdf => &["pdf"][..],
+                PlotRenderFormat::Tiff => &["tiff", "tif"][..],
+            };
+            if !valid.contains(&ext.as_str()) {
+                return Err(anyhow!(
+                    "Can't export plot to '{}': extension doesn't match format `{format}`",
+                    path.display()
+                ));
+            }
+        }
+
+        Ok(path)
+    }
+
     #[tracing::instrument(level = "trace", skip(self))]
     fn on_plot_closed(&self, id: &PlotId) {
         self.comm_ids.borrow_mut().remove(id);
@@ -556,6 +759,10 @@ impl DeviceContext {
     /// Uses execution context stored via `graphics_on_execute_request()`.
     #[tracing::instrument(level = "trace", skip_all)]
     pub(crate) fn process_changes(self: &Rc<Self>, console: &Console) {
+        // The device sends on its own IOPub channel, so make sure output
+        // emitted before the plot is sent first
+        console.flush_stream();
+
         let id = self.id();
 
         if !self.has_changes.get() {
@@ -637,24 +844,37 @@ impl DeviceContext {
             .render_settings
             .unwrap_or_else(|| self.prerender_settings.get());
 
-        let open_data = match self.render_plot(id, &settings) {
+        let pre_render = match self.render_plot(id, &settings) {
             Ok(pre_render) => {
                 let mime_type = Self::get_mime_type(&PlotRenderFormat::Png);
 
-                let pre_render = PlotResult {
+                Some(PlotResult {
                     data: pre_render,
                     mime_type: mime_type.to_string(),
                     settings: Some(settings),
-                };
-
-                serde_json::json!({ "pre_render": pre_render })
+                })
             },
             Err(err) => {
                 log::warn!("Can't pre-render plot: {err:?}");
-                serde_json::Value::Null
+                None
             },
         };
 
+        // Report the natural size of the plot so the frontend can size the
+        // plot pane without distorting the plot
+        let (intrinsic_size, fixed_aspect_ratio) = self
+            .plot_contexts
+            .borrow()
+            .get(id)
+            .map(|ctx| (ctx.intrinsic_size.clone(), ctx.fixed_aspect_ratio))
+            .unwrap_or_default();
+
+        let open_data = serde_json::json!({
+            "pre_render": pre_render,
+            "intrinsic_size": intrinsic_size,
+            "fixed_aspect_ratio": fixed_aspect_ratio,
+        });
+
         let plot_comm = PlotComm {
             id: id.clone(),
             open_data,
@@ -709,10 +929,21 @@ impl DeviceContext {
     }
 
     /// Store intrinsic size and metadata for a new plot from the execution context.
+    ///
+    /// The intrinsic size of the execution context takes precedence over the
+    /// size requested with the `ark.plot.width` and `ark.plot.height` options.
     fn store_plot_context(&self, id: &PlotId, ctx: &ExecutionContext) {
         let kind = self.detect_plot_kind(id);
         let name = self.generate_plot_name(&kind);
         let origin = self.take_pending_origin(ctx);
+        let fixed_aspect_ratio = self.detect_fixed_aspect_ratio();
+
+        let intrinsic_size = ctx.intrinsic_size.clone().or_else(|| {
+            requested_intrinsic_size(
+                r_option_positive_f64("ark.plot.width"),
+                r_option_positive_f64("ark.plot.height"),
+            )
+        });
 
         self.plot_contexts
             .borrow_mut()
@@ -724,7 +955,8 @@ impl DeviceContext {
                     code: ctx.code.clone(),
                     origin,
                 },
-                intrinsic_size: ctx.intrinsic_size.clone(),
+                intrinsic_size,
+                fixed_aspect_ratio,
             });
     }
 
@@ -849,8 +1081,44 @@ impl DeviceContext {
 
     #[tracing::instrument(level = "trace", skip(self))]
     fn render_plot(&self, id: &PlotId, settings: &PlotRenderSettings) -> anyhow::Result<String> {
+        let image_path = self.render_plot_to_file(id, settings)?;
+
+        let conn = File::open(image_path)?;
+        let mut reader = BufReader::new(conn);
+
+        let mut buffer = vec![];
+        reader.read_to_end(&mut buffer)?;
+
+        Ok(RawVector::from_bytes(&buffer).to_base64())
+    }
+
+    /// Replay the plot's recording on a device for the requested format.
+    /// Returns the path to the rendered file, in the session's temporary
+    /// directory.
+    #[tracing::instrument(level = "trace", skip(self))]
+    fn render_plot_to_file(
+        &self,
+        id: &PlotId,
+        settings: &PlotRenderSettings,
+    ) -> anyhow::Result<String> {
         log::trace!("Rendering plot");
 
+        let max_size = max_plot_size();
+        let clamped = max_size.clamp(settings);
+        if clamped.size != settings.size {
+            log::warn!(
+                "Clamping plot {id} from {}x{} to {}x{} (pixel ratio {}) to fit the maximum plot size of {}x{}",
+                settings.size.width,
+                settings.size.height,
+                clamped.size.width,
+                clamped.size.height,
+                settings.pixel_ratio,
+                max_size.width,
+                max_size.height,
+            );
+        }
+        let settings = &clamped;
+
         let image_path: String = RFunction::from(".ps.graphics.render_plot_from_recording")
             .param("id", id)
             .param("width", RObject::try_from(settings.size.width)?)
@@ -863,15 +1131,7 @@ impl DeviceContext {
 
         log::trace!("Rendered plot to {image_path}");
 
-        let conn = File::open(image_path)?;
-        let mut reader = BufReader::new(conn);
-
-        let mut buffer = vec![];
-        reader.read_to_end(&mut buffer)?;
-
-        let data = BASE64_STANDARD_NO_PAD.encode(buffer);
-
-        Ok(data)
+        Ok(image_path)
     }
 
     #[tracing::instrument(level = "trace", skip_all, fields(id = %id))]
@@ -1076,6 +1336,21 @@ impl FromExecuteRequest for IntrinsicSize {
     }
 }
 
+/// Intrinsic size requested in inches, e.g. with the `ark.plot.width` and
+/// `ark.plot.height` options. Both dimensions must be supplied.
+fn requested_intrinsic_size(width: Option<f64>, height: Option<f64>) -> Option<IntrinsicSize> {
+    let (Some(width), Some(height)) = (width, height) else {
+        return None;
+    };
+
+    Some(IntrinsicSize {
+        width,
+        height,
+        unit: PlotUnit::Inches,
+        source: String::from("R"),
+    })
+}
+
 /// Compute render settings and intrinsic size from execute request metadata.
 pub(crate) fn compute_plot_overrides(
     req: &ExecuteRequestPositron,
@@ -1296,15 +1571,13 @@ fn ps_graphics_get_metadata(console: &Console, id: SEXP) -> anyhow::Result<SEXP>
     ];
     let list = RObject::try_from(values)?;
 
-    let names: Vec<String> = vec![
-        "name".to_string(),
-        "kind".to_string(),
-        "execution_id".to_string(),
-        "code".to_string(),
-        "origin_uri".to_string(),
-    ];
-    let names = RObject::from(names);
-    libr::Rf_setAttrib(list.sexp, libr::R_NamesSymbol, names.sexp);
+    harp::list_set_names(list.sexp, &[
+        "name",
+        "kind",
+        "execution_id",
+        "code",
+        "origin_uri",
+    ])?;
 
     Ok(list.sexp)
 }
@@ -1360,6 +1633,76 @@ fn r_option_positive_f64(name: &str) -> Option<f64> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_max_plot_size_parse() {
+        assert_eq!("4000x3000".parse::<MaxPlotSize>().unwrap(), MaxPlotSize {
+            width: 4000,
+            height: 3000
+        });
+        assert!("4000".parse::<MaxPlotSize>().is_err());
+        assert!("4000x".parse::<MaxPlotSize>().is_err());
+        assert!("0x3000".parse::<MaxPlotSize>().is_err());
+        assert!("-1x3000".parse::<MaxPlotSize>().is_err());
+        assert!("axb".parse::<MaxPlotSize>().is_err());
+    }
+
+    #[test]
+    fn test_requested_intrinsic_size() {
+        let size = requested_intrinsic_size(Some(7.0), Some(5.0)).unwrap();
+        assert_eq!(size.width, 7.0);
+        assert_eq!(size.height, 5.0);
+        assert_eq!(size.unit, PlotUnit::Inches);
+        assert_eq!(size.to_plot_size(), PlotSize {
+            width: (7.0 * DEFAULT_DPI).round() as i64,
+            height: (5.0 * DEFAULT_DPI).round() as i64,
+        });
+
+        // Both dimensions are required
+        assert!(requested_intrinsic_size(Some(7.0), None).is_none());
+        assert!(requested_intrinsic_size(None, Some(5.0)).is_none());
+    }
+
+    #[test]
+    fn test_max_plot_size_clamps_oversized_render() {
+        let max = MaxPlotSize {
+            width: 1000,
+            height: 800,
+        };
+        let settings = |width, height, pixel_ratio| PlotRenderSettings {
+            size: PlotSize { width, height },
+            pixel_ratio,
+            format: PlotRenderFormat::Png,
+        };
+
+        // Within bounds
+        let clamped = max.clamp(&settings(500, 400, 1.));
+        assert_eq!(clamped.size, PlotSize {
+            width: 500,
+            height: 400
+        });
+
+        // Oversized, the aspect ratio is preserved
+        let clamped = max.clamp(&settings(100000, 400, 1.));
+        assert_eq!(clamped.size, PlotSize {
+            width: 1000,
+            height: 4
+        });
+
+        let clamped = max.clamp(&settings(2000, 2000, 1.));
+        assert_eq!(clamped.size, PlotSize {
+            width: 800,
+            height: 800
+        });
+
+        // The bound applies to physical pixels
+        let clamped = max.clamp(&settings(800, 800, 2.));
+        assert_eq!(clamped.size, PlotSize {
+            width: 400,
+            height: 400
+        });
+        assert_eq!(clamped.pixel_ratio, 2.);
+    }
+
     fn test_device_context() -> DeviceContext {
         let (tx, _rx) = crossbeam::channel::unbounded();
         DeviceContext::new(tx)
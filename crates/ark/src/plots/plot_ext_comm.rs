@@ -0,0 +1,76 @@
+//
+// plot_ext_comm.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+//! Plot comm methods supported by Ark on top of the ones generated from the
+//! Positron comm schema in `amalthea::comm::plot_comm`. The generated file
+//! can't be edited by hand, so these live here until they are added to the
+//! schema.
+
+use amalthea::comm::plot_comm::PlotBackendReply;
+use amalthea::comm::plot_comm::PlotBackendRequest;
+use amalthea::comm::plot_comm::PlotRenderFormat;
+use amalthea::comm::plot_comm::PlotSize;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A request on the plot comm, either from the schema or Ark specific
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum PlotRequest {
+    Comm(PlotBackendRequest),
+    Ext(PlotExtBackendRequest),
+}
+
+/// A reply on the plot comm, either from the schema or Ark specific
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum PlotReply {
+    Comm(PlotBackendReply),
+    Ext(PlotExtBackendReply),
+}
+
+/// The result of exporting a plot to a file
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ExportResult {
+    /// The path of the written file
+    pub path: String,
+}
+
+/// Parameters for the Export method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ExportParams {
+    /// The path of the file to write the plot to
+    pub path: String,
+
+    /// The requested size of the plot. If not provided, the plot will be
+    /// rendered at its intrinsic size.
+    pub size: Option<PlotSize>,
+
+    /// The pixel ratio of the display device
+    pub pixel_ratio: f64,
+
+    /// The requested plot format
+    pub format: PlotRenderFormat,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", content = "params")]
+pub enum PlotExtBackendRequest {
+    /// Export a plot to a file
+    ///
+    /// Renders the plot in the requested format and writes it to a file at
+    /// the given path. Returns the path of the written file.
+    #[serde(rename = "export")]
+    Export(ExportParams),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", content = "result")]
+pub enum PlotExtBackendReply {
+    /// The result of exporting a plot to a file
+    ExportReply(ExportResult),
+}
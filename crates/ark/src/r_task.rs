@@ -373,10 +373,29 @@ where
     }
 }
 
+/// When an [RTask] is allowed to run.
+///
+/// Tasks never run while R is evaluating code: they are polled from the
+/// Console event loop while R waits at a prompt. The priority determines
+/// which prompts qualify. There is no interrupt-time priority. Code that
+/// must run while R is busy should use [r_task()], which blocks until the
+/// next prompt instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RTaskPriority {
+    /// Only at a top-level idle prompt. Suited for background work that
+    /// shouldn't run while the user is stepping through the debugger, such
+    /// as generating srcrefs for namespaces.
+    TopLevelIdle,
+    /// At any idle prompt, including browser prompts, but not at input
+    /// prompts (`readline()`, `menu()`, etc.).
+    AnyIdle,
+}
+
 /// An async task to be run on the R thread.
 ///
 /// Construct via [RTask::idle] or [RTask::idle_any_prompt] when spawning from the R
-/// thread. Use the `Send` variants ([RTask::send_idle], etc.) when spawning from other
+/// thread, or via [RTask::with_priority] when the prompt kind is decided by the
+/// caller. Use the `Send` variants ([RTask::send_idle], etc.) when spawning from other
 /// threads.
 ///
 /// Console output is automatically captured during the task's execution via a
@@ -453,6 +472,40 @@ impl RTask {
         RTask::SendIdleAnyPrompt(Self::pin_with_capture(fun))
     }
 
+    /// Create a task that runs at prompts allowed by `priority`. Must be
+    /// spawned from the R thread.
+    pub(crate) fn with_priority<F, Fut>(priority: RTaskPriority, fun: F) -> Self
+    where
+        F: FnOnce(ConsoleOutputCapture) -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let fut = Self::pin_with_capture(fun);
+        match priority {
+            RTaskPriority::TopLevelIdle => RTask::Idle(fut),
+            RTaskPriority::AnyIdle => RTask::IdleAnyPrompt(fut),
+        }
+    }
+
+    /// Like [Self::with_priority()], but can be spawned from any thread.
+    pub(crate) fn send_with_priority<F, Fut>(priority: RTaskPriority, fun: F) -> Self
+    where
+        F: FnOnce(ConsoleOutputCapture) -> Fut + 'static + Send,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let fut = Self::pin_with_capture(fun);
+        match priority {
+            RTaskPriority::TopLevelIdle => RTask::SendIdle(fut),
+            RTaskPriority::AnyIdle => RTask::SendIdleAnyPrompt(fut),
+        }
+    }
+
+    pub(crate) fn priority(&self) -> RTaskPriority {
+        match self {
+            RTask::Idle(_) | RTask::SendIdle(_) => RTaskPriority::TopLevelIdle,
+            RTask::IdleAnyPrompt(_) | RTask::SendIdleAnyPrompt(_) => RTaskPriority::AnyIdle,
+        }
+    }
+
     // Note that `start_capture()` sets `options(warn = 1)` and this persists
     // for the life of `f`, so avoid using capturing on long running event loops
     // that don't emit R output
@@ -499,9 +552,15 @@ pub(crate) fn spawn(task: RTask) {
         panic!("`spawn()` must be called from the R thread, not thread '{name}'");
     }
 
-    let (fut, tasks_tx) = match task {
-        RTask::Idle(fut) | RTask::SendIdle(fut) => (fut, IDLE_TASKS.tx()),
-        RTask::IdleAnyPrompt(fut) | RTask::SendIdleAnyPrompt(fut) => (fut, IDLE_ANY_TASKS.tx()),
+    let tasks_tx = match task.priority() {
+        RTaskPriority::TopLevelIdle => IDLE_TASKS.tx(),
+        RTaskPriority::AnyIdle => IDLE_ANY_TASKS.tx(),
+    };
+    let fut = match task {
+        RTask::Idle(fut) |
+        RTask::IdleAnyPrompt(fut) |
+        RTask::SendIdle(fut) |
+        RTask::SendIdleAnyPrompt(fut) => fut,
     };
 
     let task = QueuedRTask::Async(RTaskAsync {
@@ -605,6 +664,34 @@ unsafe extern "C-unwind" fn ps_test_spawn_sleeping_idle_tasks(
     Ok(libr::R_NilValue)
 }
 
+/// Spawn a task that evaluates an R expression with the given priority,
+/// either `"top_level_idle"` or `"any_idle"`. Used in integration tests.
+#[cfg(debug_assertions)]
+#[harp::register]
+unsafe extern "C-unwind" fn ps_test_spawn_eval_task(
+    code: SEXP,
+    priority: SEXP,
+) -> anyhow::Result<SEXP> {
+    stdext::assert_testing();
+
+    let code: String = harp::RObject::view(code).try_into()?;
+    let priority: String = harp::RObject::view(priority).try_into()?;
+
+    let priority = match priority.as_str() {
+        "top_level_idle" => RTaskPriority::TopLevelIdle,
+        "any_idle" => RTaskPriority::AnyIdle,
+        _ => return Err(anyhow::anyhow!("Unknown task priority '{priority}'")),
+    };
+
+    spawn(RTask::with_priority(priority, async move |_capture| {
+        if let Err(err) = harp::parse_eval_global(&code) {
+            log::error!("Task eval failed: {err:?}");
+        }
+    }));
+
+    Ok(libr::R_NilValue)
+}
+
 /// Spawn an idle task that evaluates an R expression. Used in integration tests.
 #[cfg(debug_assertions)]
 #[harp::register]
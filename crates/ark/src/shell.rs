@@ -93,7 +93,7 @@ impl Shell {
             }),
             Ok(ParseResult::Incomplete) => Ok(IsCompleteReply {
                 status: IsComplete::Incomplete,
-                indent: String::from("+"),
+                indent: continuation_indent(&req.code),
             }),
             Err(_) | Ok(ParseResult::SyntaxError { .. }) => Ok(IsCompleteReply {
                 status: IsComplete::Invalid,
@@ -103,6 +103,103 @@ impl Shell {
     }
 }
 
+/// Binary operators that continue an expression on the next line
+const CONTINUATION_OPERATORS: &[&str] = &[
+    "|>", "special", "+", "-", "*", "/", "^", "**", "~", "|", "&", "||", "&&", "<", "<=", ">",
+    ">=", "==", "!=", "<-", "<<-", "=", ":",
+];
+
+/// Indent for the next line of incomplete `code`, in the tidyverse style.
+///
+/// Lines within an unclosed `(`, `[`, or `{` are indented by two spaces
+/// relative to the line that opened it. A trailing operator such as `|>`
+/// indents the next line by two more spaces, unless the last line is itself
+/// the continuation of a pipeline, in which case its indent is kept so that
+/// the steps of the pipeline line up.
+fn continuation_indent(code: &str) -> String {
+    let mut parser = tree_sitter::Parser::new();
+    if parser
+        .set_language(&tree_sitter_r::LANGUAGE.into())
+        .is_err()
+    {
+        return String::new();
+    }
+    let Some(tree) = parser.parse(code, None) else {
+        return String::new();
+    };
+
+    let lines: Vec<&str> = code.lines().collect();
+    let line_indent = |row: usize| {
+        lines
+            .get(row)
+            .map(|line| line.len() - line.trim_start().len())
+            .unwrap_or(0)
+    };
+
+    // Tokens are reliable even when the incomplete input doesn't parse to a
+    // well-formed tree, so we track open delimiters from them rather than
+    // from the shape of the tree
+    let tokens: Vec<tree_sitter::Node> = leaves(tree.root_node())
+        .into_iter()
+        .filter(|node| !node.is_missing() && node.kind() != "comment")
+        .collect();
+
+    let mut open: Vec<usize> = Vec::new();
+    for token in tokens.iter() {
+        match token.kind() {
+            "(" | "[" | "[[" | "{" => open.push(token.start_position().row),
+            ")" | "]" | "]]" | "}" => {
+                open.pop();
+            },
+            _ => {},
+        }
+    }
+
+    let mut indent = open.last().map(|&row| line_indent(row) + 2).unwrap_or(0);
+
+    let is_operator = |token: &tree_sitter::Node| CONTINUATION_OPERATORS.contains(&token.kind());
+
+    if let Some(last) = tokens.last().filter(|token| is_operator(token)) {
+        let row = last.end_position().row;
+
+        // Is the last line already the continuation of a previous line?
+        let first_on_row = tokens
+            .iter()
+            .position(|token| token.start_position().row == row)
+            .unwrap_or(0);
+        let is_continuation = first_on_row > 0 && is_operator(&tokens[first_on_row - 1]);
+
+        let operator_indent = if is_continuation {
+            line_indent(row)
+        } else {
+            line_indent(row) + 2
+        };
+        indent = indent.max(operator_indent);
+    }
+
+    " ".repeat(indent)
+}
+
+/// The leaves of the tree rooted at `node`, in order
+fn leaves(node: tree_sitter::Node) -> Vec<tree_sitter::Node> {
+    let mut leaves = Vec::new();
+    let mut cursor = node.walk();
+
+    loop {
+        if cursor.node().child_count() == 0 {
+            leaves.push(cursor.node());
+        } else if cursor.goto_first_child() {
+            continue;
+        }
+
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return leaves;
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl ShellHandler for Shell {
     async fn handle_info_request(
@@ -151,7 +248,7 @@ impl ShellHandler for Shell {
             status: Status::Ok,
             banner: kernel_info.banner.clone(),
             debugger: true,
-            help_links: Vec::new(),
+            help_links: kernel_info.help_links.clone(),
             language_info: info,
             implementation: String::from("ark"),
             // We use CARGO_PKG_VERSION here vs. the build version override since the Jupyter spec
@@ -364,3 +461,33 @@ fn handle_comm_open_help(
 
     Ok((true, Some(done_rx)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::continuation_indent;
+
+    #[test]
+    fn test_continuation_indent_brackets() {
+        assert_eq!(continuation_indent("f("), "  ");
+        assert_eq!(continuation_indent("list(a = 1,"), "  ");
+        assert_eq!(continuation_indent("x[1,"), "  ");
+        assert_eq!(continuation_indent("function() {"), "  ");
+        assert_eq!(continuation_indent("function() {\n  if (x) {"), "    ");
+        assert_eq!(continuation_indent("function() {\n  if (x) {\n  }"), "  ");
+
+        // Delimiters in strings and comments don't count
+        assert_eq!(continuation_indent("f('(', # {\n"), "  ");
+    }
+
+    #[test]
+    fn test_continuation_indent_pipeline() {
+        assert_eq!(continuation_indent("mtcars |>"), "  ");
+        assert_eq!(continuation_indent("mtcars |>\n  head() |>"), "  ");
+        assert_eq!(continuation_indent("mtcars %>%\n  head() %>%"), "  ");
+        assert_eq!(continuation_indent("x <-"), "  ");
+
+        // Pipelines within braces
+        assert_eq!(continuation_indent("{\n  mtcars |>"), "    ");
+        assert_eq!(continuation_indent("{\n  mtcars |>\n    head() |>"), "    ");
+    }
+}
@@ -5,6 +5,7 @@
  *
  */
 
+pub use crate::sys::signals::initialize_shutdown_handler;
 pub use crate::sys::signals::initialize_signal_block;
 pub use crate::sys::signals::initialize_signal_handlers;
 pub use crate::sys::signals::interrupts_pending;
@@ -6,6 +6,7 @@
 //
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -20,6 +21,7 @@ use bus::Bus;
 use crossbeam::channel::bounded;
 use crossbeam::channel::unbounded;
 
+use crate::console::BannerMode;
 use crate::console::ConsoleNotification;
 use crate::console::SessionMode;
 use crate::control::Control;
@@ -29,6 +31,7 @@ use crate::repos::DefaultRepos;
 use crate::request::KernelRequest;
 use crate::request::RRequest;
 use crate::shell::Shell;
+use crate::startup::StartupProfile;
 
 /// Exported for unit tests.
 pub fn start_kernel(
@@ -37,9 +40,14 @@ pub fn start_kernel(
     r_args: Vec<String>,
     startup_file: Option<String>,
     session_mode: SessionMode,
+    banner_mode: BannerMode,
     capture_streams: bool,
+    capture_log: Option<PathBuf>,
     default_repos: DefaultRepos,
+    profile_startup: bool,
 ) {
+    let mut startup_profile = StartupProfile::new(profile_startup);
+
     // Locate R home directory
     let r_home = match harp::command::r_home_setup() {
         Ok(r_home) => r_home,
@@ -63,6 +71,8 @@ pub fn start_kernel(
         );
     };
 
+    startup_profile.record("r_home");
+
     // Create the channels used for communication. These are created here
     // as they need to be shared across different components / threads.
     let (iopub_tx, iopub_rx) = bounded::<IOPubMessage>(10);
@@ -92,6 +102,7 @@ pub fn start_kernel(
         r_home.clone(),
         kernel_init_tx.add_rx(),
         console_notification_tx.clone(),
+        r_request_tx.clone(),
     )));
 
     // DAP needs the `RRequest` channel to communicate with
@@ -122,7 +133,7 @@ pub fn start_kernel(
     // Create the stream behavior; this determines whether the kernel should
     // capture stdout/stderr and send them to the frontend as IOPub messages
     let stream_behavior = match capture_streams {
-        true => amalthea::kernel::StreamBehavior::Capture,
+        true => amalthea::kernel::StreamBehavior::Capture { tee: capture_log },
         false => amalthea::kernel::StreamBehavior::None,
     };
 
@@ -156,12 +167,18 @@ pub fn start_kernel(
         panic!("Couldn't connect to frontend: {err:?}");
     }
 
+    startup_profile.record("connect");
+
     // Start parent process monitoring for graceful shutdown if applicable. Currently we
     // only do this for Linux since it uses `prctl()`.
     if let Err(err) = crate::sys::parent_monitor::start_parent_monitoring(r_request_tx.clone()) {
         log::error!("Failed to start parent process monitoring: {err}");
     }
 
+    // Shut down gracefully when asked to terminate by the OS (`SIGTERM` on
+    // Unix, console close events on Windows)
+    crate::signals::initialize_shutdown_handler(r_request_tx.clone());
+
     // Start R
     crate::console::Console::start(
         r_home,
@@ -176,7 +193,9 @@ pub fn start_kernel(
         kernel_request_rx,
         dap,
         session_mode,
+        banner_mode,
         default_repos,
         console_notification_rx,
+        startup_profile,
     )
 }
@@ -8,6 +8,8 @@
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
 
 use amalthea::socket::iopub::IOPubMessage;
 use amalthea::wire::stream::Stream;
@@ -21,6 +23,78 @@ use stdext::result::ResultExt;
 use crate::console::Console;
 use crate::sys;
 
+/// Phase-by-phase timings of kernel startup, enabled with `--profile-startup`.
+///
+/// When disabled, recording a phase is a no-op so the timer can be threaded
+/// through startup unconditionally.
+pub struct StartupProfile {
+    timings: Option<StartupTimings>,
+}
+
+struct StartupTimings {
+    start: Instant,
+    last: Instant,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl StartupProfile {
+    pub fn new(enabled: bool) -> Self {
+        let timings = enabled.then(|| {
+            let now = Instant::now();
+            StartupTimings {
+                start: now,
+                last: now,
+                phases: Vec::new(),
+            }
+        });
+        Self { timings }
+    }
+
+    /// Records the time elapsed since the previous phase ended.
+    pub fn record(&mut self, phase: &'static str) {
+        let Some(timings) = &mut self.timings else {
+            return;
+        };
+        let now = Instant::now();
+        timings.phases.push((phase, now - timings.last));
+        timings.last = now;
+    }
+
+    /// Logs the summary table, if profiling is enabled.
+    pub fn report(&self) {
+        if let Some(report) = self.format_report() {
+            log::info!("Startup profile:\n{report}");
+        }
+    }
+
+    fn format_report(&self) -> Option<String> {
+        let timings = self.timings.as_ref()?;
+        let total = timings.last - timings.start;
+
+        let width = timings
+            .phases
+            .iter()
+            .map(|(phase, _)| phase.len())
+            .chain(std::iter::once("total".len()))
+            .max()
+            .unwrap_or_default();
+
+        let mut lines: Vec<String> = timings
+            .phases
+            .iter()
+            .map(|(phase, elapsed)| format_row(phase, *elapsed, width))
+            .collect();
+        lines.push(format_row("total", total, width));
+
+        Some(lines.join("\n"))
+    }
+}
+
+fn format_row(phase: &str, elapsed: Duration, width: usize) -> String {
+    let ms = elapsed.as_secs_f64() * 1000.0;
+    format!("{phase:<width$}  {ms:>10.2} ms")
+}
+
 pub(crate) fn should_ignore_site_r_profile(args: &[String]) -> bool {
     args.iter()
         .any(|arg| arg == "--no-site-file" || arg == "--vanilla")
@@ -180,3 +254,33 @@ fn find_user_r_profile() -> Option<PathBuf> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::startup::StartupProfile;
+
+    #[test]
+    fn test_startup_profile_disabled() {
+        let mut profile = StartupProfile::new(false);
+        profile.record("phase");
+        assert!(profile.format_report().is_none());
+    }
+
+    #[test]
+    fn test_startup_profile_report() {
+        let mut profile = StartupProfile::new(true);
+        std::thread::sleep(Duration::from_millis(1));
+        profile.record("setup");
+        profile.record("modules");
+
+        let report = profile.format_report().unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("setup  "));
+        assert!(lines[1].starts_with("modules"));
+        assert!(lines[2].starts_with("total  "));
+        assert!(lines.iter().all(|line| line.ends_with(" ms")));
+    }
+}
@@ -5,8 +5,19 @@
  *
  */
 
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use crossbeam::channel::Sender;
 use libr::R_interrupts_pending;
 use nix::sys::signal::*;
+use stdext::spawn;
+
+use crate::request::RRequest;
+
+/// Set from the `SIGTERM` handler, picked up by the `ark-sigterm` thread
+static SIGTERM_RECEIVED: AtomicBool = AtomicBool::new(false);
 
 /// Reset the signal block.
 ///
@@ -74,3 +85,49 @@ pub fn set_interrupts_pending(pending: bool) {
 pub extern "C" fn handle_interrupt(_signal: libc::c_int) {
     set_interrupts_pending(true);
 }
+
+/// Shut down gracefully on `SIGTERM`, e.g. when a container orchestrator stops
+/// the kernel
+///
+/// The signal handler only sets a flag. A watcher thread then takes the same
+/// path as a `shutdown_request`: it interrupts R and sends a shutdown request
+/// that is handled once R is idle, so that comms are closed and IOPub is
+/// flushed before R exits. A second `SIGTERM` kills the process right away
+/// since the handler is reset to the default disposition after the first one.
+pub fn initialize_shutdown_handler(r_request_tx: Sender<RRequest>) {
+    unsafe {
+        if let Err(err) = signal(SIGTERM, SigHandler::Handler(handle_sigterm)) {
+            log::error!("Failed to install SIGTERM handler: {err}");
+            return;
+        }
+    }
+
+    spawn!("ark-sigterm", move || loop {
+        if SIGTERM_RECEIVED.load(Ordering::SeqCst) {
+            log::info!("Received SIGTERM, initiating graceful shutdown");
+
+            crate::sys::control::handle_interrupt_request();
+
+            if let Err(err) = r_request_tx.send(RRequest::Shutdown(false)) {
+                log::error!("Failed to send shutdown request, exiting: {err}");
+                std::process::exit(1);
+            }
+            break;
+        }
+
+        std::thread::sleep(Duration::from_millis(100));
+    });
+}
+
+/// Unix `SIGTERM` handler
+///
+/// # Safety
+///
+/// Same constraints as [handle_interrupt]. Only async-signal-safe operations
+/// are allowed here.
+extern "C" fn handle_sigterm(_signal: libc::c_int) {
+    SIGTERM_RECEIVED.store(true, Ordering::SeqCst);
+    unsafe {
+        let _ = signal(SIGTERM, SigHandler::SigDfl);
+    }
+}
@@ -5,9 +5,22 @@
  *
  */
 
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crossbeam::channel::Sender;
 use libr::Rboolean_FALSE;
 use libr::Rboolean_TRUE;
 use libr::UserBreak;
+use windows_sys::core::BOOL;
+use windows_sys::Win32::System::Console::SetConsoleCtrlHandler;
+use windows_sys::Win32::System::Console::CTRL_CLOSE_EVENT;
+use windows_sys::Win32::System::Console::CTRL_LOGOFF_EVENT;
+use windows_sys::Win32::System::Console::CTRL_SHUTDOWN_EVENT;
+
+use crate::request::RRequest;
+
+static SHUTDOWN_TX: OnceLock<Sender<RRequest>> = OnceLock::new();
 
 pub fn initialize_signal_handlers() {
     // Nothing to do on Windows. Signal blocking is POSIX only.
@@ -28,3 +41,48 @@ pub fn set_interrupts_pending(pending: bool) {
         unsafe { libr::set(UserBreak, Rboolean_FALSE) };
     }
 }
+
+/// Shut down gracefully when the console is closed or the user logs off,
+/// the Windows equivalent of `SIGTERM`
+///
+/// Windows only gives us a few seconds before killing the process once the
+/// control handler has been called, so the handler blocks while the R thread
+/// goes through the same path as a `shutdown_request`.
+pub fn initialize_shutdown_handler(r_request_tx: Sender<RRequest>) {
+    if SHUTDOWN_TX.set(r_request_tx).is_err() {
+        log::warn!("Shutdown handler is already installed");
+        return;
+    }
+
+    if unsafe { SetConsoleCtrlHandler(Some(handle_console_ctrl), 1) } == 0 {
+        log::error!("Failed to install console control handler");
+    }
+}
+
+/// Called by Windows on a dedicated thread
+unsafe extern "system" fn handle_console_ctrl(ctrl_type: u32) -> BOOL {
+    if !matches!(
+        ctrl_type,
+        CTRL_CLOSE_EVENT | CTRL_LOGOFF_EVENT | CTRL_SHUTDOWN_EVENT
+    ) {
+        // Let the next handler deal with Ctrl-C and Ctrl-Break
+        return 0;
+    }
+
+    log::info!("Received console control event {ctrl_type}, initiating graceful shutdown");
+
+    let Some(r_request_tx) = SHUTDOWN_TX.get() else {
+        return 0;
+    };
+
+    set_interrupts_pending(true);
+    if let Err(err) = r_request_tx.send(RRequest::Shutdown(false)) {
+        log::error!("Failed to send shutdown request: {err}");
+        return 0;
+    }
+
+    // The process is terminated as soon as we return, give R a chance to
+    // exit on its own first
+    std::thread::sleep(Duration::from_secs(4));
+    1
+}
@@ -22,6 +22,8 @@ use amalthea::comm::ui_comm::WorkingDirectoryParams;
 use harp::eval::parse_eval_global;
 use harp::exec::RFunction;
 use harp::exec::RFunctionExt;
+use harp::json::to_json_value;
+use harp::json::JsonLimits;
 use harp::object::RObject;
 use serde_json::Value;
 use stdext::result::ResultExt;
@@ -153,7 +155,7 @@ impl UiComm {
             call.add(p);
         }
         let result = call.call()?;
-        let result = Value::try_from(result)?;
+        let result = to_json_value(result, &JsonLimits::default())?;
 
         Ok(UiBackendReply::CallMethodReply(result))
     }
@@ -211,7 +213,7 @@ impl UiComm {
 
         match value {
             Ok(evaluated) => {
-                let result = Value::try_from(evaluated)?;
+                let result = to_json_value(evaluated, &JsonLimits::default())?;
                 Ok(UiBackendReply::EvaluateCodeReply(EvalResult {
                     result,
                     output,
@@ -7,3 +7,4 @@
 
 pub mod r_variables;
 pub mod variable;
+pub mod variables_ext_comm;
@@ -22,6 +22,7 @@ use anyhow::anyhow;
 use crossbeam::channel::select;
 use crossbeam::channel::unbounded;
 use harp::environment::Binding;
+use harp::environment::BindingValue;
 use harp::environment::Environment;
 use harp::environment::EnvironmentFilter;
 use harp::exec::RFunction;
@@ -29,7 +30,11 @@ use harp::exec::RFunctionExt;
 use harp::get_option;
 use harp::object::RObject;
 use harp::utils::r_assert_type;
+use harp::utils::r_classes_implicit;
+use harp::utils::r_env_is_locked;
 use harp::utils::r_is_function;
+use harp::utils::r_promise_is_forced;
+use harp::utils::r_promise_value;
 use harp::vector::CharacterVector;
 use harp::vector::Vector;
 use harp::RSymbol;
@@ -53,6 +58,10 @@ use crate::r_task::RTask;
 use crate::thread::RThreadSafe;
 use crate::variables::variable::try_dispatch_view;
 use crate::variables::variable::PositronVariable;
+use crate::variables::variables_ext_comm::VariablesExtBackendReply;
+use crate::variables::variables_ext_comm::VariablesExtBackendRequest;
+use crate::variables::variables_ext_comm::VariablesReply;
+use crate::variables::variables_ext_comm::VariablesRequest;
 use crate::view::view;
 
 /**
@@ -218,7 +227,39 @@ impl RVariables {
         variables
     }
 
-    fn handle_rpc(
+    /// Like `list_variables()` but only includes the bindings whose name
+    /// fuzzy-matches `query` and, if supplied, whose value inherits from
+    /// `class`. Inheritance is based on the implicit class, so unclassed
+    /// values like `1:3` match `"integer"`. Unforced promises and active
+    /// bindings never match a class since we don't evaluate them.
+    #[tracing::instrument(level = "trace", skip_all)]
+    fn search_variables(&mut self, query: &str, class: Option<&str>) -> Vec<Variable> {
+        let mut variables: Vec<Variable> = vec![];
+        r_task(|| {
+            for binding in self.current_bindings.get() {
+                if !fuzzy_match(&binding.name.to_string(), query) {
+                    continue;
+                }
+                if let Some(class) = class {
+                    if !binding_inherits(binding, class) {
+                        continue;
+                    }
+                }
+                variables.push(PositronVariable::new(binding).var());
+            }
+        });
+
+        variables
+    }
+
+    fn handle_rpc(&mut self, req: VariablesRequest) -> anyhow::Result<VariablesReply> {
+        match req {
+            VariablesRequest::Comm(req) => Ok(VariablesReply::Comm(self.handle_comm_rpc(req)?)),
+            VariablesRequest::Ext(req) => Ok(VariablesReply::Ext(self.handle_ext_rpc(req)?)),
+        }
+    }
+
+    fn handle_comm_rpc(
         &mut self,
         req: VariablesBackendRequest,
     ) -> anyhow::Result<VariablesBackendReply> {
@@ -266,6 +307,29 @@ impl RVariables {
         }
     }
 
+    fn handle_ext_rpc(
+        &mut self,
+        req: VariablesExtBackendRequest,
+    ) -> anyhow::Result<VariablesExtBackendReply> {
+        match req {
+            VariablesExtBackendRequest::CopyAsCode(params) => {
+                let content = self.copy_as_code(&params.path)?;
+                Ok(VariablesExtBackendReply::CopyAsCodeReply(
+                    FormattedVariable { content },
+                ))
+            },
+            VariablesExtBackendRequest::Search(params) => {
+                let list = self.search_variables(&params.query, params.class.as_deref());
+                let count = list.len() as i64;
+                Ok(VariablesExtBackendReply::SearchReply(VariableList {
+                    variables: list,
+                    length: count,
+                    version: Some(self.version as i64),
+                }))
+            },
+        }
+    }
+
     /**
      * Clear the environment. Uses rm(envir = <env>, list = ls(<env>, all.names = TRUE))
      */
@@ -273,6 +337,10 @@ impl RVariables {
         r_task(|| unsafe {
             let env = self.env.get().clone();
 
+            if r_env_is_locked(*env) {
+                return Err(harp::Error::LockedEnvironmentError);
+            }
+
             let mut list = RFunction::new("base", "ls")
                 .param("envir", *env)
                 .param("all.names", Rf_ScalarLogical(include_hidden_objects as i32))
@@ -303,6 +371,10 @@ impl RVariables {
 
             let env = self.env.get().clone();
 
+            if r_env_is_locked(*env) {
+                return Err(harp::Error::LockedEnvironmentError);
+            }
+
             let result = RFunction::new("base", "rm")
                 .param("list", CharacterVector::create(variables))
                 .param("envir", env)
@@ -324,6 +396,13 @@ impl RVariables {
         })
     }
 
+    fn copy_as_code(&mut self, path: &[String]) -> anyhow::Result<String> {
+        r_task(|| {
+            let env = self.env.get().clone();
+            PositronVariable::copy_as_code(env, path)
+        })
+    }
+
     fn inspect(&mut self, path: &Vec<String>) -> anyhow::Result<Vec<Variable>> {
         r_task(|| {
             let env = self.env.get().clone();
@@ -631,3 +710,52 @@ impl RVariables {
         RThreadSafe::new(bindings)
     }
 }
+
+/// Whether the characters of `query` appear in order in `name`, ignoring
+/// case. An empty query matches everything.
+fn fuzzy_match(name: &str, query: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| name.any(|c| c == q))
+}
+
+fn binding_inherits(binding: &Binding, class: &str) -> bool {
+    let object = match &binding.value {
+        BindingValue::Standard { object } => object.sexp,
+        BindingValue::Altrep { object, .. } => object.sexp,
+        BindingValue::Promise { promise } => {
+            if !r_promise_is_forced(promise.sexp) {
+                return false;
+            }
+            r_promise_value(promise.sexp)
+        },
+        BindingValue::Active { .. } => return false,
+    };
+
+    // Use the implicit class rather than `Rf_inherits()`, which only looks
+    // at the class attribute
+    match r_classes_implicit(object) {
+        Ok(classes) => classes.iter().any(|elt| elt == class),
+        Err(err) => {
+            log::warn!("Can't get classes of '{}': {err:?}", binding.name);
+            false
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::variables::r_variables::fuzzy_match;
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert!(fuzzy_match("my_data_frame", "data"));
+        assert!(fuzzy_match("my_data_frame", "DATA"));
+        assert!(fuzzy_match("my_data_frame", "mdf"));
+        assert!(fuzzy_match("my_data_frame", ""));
+        assert!(!fuzzy_match("my_data_frame", "fdm"));
+        assert!(!fuzzy_match("x", "xx"));
+    }
+}
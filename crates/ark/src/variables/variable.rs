@@ -21,9 +21,11 @@ use harp::error::Error;
 use harp::exec::RFunction;
 use harp::exec::RFunctionExt;
 use harp::object::r_length;
+use harp::object::r_null_or_try_into;
 use harp::object::RObject;
 use harp::r_null;
 use harp::r_symbol;
+use harp::size::r_size;
 use harp::symbol::RSymbol;
 use harp::utils::pairlist_size;
 use harp::utils::r_altrep_class;
@@ -40,8 +42,8 @@ use harp::utils::r_is_s4;
 use harp::utils::r_is_simple_vector;
 use harp::utils::r_is_unbound;
 use harp::utils::r_promise_force_with_rollback;
-use harp::utils::r_type2char;
 use harp::utils::r_typeof;
+use harp::utils::r_typeof_name;
 use harp::utils::r_vec_is_single_dimension_with_single_value;
 use harp::utils::r_vec_shape;
 use harp::utils::r_vec_type;
@@ -57,6 +59,7 @@ use libr::*;
 use stdext::local;
 use stdext::unwrap;
 
+use crate::data_explorer::format::format_tsv;
 use crate::methods::ArkGenerics;
 use crate::modules::ARK_ENVS;
 
@@ -64,6 +67,15 @@ use crate::modules::ARK_ENVS;
 const MAX_DISPLAY_VALUE_ENTRIES: usize = 1_000;
 const MAX_DISPLAY_VALUE_LENGTH: usize = 100;
 
+/// Limits on the rows and columns of data frames and matrices copied to the
+/// clipboard
+const MAX_CLIPBOARD_ROWS: usize = 10_000;
+const MAX_CLIPBOARD_COLUMNS: usize = 1_000;
+
+/// Limit on the size of values copied as code, both in memory (in bytes)
+/// and once deparsed (in characters)
+const MAX_COPY_AS_CODE_SIZE: usize = 100_000;
+
 pub struct WorkspaceVariableDisplayValue {
     pub display_value: String,
     pub is_truncated: bool,
@@ -105,7 +117,7 @@ impl WorkspaceVariableDisplayValue {
             _ if r_is_s4(value) => Self::from_s4(value)?,
             _ => Self::from_error(Error::Anyhow(anyhow!(
                 "Unexpected type {}",
-                r_type2char(r_typeof(value))
+                r_typeof_name(value)
             ))),
         };
 
@@ -1002,12 +1014,14 @@ impl PositronVariable {
 
         match node {
             EnvironmentVariableNode::Concrete { object } => {
-                if r_is_data_frame(object.sexp) {
-                    let formatted = RFunction::from(".ps.environment.clipboardFormatDataFrame")
+                if r_is_data_frame(object.sexp) || r_is_matrix(object.sexp) {
+                    let cells = RFunction::from(".ps.environment.clipboardCells")
                         .add(object)
+                        .param("max_rows", MAX_CLIPBOARD_ROWS as i32)
+                        .param("max_cols", MAX_CLIPBOARD_COLUMNS as i32)
                         .call()?;
 
-                    Ok(FormattedVector::new(formatted)?.iter()?.join("\n"))
+                    Ok(format_tsv(&cells, clipboard_na_as_empty())?)
                 } else if r_typeof(object.sexp) == CLOSXP {
                     let deparsed: Vec<String> = RFunction::from("deparse")
                         .add(object.sexp)
@@ -1031,6 +1045,37 @@ impl PositronVariable {
         }
     }
 
+    /// R code that reconstructs the value at `path`, in the style of `dput()`
+    pub fn copy_as_code(env: RObject, path: &[String]) -> anyhow::Result<String> {
+        let node = Self::resolve_object_from_path(env, path)?;
+
+        let EnvironmentVariableNode::Concrete { object } = node else {
+            return Err(anyhow!("This variable can't be copied as code."));
+        };
+
+        // Check the size up front to avoid deparsing large objects
+        if r_size(object.sexp)? > MAX_COPY_AS_CODE_SIZE {
+            return Err(anyhow!("This variable is too large to copy as code."));
+        }
+
+        let code = RFunction::from(".ps.environment.copyAsCode")
+            .add(object)
+            .call()?;
+
+        if r_is_null(code.sexp) {
+            return Err(anyhow!("This variable can't be reconstructed from code."));
+        }
+
+        let code: Vec<String> = code.try_into()?;
+        let code = code.join("\n");
+
+        if code.len() > MAX_COPY_AS_CODE_SIZE {
+            return Err(anyhow!("This variable is too large to copy as code."));
+        }
+
+        Ok(code)
+    }
+
     pub fn resolve_data_object(
         env: RObject,
         path: &[String],
@@ -1141,7 +1186,18 @@ impl PositronVariable {
         match r_typeof(object.sexp) {
             ENVSXP => Self::get_envsxp_child_node_at(object, access_key),
             VECSXP | EXPRSXP => {
-                let index = parse_index(access_key)?;
+                // Children are keyed by position, but also accept a name so
+                // elements can be reached as with `x[["foo"]]`
+                let index = match parse_index(access_key) {
+                    Ok(index) => index,
+                    Err(err) => {
+                        let names = Names::new(object.sexp, |_i| String::from(""));
+                        match names.index().get(access_key) {
+                            Some(index) => *index as isize,
+                            None => return Err(err),
+                        }
+                    },
+                };
                 Ok(EnvironmentVariableNode::Concrete {
                     object: RObject::view(harp::list_get(object.sexp, index)),
                 })
@@ -1611,6 +1667,16 @@ impl PositronVariable {
     }
 }
 
+/// Whether missing values are copied to the clipboard as empty cells rather
+/// than `NA`, set with `options(ark.variables.clipboard_na_as_empty = TRUE)`
+fn clipboard_na_as_empty() -> bool {
+    let opt: Option<bool> =
+        r_null_or_try_into(harp::get_option("ark.variables.clipboard_na_as_empty"))
+            .ok()
+            .flatten();
+    opt.unwrap_or(false)
+}
+
 fn parse_custom_access_key(access_key: &str) -> anyhow::Result<Option<(RObject, i32)>> {
     let parsed_access_key: Vec<&str> = access_key.splitn(4, '-').collect();
 
@@ -1996,6 +2062,34 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_copy_as_code() {
+        r_task(|| {
+            let env = harp::parse_eval_global("new.env()").unwrap();
+
+            harp::parse_eval0(
+                r#"
+                x <- list(a = 1, b = c(x = "y"), c = 1:3)
+                y <- new.env()
+                z <- 1:1e6 + 0
+            "#,
+                env.clone(),
+            )
+            .unwrap();
+
+            let code = PositronVariable::copy_as_code(env.clone(), &[String::from("x")]).unwrap();
+            assert_eq!(code, r#"list(a = 1, b = c(x = "y"), c = 1:3)"#);
+
+            // Environments can't be deparsed
+            let err = PositronVariable::copy_as_code(env.clone(), &[String::from("y")]);
+            assert!(err.is_err());
+
+            // Large vectors are refused
+            let err = PositronVariable::copy_as_code(env.clone(), &[String::from("z")]);
+            assert!(err.unwrap_err().to_string().contains("too large"));
+        })
+    }
+
     fn inspect_from_expr(code: &str) -> Vec<Variable> {
         let env = Environment::new(harp::parse_eval_base("new.env(parent = emptyenv())").unwrap());
         let value = harp::parse_eval_base(code).unwrap();
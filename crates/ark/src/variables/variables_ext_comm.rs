@@ -0,0 +1,83 @@
+//
+// variables_ext_comm.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+//! Variables comm methods supported by Ark on top of the ones generated from
+//! the Positron comm schema in `amalthea::comm::variables_comm`. The generated
+//! file can't be edited by hand, so these live here until they are added to
+//! the schema.
+
+use amalthea::comm::variables_comm::FormattedVariable;
+use amalthea::comm::variables_comm::VariableList;
+use amalthea::comm::variables_comm::VariablesBackendReply;
+use amalthea::comm::variables_comm::VariablesBackendRequest;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A request on the variables comm, either from the schema or Ark specific
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum VariablesRequest {
+    Comm(VariablesBackendRequest),
+    Ext(VariablesExtBackendRequest),
+}
+
+/// A reply on the variables comm, either from the schema or Ark specific
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum VariablesReply {
+    Comm(VariablesBackendReply),
+    Ext(VariablesExtBackendReply),
+}
+
+/// Parameters for the CopyAsCode method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CopyAsCodeParams {
+    /// The path to the variable to copy, as an array of access keys.
+    pub path: Vec<String>,
+}
+
+/// Parameters for the Search method.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SearchParams {
+    /// The text to search for in variable names. Matching is case-insensitive
+    /// and fuzzy: the characters of the query must appear in order in the
+    /// name, but not necessarily contiguously.
+    pub query: String,
+
+    /// If supplied, only return variables whose value inherits from this
+    /// class.
+    pub class: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", content = "params")]
+pub enum VariablesExtBackendRequest {
+    /// Copy a variable as code
+    ///
+    /// Requests R code that reconstructs the value of a variable, for sharing
+    /// reproducible snippets. Fails for values that are too large or can't be
+    /// represented as code.
+    #[serde(rename = "copy_as_code")]
+    CopyAsCode(CopyAsCodeParams),
+
+    /// Search variables
+    ///
+    /// Returns the variables whose names match a query, and optionally whose
+    /// value inherits from a class.
+    #[serde(rename = "search")]
+    Search(SearchParams),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "method", content = "result")]
+pub enum VariablesExtBackendReply {
+    /// Code that reconstructs the variable.
+    CopyAsCodeReply(FormattedVariable),
+
+    /// The variables matching the search.
+    SearchReply(VariableList),
+}
@@ -37,6 +37,18 @@ impl RVersion {
     }
 }
 
+impl std::fmt::Display for RVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// An R installation found by [detect_r_installations()]
+pub struct RInstallation {
+    pub r_home: PathBuf,
+    pub version: RVersion,
+}
+
 pub fn from_r_home(r_home: &Path) -> anyhow::Result<RVersion> {
     let path = r_home.join("library").join("base").join("DESCRIPTION");
 
@@ -55,6 +67,216 @@ pub fn from_r_home(r_home: &Path) -> anyhow::Result<RVersion> {
     })
 }
 
+/// Search common locations for R installations
+///
+/// Looks at `R_HOME`, the R found on the `PATH`, and the platform's usual
+/// install locations, including those managed by rig. Candidates that don't
+/// look like an `R_HOME` (i.e. whose version can't be read) are skipped.
+/// Installations are deduplicated by canonical path and sorted by version,
+/// most recent first.
+pub fn detect_r_installations() -> Vec<RInstallation> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    if let Ok(home) = env::var("R_HOME") {
+        candidates.push(PathBuf::from(home));
+    }
+
+    if let Ok(output) = harp::command::r_command_from_path(|command| {
+        command.arg("RHOME");
+    }) {
+        if let Ok(home) = String::from_utf8(output.stdout) {
+            candidates.push(PathBuf::from(home.trim()));
+        }
+    }
+
+    candidates.extend(platform_r_homes());
+
+    r_installations_from(candidates)
+}
+
+fn r_installations_from(candidates: Vec<PathBuf>) -> Vec<RInstallation> {
+    let mut installations: Vec<RInstallation> = Vec::new();
+
+    for candidate in candidates {
+        let Ok(r_home) = candidate.canonicalize() else {
+            continue;
+        };
+        if installations.iter().any(|x| x.r_home == r_home) {
+            continue;
+        }
+        let Ok(version) = from_r_home(&r_home) else {
+            continue;
+        };
+        installations.push(RInstallation { r_home, version });
+    }
+
+    installations
+        .sort_by_key(|x| std::cmp::Reverse((x.version.major, x.version.minor, x.version.patch)));
+
+    installations
+}
+
+/// List `{dir}/*/{suffix}` for each subdirectory of `dir`
+fn subdir_r_homes(dir: &Path, suffix: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .map(|path| path.join(suffix))
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_r_homes() -> Vec<PathBuf> {
+    // Posit's R builds and rig install under `/opt/R/{version}`
+    let mut homes = subdir_r_homes(Path::new("/opt/R"), "lib/R");
+    homes.push(PathBuf::from("/usr/lib/R"));
+    homes.push(PathBuf::from("/usr/local/lib/R"));
+    homes
+}
+
+#[cfg(target_os = "macos")]
+fn platform_r_homes() -> Vec<PathBuf> {
+    // CRAN installers and rig install side by side versions under the
+    // framework. `Current` is a symlink deduplicated by canonicalisation.
+    let mut homes = subdir_r_homes(
+        Path::new("/Library/Frameworks/R.framework/Versions"),
+        "Resources",
+    );
+    homes.push(PathBuf::from("/opt/homebrew/lib/R"));
+    homes.push(PathBuf::from("/usr/local/lib/R"));
+    homes
+}
+
+#[cfg(target_os = "windows")]
+fn platform_r_homes() -> Vec<PathBuf> {
+    // The CRAN installer and rig install under `{ProgramFiles}\R\R-{version}`
+    let mut homes = Vec::new();
+    for var in ["ProgramFiles", "ProgramW6432", "LOCALAPPDATA"] {
+        if let Ok(dir) = env::var(var) {
+            let dir = PathBuf::from(dir);
+            homes.extend(subdir_r_homes(&dir.join("R"), ""));
+            homes.extend(subdir_r_homes(&dir.join("Programs").join("R"), ""));
+        }
+    }
+
+    // Installations in custom locations are only found through the registry
+    homes.extend(registry_r_homes());
+
+    homes
+}
+
+/// List the `InstallPath` of the R installations registered under
+/// `Software\R-core\R`, for all users and for the current user. The key
+/// holds the path of the current version, and each version has its own
+/// `{version}\InstallPath`.
+#[cfg(target_os = "windows")]
+fn registry_r_homes() -> Vec<PathBuf> {
+    use windows_sys::Win32::System::Registry::HKEY_CURRENT_USER;
+    use windows_sys::Win32::System::Registry::HKEY_LOCAL_MACHINE;
+
+    let key = r"Software\R-core\R";
+    let mut homes = Vec::new();
+
+    for root in [HKEY_LOCAL_MACHINE, HKEY_CURRENT_USER] {
+        homes.extend(registry_string(root, key, "InstallPath"));
+        for version in registry_subkeys(root, key) {
+            let version_key = format!(r"{key}\{version}");
+            homes.extend(registry_string(root, &version_key, "InstallPath"));
+        }
+    }
+
+    homes.into_iter().map(PathBuf::from).collect()
+}
+
+#[cfg(target_os = "windows")]
+fn registry_string(
+    root: windows_sys::Win32::System::Registry::HKEY,
+    key: &str,
+    value: &str,
+) -> Option<String> {
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Registry::RegGetValueW;
+    use windows_sys::Win32::System::Registry::RRF_RT_REG_SZ;
+
+    let key = to_wide(key);
+    let value = to_wide(value);
+    let mut buffer = [0u16; 1024];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+
+    let status = unsafe {
+        RegGetValueW(
+            root,
+            key.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_SZ,
+            std::ptr::null_mut(),
+            buffer.as_mut_ptr().cast(),
+            &mut size,
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return None;
+    }
+
+    // `RRF_RT_REG_SZ` guarantees a null terminated string
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+#[cfg(target_os = "windows")]
+fn registry_subkeys(root: windows_sys::Win32::System::Registry::HKEY, key: &str) -> Vec<String> {
+    use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+    use windows_sys::Win32::System::Registry::RegCloseKey;
+    use windows_sys::Win32::System::Registry::RegEnumKeyExW;
+    use windows_sys::Win32::System::Registry::RegOpenKeyExW;
+    use windows_sys::Win32::System::Registry::HKEY;
+    use windows_sys::Win32::System::Registry::KEY_READ;
+
+    let key = to_wide(key);
+    let mut handle: HKEY = std::ptr::null_mut();
+
+    let status = unsafe { RegOpenKeyExW(root, key.as_ptr(), 0, KEY_READ, &mut handle) };
+    if status != ERROR_SUCCESS {
+        return Vec::new();
+    }
+
+    let mut subkeys = Vec::new();
+    let mut buffer = [0u16; 256];
+
+    for index in 0.. {
+        let mut len = buffer.len() as u32;
+        let status = unsafe {
+            RegEnumKeyExW(
+                handle,
+                index,
+                buffer.as_mut_ptr(),
+                &mut len,
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        if status != ERROR_SUCCESS {
+            break;
+        }
+        subkeys.push(String::from_utf16_lossy(&buffer[..len as usize]));
+    }
+
+    unsafe { RegCloseKey(handle) };
+    subkeys
+}
+
+#[cfg(target_os = "windows")]
+fn to_wide(x: &str) -> Vec<u16> {
+    x.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
 fn parse_version_string(s: &str) -> anyhow::Result<RVersion> {
     let parts = s.trim().split('.').map(|x| x.parse::<u32>());
 
@@ -153,6 +375,39 @@ mod tests {
         assert!(version.is_supported());
     }
 
+    #[test]
+    fn test_r_installations_from() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let make_r_home = |name: &str, version: &str| {
+            let base = dir.path().join(name).join("library").join("base");
+            std::fs::create_dir_all(&base).unwrap();
+            let description = format!("Package: base\nVersion: {version}\n");
+            std::fs::write(base.join("DESCRIPTION"), description).unwrap();
+        };
+        make_r_home("4.3.2", "4.3.2");
+        make_r_home("4.5.1", "4.5.1");
+
+        // Not an R home
+        std::fs::create_dir_all(dir.path().join("other")).unwrap();
+
+        let mut candidates = subdir_r_homes(dir.path(), "");
+        // Duplicates and missing paths are skipped
+        candidates.push(dir.path().join("4.3.2"));
+        candidates.push(dir.path().join("missing"));
+
+        let installations = r_installations_from(candidates);
+        let versions: Vec<String> = installations
+            .iter()
+            .map(|x| x.version.to_string())
+            .collect();
+        assert_eq!(versions, vec!["4.5.1", "4.3.2"]);
+        assert_eq!(
+            installations[0].r_home,
+            dir.path().join("4.5.1").canonicalize().unwrap()
+        );
+    }
+
     #[test]
     fn test_parse_version_string_basic() {
         let version = parse_version_string("4.5.1").unwrap();
@@ -0,0 +1,198 @@
+//
+// watchdog.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+//! Optional watchdog that interrupts R when a computation runs for longer
+//! than a timeout, enabled with `--interrupt-on-idle-timeout`.
+//!
+//! R is considered busy from the time `ReadConsole` hands over some input
+//! until it asks for the next one. Time spent waiting for input, including
+//! at a browser or `readline()` prompt, never counts towards the timeout.
+//! This is meant for unattended sessions (e.g. background sessions driven by
+//! automation) where a runaway job should abort itself since there is no UI
+//! to interrupt it.
+
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use stdext::spawn;
+
+use crate::signals::set_interrupts_pending;
+
+static WATCHDOG: OnceLock<InterruptWatchdog> = OnceLock::new();
+
+/// Source of the current time, stubbed in tests
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+pub struct InterruptWatchdog<C: Clock = SystemClock> {
+    timeout: Duration,
+    clock: C,
+
+    /// When R last started working on some input. `None` while R waits for
+    /// input.
+    busy_since: Mutex<Option<Instant>>,
+}
+
+impl<C: Clock> InterruptWatchdog<C> {
+    pub fn new(timeout: Duration, clock: C) -> Self {
+        Self {
+            timeout,
+            clock,
+            busy_since: Mutex::new(None),
+        }
+    }
+
+    /// Called when `ReadConsole` returns some input for R to evaluate
+    pub fn on_busy(&self) {
+        *self.busy_since.lock().unwrap() = Some(self.clock.now());
+    }
+
+    /// Called when `ReadConsole` starts waiting for input
+    pub fn on_idle(&self) {
+        *self.busy_since.lock().unwrap() = None;
+    }
+
+    /// Whether R has been busy for longer than the timeout. Once the timeout
+    /// has elapsed this returns `true` only once, and the timer starts over
+    /// in case the interrupt gets caught by the running code.
+    pub fn check(&self) -> bool {
+        let mut busy_since = self.busy_since.lock().unwrap();
+
+        let Some(since) = *busy_since else {
+            return false;
+        };
+
+        let now = self.clock.now();
+        if now.saturating_duration_since(since) < self.timeout {
+            return false;
+        }
+
+        *busy_since = Some(now);
+        true
+    }
+}
+
+/// Start the watchdog thread. Must be called at most once, before R starts.
+pub fn start(timeout: Duration) {
+    if WATCHDOG
+        .set(InterruptWatchdog::new(timeout, SystemClock))
+        .is_err()
+    {
+        log::error!("The interrupt watchdog is already running");
+        return;
+    }
+
+    // Poll often enough that the interrupt isn't sent much later than the
+    // timeout
+    let interval = (timeout / 10).clamp(Duration::from_millis(10), Duration::from_secs(1));
+
+    spawn!("ark-watchdog", move || {
+        let Some(watchdog) = WATCHDOG.get() else {
+            return;
+        };
+        loop {
+            std::thread::sleep(interval);
+            if watchdog.check() {
+                log::warn!("R has been busy for more than {timeout:?}, interrupting");
+                set_interrupts_pending(true);
+            }
+        }
+    });
+}
+
+/// Notify the watchdog, if running, that R started evaluating some input
+pub(crate) fn notify_busy() {
+    if let Some(watchdog) = WATCHDOG.get() {
+        watchdog.on_busy();
+    }
+}
+
+/// Notify the watchdog, if running, that R is waiting for input
+pub(crate) fn notify_idle() {
+    if let Some(watchdog) = WATCHDOG.get() {
+        watchdog.on_idle();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use super::Clock;
+    use super::InterruptWatchdog;
+
+    struct StubClock {
+        now: Mutex<Instant>,
+    }
+
+    impl StubClock {
+        fn advance(&self, duration: Duration) {
+            *self.now.lock().unwrap() += duration;
+        }
+    }
+
+    impl Clock for &StubClock {
+        fn now(&self) -> Instant {
+            *self.now.lock().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_watchdog_fires_after_timeout() {
+        let clock = StubClock {
+            now: Mutex::new(Instant::now()),
+        };
+        let watchdog = InterruptWatchdog::new(Duration::from_secs(10), &clock);
+
+        // Waiting for input never fires
+        clock.advance(Duration::from_secs(60));
+        assert!(!watchdog.check());
+
+        watchdog.on_busy();
+        clock.advance(Duration::from_secs(9));
+        assert!(!watchdog.check());
+
+        clock.advance(Duration::from_secs(1));
+        assert!(watchdog.check());
+
+        // Fires only once per timeout period
+        assert!(!watchdog.check());
+        clock.advance(Duration::from_secs(10));
+        assert!(watchdog.check());
+    }
+
+    #[test]
+    fn test_watchdog_resets_when_idle() {
+        let clock = StubClock {
+            now: Mutex::new(Instant::now()),
+        };
+        let watchdog = InterruptWatchdog::new(Duration::from_secs(10), &clock);
+
+        watchdog.on_busy();
+        clock.advance(Duration::from_secs(9));
+        watchdog.on_idle();
+        assert!(!watchdog.check());
+
+        // Progress restarts the timer
+        watchdog.on_busy();
+        clock.advance(Duration::from_secs(9));
+        assert!(!watchdog.check());
+    }
+}
@@ -0,0 +1,71 @@
+//
+// dap_data_breakpoints.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use amalthea::fixtures::dummy_frontend::ExecuteRequestOptions;
+use ark_test::DummyArkFrontend;
+
+#[test]
+fn test_dap_data_breakpoint_info() {
+    let frontend = DummyArkFrontend::lock();
+    let mut dap = frontend.start_dap();
+
+    let info = dap.data_breakpoint_info("x", None);
+    assert_eq!(info.data_id.as_deref(), Some("x"));
+    assert!(info.description.contains("`x`"));
+}
+
+/// Test that assigning a new value to a watched global variable stops in the
+/// function that made the assignment
+#[test]
+fn test_dap_data_breakpoint_stops_on_change() {
+    let frontend = DummyArkFrontend::lock();
+    let mut dap = frontend.start_dap();
+
+    frontend.execute_request_invisibly("x <- 1");
+    frontend.execute_request_invisibly("f <- function() x <<- 2");
+
+    let breakpoints = dap.set_data_breakpoints(&["x", "does_not_exist"]);
+    assert_eq!(breakpoints.len(), 2);
+    assert!(breakpoints[0].verified);
+    assert!(!breakpoints[1].verified);
+
+    // Assigning the same value doesn't stop
+    frontend.execute_request_invisibly("x <- 1");
+
+    frontend.send_execute_request("f()", ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_execute_input();
+    frontend.recv_iopub_start_debug();
+
+    let description = dap.recv_stopped_data_breakpoint();
+    assert!(description.contains("`x`"));
+
+    // The watch frame is hidden, we're stopped in the assigning function
+    let stack = dap.stack_trace();
+    assert_eq!(stack[0].name, "f()");
+
+    frontend.recv_iopub_idle();
+    frontend.recv_shell_execute_reply();
+
+    frontend.send_execute_request("c", ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_execute_input();
+    frontend.recv_iopub_stop_debug();
+    frontend.recv_iopub_idle();
+    frontend.recv_shell_execute_reply();
+
+    dap.recv_continued();
+
+    // Clearing data breakpoints restores a plain binding with the new value
+    let breakpoints = dap.set_data_breakpoints(&[]);
+    assert!(breakpoints.is_empty());
+
+    frontend.execute_request("bindingIsActive('x', globalenv())", |result| {
+        assert_eq!(result, "[1] FALSE")
+    });
+    frontend.execute_request("x", |result| assert_eq!(result, "[1] 2"));
+}
@@ -773,3 +773,43 @@ foo()
     frontend.recv_shell_execute_reply_exception(); // c
     frontend.recv_shell_execute_reply(); // Q
 }
+
+/// Silent executions don't broadcast output, but errors must still honor
+/// exception breakpoints and stop the debugger
+#[test]
+fn test_dap_break_on_error_silent_execution() {
+    let frontend = DummyArkFrontend::lock();
+    let mut dap = frontend.start_dap();
+
+    dap.set_exception_breakpoints(&["error"]);
+
+    let code = "cat('silent output\\n'); message('silent message'); stop('silent error')";
+    frontend.send_execute_request(code, ExecuteRequestOptions {
+        silent: true,
+        ..Default::default()
+    });
+
+    // No `execute_input` and no streams for silent requests. Unexpected
+    // streams make `recv_iopub_idle()` panic.
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_start_debug();
+
+    let (text, description) = dap.recv_stopped_exception();
+    assert!(text.contains("simpleError"));
+    assert!(description.contains("silent error"));
+
+    frontend.recv_iopub_idle();
+    frontend.recv_shell_execute_reply();
+
+    // Continue out of debugger - error propagates
+    frontend.send_execute_request("c", ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_execute_input();
+    frontend.recv_iopub_stop_debug();
+    let evalue = frontend.recv_iopub_execute_error();
+    assert!(evalue.contains("silent error"));
+    frontend.recv_iopub_idle();
+    frontend.recv_shell_execute_reply_exception();
+
+    dap.recv_continued();
+}
@@ -2072,6 +2072,30 @@ fn test_row_names_matrix_empty_selection() {
     });
 }
 
+#[test]
+fn test_named_vector() {
+    let setup = open_data_explorer_from_expression("c(a = 1, b = 2, c = 3)", Some("x")).unwrap();
+
+    // Shown as a single column of values, with names as row labels
+    assert_match!(setup.rpc(RequestBuilder::get_schema(vec![0])),
+        DataExplorerBackendReply::GetSchemaReply(schema) => {
+            assert_eq!(schema.columns.len(), 1);
+            assert_eq!(schema.columns[0].column_name, "value");
+        }
+    );
+    TestAssertions::assert_state(&setup, |state| {
+        assert!(state.has_row_labels);
+        assert_eq!(state.table_shape.num_rows, 3);
+        assert_eq!(state.table_shape.num_columns, 1);
+    });
+    TestAssertions::assert_row_labels(&setup, SelectionBuilder::indices(vec![0, 1, 2]), |labels| {
+        assert_eq!(labels[0], vec!["a", "b", "c"]);
+    });
+    TestAssertions::assert_data_values(&setup, 0, 3, vec![0], |data| {
+        assert_eq!(data[0][2], ColumnValue::FormattedValue("3.00".to_string()));
+    });
+}
+
 #[test]
 fn test_schema_identification() {
     let setup = open_data_explorer_from_expression(
@@ -2110,6 +2134,80 @@ fn test_schema_identification() {
     );
 }
 
+#[test]
+fn test_complex_column() {
+    let setup =
+        open_data_explorer_from_expression("data.frame(x = c(1+2i, 3-4i, NA, 0.5+0i))", None)
+            .unwrap();
+
+    let req = DataExplorerBackendRequest::GetSchema(GetSchemaParams {
+        column_indices: vec![0],
+    });
+
+    assert_match!(setup.rpc(req),
+        DataExplorerBackendReply::GetSchemaReply(schema) => {
+            assert_eq!(schema.columns[0].type_display, ColumnDisplayType::Object);
+            assert_eq!(schema.columns[0].type_name, "cplx".to_string());
+        }
+    );
+
+    let req = get_data_values_request(0, 4, vec![0], default_format_options());
+
+    assert_match!(setup.rpc(req),
+        DataExplorerBackendReply::GetDataValuesReply(data) => {
+            assert_eq!(data.columns[0], vec![
+                ColumnValue::FormattedValue("1+2i".to_string()),
+                ColumnValue::FormattedValue("3-4i".to_string()),
+                ColumnValue::SpecialValueCode(1),
+                ColumnValue::FormattedValue("0.5+0i".to_string()),
+            ]);
+        }
+    );
+}
+
+#[test]
+fn test_list_column() {
+    let setup = open_data_explorer_from_expression(
+        "local({
+            x <- data.frame(id = 1:3)
+            x$bare <- list(1:3, list(a = 1, b = 2), NULL)
+            x$asis <- I(list('a', 'b', 'c'))
+            x
+        })",
+        None,
+    )
+    .unwrap();
+
+    let req = DataExplorerBackendRequest::GetSchema(GetSchemaParams {
+        column_indices: vec![1, 2],
+    });
+
+    assert_match!(setup.rpc(req),
+        DataExplorerBackendReply::GetSchemaReply(schema) => {
+            assert_eq!(schema.columns[0].type_display, ColumnDisplayType::Object);
+            assert_eq!(schema.columns[1].type_display, ColumnDisplayType::Object);
+        }
+    );
+
+    let req = get_data_values_request(0, 3, vec![1, 2], default_format_options());
+
+    assert_match!(setup.rpc(req),
+        DataExplorerBackendReply::GetDataValuesReply(data) => {
+            assert_eq!(data.columns[0], vec![
+                ColumnValue::FormattedValue("<integer [3]>".to_string()),
+                ColumnValue::FormattedValue("<list [2]>".to_string()),
+                ColumnValue::SpecialValueCode(0),
+            ]);
+            // Classed lists keep using their `format()` method
+            assert_eq!(data.columns[1], vec![
+                ColumnValue::FormattedValue("a".to_string()),
+                ColumnValue::FormattedValue("b".to_string()),
+                ColumnValue::FormattedValue("c".to_string()),
+            ]);
+        }
+    );
+}
+
 #[test]
 fn test_search_schema_text_filters() {
     let setup = TestDataBuilder::create_search_test_dataframe().unwrap();
@@ -17,6 +17,7 @@ use amalthea::comm::data_explorer_comm::ColumnSelection;
 use amalthea::comm::data_explorer_comm::ColumnValue;
 use amalthea::comm::data_explorer_comm::DataExplorerBackendReply;
 use amalthea::comm::data_explorer_comm::DataExplorerBackendRequest;
+use amalthea::comm::data_explorer_comm::DataExplorerFrontendEvent;
 use amalthea::comm::data_explorer_comm::DataSelectionRange;
 use amalthea::comm::data_explorer_comm::FormatOptions;
 use amalthea::comm::data_explorer_comm::GetDataValuesParams;
@@ -276,6 +277,46 @@ fn test_data_explorer_does_not_force_delayed_binding() {
     );
 }
 
+/// The `set_data_object` request swaps the object backing an existing data
+/// explorer comm instead of opening a new one.
+#[test]
+fn test_set_data_object_swaps_data_explorer_object() {
+    let frontend = DummyArkFrontend::lock();
+
+    execute_silently(
+        &frontend,
+        "swap_df1 <- data.frame(a = c(1, 2, 3)); swap_df2 <- data.frame(a = c(4, 5, 6))",
+    );
+    let comm_id = frontend.open_data_explorer("swap_df1");
+
+    // Swap between frames with the same schema. The comm is told its data
+    // changed and then shows the new frame.
+    for (name, first) in [("swap_df2", "4.00"), ("swap_df1", "1.00")] {
+        let data = serde_json::json!({
+            "id": "set-data-object-rpc",
+            "method": "set_data_object",
+            "params": { "name": name },
+        });
+        frontend.send_shell_comm_msg(comm_id.clone(), data);
+        frontend.recv_iopub_busy();
+        assert_eq!(
+            frontend.recv_data_explorer_event(),
+            DataExplorerFrontendEvent::DataUpdate
+        );
+        let reply = frontend.recv_iopub_comm_msg();
+        assert_eq!(reply.comm_id, comm_id);
+        assert_eq!(reply.data["method"], "SetDataObjectReply");
+        frontend.recv_iopub_idle();
+        frontend.assert_iopub_empty();
+
+        let table = get_data_values(&frontend, &comm_id, 3);
+        assert_eq!(
+            table.columns[0][0],
+            ColumnValue::FormattedValue(first.to_string())
+        );
+    }
+}
+
 /// Run `code` at top level, asserting the standard Busy/Idle message sequence
 /// with no output beyond the execute input.
 fn execute_silently(frontend: &DummyArkFrontend, code: &str) {
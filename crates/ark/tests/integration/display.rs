@@ -0,0 +1,63 @@
+//
+// display.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use amalthea::fixtures::dummy_frontend::ExecuteRequestOptions;
+use ark_test::DummyArkFrontend;
+
+#[test]
+fn test_display_data_with_update() {
+    let frontend = DummyArkFrontend::lock();
+
+    let code = r#"
+.ps.display_data(list("text/plain" = "0%"), display_id = "progress")
+.ps.update_display_data(list("text/plain" = "100%"), display_id = "progress")
+"#;
+    frontend.send_execute_request(code, ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+
+    let input = frontend.recv_iopub_execute_input();
+    assert_eq!(input.code, code);
+
+    assert_eq!(frontend.recv_iopub_display_data_id(), "progress");
+    frontend.recv_iopub_update_display_data();
+
+    frontend.recv_iopub_idle();
+    assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
+}
+
+#[test]
+fn test_update_display_data_requires_display_id() {
+    let frontend = DummyArkFrontend::lock();
+
+    frontend.execute_request_error(
+        r#".ps.update_display_data(list("text/plain" = "100%"), display_id = NULL)"#,
+        |error| assert!(error.contains("`display_id` must be a string")),
+    );
+}
+
+#[test]
+fn test_clear_output() {
+    let frontend = DummyArkFrontend::lock();
+
+    let code = r#"
+.ps.display_data(list("text/plain" = "frame 1"))
+.ps.clear_output(wait = TRUE)
+.ps.clear_output()
+"#;
+    frontend.send_execute_request(code, ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+
+    let input = frontend.recv_iopub_execute_input();
+    assert_eq!(input.code, code);
+
+    frontend.recv_iopub_display_data();
+    assert!(frontend.recv_iopub_clear_output());
+    assert!(!frontend.recv_iopub_clear_output());
+
+    frontend.recv_iopub_idle();
+    assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
+}
@@ -21,6 +21,31 @@ fn test_kernel_info() {
     frontend.recv_iopub_idle();
 }
 
+#[test]
+fn test_kernel_info_help_links() {
+    let frontend = DummyArkFrontend::lock();
+
+    frontend.send_shell(KernelInfoRequest {});
+
+    assert_match!(frontend.recv_shell(), Message::KernelInfoReply(reply) => {
+        let links = reply.content.help_links;
+        let has_link = |text: &str| links.iter().any(|link| link.text == text);
+
+        assert!(has_link("An Introduction to R"));
+        assert!(has_link("The R Language Definition"));
+        assert!(has_link("CRAN"));
+
+        // Links are deduplicated
+        let mut urls: Vec<&str> = links.iter().map(|link| link.url.as_str()).collect();
+        urls.sort();
+        urls.dedup();
+        assert_eq!(urls.len(), links.len());
+    });
+
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_idle();
+}
+
 #[test]
 fn test_env_vars() {
     // These environment variables are set by R's shell script frontend.
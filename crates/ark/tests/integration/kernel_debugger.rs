@@ -669,3 +669,65 @@ fn test_browser_with_r_task() {
 
     frontend.execute_request_invisibly("Q");
 }
+
+/// Tasks with top-level idle priority must wait until R leaves the browser,
+/// while tasks with any-idle priority run at the browser prompt.
+#[test]
+fn test_browser_defers_top_level_idle_task() {
+    let frontend = DummyArkFrontend::lock();
+
+    let task_ran = |option: &str| {
+        let ran = std::cell::Cell::new(false);
+        frontend.execute_request(&format!("getOption('{option}', FALSE)"), |result| {
+            ran.set(result.contains("TRUE"));
+        });
+        ran.get()
+    };
+
+    frontend.send_execute_request("browser()", ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_execute_input();
+    frontend.recv_iopub_idle();
+    frontend.recv_shell_execute_reply();
+
+    frontend.execute_request_invisibly(
+        r#"invisible(.Call("ps_test_spawn_eval_task", "options(ark.test.top_level_ran = TRUE)", "top_level_idle"))"#,
+    );
+    frontend.execute_request_invisibly(
+        r#"invisible(.Call("ps_test_spawn_eval_task", "options(ark.test.any_ran = TRUE)", "any_idle"))"#,
+    );
+
+    // Give the event loop idle time at the browser prompt until the any-idle
+    // task has run. Execute requests take priority over tasks, so sleep
+    // between polls.
+    let deadline = std::time::Instant::now() + ark_test::comm::RECV_TIMEOUT;
+    loop {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        if task_ran("ark.test.any_ran") {
+            break;
+        }
+        if std::time::Instant::now() >= deadline {
+            panic!("Timed out waiting for any-idle task to run");
+        }
+    }
+
+    // The top-level task is still queued while we're in the browser
+    assert!(!task_ran("ark.test.top_level_ran"));
+
+    frontend.execute_request_invisibly("Q");
+
+    let deadline = std::time::Instant::now() + ark_test::comm::RECV_TIMEOUT;
+    loop {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        if task_ran("ark.test.top_level_ran") {
+            break;
+        }
+        if std::time::Instant::now() >= deadline {
+            panic!("Timed out waiting for top-level idle task to run");
+        }
+    }
+
+    frontend.execute_request_invisibly(
+        "options(ark.test.top_level_ran = NULL, ark.test.any_ran = NULL)",
+    );
+}
@@ -1,4 +1,9 @@
+use std::time::Duration;
+
 use amalthea::fixtures::dummy_frontend::ExecuteRequestOptions;
+use amalthea::wire::jupyter_message::Message;
+use amalthea::wire::status::ExecutionState;
+use amalthea::wire::stream::Stream;
 use ark_test::DummyArkFrontend;
 
 #[test]
@@ -7,6 +12,42 @@ fn test_execute_request() {
     frontend.execute_request("42", |result| assert_eq!(result, "[1] 42"));
 }
 
+#[test]
+fn test_execute_request_user_expressions() {
+    let frontend = DummyArkFrontend::lock();
+
+    let options = ExecuteRequestOptions {
+        user_expressions: serde_json::json!({
+            "pi": "pi",
+            "oops": "stop('oops')",
+        }),
+        ..Default::default()
+    };
+
+    frontend.send_execute_request("invisible(1)", options);
+    frontend.recv_iopub_busy();
+    let input = frontend.recv_iopub_execute_input();
+    frontend.recv_iopub_idle();
+
+    match frontend.recv_shell() {
+        Message::ExecuteReply(data) => {
+            assert_eq!(data.content.execution_count, input.execution_count);
+
+            let expressions = &data.content.user_expressions;
+            assert_eq!(expressions["pi"]["status"], "ok");
+            assert_eq!(expressions["pi"]["data"]["text/plain"], "[1] 3.141593");
+
+            // Errors are reported per expression
+            assert_eq!(expressions["oops"]["status"], "error");
+            assert!(expressions["oops"]["evalue"]
+                .as_str()
+                .unwrap()
+                .contains("oops"));
+        },
+        other => panic!("Expected ExecuteReply, got {other:?}"),
+    }
+}
+
 #[test]
 fn test_execute_request_empty() {
     let frontend = DummyArkFrontend::lock();
@@ -141,3 +182,52 @@ fn test_execute_request_data_frame() {
     frontend.recv_iopub_idle();
     frontend.recv_shell_execute_reply();
 }
+
+#[test]
+fn test_execute_request_stream_coalescing() {
+    let frontend = DummyArkFrontend::lock();
+
+    let code = "for (i in 1:2000) cat('a'); message('b'); for (i in 1:2000) cat('c')";
+    frontend.send_execute_request(code, ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_execute_input();
+
+    // Collect stream messages by hand so we can inspect message boundaries
+    // and the ordering across stdout and stderr. All output must arrive
+    // before Idle.
+    let mut messages: Vec<(Stream, String)> = Vec::new();
+    let mut n_stream_messages = 0;
+    loop {
+        let msg = frontend
+            .recv_iopub_with_timeout(Duration::from_secs(10))
+            .expect("Timed out waiting for Idle");
+
+        match msg {
+            Message::Stream(data) => {
+                n_stream_messages += 1;
+                match messages.last_mut() {
+                    Some((name, text)) if *name == data.content.name => {
+                        text.push_str(&data.content.text);
+                    },
+                    _ => messages.push((data.content.name, data.content.text)),
+                }
+            },
+            Message::Status(data) if data.content.execution_state == ExecutionState::Idle => {
+                break;
+            },
+            other => panic!("Unexpected message: {other:?}"),
+        }
+    }
+    frontend.recv_shell_execute_reply();
+
+    // The 4001 writes are coalesced into far fewer messages
+    assert!(n_stream_messages < 100);
+
+    // Consecutive writes to the same stream are merged and stay in order
+    // relative to writes on the other stream
+    assert_eq!(messages, vec![
+        (Stream::Stdout, "a".repeat(2000)),
+        (Stream::Stderr, String::from("b\n")),
+        (Stream::Stdout, "c".repeat(2000)),
+    ]);
+}
@@ -214,3 +214,58 @@ fn test_notebook_stdin_followed_by_an_expression_on_the_next_line() {
 
     assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
 }
+
+#[test]
+fn test_notebook_html_table_output() {
+    let frontend = DummyArkFrontendNotebook::lock();
+
+    if !frontend.is_installed("knitr") {
+        return;
+    }
+
+    let code = "knitr::kable(data.frame(x = 1), format = 'html')";
+    frontend.send_execute_request(code, ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    let input = frontend.recv_iopub_execute_input();
+
+    let data = frontend.recv_iopub_execute_result_data();
+    assert!(data.contains_key("text/plain"));
+    assert!(data["text/html"].as_str().unwrap().contains("<table>"));
+
+    frontend.recv_iopub_idle();
+    assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
+
+    // Markdown tables only have a plain text representation
+    let code = "knitr::kable(data.frame(x = 1), format = 'pipe')";
+    frontend.send_execute_request(code, ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    let input = frontend.recv_iopub_execute_input();
+
+    let data = frontend.recv_iopub_execute_result_data();
+    assert!(data.contains_key("text/plain"));
+    assert!(!data.contains_key("text/html"));
+
+    frontend.recv_iopub_idle();
+    assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
+}
+
+#[test]
+fn test_notebook_html_gt_output() {
+    let frontend = DummyArkFrontendNotebook::lock();
+
+    if !frontend.is_installed("gt") {
+        return;
+    }
+
+    let code = "gt::gt(data.frame(x = 1))";
+    frontend.send_execute_request(code, ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    let input = frontend.recv_iopub_execute_input();
+
+    let data = frontend.recv_iopub_execute_result_data();
+    assert!(data.contains_key("text/plain"));
+    assert!(data["text/html"].as_str().unwrap().contains("<table"));
+
+    frontend.recv_iopub_idle();
+    assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
+}
@@ -0,0 +1,21 @@
+use amalthea::wire::jupyter_message::Message;
+use amalthea::wire::kernel_info_request::KernelInfoRequest;
+use ark_test::DummyArkFrontendQuiet;
+use stdext::assert_match;
+
+// You must run these tests with `cargo nextest` because they initialise
+// incompatible process singletons
+
+#[test]
+fn test_kernel_info_quiet_banner() {
+    let frontend = DummyArkFrontendQuiet::lock();
+
+    frontend.send_shell(KernelInfoRequest {});
+
+    assert_match!(frontend.recv_shell(), Message::KernelInfoReply(reply) => {
+        assert_eq!(reply.content.banner, "");
+    });
+
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_idle();
+}
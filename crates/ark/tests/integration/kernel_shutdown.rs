@@ -1,9 +1,15 @@
 #[cfg(unix)]
 use amalthea::fixtures::dummy_frontend::ExecuteRequestOptions;
 #[cfg(unix)]
+use amalthea::wire::jupyter_message::Message;
+#[cfg(unix)]
 use amalthea::wire::jupyter_message::Status;
 #[cfg(unix)]
+use amalthea::wire::status::ExecutionState;
+#[cfg(unix)]
 use ark_test::DummyArkFrontend;
+#[cfg(unix)]
+use serde_json::json;
 
 /// Install a SIGINT handler for shutdown tests. This overrides the test runner
 /// handler so it doesn't cancel our test.
@@ -57,6 +63,101 @@ fn test_shutdown_request_with_restart() {
     DummyArkFrontend::wait_for_cleanup();
 }
 
+#[test]
+#[cfg(unix)]
+fn test_shutdown_request_with_restart_closes_comms() {
+    install_sigint_handler();
+    let frontend = DummyArkFrontend::lock();
+
+    frontend.send_execute_request("x <- data.frame(a = 1)", ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_execute_input();
+    frontend.recv_iopub_idle();
+    frontend.recv_shell_execute_reply();
+
+    let comm_id = frontend.open_data_explorer("x");
+
+    frontend.send_shutdown_request(true);
+    frontend.recv_iopub_busy();
+
+    let reply = frontend.recv_control_shutdown_reply();
+    assert_eq!(reply.status, Status::Ok);
+    assert!(reply.restart);
+
+    // The comm is closed from the R thread, so the `comm_close` message races
+    // with the idle status of the control request
+    let mut got_idle = false;
+    let mut got_close = false;
+    while !(got_idle && got_close) {
+        match frontend.recv_iopub() {
+            Message::Status(msg) => {
+                assert_eq!(msg.content.execution_state, ExecutionState::Idle);
+                got_idle = true;
+            },
+            Message::CommClose(msg) => {
+                assert_eq!(msg.content.comm_id, comm_id);
+                got_close = true;
+            },
+            Message::Stream(_) | Message::CommMsg(_) => {},
+            other => panic!("Unexpected IOPub message: {other:?}"),
+        }
+    }
+
+    DummyArkFrontend::wait_for_cleanup();
+}
+
+#[test]
+#[cfg(unix)]
+fn test_restart_command_closes_comms() {
+    install_sigint_handler();
+    let frontend = DummyArkFrontend::lock();
+    let mut lsp = frontend.start_lsp();
+
+    frontend.send_execute_request("x <- data.frame(a = 1)", ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_execute_input();
+    frontend.recv_iopub_idle();
+    frontend.recv_shell_execute_reply();
+
+    let comm_id = frontend.open_data_explorer("x");
+
+    let params = json!({
+        "command": "ark.restartR",
+        "arguments": [],
+    });
+    lsp.send_request("workspace/executeCommand", params);
+
+    loop {
+        match frontend.recv_iopub() {
+            Message::CommClose(msg) => {
+                assert_eq!(msg.content.comm_id, comm_id);
+                break;
+            },
+            Message::Status(_) | Message::Stream(_) | Message::CommMsg(_) => {},
+            other => panic!("Unexpected IOPub message: {other:?}"),
+        }
+    }
+
+    DummyArkFrontend::wait_for_cleanup();
+}
+
+#[test]
+#[cfg(unix)]
+fn test_shutdown_on_sigterm() {
+    use nix::sys::signal::kill;
+    use nix::sys::signal::Signal;
+    use nix::unistd::Pid;
+
+    install_sigint_handler();
+    let _frontend = DummyArkFrontend::lock();
+
+    // Each test runs in its own process with nextest, so this is the kernel's
+    // process. The SIGTERM handler turns the signal into a shutdown request.
+    kill(Pid::this(), Signal::SIGTERM).unwrap();
+
+    DummyArkFrontend::wait_for_cleanup();
+}
+
 #[cfg(unix)]
 static SHUTDOWN_TESTS_ENABLED: bool = false;
 
@@ -1,4 +1,6 @@
 use amalthea::fixtures::dummy_frontend::ExecuteRequestOptions;
+use amalthea::wire::jupyter_message::Message;
+use ark::console::CONSOLE_INPUT_TOO_LONG;
 use ark_test::DummyArkFrontend;
 
 #[test]
@@ -29,6 +31,31 @@ fn test_stdin_basic_prompt() {
     assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
 }
 
+#[test]
+fn test_stdin_not_allowed() {
+    let frontend = DummyArkFrontend::lock();
+
+    // `allow_stdin` is `false` by default
+    let code = "readline('prompt>')";
+    frontend.send_execute_request(code, ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+
+    let input = frontend.recv_iopub_execute_input();
+    assert_eq!(input.code, code);
+
+    // No `input_request` is sent, R throws an error right away
+    assert!(frontend
+        .recv_iopub_execute_error()
+        .contains("The frontend doesn't allow input for this execution"));
+
+    frontend.recv_iopub_idle();
+
+    assert_eq!(
+        frontend.recv_shell_execute_reply_exception(),
+        input.execution_count
+    );
+}
+
 #[test]
 fn test_stdin_followed_by_an_expression_on_the_same_line() {
     let frontend = DummyArkFrontend::lock();
@@ -155,6 +182,94 @@ fn test_stdin_from_menu() {
     assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
 }
 
+#[test]
+fn test_stdin_line_too_long() {
+    let frontend = DummyArkFrontend::lock();
+
+    let options = ExecuteRequestOptions {
+        allow_stdin: true,
+        ..Default::default()
+    };
+
+    let code = "readline('prompt>')";
+    frontend.send_execute_request(code, options);
+    frontend.recv_iopub_busy();
+
+    let input = frontend.recv_iopub_execute_input();
+    assert_eq!(input.code, code);
+
+    let prompt = frontend.recv_stdin_input_request();
+    assert_eq!(prompt, String::from("prompt>"));
+
+    // A single line larger than R's 4096 bytes console buffer
+    frontend.send_stdin_input_reply("x".repeat(10000));
+
+    let exception = frontend.recv_iopub_execute_error_exception();
+    assert_eq!(exception.ename, CONSOLE_INPUT_TOO_LONG);
+    assert!(exception
+        .evalue
+        .contains("a single line exceeds R's internal console buffer size"));
+
+    frontend.recv_iopub_idle();
+
+    match frontend.recv_shell() {
+        Message::ExecuteReplyException(data) => {
+            assert_eq!(data.content.execution_count, input.execution_count);
+            assert_eq!(data.content.exception.ename, CONSOLE_INPUT_TOO_LONG);
+        },
+        other => panic!("Expected ExecuteReplyException, got {other:?}"),
+    }
+
+    // The classification doesn't leak into subsequent errors
+    frontend.send_execute_request("stop('foo')", ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    let input = frontend.recv_iopub_execute_input();
+    let exception = frontend.recv_iopub_execute_error_exception();
+    assert_eq!(exception.ename, "");
+    frontend.recv_iopub_idle();
+    assert_eq!(
+        frontend.recv_shell_execute_reply_exception(),
+        input.execution_count
+    );
+}
+
+#[test]
+fn test_stdin_line_too_long_caught() {
+    let frontend = DummyArkFrontend::lock();
+
+    let options = ExecuteRequestOptions {
+        allow_stdin: true,
+        ..Default::default()
+    };
+
+    let code = "invisible(tryCatch(readline('prompt>'), error = function(e) NULL))";
+    frontend.send_execute_request(code, options);
+    frontend.recv_iopub_busy();
+
+    let input = frontend.recv_iopub_execute_input();
+    assert_eq!(input.code, code);
+
+    let prompt = frontend.recv_stdin_input_request();
+    assert_eq!(prompt, String::from("prompt>"));
+
+    frontend.send_stdin_input_reply("x".repeat(10000));
+
+    frontend.recv_iopub_idle();
+    assert_eq!(frontend.recv_shell_execute_reply(), input.execution_count);
+
+    // The caught error isn't reported as the `ename` of the next error
+    frontend.send_execute_request("stop('foo')", ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    let input = frontend.recv_iopub_execute_input();
+    let exception = frontend.recv_iopub_execute_error_exception();
+    assert_eq!(exception.ename, "");
+    frontend.recv_iopub_idle();
+    assert_eq!(
+        frontend.recv_shell_execute_reply_exception(),
+        input.execution_count
+    );
+}
+
 #[test]
 fn test_stdin_readline_during_autoprint() {
     let frontend = DummyArkFrontend::lock();
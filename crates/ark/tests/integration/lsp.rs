@@ -62,3 +62,45 @@ fn test_rename_to_empty_name_returns_clean_error() {
 
     assert_eq!(message, "Identifier cannot be empty");
 }
+
+#[test]
+fn test_execute_command_clear_environment() {
+    let frontend = DummyArkFrontend::lock();
+    let mut lsp = frontend.start_lsp();
+
+    let commands = &lsp
+        .server_capabilities()
+        .execute_command_provider
+        .as_ref()
+        .unwrap()
+        .commands;
+    assert_eq!(commands, &vec![
+        String::from("ark.restartR"),
+        String::from("ark.clearEnvironment")
+    ]);
+
+    frontend.execute_request_invisibly("x <- 1; y <- 2");
+    frontend.execute_request("exists('x')", |result| assert_eq!(result, "[1] TRUE"));
+
+    let params = json!({
+        "command": "ark.clearEnvironment",
+        "arguments": [],
+    });
+    lsp.send_request("workspace/executeCommand", params);
+
+    frontend.execute_request("ls()", |result| assert_eq!(result, "character(0)"));
+}
+
+#[test]
+fn test_execute_command_unknown() {
+    let frontend = DummyArkFrontend::lock();
+    let mut lsp = frontend.start_lsp();
+
+    let params = json!({
+        "command": "ark.unknown",
+        "arguments": [],
+    });
+    let message = lsp.send_request_expect_error("workspace/executeCommand", params);
+
+    assert_eq!(message, "Unknown command 'ark.unknown'");
+}
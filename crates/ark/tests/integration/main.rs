@@ -10,6 +10,7 @@ mod dap_breakpoints_reconnect;
 mod dap_breakpoints_stepping;
 mod dap_breakpoints_symlink;
 mod dap_breakpoints_verification;
+mod dap_data_breakpoints;
 mod dap_evaluate;
 mod dap_exception_breakpoints;
 mod dap_notebook;
@@ -19,6 +20,7 @@ mod dap_vdoc;
 mod data_explorer;
 mod data_explorer_debug;
 mod data_explorer_integration;
+mod display;
 mod evaluate_code;
 mod help;
 mod kernel;
@@ -33,6 +35,7 @@ mod kernel_hooks_timestamp;
 mod kernel_hooks_view;
 mod kernel_notebook;
 mod kernel_notebook_data_explorer;
+mod kernel_quiet;
 mod kernel_r_profile;
 mod kernel_shutdown;
 mod kernel_srcref;
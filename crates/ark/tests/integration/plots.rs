@@ -3,6 +3,8 @@ use amalthea::wire::execute_request::ExecuteRequestPositron;
 use amalthea::wire::execute_request::JupyterPositronLocation;
 use amalthea::wire::execute_request::JupyterPositronPosition;
 use amalthea::wire::execute_request::JupyterPositronRange;
+use ark::plots::plot_ext_comm::ExportResult;
+use ark::plots::plot_ext_comm::PlotExtBackendReply;
 use ark_test::comm::RECV_TIMEOUT;
 use ark_test::DummyArkFrontend;
 use ark_test::SourceFile;
@@ -833,6 +835,115 @@ fn test_positron_simple_plot() {
     frontend.recv_shell_execute_reply();
 }
 
+/// Positron path: the plot comm reports the size requested with the
+/// `ark.plot.width` and `ark.plot.height` options as the intrinsic size.
+#[test]
+fn test_positron_plot_intrinsic_size() {
+    let frontend = DummyArkFrontend::lock();
+    frontend.open_ui_comm();
+
+    frontend.send_execute_request(
+        "options(ark.plot.width = 7, ark.plot.height = 5)\nplot(1:10)",
+        ExecuteRequestOptions::default(),
+    );
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_execute_input();
+    let open = frontend.recv_iopub_comm_open();
+    assert_eq!(open.target_name, "positron.plot");
+    frontend.recv_iopub_ui_prompt_state();
+    frontend.recv_iopub_idle();
+    frontend.recv_shell_execute_reply();
+
+    assert_eq!(
+        open.data["intrinsic_size"],
+        serde_json::json!({
+            "width": 7.0,
+            "height": 5.0,
+            "unit": "inches",
+            "source": "R",
+        })
+    );
+    assert_eq!(open.data["fixed_aspect_ratio"], false);
+    assert!(open.data["pre_render"].is_object());
+
+    frontend.send_execute_request(
+        "options(ark.plot.width = NULL, ark.plot.height = NULL)",
+        ExecuteRequestOptions::default(),
+    );
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_execute_input();
+    frontend.recv_iopub_ui_prompt_state();
+    frontend.recv_iopub_idle();
+    frontend.recv_shell_execute_reply();
+}
+
+/// Positron path: the `export` RPC writes the plot to the requested file.
+#[test]
+fn test_positron_plot_export() {
+    let frontend = DummyArkFrontend::lock();
+    frontend.open_ui_comm();
+
+    frontend.send_execute_request("plot(1:10)", ExecuteRequestOptions::default());
+    frontend.recv_iopub_busy();
+    frontend.recv_iopub_execute_input();
+    let open = frontend.recv_iopub_comm_open();
+    assert_eq!(open.target_name, "positron.plot");
+    frontend.recv_iopub_ui_prompt_state();
+    frontend.recv_iopub_idle();
+    frontend.recv_shell_execute_reply();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("plot.png");
+
+    let data = serde_json::json!({
+        "method": "export",
+        "params": {
+            "path": path.to_string_lossy(),
+            "size": { "width": 400, "height": 300 },
+            "pixel_ratio": 1.0,
+            "format": "png",
+        },
+        "id": "export-rpc",
+    });
+    frontend.send_shell_comm_msg(open.comm_id.clone(), data);
+    frontend.recv_iopub_busy();
+
+    let reply = frontend.recv_iopub_comm_msg();
+    assert_eq!(reply.comm_id, open.comm_id);
+    frontend.recv_iopub_idle();
+
+    let reply = serde_json::from_value::<PlotExtBackendReply>(reply.data).unwrap();
+    assert_eq!(
+        reply,
+        PlotExtBackendReply::ExportReply(ExportResult {
+            path: path.to_string_lossy().to_string(),
+        })
+    );
+
+    let metadata = std::fs::metadata(&path).unwrap();
+    assert!(metadata.len() > 0);
+
+    // A mismatched extension is rejected without writing anything
+    let bad_path = dir.path().join("plot.pdf");
+    let data = serde_json::json!({
+        "method": "export",
+        "params": {
+            "path": bad_path.to_string_lossy(),
+            "size": { "width": 400, "height": 300 },
+            "pixel_ratio": 1.0,
+            "format": "png",
+        },
+        "id": "export-rpc-error",
+    });
+    frontend.send_shell_comm_msg(open.comm_id.clone(), data);
+    frontend.recv_iopub_busy();
+
+    let reply = frontend.recv_iopub_comm_msg();
+    assert!(reply.data.get("error").is_some());
+    frontend.recv_iopub_idle();
+    assert!(!bad_path.exists());
+}
+
 /// Positron path: two plots in a single request each open their own comm.
 #[test]
 fn test_positron_multiple_plots() {
@@ -19,6 +19,9 @@ use ark::lsp::events::EVENTS;
 use ark::r_task::r_task;
 use ark::thread::RThreadSafe;
 use ark::variables::r_variables::RVariables;
+use ark::variables::variables_ext_comm::SearchParams;
+use ark::variables::variables_ext_comm::VariablesExtBackendReply;
+use ark::variables::variables_ext_comm::VariablesExtBackendRequest;
 use ark_test::dummy_jupyter_header;
 use ark_test::IOPubReceiverExt;
 use crossbeam::channel::bounded;
@@ -324,6 +327,98 @@ fn test_variables_list() {
     incoming_tx.send(CommMsg::Close).unwrap();
 }
 
+#[test]
+fn test_variables_search() {
+    let test_env = r_task(|| {
+        let env = harp::parse_eval_base(
+            "local({
+                env <- new.env(parent = emptyenv())
+                env$sales_2023 <- data.frame(x = 1)
+                env$sales_2024 <- 1:3
+                env$SalesTotal <- 10
+                env$costs <- data.frame(y = 2)
+                env
+            })",
+        )
+        .unwrap();
+        RThreadSafe::new(env)
+    });
+
+    let (iopub_tx, iopub_rx) = bounded::<IOPubMessage>(10);
+
+    let comm = CommSocket::new(
+        CommInitiator::FrontEnd,
+        String::from("test-variables-search-comm-id"),
+        String::from("positron.environment"),
+        iopub_tx.clone(),
+    );
+    let incoming_tx = comm.incoming_tx.clone();
+
+    r_task(|| {
+        let test_env = test_env.get().clone();
+        RVariables::start(test_env, comm.clone());
+    });
+
+    // Consume the initial refresh event
+    let _ = iopub_rx.recv_comm_msg();
+
+    let search = |query: &str, class: Option<&str>| -> Vec<String> {
+        let request = VariablesExtBackendRequest::Search(SearchParams {
+            query: String::from(query),
+            class: class.map(String::from),
+        });
+        let data = serde_json::to_value(request).unwrap();
+        let request_id = String::from("search-id-1237");
+        incoming_tx
+            .send(CommMsg::Rpc {
+                id: request_id.clone(),
+                parent_header: dummy_jupyter_header(),
+                data,
+            })
+            .unwrap();
+
+        let data = match iopub_rx.recv_comm_msg() {
+            CommMsg::Rpc {
+                id: reply_id, data, ..
+            } => {
+                assert_eq!(request_id, reply_id);
+                data
+            },
+            msg => panic!("Expected RPC message, got {msg:?}"),
+        };
+
+        let reply: VariablesExtBackendReply = serde_json::from_value(data).unwrap();
+        match reply {
+            VariablesExtBackendReply::SearchReply(list) => {
+                assert_eq!(list.length as usize, list.variables.len());
+                list.variables
+                    .into_iter()
+                    .map(|variable| variable.display_name)
+                    .collect()
+            },
+            _ => panic!("Expected search reply"),
+        }
+    };
+
+    // Substring matches are case-insensitive
+    assert_eq!(search("sales", None), vec![
+        "SalesTotal",
+        "sales_2023",
+        "sales_2024"
+    ]);
+    assert_eq!(search("2024", None), vec!["sales_2024"]);
+
+    // Fuzzy matching
+    assert_eq!(search("sltot", None), vec!["SalesTotal"]);
+    assert!(search("zzz", None).is_empty());
+
+    // Class filter
+    assert_eq!(search("s", Some("data.frame")), vec!["costs", "sales_2023"]);
+    assert_eq!(search("", Some("integer")), vec!["sales_2024"]);
+
+    incoming_tx.send(CommMsg::Close).unwrap();
+}
+
 #[test]
 fn test_query_table_summary() {
     // Create a dummy iopub channel to receive responses.
@@ -22,6 +22,7 @@ use dap::events::StoppedEventBody;
 use dap::requests::AttachRequestArguments;
 use dap::requests::Command;
 use dap::requests::ContinueArguments;
+use dap::requests::DataBreakpointInfoArguments;
 use dap::requests::DisconnectArguments;
 use dap::requests::EvaluateArguments;
 use dap::requests::InitializeArguments;
@@ -30,16 +31,19 @@ use dap::requests::PauseArguments;
 use dap::requests::Request;
 use dap::requests::ScopesArguments;
 use dap::requests::SetBreakpointsArguments;
+use dap::requests::SetDataBreakpointsArguments;
 use dap::requests::SetExceptionBreakpointsArguments;
 use dap::requests::StackTraceArguments;
 use dap::requests::StepInArguments;
 use dap::requests::VariablesArguments;
+use dap::responses::DataBreakpointInfoResponse;
 use dap::responses::Response;
 use dap::responses::ResponseBody;
 use dap::responses::ResponseMessage;
 use dap::responses::StackTraceResponse;
 use dap::types::Breakpoint;
 use dap::types::Capabilities;
+use dap::types::DataBreakpoint;
 use dap::types::Scope;
 use dap::types::Source;
 use dap::types::SourceBreakpoint;
@@ -375,6 +379,57 @@ impl DapClient {
         );
     }
 
+    /// Ask whether the variable `name` can be watched with a data breakpoint.
+    #[track_caller]
+    pub fn data_breakpoint_info(
+        &mut self,
+        name: &str,
+        variables_reference: Option<i64>,
+    ) -> DataBreakpointInfoResponse {
+        let seq = self
+            .send(Command::DataBreakpointInfo(DataBreakpointInfoArguments {
+                variables_reference,
+                name: name.to_string(),
+            }))
+            .unwrap();
+
+        let response = self.recv_response(seq);
+        assert!(response.success, "DataBreakpointInfo request failed");
+        match response.body {
+            Some(ResponseBody::DataBreakpointInfo(info)) => info,
+            other => panic!("Expected DataBreakpointInfo response body, got {:?}", other),
+        }
+    }
+
+    /// Replace all data breakpoints with watches on `data_ids`.
+    ///
+    /// Returns the breakpoints as reported by the server.
+    #[track_caller]
+    pub fn set_data_breakpoints(&mut self, data_ids: &[&str]) -> Vec<Breakpoint> {
+        let breakpoints = data_ids
+            .iter()
+            .map(|data_id| DataBreakpoint {
+                data_id: data_id.to_string(),
+                access_type: None,
+                condition: None,
+                hit_condition: None,
+            })
+            .collect();
+
+        let seq = self
+            .send(Command::SetDataBreakpoints(SetDataBreakpointsArguments {
+                breakpoints,
+            }))
+            .unwrap();
+
+        let response = self.recv_response(seq);
+        assert!(response.success, "SetDataBreakpoints request failed");
+        match response.body {
+            Some(ResponseBody::SetDataBreakpoints(body)) => body.breakpoints,
+            other => panic!("Expected SetDataBreakpoints response body, got {:?}", other),
+        }
+    }
+
     /// Send a pause request to break into the debugger.
     #[track_caller]
     pub fn pause(&mut self) {
@@ -855,6 +910,25 @@ impl DapClient {
         (text, description)
     }
 
+    /// Receive and assert the next message is a Stopped event with reason "data breakpoint".
+    ///
+    /// Returns the event description.
+    #[track_caller]
+    pub fn recv_stopped_data_breakpoint(&mut self) -> String {
+        let event = self.recv_event();
+        let Event::Stopped(body) = &event else {
+            panic!("Expected Stopped event, got {:?}", event);
+        };
+        assert!(
+            matches!(body.reason, StoppedEventReason::DataBreakpoint),
+            "Expected Stopped reason 'data breakpoint', got {:?}",
+            body.reason
+        );
+        assert_eq!(body.thread_id, Some(-1));
+        assert_eq!(body.all_threads_stopped, Some(true));
+        body.description.clone().unwrap_or_default()
+    }
+
     /// Receive and assert the next message is a Breakpoint event with verified=true.
     ///
     /// Returns the breakpoint from the event.
@@ -21,12 +21,14 @@ use amalthea::fixtures::dummy_frontend::DummyConnection;
 use amalthea::fixtures::dummy_frontend::DummyFrontend;
 use amalthea::fixtures::dummy_frontend::ExecuteRequestOptions;
 use amalthea::wire::comm_open::CommOpen;
+use amalthea::wire::exception::Exception;
 use amalthea::wire::execute_request::ExecuteRequestPositron;
 use amalthea::wire::execute_request::JupyterPositronLocation;
 use amalthea::wire::execute_request::JupyterPositronPosition;
 use amalthea::wire::execute_request::JupyterPositronRange;
 use amalthea::wire::jupyter_message::Message;
 use amalthea::wire::stream::Stream;
+use ark::console::BannerMode;
 use ark::console::SessionMode;
 use ark::repos::DefaultRepos;
 use regex::Regex;
@@ -130,6 +132,7 @@ struct DummyArkFrontendOptions {
     r_environ: bool,
     capture_streams: bool,
     session_mode: SessionMode,
+    banner_mode: BannerMode,
     default_repos: DefaultRepos,
     startup_file: Option<String>,
 }
@@ -744,6 +747,18 @@ impl DummyArkFrontend {
         }
     }
 
+    /// Receive from IOPub and assert ExecuteError message.
+    /// Automatically skips any Stream messages.
+    /// Returns the whole `exception`, including its `ename`.
+    #[track_caller]
+    pub fn recv_iopub_execute_error_exception(&self) -> Exception {
+        let msg = self.recv_iopub_next();
+        match msg {
+            Message::ExecuteError(data) => data.content.exception,
+            other => panic!("Expected ExecuteError, got {:?}", other),
+        }
+    }
+
     /// Receive from IOPub and assert DisplayData message.
     /// Automatically skips any Stream messages.
     #[track_caller]
@@ -792,6 +807,18 @@ impl DummyArkFrontend {
         }
     }
 
+    /// Receive from IOPub and assert ClearOutput message.
+    /// Automatically skips any Stream messages.
+    /// Returns the `wait` flag.
+    #[track_caller]
+    pub fn recv_iopub_clear_output(&self) -> bool {
+        let msg = self.recv_iopub_next();
+        match msg {
+            Message::ClearOutput(data) => data.content.wait,
+            other => panic!("Expected ClearOutput, got {:?}", other),
+        }
+    }
+
     /// Receive from IOPub and assert CommMsg message.
     /// Automatically skips any Stream messages.
     #[track_caller]
@@ -1975,8 +2002,11 @@ impl DummyArkFrontend {
                 r_args,
                 options.startup_file,
                 options.session_mode,
+                options.banner_mode,
                 options.capture_streams,
+                None,
                 options.default_repos,
+                false,
             );
         });
 
@@ -2328,6 +2358,46 @@ impl DerefMut for DummyArkFrontendStreamCapture {
     }
 }
 
+/// Wrapper around `DummyArkFrontend` that discards the R startup banner, as
+/// with `ark --quiet`
+pub struct DummyArkFrontendQuiet {
+    inner: DummyArkFrontend,
+}
+
+impl DummyArkFrontendQuiet {
+    /// NOTE: Only one `DummyArkFrontend` variant should call `lock()` within
+    /// a given process.
+    pub fn lock() -> Self {
+        Self::init();
+
+        Self {
+            inner: DummyArkFrontend::lock(),
+        }
+    }
+
+    fn init() {
+        let options = DummyArkFrontendOptions {
+            banner_mode: BannerMode::Quiet,
+            ..Default::default()
+        };
+        FRONTEND.get_or_init(|| Arc::new(Mutex::new(DummyArkFrontend::init(options))));
+    }
+}
+
+impl Deref for DummyArkFrontendQuiet {
+    type Target = DummyArkFrontend;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for DummyArkFrontendQuiet {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
 impl Default for DummyArkFrontendOptions {
     fn default() -> Self {
         Self {
@@ -2337,6 +2407,7 @@ impl Default for DummyArkFrontendOptions {
             r_environ: false,
             capture_streams: false,
             session_mode: SessionMode::Console,
+            banner_mode: BannerMode::Forward,
             default_repos: DefaultRepos::Auto,
             startup_file: None,
         }
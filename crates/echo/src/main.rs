@@ -16,6 +16,7 @@ use amalthea::comm::event::CommEvent;
 use amalthea::connection_file::ConnectionFile;
 use amalthea::kernel;
 use amalthea::kernel::StreamBehavior;
+use amalthea::kernel_spec::InterruptMode;
 use amalthea::kernel_spec::KernelSpec;
 use amalthea::registration_file::RegistrationFile;
 use amalthea::socket::iopub::IOPubMessage;
@@ -90,8 +91,10 @@ fn install_kernel_spec() {
                 language: String::from("Echo"),
                 display_name: String::from("Amalthea Echo"),
                 env: serde_json::Map::new(),
+                interrupt_mode: InterruptMode::Signal,
+                metadata: None,
             };
-            if let Err(err) = spec.install(String::from("amalthea")) {
+            if let Err(err) = spec.install(String::from("amalthea"), None) {
                 eprintln!("Failed to install Jupyter kernelspec. {}", err);
             } else {
                 println!("Successfully installed Jupyter kernelspec.")
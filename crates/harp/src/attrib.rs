@@ -1,6 +1,14 @@
+use std::borrow::Cow;
+use std::ffi::CStr;
+
 use libr::SEXP;
+use smallvec::smallvec;
+use smallvec::SmallVec;
 
+use crate::object::r_chr_get;
+use crate::object::r_dim;
 use crate::object::r_length;
+use crate::r::attrib_get;
 use crate::r::attrib_poke;
 use crate::r::attrib_poke_from;
 use crate::r::fn_body;
@@ -9,8 +17,108 @@ use crate::r::fn_formals;
 use crate::r::new_function;
 use crate::r_null;
 use crate::r_symbol;
+use crate::utils::r_str_as_utf8_unchecked;
+use crate::utils::r_typeof;
 use crate::RObject;
 
+/// The class vector of an R object, see [class_of()]
+pub enum Class {
+    /// The `class` attribute of a classed object
+    Explicit(RObject),
+
+    /// The implicit class of an unclassed object. Class vectors rarely have
+    /// more than two elements so they are stored inline.
+    Implicit(SmallVec<[&'static str; 2]>),
+}
+
+impl Class {
+    /// Iterates over class names. Names are borrowed from the class vector
+    /// unless they need to be translated to UTF-8.
+    pub fn iter(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Class::Explicit(class) => r_length(class.sexp) as usize,
+            Class::Implicit(class) => class.len(),
+        }
+    }
+
+    fn get(&self, i: usize) -> Cow<'_, str> {
+        match self {
+            // SAFETY: The class vector is protected by `self`
+            Class::Explicit(class) => unsafe {
+                r_str_as_utf8_unchecked(r_chr_get(class.sexp, i as isize))
+            },
+            Class::Implicit(class) => Cow::Borrowed(class[i]),
+        }
+    }
+}
+
+/// The class vector of `x`, as returned by `class()`.
+///
+/// Reads the `class` attribute directly rather than evaluating `class()`, and
+/// computes the implicit class of unclassed objects (e.g. `"numeric"`,
+/// `c("matrix", "array")`, or `"function"`).
+pub fn class_of(x: SEXP) -> Class {
+    let class = attrib_get(x, r_symbol!("class"));
+
+    if r_typeof(class) == libr::STRSXP {
+        return Class::Explicit(RObject::new(class));
+    }
+
+    Class::Implicit(implicit_class(x))
+}
+
+fn implicit_class(x: SEXP) -> SmallVec<[&'static str; 2]> {
+    // Only vectors can carry a `dim` attribute
+    let kind = r_typeof(x);
+    if !matches!(kind, libr::CHARSXP | libr::SYMSXP) {
+        match r_length(r_dim(x)) {
+            0 => {},
+            2 => return smallvec!["matrix", "array"],
+            _ => return smallvec!["array"],
+        }
+    }
+
+    let class = match kind {
+        libr::INTSXP => "integer",
+        libr::REALSXP => "numeric",
+        libr::CLOSXP | libr::BUILTINSXP | libr::SPECIALSXP => "function",
+        libr::SYMSXP => "name",
+        libr::LANGSXP => lang_class(x),
+        // R's type names are static strings
+        _ => unsafe { CStr::from_ptr(libr::Rf_type2char(kind)) }
+            .to_str()
+            .unwrap_or("unknown"),
+    };
+
+    smallvec![class]
+}
+
+// Calls to some syntactic functions have their own class, e.g. `"if"` or `"{"`
+fn lang_class(x: SEXP) -> &'static str {
+    let fun = unsafe { libr::CAR(x) };
+
+    if r_typeof(fun) != libr::SYMSXP {
+        return "call";
+    }
+
+    let name = unsafe { CStr::from_ptr(libr::R_CHAR(libr::PRINTNAME(fun))) };
+
+    match name.to_bytes() {
+        b"if" => "if",
+        b"while" => "while",
+        b"for" => "for",
+        b"=" => "=",
+        b"<-" => "<-",
+        b"(" => "(",
+        b"{" => "{",
+        _ => "call",
+    }
+}
+
 pub fn zap_srcref(x: SEXP) -> RObject {
     let x = RObject::new(x);
 
@@ -78,3 +186,99 @@ fn zap_srcref_attrib(x: SEXP) {
     x.set_attribute("srcref", r_null());
     x.set_attribute("wholeSrcref", r_null());
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crate::attrib::class_of;
+    use crate::parse_eval_base;
+    use crate::utils::r_inherits;
+
+    fn class_of_code(code: &str) -> Vec<String> {
+        let x = parse_eval_base(code).unwrap();
+        class_of(x.sexp)
+            .iter()
+            .map(|cls| cls.into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_class_of() {
+        crate::r_task(|| {
+            let cases = [
+                "1",
+                "1L",
+                "'a'",
+                "TRUE",
+                "NULL",
+                "list()",
+                "factor('a')",
+                "Sys.Date()",
+                "data.frame()",
+                "matrix(1:4, 2)",
+                "array(1:8, c(2, 2, 2))",
+                "array(1:2)",
+                "mean",
+                "sum",
+                "quote(x)",
+                "quote(f(x))",
+                "quote(if (x) y)",
+                "quote({ x })",
+                "quote(x <- 1)",
+                "structure(1, class = c('a', 'b', 'c'))",
+                "globalenv()",
+            ];
+
+            for code in cases {
+                let expected: Vec<String> = parse_eval_base(&format!("class({code})"))
+                    .unwrap()
+                    .try_into()
+                    .unwrap();
+                assert_eq!(class_of_code(code), expected);
+            }
+        })
+    }
+
+    /// Compares the class checks of the data explorer's `display_type()` done
+    /// with `r_inherits()` against the same checks on a single `class_of()`.
+    /// Run with `just test bench_class_of --run-ignored only` and trace
+    /// logging enabled to see the timings.
+    #[test]
+    #[ignore]
+    fn bench_class_of() {
+        crate::r_task(|| {
+            let x = parse_eval_base("structure(1, class = c('a', 'b', 'POSIXct'))").unwrap();
+            let checks = [
+                "haven_labelled",
+                "logical",
+                "integer",
+                "double",
+                "complex",
+                "numeric",
+                "character",
+                "factor",
+                "Date",
+                "POSIXct",
+            ];
+            let n = 100_000;
+
+            let start = Instant::now();
+            for _ in 0..n {
+                let found = checks.iter().position(|cls| r_inherits(x.sexp, cls));
+                assert_eq!(found, Some(9));
+            }
+            log::trace!("r_inherits(): {:?}", start.elapsed());
+
+            let start = Instant::now();
+            for _ in 0..n {
+                let class = class_of(x.sexp);
+                let found = checks
+                    .iter()
+                    .position(|name| class.iter().any(|cls| cls == *name));
+                assert_eq!(found, Some(9));
+            }
+            log::trace!("class_of(): {:?}", start.elapsed());
+        })
+    }
+}
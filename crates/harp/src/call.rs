@@ -12,6 +12,8 @@ use crate::exec::RFunctionExt;
 use crate::modules::HARP_ENV;
 use crate::object::RObject;
 use crate::r_symbol;
+use crate::symbol::RSymbol;
+use crate::utils::r_is_null;
 use crate::utils::r_typeof;
 
 pub struct RCall {
@@ -77,6 +79,109 @@ pub fn expr_deparse_collapse(x: SEXP) -> harp::Result<String> {
     Ok(x)
 }
 
+/// Match the arguments of `call` to the `formals` of a function, following
+/// the rules of `match.call()`: exact matching on names, then partial matching
+/// on names, then positional matching. Formals after `...` can only be matched
+/// exactly, and remaining arguments are captured by `...` if there is one.
+///
+/// Returns the arguments of `call` in order, paired with the name of the formal
+/// they are matched to (`"..."` for arguments captured by dots). Unlike R, this
+/// never fails so it can be used on incomplete code: arguments that can't be
+/// matched, e.g. ambiguous partial names or supernumerary positional arguments,
+/// are paired with `None`.
+pub fn match_call(call: SEXP, formals: SEXP) -> Vec<(Option<String>, SEXP)> {
+    let formals: Vec<String> = pairlist_tags(formals)
+        .into_iter()
+        .map(|(tag, _)| tag.unwrap_or_default())
+        .collect();
+    let arguments = pairlist_tags(unsafe { CDR(call) });
+
+    let dots = formals.iter().position(|formal| formal == "...");
+    let n_before_dots = dots.unwrap_or(formals.len());
+
+    let mut used = vec![false; formals.len()];
+    let mut matched: Vec<Option<usize>> = vec![None; arguments.len()];
+
+    // Exact matching
+    for (i, (tag, _)) in arguments.iter().enumerate() {
+        let Some(tag) = tag else {
+            continue;
+        };
+        let formal = formals
+            .iter()
+            .enumerate()
+            .position(|(j, formal)| !used[j] && formal != "..." && formal == tag);
+        if let Some(j) = formal {
+            used[j] = true;
+            matched[i] = Some(j);
+        }
+    }
+
+    // Partial matching, only for formals before `...`
+    for (i, (tag, _)) in arguments.iter().enumerate() {
+        let Some(tag) = tag else {
+            continue;
+        };
+        if matched[i].is_some() {
+            continue;
+        }
+
+        let candidates: Vec<usize> = (0..n_before_dots)
+            .filter(|&j| !used[j] && formals[j].starts_with(tag.as_str()))
+            .collect();
+
+        if let [j] = candidates[..] {
+            used[j] = true;
+            matched[i] = Some(j);
+        }
+    }
+
+    // Positional matching of unnamed arguments, up to `...`
+    let mut next = 0;
+    for (i, (tag, _)) in arguments.iter().enumerate() {
+        if tag.is_some() {
+            continue;
+        }
+        while next < n_before_dots && used[next] {
+            next += 1;
+        }
+        if next < n_before_dots {
+            used[next] = true;
+            matched[i] = Some(next);
+        }
+    }
+
+    // Everything left is captured by `...`, if any
+    arguments
+        .into_iter()
+        .zip(matched)
+        .map(|((_, value), j)| match j.or(dots) {
+            Some(j) => (Some(formals[j].clone()), value),
+            None => (None, value),
+        })
+        .collect()
+}
+
+/// Collect the tags and values of a pairlist. Empty tags are `None`.
+fn pairlist_tags(mut x: SEXP) -> Vec<(Option<String>, SEXP)> {
+    let mut out = Vec::new();
+
+    unsafe {
+        while !r_is_null(x) {
+            let tag = TAG(x);
+            let tag = if r_is_null(tag) {
+                None
+            } else {
+                Some(String::from(RSymbol::new_unchecked(tag))).filter(|tag| !tag.is_empty())
+            };
+            out.push((tag, CAR(x)));
+            x = CDR(x);
+        }
+    }
+
+    out
+}
+
 pub struct RArgument {
     pub name: String,
     pub value: RObject,
@@ -90,3 +195,98 @@ impl RArgument {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::call::match_call;
+    use crate::parse_eval_base;
+    use crate::parse_expr;
+    use crate::r_dbl_get;
+
+    fn matched_names(fun: &str, call: &str) -> Vec<Option<String>> {
+        let formals = parse_eval_base(&format!("formals({fun})")).unwrap();
+        let call = parse_expr(call).unwrap();
+        match_call(call.sexp, formals.sexp)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    fn names(names: &[Option<&str>]) -> Vec<Option<String>> {
+        names.iter().map(|name| name.map(String::from)).collect()
+    }
+
+    #[test]
+    fn test_match_call_exact() {
+        crate::r_task(|| {
+            let fun = "function(x, y, z) NULL";
+
+            assert_eq!(
+                matched_names(fun, "f(y = 1, 2, 3)"),
+                names(&[Some("y"), Some("x"), Some("z")])
+            );
+
+            // Supernumerary arguments are unmatched
+            assert_eq!(
+                matched_names(fun, "f(1, 2, 3, 4, w = 5)"),
+                names(&[Some("x"), Some("y"), Some("z"), None, None])
+            );
+
+            // Values are returned in call order
+            let formals = parse_eval_base(&format!("formals({fun})")).unwrap();
+            let call = parse_expr("f(z = 1, 2)").unwrap();
+            let matched = match_call(call.sexp, formals.sexp);
+            assert_eq!(r_dbl_get(matched[0].1, 0), 1.0);
+            assert_eq!(r_dbl_get(matched[1].1, 0), 2.0);
+        })
+    }
+
+    #[test]
+    fn test_match_call_partial() {
+        crate::r_task(|| {
+            let fun = "function(value, verbose, x) NULL";
+
+            assert_eq!(
+                matched_names(fun, "f(verb = 1, val = 2, 3)"),
+                names(&[Some("verbose"), Some("value"), Some("x")])
+            );
+
+            // Exact matches take precedence over partial matches
+            assert_eq!(
+                matched_names("function(value, val) NULL", "f(val = 1, 2)"),
+                names(&[Some("val"), Some("value")])
+            );
+
+            // Ambiguous partial matches are unmatched
+            assert_eq!(
+                matched_names(fun, "f(v = 1, 2)"),
+                names(&[None, Some("value")])
+            );
+        })
+    }
+
+    #[test]
+    fn test_match_call_dots() {
+        crate::r_task(|| {
+            let fun = "function(x, ..., na.rm = FALSE) NULL";
+
+            // Formals after `...` must be matched exactly
+            assert_eq!(
+                matched_names(fun, "f(1, 2, na = 3, na.rm = 4, z = 5)"),
+                names(&[
+                    Some("x"),
+                    Some("..."),
+                    Some("..."),
+                    Some("na.rm"),
+                    Some("...")
+                ])
+            );
+
+            // Named arguments can match before positional ones fill in
+            assert_eq!(
+                matched_names(fun, "f(1, x = 2)"),
+                names(&[Some("..."), Some("x")])
+            );
+        })
+    }
+}
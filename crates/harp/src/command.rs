@@ -85,6 +85,27 @@ pub fn r_home_setup() -> anyhow::Result<PathBuf> {
     }
 }
 
+/// Check that `r_home` is an R installation, i.e. a directory containing an R
+/// executable in `bin/` (see `r_executable()`)
+pub fn r_home_validate(r_home: &Path) -> anyhow::Result<()> {
+    if !r_home.is_dir() {
+        return Err(anyhow!(
+            "The R home '{}' is not a directory.",
+            r_home.display()
+        ));
+    }
+
+    if r_executable(r_home).is_none() {
+        return Err(anyhow!(
+            "The R home '{}' is not a valid R installation: no R executable found in '{}'.",
+            r_home.display(),
+            r_home.join("bin").display()
+        ));
+    }
+
+    Ok(())
+}
+
 /// Locate an R executable, given an `R_HOME` path
 ///
 /// - For unix, this look for `{R_HOME}/bin/R`
@@ -148,3 +169,31 @@ where
     // Unwrap: The `assert!` above ensures at least 1 program location is provided
     out.unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::command::r_home_validate;
+    use crate::sys::command::COMMAND_R_NAMES;
+
+    #[test]
+    fn test_r_home_validate() {
+        let dir = std::env::temp_dir().join(format!("harp-r-home-{}", std::process::id()));
+
+        // Doesn't exist
+        assert!(r_home_validate(&dir).is_err());
+
+        // Exists but isn't an R installation
+        std::fs::create_dir_all(dir.join("bin")).unwrap();
+        let err = r_home_validate(&dir).unwrap_err();
+        assert!(err.to_string().contains("not a valid R installation"));
+
+        // Has an R executable
+        std::fs::write(dir.join("bin").join(COMMAND_R_NAMES[0]), "").unwrap();
+        assert!(r_home_validate(&dir).is_ok());
+
+        // A file is not an R home
+        assert!(r_home_validate(&dir.join("bin").join(COMMAND_R_NAMES[0])).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
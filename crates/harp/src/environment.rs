@@ -11,9 +11,14 @@ use stdext::unwrap;
 pub use crate::environment_iter::*;
 use crate::exec::RFunction;
 use crate::exec::RFunctionExt;
+use crate::object::alloc_list;
+use crate::object::list_poke;
+use crate::object::list_set_names;
 use crate::object::RObject;
 use crate::r_env_binding_is_active;
+use crate::r_symbol;
 use crate::symbol::RSymbol;
+use crate::utils::r_env_is_pkg_env;
 
 #[derive(Clone, Debug)]
 pub struct Environment {
@@ -28,6 +33,16 @@ pub enum EnvironmentFilter {
     ExcludeHidden,
 }
 
+/// How [Environment::to_list()] handles bindings to unforced promises
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PromiseHandling {
+    /// Force the promise and include its value. Promises that fail to
+    /// evaluate are skipped.
+    Force,
+    /// Leave the promise alone and skip the binding.
+    Skip,
+}
+
 pub struct REnvs {
     pub global: SEXP,
     pub base: SEXP,
@@ -44,6 +59,56 @@ pub static R_ENVS: Lazy<REnvs> = Lazy::new(|| unsafe {
     }
 });
 
+/// Kind of an environment on the search path
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchPathKind {
+    /// The global environment, always first
+    Global,
+    /// An attached package, including `package:base` which is always last
+    Package,
+    /// Anything else attached with `attach()`, like lists, data frames, or
+    /// tool environments such as `tools:rstudio`
+    Attached,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchPathEntry {
+    /// Name as reported by `search()`, e.g. `package:stats`
+    pub name: String,
+    pub kind: SearchPathKind,
+    pub env: Environment,
+}
+
+impl REnvs {
+    /// Returns the environments of the search path, in the order of
+    /// `search()`
+    pub fn search_path(&self) -> Vec<SearchPathEntry> {
+        Environment::view(self.global)
+            .ancestors()
+            .map(|env| {
+                let sexp = env.inner.sexp;
+
+                let (name, kind) = if sexp == self.global {
+                    (String::from(".GlobalEnv"), SearchPathKind::Global)
+                } else if sexp == self.base {
+                    (String::from("package:base"), SearchPathKind::Package)
+                } else {
+                    let name = RObject::view(unsafe { Rf_getAttrib(sexp, r_symbol!("name")) });
+                    let name = String::try_from(name).unwrap_or_default();
+
+                    if r_env_is_pkg_env(sexp) {
+                        (name, SearchPathKind::Package)
+                    } else {
+                        (name, SearchPathKind::Attached)
+                    }
+                };
+
+                SearchPathEntry { name, kind, env }
+            })
+            .collect()
+    }
+}
+
 impl Environment {
     pub fn new(env: RObject) -> Self {
         Self::new_filtered(env, EnvironmentFilter::default())
@@ -107,10 +172,20 @@ impl Environment {
         EnvironmentIter::new(self.clone())
     }
 
+    /// Whether `name` is bound in this environment. Never forces promises or
+    /// calls active bindings.
     pub fn exists(&self, name: impl Into<RSymbol>) -> bool {
         unsafe { libr::R_existsVarInFrame(self.inner.sexp, name.into().sexp) != 0 }
     }
 
+    /// Whether `name` is bound in this environment or one of its ancestors,
+    /// like `exists(inherits = TRUE)`. Never forces promises or calls active
+    /// bindings.
+    pub fn exists_inherited(&self, name: impl Into<RSymbol>) -> bool {
+        let name = name.into();
+        self.ancestors().any(|env| env.exists(name))
+    }
+
     pub fn find(&self, name: impl Into<RSymbol>) -> harp::Result<SEXP> {
         let name = name.into();
         unsafe {
@@ -230,6 +305,50 @@ impl Environment {
             .param("all.names", true)
             .call_in(R_ENVS.base)
     }
+
+    /// Snapshot the bindings of the environment as a named list, sorted by
+    /// name and respecting the environment filter
+    ///
+    /// Unlike the environment, the list is not affected by later changes to
+    /// the bindings, which makes it suitable for transferring the contents to
+    /// another thread with `RThreadSafe`. Active bindings are skipped because
+    /// evaluating them might have side effects.
+    pub fn to_list(&self, promises: PromiseHandling) -> harp::Result<RObject> {
+        let mut names: Vec<String> = Vec::new();
+        let mut values: Vec<RObject> = Vec::new();
+
+        for binding in self.iter() {
+            let binding = binding?;
+
+            let value = match binding.value {
+                BindingValue::Active { .. } => continue,
+                BindingValue::Promise { promise } => match promises {
+                    PromiseHandling::Skip => continue,
+                    PromiseHandling::Force => {
+                        match crate::utils::r_promise_force_with_rollback(promise.sexp) {
+                            Ok(value) => value,
+                            Err(_) => continue,
+                        }
+                    },
+                },
+                BindingValue::Altrep { object, .. } => object,
+                BindingValue::Standard { object } => object,
+            };
+
+            names.push(binding.name.into());
+            values.push(value);
+        }
+
+        let list = RObject::new(alloc_list(values.len())?);
+        for (i, value) in values.iter().enumerate() {
+            list_poke(list.sexp, i as isize, value.sexp);
+        }
+
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        list_set_names(list.sexp, &names)?;
+
+        Ok(list)
+    }
 }
 
 impl From<Environment> for SEXP {
@@ -270,8 +389,10 @@ mod tests {
     use super::*;
     use crate::exec::RFunction;
     use crate::exec::RFunctionExt;
+    use crate::object::list_get;
     use crate::object::r_length;
     use crate::r_symbol;
+    use crate::utils::r_typeof;
 
     fn new_test_environment(hash: bool) -> Environment {
         let test_env = RFunction::new("base", "new.env")
@@ -294,6 +415,37 @@ mod tests {
         Environment::new(test_env)
     }
 
+    #[test]
+    fn test_search_path() {
+        crate::r_task(|| {
+            harp::parse_eval_global(r#"attach(list(x = 1), name = "harp:test")"#).unwrap();
+
+            let search_path = R_ENVS.search_path();
+
+            let names: Vec<&str> = search_path.iter().map(|x| x.name.as_str()).collect();
+            let expected: Vec<String> = harp::parse_eval_global("search()")
+                .unwrap()
+                .try_into()
+                .unwrap();
+            assert_eq!(names, expected);
+
+            assert_eq!(search_path[0].kind, SearchPathKind::Global);
+            assert_eq!(search_path.last().unwrap().name, "package:base");
+            assert_eq!(search_path.last().unwrap().kind, SearchPathKind::Package);
+
+            let attached = search_path.iter().find(|x| x.name == "harp:test").unwrap();
+            assert_eq!(attached.kind, SearchPathKind::Attached);
+            assert_eq!(attached.env.names(), vec!["x"]);
+
+            for entry in search_path.iter() {
+                let is_package = entry.name.starts_with("package:");
+                assert_eq!(entry.kind == SearchPathKind::Package, is_package);
+            }
+
+            harp::parse_eval_global(r#"detach("harp:test")"#).unwrap();
+        })
+    }
+
     #[test]
     fn test_environment_iter_count() {
         crate::r_task(|| {
@@ -363,6 +515,45 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_environment_to_list() {
+        crate::r_task(|| {
+            let env = harp::parse_eval_global(
+                "local({
+                    env <- new.env(parent = emptyenv())
+                    env$b <- 'two'
+                    env$a <- 1
+                    env$.hidden <- TRUE
+                    delayedAssign('lazy', 1 + 2, assign.env = env)
+                    makeActiveBinding('active', function() stop('ouch'), env)
+                    env
+                })",
+            )
+            .unwrap();
+
+            let env = Environment::new_filtered(env, EnvironmentFilter::ExcludeHidden);
+
+            let elt = |list: &RObject, i: isize| RObject::view(list_get(list.sexp, i));
+
+            let list = env.to_list(PromiseHandling::Skip).unwrap();
+            assert_eq!(list.names().unwrap(), vec![
+                Some(String::from("a")),
+                Some(String::from("b"))
+            ]);
+            assert_eq!(f64::try_from(elt(&list, 0)).unwrap(), 1.0);
+            assert_eq!(String::try_from(elt(&list, 1)).unwrap(), "two");
+
+            let list = env.to_list(PromiseHandling::Force).unwrap();
+            assert_eq!(list.length(), 3);
+            assert_eq!(list.names().unwrap()[2], Some(String::from("lazy")));
+            assert_eq!(f64::try_from(elt(&list, 2)).unwrap(), 3.0);
+
+            // The list is a snapshot unaffected by later changes
+            env.bind("a".into(), &RObject::from(2.0));
+            assert_eq!(f64::try_from(elt(&list, 0)).unwrap(), 1.0);
+        })
+    }
+
     #[test]
     fn test_filtered_env() {
         crate::r_task(|| {
@@ -376,4 +567,32 @@ mod tests {
             assert_eq!(env.iter().count(), 2);
         })
     }
+
+    #[test]
+    fn test_exists_does_not_force() {
+        crate::r_task(|| {
+            let parent = harp::parse_eval_base(
+                "local({ e <- new.env(); delayedAssign('x', stop('forced'), assign.env = e); e })",
+            )
+            .unwrap();
+            let parent = Environment::new(parent);
+            let child = Environment::new(
+                RFunction::new("base", "new.env")
+                    .param("parent", parent.inner.clone())
+                    .call()
+                    .unwrap(),
+            );
+
+            assert!(parent.exists("x"));
+            assert!(parent.exists_inherited("x"));
+            assert!(!child.exists("x"));
+            assert!(child.exists_inherited("x"));
+            assert!(!child.exists_inherited("does_not_exist"));
+
+            // The binding is still an unforced promise
+            let x = parent.find("x").unwrap();
+            assert_eq!(r_typeof(x), PROMSXP);
+            assert_eq!(unsafe { PRVALUE(x) }, unsafe { R_UnboundValue });
+        })
+    }
 }
@@ -9,6 +9,7 @@ use std::backtrace::Backtrace;
 use std::fmt;
 use std::str::Utf8Error;
 
+use crate::utils::r_sexptype_name;
 use crate::utils::r_type2char;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -48,12 +49,26 @@ pub enum Error {
     MissingBindingError {
         name: String,
     },
+    MissingNameError {
+        index: usize,
+    },
+    LockedEnvironmentError,
+    LockedBindingError {
+        name: String,
+    },
     OutOfMemory {
         size: usize,
     },
+    JsonLimitError {
+        message: String,
+    },
     InspectError {
         path: Vec<String>,
     },
+    SourceFileError {
+        path: String,
+        error: Box<Error>,
+    },
     StackUsageError {
         message: String,
         backtrace: Backtrace,
@@ -161,7 +176,7 @@ impl fmt::Display for Error {
                 let actual = r_type2char(*actual);
                 let expected = expected
                     .iter()
-                    .map(|value| r_type2char(*value))
+                    .map(|value| r_sexptype_name(*value))
                     .collect::<Vec<_>>()
                     .join(" | ");
                 write!(
@@ -207,6 +222,10 @@ impl fmt::Display for Error {
                 write!(f, "Error inspecting path {}", path.join(" / "))
             },
 
+            Error::SourceFileError { path, error } => {
+                write!(f, "Can't source file '{path}': {error}")
+            },
+
             Error::StackUsageError { .. } => {
                 write!(f, "C stack usage too close to the limit")
             },
@@ -223,12 +242,29 @@ impl fmt::Display for Error {
                 write!(f, "Can't find binding `{name}` in environment")
             },
 
+            Error::MissingNameError { index } => {
+                let index = index + 1;
+                write!(f, "Can't convert to map: element {index} must be named")
+            },
+
+            Error::LockedEnvironmentError => {
+                write!(f, "Can't modify environment: environment is locked")
+            },
+
+            Error::LockedBindingError { name } => {
+                write!(f, "Can't modify `{name}`: binding is locked")
+            },
+
             Error::OutOfMemory { size } => {
                 write!(
                     f,
                     "Can't allocate object of size {size} as the system is out of memory"
                 )
             },
+
+            Error::JsonLimitError { message } => {
+                write!(f, "Can't convert R object to JSON: {message}")
+            },
         }
     }
 }
@@ -14,6 +14,7 @@ use libr::*;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::call::r_expr_quote;
 use crate::call::RCall;
 use crate::environment::R_ENVS;
 use crate::error::Error;
@@ -39,6 +40,8 @@ where
 {
     res: &'a mut Option<harp::Result<T>>,
     closure: Option<F>,
+    /// Slot for the condition object caught by `try_catch()`, if requested
+    condition: Option<&'a mut Option<RObject>>,
 }
 
 impl RFunction {
@@ -93,6 +96,17 @@ impl RFunction {
         let user_call = self.call.build();
         try_eval(user_call.sexp, env)
     }
+
+    /// Like [RFunction::call_in()] but evaluates the call in a [r_sandbox()]
+    ///
+    /// Use this when calling back into R from a context that might have
+    /// condition handlers established, e.g. from a comm handler. Handlers of
+    /// the surrounding context don't see conditions signalled by the call,
+    /// and interrupts are suspended for its duration.
+    pub fn call_in_sandbox(&mut self, env: SEXP) -> Result<RObject> {
+        let user_call = self.call.build();
+        r_sandbox(|| try_eval(user_call.sexp, env))?
+    }
 }
 
 /// Evaluate R code in a context protected from errors and longjumps
@@ -110,6 +124,48 @@ pub fn try_eval(expr: SEXP, env: SEXP) -> crate::Result<RObject> {
     res
 }
 
+/// Evaluate `expr` in the context of a frame
+///
+/// With `enclosing` set to `None`, `expr` is evaluated directly in
+/// `frame_env`, so assignments create or update bindings in the frame. This
+/// is what "evaluate in the context of the frame" means in a debugger
+/// console.
+///
+/// With `Some(parent)`, `expr` is evaluated in a fresh child environment of
+/// `parent`, usually `frame_env` itself. Lookups still see the frame's
+/// bindings but local assignments are discarded with the child environment.
+/// Note that this doesn't make evaluation side effect free: `<<-`, `assign()`
+/// with an explicit environment, and mutation of reference objects like
+/// environments still reach the frame.
+pub fn eval_in_frame(expr: SEXP, frame_env: SEXP, enclosing: Option<SEXP>) -> Result<RObject> {
+    let Some(parent) = enclosing else {
+        return try_eval(expr, frame_env);
+    };
+
+    // Passing `size = 0` causes default size to be picked up
+    let env = RObject::new(unsafe { R_NewEnv(parent, 1, 0) });
+    try_eval(expr, env.sexp)
+}
+
+/// Evaluate `expr` in `env`, capturing what it prints
+///
+/// Returns the value of `expr` along with the text written to stdout during
+/// evaluation, e.g. by `print()` or `cat()`, with lines separated by `\n`.
+/// Output is diverted to a text connection with `sink()` and doesn't reach
+/// the console. The value itself is not printed. Messages and warnings go to
+/// stderr and are not captured.
+pub fn r_capture_output(expr: SEXP, env: SEXP) -> Result<(RObject, String)> {
+    let out = RFunction::from("capture_output")
+        .add(r_expr_quote(expr))
+        .param("env", env)
+        .call_in(unsafe { HARP_ENV.unwrap() })?;
+
+    let value = RObject::new(harp::list_get(out.sexp, 0));
+    let output: Vec<String> = RObject::view(harp::list_get(out.sexp, 1)).try_into()?;
+
+    Ok((value, output.join("\n")))
+}
+
 impl From<&str> for RFunction {
     fn from(function: &str) -> Self {
         RFunction::new("", function)
@@ -204,6 +260,14 @@ pub unsafe fn with_calling_error_handler(
 /// `try_catch()` context. It's fine to longjump (e.g. throw an R error) over
 /// a Rust stack as long as it doesn't contain destructors.
 pub fn try_catch<'env, F, T>(fun: F) -> harp::Result<T>
+where
+    F: FnOnce() -> T,
+    F: 'env,
+{
+    try_catch_impl(fun, None)
+}
+
+fn try_catch_impl<'env, F, T>(fun: F, condition: Option<&mut Option<RObject>>) -> harp::Result<T>
 where
     F: FnOnce() -> T,
     F: 'env,
@@ -215,6 +279,7 @@ where
     let mut callback_data = CallbackData {
         res: &mut res,
         closure: Some(fun),
+        condition,
     };
     let payload = &mut callback_data as *mut _ as *mut c_void;
 
@@ -241,6 +306,12 @@ where
     {
         let data: &mut CallbackData<F, T> = unsafe { &mut *(payload as *mut CallbackData<F, T>) };
 
+        // Keep the condition alive for the caller. The slot lives outside the
+        // context we're about to jump out of.
+        if let Some(condition) = data.condition.as_mut() {
+            **condition = Some(RObject::new(err));
+        }
+
         // Run in lambda to collect errors more easily
         if let Err(err) = (|| -> harp::Result<()> {
             let err: RObject = unsafe {
@@ -355,6 +426,7 @@ where
     let mut callback_data = CallbackData {
         res: &mut res,
         closure: Some(fun),
+        condition: None,
     };
     let payload = &mut callback_data as *mut _ as *mut c_void;
 
@@ -487,6 +559,37 @@ where
     result.unwrap()
 }
 
+/// Evaluate `expr` in `env` with an R restart established, like
+/// `withRestarts()`
+///
+/// If the restart called `name` is invoked while `expr` is evaluated, e.g. by
+/// a calling handler doing `invokeRestart(name, ...)`, the R stack is unwound
+/// back to the restart and `handler` is called with the list of arguments
+/// passed to `invokeRestart()`. Otherwise the value of `expr` is returned.
+///
+/// The restart is established and invoked on the R side, within a [try_eval()]
+/// sandbox, so invoking it never longjumps over Rust frames. R errors are
+/// caught as in [try_catch()].
+pub fn with_restart<H>(name: &str, expr: SEXP, env: SEXP, handler: H) -> harp::Result<RObject>
+where
+    H: FnOnce(RObject) -> harp::Result<RObject>,
+{
+    let out = RFunction::from("with_restart")
+        .param("name", name)
+        .add(r_expr_quote(expr))
+        .param("env", env)
+        .call_in(unsafe { HARP_ENV.unwrap() })?;
+
+    let invoked: bool = RObject::view(harp::list_get(out.sexp, 0)).try_into()?;
+    let value = RObject::new(harp::list_get(out.sexp, 1));
+
+    if invoked {
+        handler(value)
+    } else {
+        Ok(value)
+    }
+}
+
 pub fn r_peek_error_buffer() -> String {
     // SAFETY: Returns pointer to static memory buffer owned by R.
     let buffer = unsafe { R_curErrorBuf() };
@@ -513,6 +616,33 @@ where
     try_catch(f)
 }
 
+/// Error returned by [r_sandbox_catch()]
+#[derive(Debug)]
+pub struct ConditionError {
+    pub error: Error,
+
+    /// The condition object signalled by R. `None` if the failure wasn't
+    /// caused by an R error, e.g. an unexpected longjump.
+    pub condition: Option<RObject>,
+}
+
+/// Like [r_sandbox()] but also returns the R condition object on error
+///
+/// This lets callers inspect the fields of the condition, such as `message`,
+/// `call`, or any custom fields of classed conditions, without having to
+/// evaluate the code again.
+pub fn r_sandbox_catch<'env, F, T>(f: F) -> std::result::Result<T, ConditionError>
+where
+    F: FnOnce() -> T,
+    F: 'env,
+    T: 'env,
+{
+    let _scope = crate::raii::RLocalSandbox::new();
+
+    let mut condition = None;
+    try_catch_impl(f, Some(&mut condition)).map_err(|error| ConditionError { error, condition })
+}
+
 /// Unwrap Rust error and throw as R error
 ///
 /// Takes a lambda returning a `Result`. On error, converts the Rust error
@@ -708,6 +838,89 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_r_sandbox_catch() {
+        crate::r_task(|| unsafe {
+            let out = r_sandbox_catch(|| 42);
+            assert_match!(out, Ok(42));
+
+            let cnd = harp::parse_eval_base(
+                "structure(
+                    class = c('my_error', 'error', 'condition'),
+                    list(message = 'ouch', call = NULL, data = 42L)
+                )",
+            )
+            .unwrap();
+            let call = RFunction::new("base", "stop").add(cnd).call.build();
+
+            let out = r_sandbox_catch(|| {
+                Rf_eval(call.sexp, R_ENVS.base);
+            });
+
+            assert_match!(out, Err(ConditionError { error, condition }) => {
+                assert_match!(error, Error::TryCatchError(err) => {
+                    assert_eq!(err.message, "ouch");
+                });
+
+                let condition = condition.unwrap();
+                assert!(condition.inherits("my_error"));
+
+                let data = RFunction::new("base", "$")
+                    .add(condition)
+                    .add("data")
+                    .call()
+                    .unwrap();
+                assert_eq!(i32::try_from(data).unwrap(), 42);
+            });
+        })
+    }
+
+    #[test]
+    fn test_call_in_sandbox() {
+        crate::r_task(|| {
+            let out = RFunction::new("base", "identity")
+                .add(42)
+                .call_in_sandbox(R_ENVS.base)
+                .unwrap();
+            assert_eq!(i32::try_from(out).unwrap(), 42);
+
+            // An outer calling handler must not intercept the error signalled
+            // by the sandboxed call
+            struct Data {
+                out: Option<Result<RObject>>,
+                intercepted: bool,
+            }
+            let mut data = Data {
+                out: None,
+                intercepted: false,
+            };
+            let payload = &mut data as *mut Data as *mut c_void;
+
+            unsafe extern "C-unwind" fn body(payload: *mut c_void) -> SEXP {
+                let data = &mut *(payload as *mut Data);
+                data.out = Some(
+                    RFunction::new("base", "stop")
+                        .add("ouch")
+                        .call_in_sandbox(R_ENVS.base),
+                );
+                R_NilValue
+            }
+
+            unsafe extern "C-unwind" fn handler(_err: SEXP, payload: *mut c_void) -> SEXP {
+                let data = &mut *(payload as *mut Data);
+                data.intercepted = true;
+                R_NilValue
+            }
+
+            unsafe { with_calling_error_handler(body, payload, handler, payload) };
+
+            assert!(!data.intercepted);
+            assert_match!(data.out, Some(Err(Error::TryCatchError(err))) => {
+                assert_eq!(err.message, "ouch");
+            });
+        })
+    }
+
     #[test]
     fn test_top_level_exec() {
         crate::r_task(|| {
@@ -813,4 +1026,82 @@ mod tests {
             );
         })
     }
+
+    #[test]
+    fn test_with_restart() {
+        crate::r_task(|| {
+            // The expression returns normally
+            let expr = crate::parse_expr("1 + 1").unwrap();
+            let out = with_restart("harp_restart", expr.sexp, R_ENVS.global, |_| {
+                Ok(RObject::from("invoked"))
+            })
+            .unwrap();
+            assert_eq!(f64::try_from(out).unwrap(), 2.0);
+
+            // A calling handler invokes the restart with some arguments
+            let expr = crate::parse_expr(
+                r#"withCallingHandlers(
+                    warning("ouch"),
+                    warning = function(cnd) invokeRestart("harp_restart", conditionMessage(cnd))
+                )"#,
+            )
+            .unwrap();
+            let out = with_restart("harp_restart", expr.sexp, R_ENVS.global, |args| {
+                Ok(RObject::view(harp::list_get(args.sexp, 0)))
+            })
+            .unwrap();
+            assert_eq!(String::try_from(out).unwrap(), "ouch");
+
+            // Errors are caught
+            let expr = crate::parse_expr(r#"stop("ouch")"#).unwrap();
+            let out = with_restart("harp_restart", expr.sexp, R_ENVS.global, Ok);
+            assert_match!(out, Err(Error::TryCatchError(_)));
+        })
+    }
+
+    #[test]
+    fn test_eval_in_frame() {
+        crate::r_task(|| {
+            let frame = harp::parse_eval_base("local({ x <- 1; environment() })").unwrap();
+            let expr = crate::parse_expr("{ x <- x + 1; x }").unwrap();
+            let frame_x = || harp::parse_eval0("x", frame.clone()).unwrap();
+
+            // Evaluating in a child environment leaves the frame untouched
+            let out = eval_in_frame(expr.sexp, frame.sexp, Some(frame.sexp)).unwrap();
+            assert_eq!(f64::try_from(out).unwrap(), 2.0);
+            assert_eq!(f64::try_from(frame_x()).unwrap(), 1.0);
+
+            // Evaluating directly in the frame mutates it
+            let out = eval_in_frame(expr.sexp, frame.sexp, None).unwrap();
+            assert_eq!(f64::try_from(out).unwrap(), 2.0);
+            assert_eq!(f64::try_from(frame_x()).unwrap(), 2.0);
+        })
+    }
+
+    #[test]
+    fn test_r_capture_output() {
+        crate::r_task(|| {
+            let expr = crate::parse_expr(r#"{ cat("hi"); 42 }"#).unwrap();
+            let (value, output) = r_capture_output(expr.sexp, R_ENVS.global).unwrap();
+            assert_eq!(f64::try_from(value).unwrap(), 42.0);
+            assert_eq!(output, "hi");
+
+            // Lines are joined, and an invisible value is returned as is
+            let expr = crate::parse_expr("{ print(1:2); print('a'); invisible() }").unwrap();
+            let (value, output) = r_capture_output(expr.sexp, R_ENVS.global).unwrap();
+            assert!(value.sexp == unsafe { R_NilValue });
+            assert_eq!(output, "[1] 1 2\n[1] \"a\"");
+
+            // The sink is removed when evaluation fails
+            let expr = crate::parse_expr("{ cat('hi'); stop('oops') }").unwrap();
+            assert!(r_capture_output(expr.sexp, R_ENVS.global).is_err());
+
+            let sinks: i32 = RFunction::new("base", "sink.number")
+                .call()
+                .unwrap()
+                .try_into()
+                .unwrap();
+            assert_eq!(sinks, 0);
+        })
+    }
 }
@@ -7,9 +7,7 @@
 
 use std::cmp::min;
 
-use libr::R_NamesSymbol;
 use libr::Rf_allocVector;
-use libr::Rf_setAttrib;
 use libr::INTSXP;
 use libr::LGLSXP;
 use libr::NILSXP;
@@ -25,8 +23,69 @@ use serde_json::Number;
 use serde_json::Value;
 
 use crate::exec::r_check_stack;
+use crate::object::list_set_names;
 use crate::object::RObject;
 
+/// Limits applied when converting R objects to JSON with [to_json_value()].
+///
+/// Objects coming from user code can be arbitrarily deep or large. When
+/// serialising them for comm messages, these limits bound both the recursion
+/// depth (to protect the stack) and the total number of elements (to protect
+/// the size of the payload sent to the frontend).
+#[derive(Debug, Clone, Copy)]
+pub struct JsonLimits {
+    /// Maximum nesting depth of lists. An atomic vector at top level has a
+    /// depth of 0, a list of atomic vectors has a depth of 1, etc.
+    pub max_depth: usize,
+
+    /// Maximum number of elements across the whole object, counting each
+    /// element of atomic vectors as well as each list element.
+    pub max_elements: usize,
+}
+
+impl JsonLimits {
+    /// No limits. This is what the `TryFrom<RObject>` conversion uses.
+    pub fn unbounded() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_elements: usize::MAX,
+        }
+    }
+}
+
+impl Default for JsonLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 100,
+            max_elements: 1_000_000,
+        }
+    }
+}
+
+/// Convert an R object to a JSON value, failing with
+/// [crate::Error::JsonLimitError] if the object is nested deeper than
+/// `limits.max_depth` or contains more than `limits.max_elements` elements.
+///
+/// See the `TryFrom<RObject>` implementation for `Value` for details about
+/// how R objects map to JSON.
+pub fn to_json_value(obj: RObject, limits: &JsonLimits) -> crate::Result<Value> {
+    let mut count = 0;
+    to_json_value_rec(obj, limits, 0, &mut count)
+}
+
+fn check_json_elements(count: &mut usize, n: usize, limits: &JsonLimits) -> crate::Result<()> {
+    *count = count.saturating_add(n);
+
+    if *count > limits.max_elements {
+        let max = limits.max_elements;
+        return Err(crate::Error::JsonLimitError {
+            message: format!("Object has more than {max} elements"),
+        });
+    }
+
+    Ok(())
+}
+
 /// Conversion to JSON values from an R object.
 ///
 /// This is a recursive function that converts an R object to a JSON value. It
@@ -53,237 +112,267 @@ use crate::object::RObject;
 ///     {"a": 1, "b": true, "c": "applesauce"}
 /// - Named lists with duplicate keys have the values combined into an array
 ///   - e.g.: list(a = 1L, a = 2L, a = 3L) -> {"a": [1, 2, 3]}
+///
+/// This conversion is unbounded. Use [to_json_value()] to guard against
+/// deeply nested or very large objects.
 impl TryFrom<RObject> for Value {
     type Error = crate::error::Error;
     fn try_from(obj: RObject) -> Result<Self, Self::Error> {
-        // Since this function is recursive, check the stack before we proceed
-        // to make sure we aren't about to overflow it.
-        r_check_stack(None)?;
-
-        match obj.kind() {
-            // Nil becomes JSON null
-            NILSXP => Ok(Value::Null),
-
-            // Integers (INTSXP) ---
-            INTSXP => match obj.length() {
-                // A length of 0 becomes JSON null
-                0 => Ok(Value::Null),
-
-                // A single integer becomes a JSON number
-                1 => {
-                    let value = obj.to::<i32>()?;
-                    Ok(Value::Number(value.into()))
-                },
-
-                // Multiple integers become integer vectors
-                _ => {
-                    let mut arr = Vec::<Value>::with_capacity(obj.length().try_into().unwrap());
-                    let n = obj.length();
-                    for i in 0..n {
-                        arr.push(match obj.get_i32(i)? {
-                            Some(value) => value.into(),
-                            None => Value::Null,
-                        });
-                    }
-                    Ok(serde_json::Value::Array(arr))
-                },
+        to_json_value(obj, &JsonLimits::unbounded())
+    }
+}
+
+fn to_json_value_rec(
+    obj: RObject,
+    limits: &JsonLimits,
+    depth: usize,
+    count: &mut usize,
+) -> crate::Result<Value> {
+    // Since this function is recursive, check the stack before we proceed
+    // to make sure we aren't about to overflow it.
+    r_check_stack(None)?;
+
+    // Atomic vectors count one element per value, everything else
+    // (including the list container itself) counts as one element
+    let n = match obj.kind() {
+        INTSXP | REALSXP | LGLSXP | STRSXP => obj.length() as usize,
+        _ => 1,
+    };
+    check_json_elements(count, n, limits)?;
+
+    match obj.kind() {
+        // Nil becomes JSON null
+        NILSXP => Ok(Value::Null),
+
+        // Integers (INTSXP) ---
+        INTSXP => match obj.length() {
+            // A length of 0 becomes JSON null
+            0 => Ok(Value::Null),
+
+            // A single integer becomes a JSON number
+            1 => {
+                let value = obj.to::<i32>()?;
+                Ok(Value::Number(value.into()))
             },
 
-            // Real / floating point numbers (REALSXP) ---
-            REALSXP => match obj.length() {
-                // A length of 0 becomes JSON null
-                0 => Ok(Value::Null),
-
-                // A single value becomes a JSON number
-                1 => {
-                    let value = obj.to::<f64>()?;
-                    // There's no try/into implicit conversion from f64 to a
-                    // JSON number, but json! handles it.
-                    Ok(json!(value))
-                },
-
-                // Multiple values become a vector
-                _ => {
-                    let mut arr = Vec::<Value>::with_capacity(obj.length().try_into().unwrap());
-                    let n = obj.length();
-                    for i in 0..n {
-                        arr.push(match obj.get_f64(i)? {
-                            Some(value) => value.into(),
-                            None => Value::Null,
-                        });
-                    }
-                    Ok(serde_json::Value::Array(arr))
-                },
+            // Multiple integers become integer vectors
+            _ => {
+                let mut arr = Vec::<Value>::with_capacity(obj.length().try_into().unwrap());
+                let n = obj.length();
+                for i in 0..n {
+                    arr.push(match obj.get_i32(i)? {
+                        Some(value) => value.into(),
+                        None => Value::Null,
+                    });
+                }
+                Ok(serde_json::Value::Array(arr))
+            },
+        },
+
+        // Real / floating point numbers (REALSXP) ---
+        REALSXP => match obj.length() {
+            // A length of 0 becomes JSON null
+            0 => Ok(Value::Null),
+
+            // A single value becomes a JSON number
+            1 => {
+                let value = obj.to::<f64>()?;
+                // There's no try/into implicit conversion from f64 to a
+                // JSON number, but json! handles it.
+                Ok(json!(value))
             },
 
-            // Logical / Boolean values (LGLSXP) ---
-            LGLSXP => match obj.length() {
-                // A length of 0 becomes JSON null
-                0 => Ok(Value::Null),
-
-                // A single value becomes a JSON true/false value
-                1 => {
-                    let value = obj.to::<bool>()?;
-                    Ok(Value::Bool(value))
-                },
-
-                // Multiple values become a vector
-                _ => {
-                    let mut arr = Vec::<Value>::with_capacity(obj.length().try_into().unwrap());
-                    let n = obj.length();
-                    for i in 0..n {
-                        arr.push(match obj.get_bool(i)? {
-                            Some(value) => value.into(),
-                            None => Value::Null,
-                        });
-                    }
-                    Ok(serde_json::Value::Array(arr))
-                },
+            // Multiple values become a vector
+            _ => {
+                let mut arr = Vec::<Value>::with_capacity(obj.length().try_into().unwrap());
+                let n = obj.length();
+                for i in 0..n {
+                    arr.push(match obj.get_f64(i)? {
+                        Some(value) => value.into(),
+                        None => Value::Null,
+                    });
+                }
+                Ok(serde_json::Value::Array(arr))
             },
+        },
 
-            // Symbols (SYMSXP) ---
-            SYMSXP => {
-                // Try to convert the symbol to a string; this uses PRINTNAME
-                // under the hood
-                let val = Option::<String>::try_from(obj)?;
-                match val {
-                    Some(value) => Ok(Value::String(value)),
-                    None => Ok(Value::Null),
+        // Logical / Boolean values (LGLSXP) ---
+        LGLSXP => match obj.length() {
+            // A length of 0 becomes JSON null
+            0 => Ok(Value::Null),
+
+            // A single value becomes a JSON true/false value
+            1 => {
+                let value = obj.to::<bool>()?;
+                Ok(Value::Bool(value))
+            },
+
+            // Multiple values become a vector
+            _ => {
+                let mut arr = Vec::<Value>::with_capacity(obj.length().try_into().unwrap());
+                let n = obj.length();
+                for i in 0..n {
+                    arr.push(match obj.get_bool(i)? {
+                        Some(value) => value.into(),
+                        None => Value::Null,
+                    });
                 }
+                Ok(serde_json::Value::Array(arr))
             },
+        },
+
+        // Symbols (SYMSXP) ---
+        SYMSXP => {
+            // Try to convert the symbol to a string; this uses PRINTNAME
+            // under the hood
+            let val = Option::<String>::try_from(obj)?;
+            match val {
+                Some(value) => Ok(Value::String(value)),
+                None => Ok(Value::Null),
+            }
+        },
 
-            // Strings (STRSXP) ---
-            STRSXP => match obj.length() {
-                // A length of 0 becomes JSON null
-                0 => Ok(Value::Null),
-
-                // With exactly one value, convert to a string
-                1 => {
-                    let str = obj.to::<String>()?;
-                    Ok(Value::String(str))
-                },
-
-                // With multiple values, convert to a string array
-                _ => {
-                    let mut arr = Vec::<Value>::with_capacity(obj.length().try_into().unwrap());
-                    let n = obj.length();
-                    for i in 0..n {
-                        arr.push(match obj.get_string(i)? {
-                            Some(str) => Value::String(str),
-                            None => Value::Null,
-                        });
-                    }
-                    Ok(serde_json::Value::Array(arr))
-                },
+        // Strings (STRSXP) ---
+        STRSXP => match obj.length() {
+            // A length of 0 becomes JSON null
+            0 => Ok(Value::Null),
+
+            // With exactly one value, convert to a string
+            1 => {
+                let str = obj.to::<String>()?;
+                Ok(Value::String(str))
             },
 
-            // Vectors/lists (VECSXP) ---
-            VECSXP => match obj.length() {
-                // A length of 0 becomes JSON null
-                0 => Ok(Value::Null),
-
-                _ => {
-                    // See whether the object's values have names. We will try
-                    // to convert named values into a JSON object (map); unnamed
-                    // values become an array.
-                    let mut names = obj.names();
-
-                    // Check to see if all the names are empty. We want to treat
-                    // this identically to an unnamed list.
-                    let mut all_empty = true;
-                    if let Some(names) = &names {
-                        for name in names.iter().flatten() {
-                            if !name.is_empty() {
-                                all_empty = false;
-                                break;
-                            }
+            // With multiple values, convert to a string array
+            _ => {
+                let mut arr = Vec::<Value>::with_capacity(obj.length().try_into().unwrap());
+                let n = obj.length();
+                for i in 0..n {
+                    arr.push(match obj.get_string(i)? {
+                        Some(str) => Value::String(str),
+                        None => Value::Null,
+                    });
+                }
+                Ok(serde_json::Value::Array(arr))
+            },
+        },
+
+        // Vectors/lists (VECSXP) ---
+        VECSXP => match obj.length() {
+            // A length of 0 becomes JSON null
+            0 => Ok(Value::Null),
+
+            _ if depth >= limits.max_depth => {
+                let max = limits.max_depth;
+                Err(crate::Error::JsonLimitError {
+                    message: format!("Object is nested more than {max} levels deep"),
+                })
+            },
+
+            _ => {
+                // See whether the object's values have names. We will try
+                // to convert named values into a JSON object (map); unnamed
+                // values become an array.
+                let mut names = obj.names();
+
+                // Check to see if all the names are empty. We want to treat
+                // this identically to an unnamed list.
+                let mut all_empty = true;
+                if let Some(names) = &names {
+                    for name in names.iter().flatten() {
+                        if !name.is_empty() {
+                            all_empty = false;
+                            break;
                         }
                     }
-                    if all_empty {
-                        names = None;
-                    }
+                }
+                if all_empty {
+                    names = None;
+                }
 
-                    match names {
-                        Some(names) => {
-                            // The object's values have names. Create a map.
-                            let mut map = serde_json::Map::new();
-
-                            // There's no guarantee that we have the same number
-                            // of names as values, so be safe by taking the
-                            // minimum of the two.
-                            let n = min(obj.length(), names.len().try_into().unwrap());
-
-                            // Create the map. Note that `Value::try_from` below
-                            // will recurse into this function; this is how we
-                            // handle arbitrarily deep lists.
-                            //
-                            // Consider: do we need to guard against
-                            // self-referential lists?
-                            for i in 0..n {
-                                // Create the key-value pair to insert into the
-                                // object; treat a missing name as an empty
-                                // string.
-                                let key = match &names[i as usize] {
-                                    Some(name) => name.clone(),
-                                    None => String::new(),
-                                };
-                                let val = Value::try_from(obj.vector_elt(i)?)?;
-
-                                // Do we already have a value for this key? If
-                                // so, we need to convert the existing value to
-                                // an array and append the new value.
-                                match map.get_mut(&key) {
-                                    Some(existing) => match existing {
-                                        Value::Array(arr) => {
-                                            // The value is already an array; just
-                                            // append the new value.
-                                            arr.push(val);
-                                        },
-                                        _ => {
-                                            // The value is not an array; create
-                                            // one and append the new nad
-                                            // existing values.
-                                            let arr = vec![existing.clone(), val];
-                                            map.insert(key, Value::Array(arr));
-                                        },
+                match names {
+                    Some(names) => {
+                        // The object's values have names. Create a map.
+                        let mut map = serde_json::Map::new();
+
+                        // There's no guarantee that we have the same number
+                        // of names as values, so be safe by taking the
+                        // minimum of the two.
+                        let n = min(obj.length(), names.len().try_into().unwrap());
+
+                        // Create the map. Note that `to_json_value_rec()`
+                        // below will recurse into this function; this is
+                        // how we handle deep lists, up to `max_depth`.
+                        for i in 0..n {
+                            // Create the key-value pair to insert into the
+                            // object; treat a missing name as an empty
+                            // string.
+                            let key = match &names[i as usize] {
+                                Some(name) => name.clone(),
+                                None => String::new(),
+                            };
+                            let val =
+                                to_json_value_rec(obj.vector_elt(i)?, limits, depth + 1, count)?;
+
+                            // Do we already have a value for this key? If
+                            // so, we need to convert the existing value to
+                            // an array and append the new value.
+                            match map.get_mut(&key) {
+                                Some(existing) => match existing {
+                                    Value::Array(arr) => {
+                                        // The value is already an array; just
+                                        // append the new value.
+                                        arr.push(val);
                                     },
-                                    None => {
-                                        // We don't have a value for this key;
-                                        // just insert the new value.
-                                        map.insert(key, val);
+                                    _ => {
+                                        // The value is not an array; create
+                                        // one and append the new nad
+                                        // existing values.
+                                        let arr = vec![existing.clone(), val];
+                                        map.insert(key, Value::Array(arr));
                                     },
-                                }
-                            }
-                            Ok(serde_json::Value::Object(map))
-                        },
-                        None => {
-                            // The object's values don't have names. Create an array.
-                            let n = obj.length();
-                            let mut arr = Vec::<Value>::with_capacity(n.try_into().unwrap());
-
-                            // Create the array. Note that `Value::try_from`
-                            // below will recurse into this function to convert
-                            // each element of the list to a value. Just like R
-                            // list, JSON arrays can have elements of different
-                            // types.
-                            for i in 0..n {
-                                arr.push(Value::try_from(obj.vector_elt(i)?)?)
+                                },
+                                None => {
+                                    // We don't have a value for this key;
+                                    // just insert the new value.
+                                    map.insert(key, val);
+                                },
                             }
-                            Ok(serde_json::Value::Array(arr))
-                        },
-                    }
-                },
+                        }
+                        Ok(serde_json::Value::Object(map))
+                    },
+                    None => {
+                        // The object's values don't have names. Create an array.
+                        let n = obj.length();
+                        let mut arr = Vec::<Value>::with_capacity(n.try_into().unwrap());
+
+                        // Create the array. Note that `to_json_value_rec()`
+                        // below will recurse into this function to convert
+                        // each element of the list to a value. Just like R
+                        // list, JSON arrays can have elements of different
+                        // types.
+                        for i in 0..n {
+                            arr.push(to_json_value_rec(
+                                obj.vector_elt(i)?,
+                                limits,
+                                depth + 1,
+                                count,
+                            )?)
+                        }
+                        Ok(serde_json::Value::Array(arr))
+                    },
+                }
             },
+        },
 
-            // Everything else is not supported
-            _ => {
-                warn!(
-                    "Attempt to serialize unsupported R SEXP (type {})",
-                    obj.kind()
-                );
-                Ok(serde_json::Value::Null)
-            },
-        }
+        // Everything else is not supported
+        _ => {
+            warn!(
+                "Attempt to serialize unsupported R SEXP (type {})",
+                obj.kind()
+            );
+            Ok(serde_json::Value::Null)
+        },
     }
 }
 
@@ -334,13 +423,9 @@ impl TryFrom<Map<String, Value>> for RObject {
         let vals: Vec<Value> = map.values().cloned().collect();
         let list = RObject::try_from(vals)?;
 
-        // Convert the map's keys into a vector of strings, then set the names
-        // of the R object to that vector.
-        let keys: Vec<String> = map.keys().cloned().collect();
-        let names = RObject::from(keys);
-        unsafe {
-            Rf_setAttrib(list.sexp, R_NamesSymbol, names.sexp);
-        }
+        // Name the list after the map's keys
+        let keys: Vec<&str> = map.keys().map(String::as_str).collect();
+        list_set_names(list.sexp, &keys)?;
 
         Ok(list)
     }
@@ -560,4 +645,48 @@ mod tests {
             );
         })
     }
+
+    #[test]
+    fn test_json_limits_depth() {
+        crate::r_task(|| {
+            let obj = harp::parse_eval_global("x <- 1L; for (i in 1:50) x <- list(x); x").unwrap();
+
+            let limits = JsonLimits {
+                max_depth: 10,
+                ..Default::default()
+            };
+            let err = to_json_value(obj.clone(), &limits).unwrap_err();
+            assert!(matches!(err, crate::Error::JsonLimitError { .. }));
+
+            let limits = JsonLimits {
+                max_depth: 50,
+                ..Default::default()
+            };
+            assert!(to_json_value(obj, &limits).is_ok());
+        })
+    }
+
+    #[test]
+    fn test_json_limits_elements() {
+        crate::r_task(|| {
+            let limits = JsonLimits {
+                max_elements: 10,
+                ..Default::default()
+            };
+
+            let obj = harp::parse_eval_global("1:20").unwrap();
+            let err = to_json_value(obj, &limits).unwrap_err();
+            assert!(matches!(err, crate::Error::JsonLimitError { .. }));
+
+            let obj = harp::parse_eval_global("as.list(1:20)").unwrap();
+            let err = to_json_value(obj, &limits).unwrap_err();
+            assert!(matches!(err, crate::Error::JsonLimitError { .. }));
+
+            let obj = harp::parse_eval_global("list(a = 1:3, b = 4:6)").unwrap();
+            assert_eq!(
+                to_json_value(obj, &limits).unwrap(),
+                json!({"a": [1, 2, 3], "b": [4, 5, 6]})
+            );
+        })
+    }
 }
@@ -12,6 +12,11 @@ use std::os::raw::c_char;
 use std::os::raw::c_int;
 use std::sync::Once;
 
+use chrono::DateTime;
+use chrono::FixedOffset;
+use chrono::NaiveDate;
+use chrono::TimeDelta;
+use chrono::Utc;
 use libc::c_double;
 use libr::*;
 
@@ -21,6 +26,7 @@ use crate::exec::RFunctionExt;
 use crate::r_inherits;
 use crate::r_symbol;
 use crate::size::r_size;
+use crate::utils::assert_class;
 use crate::utils::r_assert_capacity;
 use crate::utils::r_assert_length;
 use crate::utils::r_assert_type;
@@ -178,6 +184,29 @@ pub fn list_poke(x: SEXP, i: isize, value: SEXP) {
     unsafe { SET_VECTOR_ELT(x, i, value) };
 }
 
+/// Sets the `names` attribute of the list `x`. Errors if `x` is not a list or
+/// if `names` doesn't have the same length as `x`.
+pub fn list_set_names(x: SEXP, names: &[&str]) -> crate::Result<()> {
+    r_assert_type(x, &[VECSXP])?;
+    r_assert_length(x, names.len())?;
+
+    unsafe {
+        let names_sexp = Rf_protect(Rf_allocVector(STRSXP, names.len() as isize));
+        for (i, name) in names.iter().enumerate() {
+            let name = Rf_mkCharLenCE(
+                name.as_ptr() as *mut c_char,
+                name.len() as i32,
+                cetype_t_CE_UTF8,
+            );
+            SET_STRING_ELT(names_sexp, i as isize, name);
+        }
+        Rf_setAttrib(x, R_NamesSymbol, names_sexp);
+        Rf_unprotect(1);
+    }
+
+    Ok(())
+}
+
 pub fn r_lgl_na() -> i32 {
     unsafe { R_NaInt }
 }
@@ -372,6 +401,38 @@ impl RObject {
         format!("{:p}", self.sexp)
     }
 
+    /// Whether both objects point to the same R object. Cheap, but objects
+    /// with different addresses may still be identical.
+    pub fn same_address(&self, other: &RObject) -> bool {
+        self.sexp == other.sexp
+    }
+
+    /// Whether both objects are equal according to R's `identical()` with
+    /// default arguments. Compares the addresses first since that's cheap.
+    pub fn identical(&self, other: &RObject) -> bool {
+        self.same_address(other) || is_identical(self.sexp, other.sexp)
+    }
+
+    /// Borrow the bytes of a raw vector without copying them.
+    ///
+    /// Returns `None` if the object is not a raw vector. The slice borrows
+    /// `self`, so the vector stays protected for as long as the slice is
+    /// alive, provided `self` was created with [RObject::new()] rather than
+    /// [RObject::view()].
+    pub fn as_raw_slice(&self) -> Option<&[u8]> {
+        if self.kind() != RAWSXP {
+            return None;
+        }
+
+        let len = self.length() as usize;
+        if len == 0 {
+            // `RAW()` doesn't return a valid pointer for empty vectors
+            return Some(&[]);
+        }
+
+        unsafe { Some(std::slice::from_raw_parts(RAW(self.sexp), len)) }
+    }
+
     /// String accessor; get a string value from a vector of strings.
     ///
     /// - `idx` - The index of the string to return.
@@ -471,6 +532,47 @@ impl RObject {
         }
     }
 
+    /// Converts a named vector or list to a `HashMap` keyed by element names.
+    ///
+    /// Each element is converted with `V::try_from()`. Elements of atomic
+    /// vectors are passed as vectors of length 1. Fails if an element is
+    /// unnamed. When a name is duplicated, the first element is kept.
+    pub fn try_into_hashmap<V>(&self) -> crate::Result<HashMap<String, V>>
+    where
+        V: TryFrom<RObject, Error = crate::Error>,
+    {
+        r_assert_type(self.sexp, &[
+            LGLSXP, INTSXP, REALSXP, CPLXSXP, STRSXP, RAWSXP, VECSXP,
+        ])?;
+
+        let n = self.length();
+        if n == 0 {
+            return Ok(HashMap::new());
+        }
+
+        let Some(names) = self.names() else {
+            return Err(Error::MissingNameError { index: 0 });
+        };
+
+        let list = RObject::new(unsafe { Rf_coerceVector(self.sexp, VECSXP) });
+        let mut map = HashMap::<String, V>::with_capacity(n as usize);
+
+        for (i, name) in names.into_iter().enumerate() {
+            let name = match name {
+                Some(name) if !name.is_empty() => name,
+                _ => return Err(Error::MissingNameError { index: i }),
+            };
+            if map.contains_key(&name) {
+                continue;
+            }
+
+            let value = V::try_from(RObject::new(list_get(list.sexp, i as isize)))?;
+            map.insert(name, value);
+        }
+
+        Ok(map)
+    }
+
     pub fn set_attribute(&self, name: &str, value: SEXP) {
         unsafe {
             Rf_protect(value);
@@ -672,6 +774,18 @@ impl From<Vec<String>> for RObject {
     }
 }
 
+impl From<&[u8]> for RObject {
+    fn from(values: &[u8]) -> Self {
+        unsafe {
+            let vector = RObject::from(Rf_allocVector(RAWSXP, values.len() as isize));
+            if !values.is_empty() {
+                std::ptr::copy_nonoverlapping(values.as_ptr(), RAW(vector.sexp), values.len());
+            }
+            vector
+        }
+    }
+}
+
 impl From<&Vec<i64>> for RObject {
     fn from(values: &Vec<i64>) -> Self {
         unsafe {
@@ -943,6 +1057,110 @@ impl TryFrom<RObject> for f64 {
     }
 }
 
+/// Converts a `Date` of length 1, stored as days since the epoch. Fractional
+/// days are rounded down like `format.Date()` does. `NA` and non-finite dates
+/// map to `None`.
+impl TryFrom<RObject> for Option<NaiveDate> {
+    type Error = crate::error::Error;
+    fn try_from(value: RObject) -> Result<Self, Self::Error> {
+        assert_class(*value, "Date")?;
+
+        let Some(days) = Option::<f64>::try_from(value)?.filter(|x| x.is_finite()) else {
+            return Ok(None);
+        };
+
+        let date = TimeDelta::try_days(days.floor() as i64)
+            .and_then(|delta| DateTime::UNIX_EPOCH.date_naive().checked_add_signed(delta));
+
+        match date {
+            Some(date) => Ok(Some(date)),
+            None => Err(crate::anyhow!("Date {days} is out of range")),
+        }
+    }
+}
+
+impl TryFrom<RObject> for NaiveDate {
+    type Error = crate::error::Error;
+    fn try_from(value: RObject) -> Result<Self, Self::Error> {
+        match Option::<NaiveDate>::try_from(value)? {
+            Some(x) => Ok(x),
+            None => Err(Error::MissingValueError),
+        }
+    }
+}
+
+/// Converts a `POSIXct` of length 1, stored as seconds since the epoch. The
+/// instant doesn't depend on the `tzone` attribute, which only affects how
+/// the date-time is displayed. `NA` and non-finite date-times map to `None`.
+impl TryFrom<RObject> for Option<DateTime<Utc>> {
+    type Error = crate::error::Error;
+    fn try_from(value: RObject) -> Result<Self, Self::Error> {
+        assert_class(*value, "POSIXct")?;
+
+        let Some(seconds) = Option::<f64>::try_from(value)?.filter(|x| x.is_finite()) else {
+            return Ok(None);
+        };
+
+        let whole = seconds.floor();
+        let nanos = ((seconds - whole) * 1e9).round().min(999_999_999.0) as u32;
+
+        match DateTime::from_timestamp(whole as i64, nanos) {
+            Some(x) => Ok(Some(x)),
+            None => Err(crate::anyhow!("Date-time {seconds} is out of range")),
+        }
+    }
+}
+
+impl TryFrom<RObject> for DateTime<Utc> {
+    type Error = crate::error::Error;
+    fn try_from(value: RObject) -> Result<Self, Self::Error> {
+        match Option::<DateTime<Utc>>::try_from(value)? {
+            Some(x) => Ok(x),
+            None => Err(Error::MissingValueError),
+        }
+    }
+}
+
+/// Converts a `POSIXct` of length 1 to a date-time carrying the UTC offset in
+/// effect at that instant in its `tzone` attribute, or in the session
+/// timezone if there is none. chrono has no timezone database so R resolves
+/// the offset, via the `gmtoff` field of `as.POSIXlt()`.
+impl TryFrom<RObject> for Option<DateTime<FixedOffset>> {
+    type Error = crate::error::Error;
+    fn try_from(value: RObject) -> Result<Self, Self::Error> {
+        let Some(utc) = Option::<DateTime<Utc>>::try_from(value.clone())? else {
+            return Ok(None);
+        };
+
+        let lt = RFunction::new("base", "as.POSIXlt").add(value).call()?;
+        let gmtoff = RFunction::new("base", ".subset2")
+            .add(lt)
+            .add("gmtoff")
+            .call()?;
+
+        let Some(gmtoff) = Option::<i32>::try_from(gmtoff)? else {
+            return Err(crate::anyhow!(
+                "Can't determine the UTC offset of date-time"
+            ));
+        };
+
+        match FixedOffset::east_opt(gmtoff) {
+            Some(offset) => Ok(Some(utc.with_timezone(&offset))),
+            None => Err(crate::anyhow!("UTC offset {gmtoff} is out of range")),
+        }
+    }
+}
+
+impl TryFrom<RObject> for DateTime<FixedOffset> {
+    type Error = crate::error::Error;
+    fn try_from(value: RObject) -> Result<Self, Self::Error> {
+        match Option::<DateTime<FixedOffset>>::try_from(value)? {
+            Some(x) => Ok(x),
+            None => Err(Error::MissingValueError),
+        }
+    }
+}
+
 // TODO(harp-try-from-robject-ref): Remove in favour of `&RObject`
 impl TryFrom<RObject> for Vec<i32> {
     type Error = crate::error::Error;
@@ -975,7 +1193,10 @@ impl TryFrom<&RObject> for Vec<f64> {
 impl TryFrom<&RObject> for Vec<u8> {
     type Error = crate::error::Error;
     fn try_from(value: &RObject) -> Result<Self, Self::Error> {
-        (&harp::vector::RawVector::try_from(value.sexp)?).try_into()
+        match value.as_raw_slice() {
+            Some(bytes) => Ok(bytes.to_vec()),
+            None => Err(Error::UnexpectedType(value.kind(), vec![RAWSXP])),
+        }
     }
 }
 
@@ -1088,6 +1309,37 @@ impl TryFrom<&Vec<i32>> for RObject {
     }
 }
 
+// Build atomic vectors from optional values, with `None` becoming `NA`
+impl FromIterator<Option<f64>> for RObject {
+    fn from_iter<I: IntoIterator<Item = Option<f64>>>(iter: I) -> Self {
+        crate::vector::NumericVector::from_iter(iter).into()
+    }
+}
+
+impl FromIterator<Option<i32>> for RObject {
+    fn from_iter<I: IntoIterator<Item = Option<i32>>>(iter: I) -> Self {
+        crate::vector::IntegerVector::from_iter(iter).into()
+    }
+}
+
+impl FromIterator<Option<bool>> for RObject {
+    fn from_iter<I: IntoIterator<Item = Option<bool>>>(iter: I) -> Self {
+        crate::vector::LogicalVector::from_iter(iter).into()
+    }
+}
+
+impl FromIterator<Option<String>> for RObject {
+    fn from_iter<I: IntoIterator<Item = Option<String>>>(iter: I) -> Self {
+        crate::vector::CharacterVector::from_iter(iter).into()
+    }
+}
+
+impl<'a> FromIterator<Option<&'a str>> for RObject {
+    fn from_iter<I: IntoIterator<Item = Option<&'a str>>>(iter: I) -> Self {
+        crate::vector::CharacterVector::from_iter(iter).into()
+    }
+}
+
 // Converts an R named character vector to a HashMap<String, String>
 // Note: Duplicated names are silently ignored, and only the first occurence is kept.
 impl TryFrom<RObject> for HashMap<String, String> {
@@ -1540,6 +1792,58 @@ mod tests {
         })
     }
 
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_tryfrom_RObject_NaiveDate() {
+        crate::r_task(|| {
+            let x = parse_eval_global("as.Date('2024-02-29')").unwrap();
+            assert_eq!(
+                NaiveDate::try_from(x).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+            );
+
+            // Before the epoch, with a fractional day that's rounded down
+            let x = parse_eval_global("structure(-0.5, class = 'Date')").unwrap();
+            assert_eq!(
+                NaiveDate::try_from(x).unwrap(),
+                NaiveDate::from_ymd_opt(1969, 12, 31).unwrap()
+            );
+
+            let x = parse_eval_global("as.Date(NA)").unwrap();
+            assert_match!(Option::<NaiveDate>::try_from(x.clone()), Ok(None) => {});
+            assert_match!(NaiveDate::try_from(x), Err(Error::MissingValueError) => {});
+
+            let x = parse_eval_global("19000").unwrap();
+            assert_match!(NaiveDate::try_from(x), Err(Error::UnexpectedClass(..)) => {});
+        })
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_tryfrom_RObject_DateTime() {
+        crate::r_task(|| {
+            let x =
+                parse_eval_global("as.POSIXct('2024-07-01 12:30:15.25', tz = 'America/New_York')")
+                    .unwrap();
+
+            let utc = DateTime::<Utc>::try_from(x.clone()).unwrap();
+            assert_eq!(utc.to_rfc3339(), "2024-07-01T16:30:15.250+00:00");
+
+            // The offset is the one in effect in the `tzone` attribute
+            let local = DateTime::<FixedOffset>::try_from(x).unwrap();
+            assert_eq!(local.to_rfc3339(), "2024-07-01T12:30:15.250-04:00");
+            assert_eq!(local, utc);
+
+            let x = parse_eval_global("as.POSIXct(NA)").unwrap();
+            assert_match!(Option::<DateTime<Utc>>::try_from(x.clone()), Ok(None) => {});
+            assert_match!(Option::<DateTime<FixedOffset>>::try_from(x.clone()), Ok(None) => {});
+            assert_match!(DateTime::<Utc>::try_from(x), Err(Error::MissingValueError) => {});
+
+            let x = parse_eval_global("as.Date('2024-02-29')").unwrap();
+            assert_match!(DateTime::<Utc>::try_from(x), Err(Error::UnexpectedClass(..)) => {});
+        })
+    }
+
     #[test]
     #[allow(non_snake_case)]
     fn test_tryfrom_RObject_Option_String() {
@@ -1653,6 +1957,36 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_try_into_hashmap() {
+        crate::r_task(|| {
+            let v = harp::parse_eval_global("c(a = '1', b = '2')").unwrap();
+            let out: HashMap<String, String> = v.try_into_hashmap().unwrap();
+            assert_eq!(out.len(), 2);
+            assert_eq!(out["a"], "1");
+            assert_eq!(out["b"], "2");
+
+            // Lists are converted element-wise, and the first duplicate is kept
+            let v = harp::parse_eval_global("list(x = 1L, y = 2L, x = 3L)").unwrap();
+            let out: HashMap<String, i32> = v.try_into_hashmap().unwrap();
+            assert_eq!(out.len(), 2);
+            assert_eq!(out["x"], 1);
+            assert_eq!(out["y"], 2);
+
+            let v = harp::parse_eval_global("character()").unwrap();
+            let out: HashMap<String, String> = v.try_into_hashmap().unwrap();
+            assert!(out.is_empty());
+
+            let v = harp::parse_eval_global("c(a = '1', '2')").unwrap();
+            let err = v.try_into_hashmap::<String>().unwrap_err();
+            assert!(matches!(err, Error::MissingNameError { index: 1 }));
+
+            let v = harp::parse_eval_global("c('1', '2')").unwrap();
+            let err = v.try_into_hashmap::<String>().unwrap_err();
+            assert!(matches!(err, Error::MissingNameError { index: 0 }));
+        })
+    }
+
     #[test]
     #[allow(non_snake_case)]
     fn test_tryfrom_RObject_Vec_Option_String() {
@@ -1769,6 +2103,35 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_list_set_names() {
+        crate::r_task(|| {
+            let list = RObject::try_from(vec![
+                RObject::from(1),
+                RObject::from("two"),
+                RObject::from(true),
+            ])
+            .unwrap();
+
+            list_set_names(list.sexp, &["a", "b", "c"]).unwrap();
+            let names: Vec<String> = list.get_attribute_names().unwrap().try_into().unwrap();
+            assert_eq!(names, vec!["a", "b", "c"]);
+
+            // Length mismatch
+            assert_match!(
+                list_set_names(list.sexp, &["a", "b"]),
+                Err(Error::UnexpectedLength(3, 2)) => {}
+            );
+
+            // Not a list
+            let x = RObject::from(1);
+            assert_match!(
+                list_set_names(x.sexp, &["a"]),
+                Err(Error::UnexpectedType(..)) => {}
+            );
+        })
+    }
+
     #[test]
     fn test_is_null() {
         crate::r_task(|| {
@@ -1779,4 +2142,68 @@ mod tests {
             assert!(!x.is_null());
         })
     }
+
+    #[test]
+    fn test_identical() {
+        crate::r_task(|| {
+            let x = parse_eval_global("list(a = 1, b = list('c'))").unwrap();
+            let y = parse_eval_global("list(a = 1, b = list('c'))").unwrap();
+            assert!(!x.same_address(&y));
+            assert!(x.identical(&y));
+            assert!(x.identical(&x));
+
+            let z = parse_eval_global("list(a = 1, b = list('d'))").unwrap();
+            assert!(!x.identical(&z));
+        })
+    }
+
+    #[test]
+    fn test_raw_slice_round_trip() {
+        crate::r_task(|| {
+            let bytes: Vec<u8> = (0..=255).collect();
+
+            let x = RObject::from(bytes.as_slice());
+            assert_eq!(x.kind(), RAWSXP);
+            assert_eq!(x.as_raw_slice(), Some(bytes.as_slice()));
+            assert_eq!(Vec::<u8>::try_from(&x).unwrap(), bytes);
+
+            let x = parse_eval_global("as.raw(c(1, 2, 255))").unwrap();
+            assert_eq!(x.as_raw_slice(), Some([1u8, 2, 255].as_slice()));
+
+            let x = RObject::from([].as_slice());
+            assert_eq!(x.as_raw_slice(), Some([].as_slice()));
+
+            let x = RObject::from(1);
+            assert_eq!(x.as_raw_slice(), None);
+        })
+    }
+
+    #[test]
+    fn test_from_iter_option() {
+        crate::r_task(|| {
+            let x: RObject = [Some(1.5), None, Some(3.0)].into_iter().collect();
+            assert!(x.identical(&parse_eval_global("c(1.5, NA, 3)").unwrap()));
+
+            // Iterators of unknown length are fine
+            let x: RObject = (1..=5)
+                .filter(|x| x % 2 == 1)
+                .map(|x| if x == 3 { None } else { Some(x) })
+                .collect();
+            assert!(x.identical(&parse_eval_global("c(1L, NA, 5L)").unwrap()));
+
+            let x: RObject = [None, Some(true), Some(false)].into_iter().collect();
+            assert!(x.identical(&parse_eval_global("c(NA, TRUE, FALSE)").unwrap()));
+
+            let x: RObject = [Some("a"), None, Some("ü")].into_iter().collect();
+            assert!(x.identical(&parse_eval_global("c('a', NA, '\\u00fc')").unwrap()));
+            assert_eq!(Vec::<Option<String>>::try_from(x).unwrap(), vec![
+                Some(String::from("a")),
+                None,
+                Some(String::from("ü"))
+            ]);
+
+            let x: RObject = std::iter::empty::<Option<String>>().collect();
+            assert!(x.identical(&parse_eval_global("character()").unwrap()));
+        })
+    }
 }
@@ -14,14 +14,40 @@ use crate::environment::R_ENVS;
 use crate::exec::RFunction;
 use crate::exec::RFunctionExt;
 use crate::modules::HARP_ENV;
+use crate::object::r_length;
 use crate::object::RObject;
+use crate::parser::srcref::SrcRef;
 use crate::protect::RProtect;
 use crate::r_lang;
 use crate::r_symbol;
+use crate::utils::r_deparse_truncated;
+use crate::utils::r_is_null;
 use crate::utils::r_typeof;
 use crate::vector::integer_vector::IntegerVector;
 use crate::vector::Vector;
 
+/// Maximum length of the deparsed call of a traceback frame
+const TRACEBACK_CALL_MAX_LENGTH: usize = 1_000;
+
+/// A frame of an R traceback.
+#[derive(Debug)]
+pub struct TracebackFrame {
+    /// The deparsed call, collapsed to a single line and truncated to
+    /// `TRACEBACK_CALL_MAX_LENGTH` characters.
+    pub call: String,
+
+    /// The file containing the call, if the call carries a srcref pointing
+    /// to an actual file (rather than e.g. code typed at the console).
+    pub file: Option<String>,
+
+    /// The 0-based line of the call in `file`.
+    pub line: Option<u32>,
+
+    /// The evaluation environment of the frame, if the frame was still on
+    /// the stack when the traceback was captured.
+    pub env: Option<RObject>,
+}
+
 // Globals
 static SESSION_INIT: Once = Once::new();
 static mut NFRAME_CALL: Option<SEXP> = None;
@@ -133,6 +159,110 @@ pub fn r_traceback() -> Vec<String> {
     }
 }
 
+/// Structured version of a traceback.
+///
+/// - `calls` is a list or pairlist of calls, typically from `sys.calls()`
+///   (oldest first) or `.Traceback` (most recent first). Frames are returned
+///   in the same order. Source locations are read from the `srcref`
+///   attribute of each call.
+/// - `frames` is an optional list or pairlist of the corresponding
+///   evaluation environments, typically from `sys.frames()`.
+pub fn r_trace_back_frames(
+    calls: SEXP,
+    frames: Option<SEXP>,
+) -> crate::Result<Vec<TracebackFrame>> {
+    crate::r_assert_type(calls, &[VECSXP, LISTSXP, NILSXP])?;
+    let calls = list_elements(calls);
+
+    let frames = match frames {
+        Some(frames) => {
+            crate::r_assert_type(frames, &[VECSXP, LISTSXP, NILSXP])?;
+            crate::r_assert_length(frames, calls.len())?;
+            Some(list_elements(frames))
+        },
+        None => None,
+    };
+
+    let mut out = Vec::with_capacity(calls.len());
+
+    for (i, call) in calls.into_iter().enumerate() {
+        let (file, line) = match call_location(&call) {
+            Some((file, line)) => (Some(file), Some(line)),
+            None => (None, None),
+        };
+
+        let env = frames.as_ref().map(|frames| RObject::new(frames[i].sexp));
+
+        out.push(TracebackFrame {
+            call: r_deparse_truncated(call.sexp, TRACEBACK_CALL_MAX_LENGTH),
+            file,
+            line,
+            env,
+        });
+    }
+
+    Ok(out)
+}
+
+/// The elements of a list or pairlist. `sys.calls()` and `sys.frames()`
+/// return pairlists while `.Traceback` is a list.
+fn list_elements(x: SEXP) -> Vec<RObject> {
+    if r_typeof(x) == VECSXP {
+        return (0..r_length(x))
+            .map(|i| RObject::view(harp::list_get(x, i)))
+            .collect();
+    }
+
+    let mut out = Vec::new();
+    let mut node = x;
+    while !r_is_null(node) {
+        out.push(RObject::view(unsafe { CAR(node) }));
+        node = unsafe { CDR(node) };
+    }
+    out
+}
+
+/// The file and 0-based line of a call, from its `srcref` attribute
+fn call_location(call: &RObject) -> Option<(String, u32)> {
+    let srcref = SrcRef::try_from(call.get_attribute("srcref")?).ok()?;
+    let file = srcref.srcfile().ok()?.filename().ok()?;
+
+    // These are not actual files: `""` for code typed at the console and
+    // `"<text>"` for `parse(text = )`d code
+    if file.is_empty() || file == "<text>" {
+        return None;
+    }
+
+    Some((file, srcref.line.start))
+}
+
+/// Values of a set of R options, captured with [r_options_snapshot()] and
+/// put back with [r_options_restore()].
+///
+/// Unlike `RLocalOption`, which sets a single option for the duration of a
+/// scope, a snapshot leaves the options untouched until explicitly restored.
+/// Options that were unset when the snapshot was taken are unset on restore.
+pub struct ROptionsSnapshot {
+    options: Vec<(String, RObject)>,
+}
+
+/// Capture the current values of the options `names`.
+pub fn r_options_snapshot(names: &[&str]) -> ROptionsSnapshot {
+    let options = names
+        .iter()
+        .map(|name| (name.to_string(), crate::get_option(name)))
+        .collect();
+
+    ROptionsSnapshot { options }
+}
+
+/// Reset the options of `snapshot` to their captured values.
+pub fn r_options_restore(snapshot: &ROptionsSnapshot) {
+    for (name, value) in snapshot.options.iter() {
+        crate::r_poke_option(r_symbol!(name), value.sexp);
+    }
+}
+
 pub fn r_format_traceback(calls: RObject) -> crate::Result<RObject> {
     RFunction::new("", "format_traceback")
         .add(calls)
@@ -170,3 +300,99 @@ fn init_interface() {
         CURRENT_FUNCTION_CALL = Some(current_function_call);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_eval_base;
+    use crate::session::r_options_restore;
+    use crate::session::r_options_snapshot;
+    use crate::session::r_trace_back_frames;
+
+    #[test]
+    fn test_trace_back_frames() {
+        crate::r_task(|| {
+            // Capture the stack from a calling handler of an error raised
+            // by nested calls to functions sourced from a file
+            let captured = parse_eval_base(
+                r#"local({
+                    lines <- c(
+                        "f <- function() {",
+                        "  g()",
+                        "}",
+                        "g <- function() {",
+                        "  stop('boom')",
+                        "}"
+                    )
+                    srcfile <- srcfilecopy("test.R", lines)
+                    exprs <- parse(text = lines, srcfile = srcfile, keep.source = TRUE)
+                    env <- new.env()
+                    for (expr in exprs) eval(expr, env)
+
+                    out <- NULL
+                    tryCatch(
+                        withCallingHandlers(
+                            env$f(),
+                            error = function(cnd) out <<- list(sys.calls(), sys.frames())
+                        ),
+                        error = function(cnd) NULL
+                    )
+                    out
+                })"#,
+            )
+            .unwrap();
+
+            let calls = crate::list_get(captured.sexp, 0);
+            let envs = crate::list_get(captured.sexp, 1);
+            let frames = r_trace_back_frames(calls, Some(envs)).unwrap();
+
+            let f = frames.iter().find(|frame| frame.call == "env$f()").unwrap();
+            assert_eq!(f.file, None);
+            assert_eq!(f.line, None);
+
+            let g = frames.iter().find(|frame| frame.call == "g()").unwrap();
+            assert_eq!(g.file.as_deref(), Some("test.R"));
+            assert_eq!(g.line, Some(1));
+            assert!(g.env.is_some());
+
+            let stop = frames
+                .iter()
+                .find(|frame| frame.call == "stop(\"boom\")")
+                .unwrap();
+            assert_eq!(stop.file.as_deref(), Some("test.R"));
+            assert_eq!(stop.line, Some(4));
+
+            // The nested calls are ordered from oldest to most recent
+            let position = |call: &str| frames.iter().position(|frame| frame.call == call);
+            assert!(position("env$f()") < position("g()"));
+            assert!(position("g()") < position("stop(\"boom\")"));
+
+            // Without frames, no environments are reported
+            let frames = r_trace_back_frames(calls, None).unwrap();
+            assert!(frames.iter().all(|frame| frame.env.is_none()));
+        })
+    }
+
+    #[test]
+    fn test_r_options_snapshot() {
+        crate::r_task(|| {
+            let digits: i32 = crate::get_option("digits").try_into().unwrap();
+            let width: i32 = crate::get_option("width").try_into().unwrap();
+
+            let snapshot = r_options_snapshot(&["digits", "width", "harp.test.unset"]);
+
+            parse_eval_base("options(digits = 3, width = 40, harp.test.unset = TRUE)").unwrap();
+            let new_digits: i32 = crate::get_option("digits").try_into().unwrap();
+            assert_eq!(new_digits, 3);
+
+            r_options_restore(&snapshot);
+
+            let restored_digits: i32 = crate::get_option("digits").try_into().unwrap();
+            let restored_width: i32 = crate::get_option("width").try_into().unwrap();
+            assert_eq!(restored_digits, digits);
+            assert_eq!(restored_width, width);
+
+            // Options that didn't exist are removed again
+            assert!(crate::get_option("harp.test.unset").is_null());
+        })
+    }
+}
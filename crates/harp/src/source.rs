@@ -24,6 +24,37 @@ pub fn source_in(file: &str, env: SEXP) -> crate::Result<()> {
     Ok(())
 }
 
+/// Source the R script at `path` in `env`, like base `source()`
+///
+/// - `encoding` is the encoding of the file, e.g. `"UTF-8"`. When `None`,
+///   R's `encoding` option is used, which defaults to the native encoding.
+/// - `chdir` temporarily changes the working directory to the directory of
+///   `path` while sourcing, for scripts that use relative paths.
+///
+/// Errors mention `path`.
+pub fn r_source_file(
+    path: &str,
+    encoding: Option<&str>,
+    chdir: bool,
+    env: impl Into<SEXP>,
+) -> crate::Result<()> {
+    let result = RFunction::new("base", "source")
+        .param("file", path)
+        .param("local", env.into())
+        .param("chdir", chdir)
+        .param("echo", false)
+        .param("encoding", encoding)
+        .call();
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(error) => Err(crate::Error::SourceFileError {
+            path: String::from(path),
+            error: Box::new(error),
+        }),
+    }
+}
+
 pub fn source_str(code: &str) -> crate::Result<()> {
     source_str_in(code, R_ENVS.base)
 }
@@ -52,3 +83,41 @@ pub fn source_exprs_in(exprs: impl Into<SEXP>, env: impl Into<SEXP>) -> crate::R
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::Environment;
+    use crate::object::RObject;
+    use crate::parse_eval_base;
+    use crate::source::r_source_file;
+
+    #[test]
+    fn test_r_source_file() {
+        crate::r_task(|| {
+            let path: String = parse_eval_base("tempfile(fileext = '.R')")
+                .unwrap()
+                .try_into()
+                .unwrap();
+            let code = "x <- \"caf\u{e9}\"\ny <- normalizePath(getwd())\n";
+            std::fs::write(&path, code).unwrap();
+
+            let env = Environment::new(parse_eval_base("new.env()").unwrap());
+            r_source_file(&path, Some("UTF-8"), true, env.inner.sexp).unwrap();
+
+            let x: String = RObject::view(env.find("x").unwrap()).try_into().unwrap();
+            assert_eq!(x, "caf\u{e9}");
+
+            // With `chdir`, the script is sourced from its own directory
+            let y: String = RObject::view(env.find("y").unwrap()).try_into().unwrap();
+            let dir = std::path::Path::new(&path).parent().unwrap();
+            let dir = std::fs::canonicalize(dir).unwrap();
+            assert_eq!(std::path::Path::new(&y), dir);
+
+            std::fs::remove_file(&path).unwrap();
+
+            // Errors mention the path
+            let err = r_source_file("does-not-exist.R", None, false, env.inner.sexp).unwrap_err();
+            assert!(format!("{err}").contains("does-not-exist.R"));
+        })
+    }
+}
@@ -20,6 +20,8 @@ use libr::SYMSXP;
 use crate::error::Result;
 use crate::object::r_length;
 use crate::r_symbol;
+use crate::syntax::is_valid_symbol;
+use crate::syntax::sym_quote_invalid;
 use crate::utils::r_assert_type;
 use crate::utils::r_str_to_owned_utf8_unchecked;
 
@@ -37,6 +39,18 @@ impl RSymbol {
         r_assert_type(sexp, &[SYMSXP])?;
         Ok(Self::new_unchecked(sexp))
     }
+
+    /// Whether the symbol can be written in R code without backticks. See
+    /// [`is_valid_symbol()`] for the rules.
+    pub fn is_syntactic(&self) -> bool {
+        is_valid_symbol(&String::from(*self))
+    }
+
+    /// Returns the symbol as R code, wrapped in backticks if it isn't
+    /// syntactic
+    pub fn to_backticked_string(&self) -> String {
+        sym_quote_invalid(&String::from(*self))
+    }
 }
 
 impl Ord for RSymbol {
@@ -122,4 +136,21 @@ mod tests {
             ]);
         })
     }
+
+    #[test]
+    fn test_rsymbol_is_syntactic() {
+        crate::r_task(|| {
+            assert!(RSymbol::from("x").is_syntactic());
+            assert!(RSymbol::from(".foo").is_syntactic());
+            assert!(!RSymbol::from("_y").is_syntactic());
+            assert!(!RSymbol::from("if").is_syntactic());
+            assert!(!RSymbol::from("my var").is_syntactic());
+
+            assert_eq!(RSymbol::from("x").to_backticked_string(), "x");
+            assert_eq!(RSymbol::from(".foo").to_backticked_string(), ".foo");
+            assert_eq!(RSymbol::from("_y").to_backticked_string(), "`_y`");
+            assert_eq!(RSymbol::from("if").to_backticked_string(), "`if`");
+            assert_eq!(RSymbol::from("my var").to_backticked_string(), "`my var`");
+        })
+    }
 }
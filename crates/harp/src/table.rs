@@ -1,17 +1,32 @@
 use libr::*;
 
+use crate::column_names::ColumnNames;
 use crate::exec::RFunction;
 use crate::exec::RFunctionExt;
 use crate::object::RObject;
 use crate::utils::r_is_data_frame;
 use crate::utils::r_is_matrix;
+use crate::utils::r_is_object;
+use crate::utils::r_typeof;
+use crate::vector::Vector;
+use crate::CharacterVector;
+use crate::DataFrame;
+use crate::Matrix;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TableKind {
     Dataframe,
     Matrix,
 }
 
+/// Dimension information of a table, computed uniformly across table kinds
+pub struct TableInfo {
+    pub kind: TableKind,
+    pub n_row: i32,
+    pub n_col: i32,
+    pub col_names: ColumnNames,
+}
+
 pub fn table_kind(x: SEXP) -> Option<TableKind> {
     if r_is_data_frame(x) {
         Some(TableKind::Dataframe)
@@ -22,6 +37,100 @@ pub fn table_kind(x: SEXP) -> Option<TableKind> {
     }
 }
 
+/// Computes the [TableInfo] of `x`.
+///
+/// Fails if `x` is not a table, i.e. [table_kind()] returns `None`.
+/// Data frame subclasses like tibbles and data.tables are handled as data
+/// frames.
+pub fn table_info(x: SEXP) -> anyhow::Result<TableInfo> {
+    let Some(kind) = table_kind(x) else {
+        return Err(anyhow::anyhow!("`x` must be a data frame or a matrix"));
+    };
+
+    // `DataFrame::n_row()` will materialize duckplyr compact row names, see
+    // notes there
+    let (n_row, n_col, col_names) = match kind {
+        TableKind::Dataframe => (
+            DataFrame::n_row(x)?,
+            DataFrame::n_col(x)?,
+            ColumnNames::from_data_frame(x)?,
+        ),
+        TableKind::Matrix => {
+            let (n_row, n_col) = Matrix::dim(x)?;
+            (n_row, n_col, ColumnNames::from_matrix(x)?)
+        },
+    };
+
+    Ok(TableInfo {
+        kind,
+        n_row,
+        n_col,
+        col_names,
+    })
+}
+
+/// Row names of a table, if it has any. Automatic data frame row names (as
+/// in `c(NA, -n)`) are not reported.
+///
+/// Not part of [TableInfo] because materializing row names is expensive for
+/// large tables, only call this where they are needed.
+pub fn tbl_row_names(x: SEXP, kind: TableKind) -> anyhow::Result<Option<CharacterVector>> {
+    let row_names = match kind {
+        TableKind::Dataframe => {
+            // Negative for automatic row names. Goes through `getAttrib0()`
+            // so compact row names are not expanded.
+            let info = RFunction::new("base", ".row_names_info")
+                .add(x)
+                .add(1)
+                .call()?;
+            if i32::try_from(info)? < 0 {
+                return Ok(None);
+            }
+            RFunction::new("base", "row.names").add(x).call()?
+        },
+        TableKind::Matrix => RFunction::new("base", "rownames").add(x).call()?,
+    };
+
+    if row_names.sexp == unsafe { R_NilValue } {
+        return Ok(None);
+    }
+
+    Ok(Some(CharacterVector::new(row_names)?))
+}
+
+/// Converts named atomic vectors and 1D arrays to a single column matrix of
+/// values, with the names as row names, so they can be shown as tables. Other
+/// objects are returned as is.
+pub fn tbl_from_vector(x: RObject) -> anyhow::Result<RObject> {
+    if !is_table_like_vector(x.sexp) {
+        return Ok(x);
+    }
+
+    // `names()` returns the `dimnames()` of 1D arrays
+    let names = RFunction::new("base", "names").add(x.clone()).call()?;
+    let dimnames = RObject::try_from(vec![names, RObject::from("value")])?;
+
+    let out = RFunction::new("base", "matrix")
+        .param("data", x)
+        .param("ncol", 1)
+        .param("dimnames", dimnames)
+        .call()?;
+
+    Ok(out)
+}
+
+fn is_table_like_vector(x: SEXP) -> bool {
+    if !matches!(r_typeof(x), LGLSXP | INTSXP | REALSXP | CPLXSXP | STRSXP) || r_is_object(x) {
+        return false;
+    }
+
+    unsafe {
+        let has_names = Rf_getAttrib(x, R_NamesSymbol) != R_NilValue;
+        let is_1d_array = Rf_xlength(Rf_getAttrib(x, R_DimSymbol)) == 1;
+        has_names || is_1d_array
+    }
+}
+
 /// Extracts a single column from a table.
 ///
 /// - `x` - The table to extract the column from.
@@ -48,3 +157,134 @@ pub fn tbl_get_column(x: SEXP, column_index: i32, kind: TableKind) -> anyhow::Re
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::table::table_info;
+    use crate::table::table_kind;
+    use crate::table::tbl_from_vector;
+    use crate::table::tbl_row_names;
+    use crate::table::TableKind;
+    use crate::vector::Vector;
+
+    #[test]
+    fn test_table_info_data_frame() {
+        crate::r_task(|| {
+            let x = harp::parse_eval_base("data.frame(x = 1:3, y = c('a', 'b', 'c'))").unwrap();
+            let info = table_info(x.sexp).unwrap();
+
+            assert_eq!(info.kind, TableKind::Dataframe);
+            assert_eq!(info.n_row, 3);
+            assert_eq!(info.n_col, 2);
+            assert_eq!(info.col_names.get_unchecked(0), Some(String::from("x")));
+            assert_eq!(info.col_names.get_unchecked(1), Some(String::from("y")));
+            assert!(tbl_row_names(x.sexp, info.kind).unwrap().is_none());
+
+            let x = harp::parse_eval_base("data.frame(x = 1:2, row.names = c('a', 'b'))").unwrap();
+            let row_names = tbl_row_names(x.sexp, TableKind::Dataframe)
+                .unwrap()
+                .unwrap();
+            assert_eq!(row_names.get_unchecked(0), Some(String::from("a")));
+            assert_eq!(row_names.get_unchecked(1), Some(String::from("b")));
+
+            // Subsetting turns automatic row names into integer row names
+            let x = harp::parse_eval_base("data.frame(x = 1:5)[c(2, 4), , drop = FALSE]").unwrap();
+            let info = table_info(x.sexp).unwrap();
+            assert_eq!(info.n_row, 2);
+            let row_names = tbl_row_names(x.sexp, info.kind).unwrap().unwrap();
+            assert_eq!(row_names.get_unchecked(0), Some(String::from("2")));
+            assert_eq!(row_names.get_unchecked(1), Some(String::from("4")));
+        })
+    }
+
+    #[test]
+    fn test_table_info_matrix() {
+        crate::r_task(|| {
+            let x = harp::parse_eval_base("matrix(1:6, nrow = 2)").unwrap();
+            let info = table_info(x.sexp).unwrap();
+
+            assert_eq!(info.kind, TableKind::Matrix);
+            assert_eq!(info.n_row, 2);
+            assert_eq!(info.n_col, 3);
+            assert_eq!(info.col_names.get_unchecked(0), None);
+            assert!(tbl_row_names(x.sexp, info.kind).unwrap().is_none());
+
+            let x = harp::parse_eval_base(
+                "matrix(1:4, nrow = 2, dimnames = list(c('r1', 'r2'), c('c1', 'c2')))",
+            )
+            .unwrap();
+            let info = table_info(x.sexp).unwrap();
+            assert_eq!(info.col_names.get_unchecked(1), Some(String::from("c2")));
+            let row_names = tbl_row_names(x.sexp, info.kind).unwrap().unwrap();
+            assert_eq!(row_names.get_unchecked(0), Some(String::from("r1")));
+            assert_eq!(row_names.get_unchecked(1), Some(String::from("r2")));
+        })
+    }
+
+    #[test]
+    fn test_table_info_tibble() {
+        crate::r_task(|| {
+            // A tibble without depending on the tibble package
+            let x = harp::parse_eval_base(
+                "structure(
+                    list(x = 1:2, y = c(TRUE, FALSE)),
+                    class = c('tbl_df', 'tbl', 'data.frame'),
+                    row.names = c(NA, -2L)
+                )",
+            )
+            .unwrap();
+            let info = table_info(x.sexp).unwrap();
+
+            assert_eq!(info.kind, TableKind::Dataframe);
+            assert_eq!(info.n_row, 2);
+            assert_eq!(info.n_col, 2);
+            assert_eq!(info.col_names.get_unchecked(1), Some(String::from("y")));
+            assert!(tbl_row_names(x.sexp, info.kind).unwrap().is_none());
+        })
+    }
+
+    #[test]
+    fn test_table_info_not_a_table() {
+        crate::r_task(|| {
+            let x = harp::parse_eval_base("1:3").unwrap();
+            assert!(table_info(x.sexp).is_err());
+        })
+    }
+
+    #[test]
+    fn test_tbl_from_vector() {
+        crate::r_task(|| {
+            let x = harp::parse_eval_base("c(a = 1, b = 2, c = 3)").unwrap();
+            let x = tbl_from_vector(x).unwrap();
+            let info = table_info(x.sexp).unwrap();
+
+            assert_eq!(info.kind, TableKind::Matrix);
+            assert_eq!(info.n_row, 3);
+            assert_eq!(info.n_col, 1);
+            assert_eq!(info.col_names.get_unchecked(0), Some(String::from("value")));
+            let row_names = tbl_row_names(x.sexp, info.kind).unwrap().unwrap();
+            assert_eq!(row_names.get_unchecked(0), Some(String::from("a")));
+            assert_eq!(row_names.get_unchecked(2), Some(String::from("c")));
+
+            // 1D arrays, with or without names
+            let x = harp::parse_eval_base("array(1:2, dimnames = list(c('x', 'y')))").unwrap();
+            let x = tbl_from_vector(x).unwrap();
+            let info = table_info(x.sexp).unwrap();
+            assert_eq!(info.n_col, 1);
+            let row_names = tbl_row_names(x.sexp, info.kind).unwrap().unwrap();
+            assert_eq!(row_names.get_unchecked(1), Some(String::from("y")));
+
+            let x = harp::parse_eval_base("array(1:2)").unwrap();
+            let x = tbl_from_vector(x).unwrap();
+            let info = table_info(x.sexp).unwrap();
+            assert_eq!(info.n_row, 2);
+            assert!(tbl_row_names(x.sexp, info.kind).unwrap().is_none());
+
+            // Other objects are returned as is
+            let x = harp::parse_eval_base("1:3").unwrap();
+            assert!(table_kind(tbl_from_vector(x).unwrap().sexp).is_none());
+            let x = harp::parse_eval_base("factor(c(a = 'x'))").unwrap();
+            assert!(table_kind(tbl_from_vector(x).unwrap().sexp).is_none());
+        })
+    }
+}
@@ -5,6 +5,7 @@
 //
 //
 
+use std::borrow::Cow;
 use std::ffi::CStr;
 use std::ffi::CString;
 
@@ -14,6 +15,7 @@ use libr::*;
 use crate::call::r_expr_quote;
 use crate::call::RArgument;
 use crate::environment::Environment;
+use crate::environment::R_ENVS;
 use crate::error::Error;
 use crate::error::Result;
 use crate::exec::RFunction;
@@ -30,6 +32,7 @@ use crate::object::r_str_blank;
 use crate::object::r_str_na;
 use crate::object::RObject;
 use crate::protect::RProtect;
+use crate::r::fn_env;
 use crate::r::fn_formals;
 use crate::r_char;
 use crate::r_lang;
@@ -185,6 +188,19 @@ pub fn r_classes(value: SEXP) -> Option<CharacterVector> {
     }
 }
 
+/// The classes R dispatches S3 methods on for `value`, as returned by
+/// `.class2()`
+///
+/// For objects without a class attribute, this is the implicit class, e.g.
+/// `c("matrix", "array", "double", "numeric")` for a numeric matrix. This
+/// includes the type-based classes that `class()` leaves out.
+pub fn r_classes_implicit(value: SEXP) -> Result<Vec<String>> {
+    RFunction::new("base", ".class2")
+        .add(r_expr_quote(value))
+        .call()?
+        .try_into()
+}
+
 /// Translates a UTF-8 string from an R character vector to a Rust string.
 ///
 /// - `x` is the R vector to translate from.
@@ -233,6 +249,33 @@ pub fn r_str_to_owned_utf8_unchecked(x: SEXP) -> String {
     }
 }
 
+/// Borrows an R string as UTF-8, translating it only when needed.
+///
+/// - `x` is a CHARSXP that is assumed to not be missing.
+///
+/// Strings that R already stores as UTF-8 or ASCII are borrowed without
+/// copying. Strings in other encodings are translated to an owned string.
+/// Invalid UTF-8 is replaced as in [r_str_to_owned_utf8_unchecked()].
+///
+/// # Safety
+///
+/// The returned string may borrow the contents of `x`, so `x` must stay
+/// protected and unmodified for the lifetime `'a`.
+pub unsafe fn r_str_as_utf8_unchecked<'a>(x: SEXP) -> Cow<'a, str> {
+    let vmax = vmaxget();
+    let translated = Rf_translateCharUTF8(x);
+
+    // No translation was needed, R handed us back the string's own buffer
+    if translated == R_CHAR(x) {
+        vmaxset(vmax);
+        return String::from_utf8_lossy(CStr::from_ptr(translated).to_bytes());
+    }
+
+    let out = CStr::from_ptr(translated).to_string_lossy().into_owned();
+    vmaxset(vmax);
+    Cow::Owned(out)
+}
+
 pub fn pairlist_size(mut pairlist: SEXP) -> Result<isize> {
     let mut n = 0;
     unsafe {
@@ -307,8 +350,58 @@ pub fn r_type2char<T: Into<u32>>(kind: T) -> String {
     }
 }
 
+/// Returns the `SEXPTYPE` constant name of a type, e.g. `"INTSXP"`
+pub fn r_sexptype_name(kind: u32) -> &'static str {
+    match kind {
+        NILSXP => "NILSXP",
+        SYMSXP => "SYMSXP",
+        LISTSXP => "LISTSXP",
+        CLOSXP => "CLOSXP",
+        ENVSXP => "ENVSXP",
+        PROMSXP => "PROMSXP",
+        LANGSXP => "LANGSXP",
+        SPECIALSXP => "SPECIALSXP",
+        BUILTINSXP => "BUILTINSXP",
+        CHARSXP => "CHARSXP",
+        LGLSXP => "LGLSXP",
+        INTSXP => "INTSXP",
+        REALSXP => "REALSXP",
+        CPLXSXP => "CPLXSXP",
+        STRSXP => "STRSXP",
+        DOTSXP => "DOTSXP",
+        ANYSXP => "ANYSXP",
+        VECSXP => "VECSXP",
+        EXPRSXP => "EXPRSXP",
+        BCODESXP => "BCODESXP",
+        EXTPTRSXP => "EXTPTRSXP",
+        WEAKREFSXP => "WEAKREFSXP",
+        RAWSXP => "RAWSXP",
+        S4SXP => "S4SXP",
+        _ => "UNKNOWNSXP",
+    }
+}
+
+/// Returns a human-readable name for the type of `object`, for use in logs
+/// and error messages. This is the name returned by `typeof()`, except that
+/// factors are reported as `"factor"` and S4 objects as `"S4"` regardless of
+/// their underlying type.
+pub fn r_typeof_name(object: SEXP) -> String {
+    if r_is_s4(object) {
+        return String::from("S4");
+    }
+
+    if r_typeof(object) == INTSXP && r_inherits(object, "factor") {
+        return String::from("factor");
+    }
+
+    r_type2char(r_typeof(object))
+}
+
 pub fn r_inherits(object: SEXP, class: &str) -> bool {
-    let class = CString::new(class).unwrap();
+    // Class names can't contain NUL so nothing inherits from such a class
+    let Ok(class) = CString::new(class) else {
+        return false;
+    };
     unsafe { libr::Rf_inherits(object, class.as_ptr()) != 0 }
 }
 
@@ -565,6 +658,27 @@ pub fn r_env_binding_is_active(env: SEXP, sym: SEXP) -> harp::Result<bool> {
     }
 }
 
+/// Check if a symbol is bound to a locked binding in an environment
+///
+/// - `Err` if `sym` doesn't exist in the `env`
+/// - `Ok(true)` if `sym` exists in the `env` and its binding is locked
+/// - `Ok(false)` if `sym` exists in the `env` and its binding is not locked
+pub fn r_binding_is_locked(env: SEXP, sym: SEXP) -> harp::Result<bool> {
+    // Like `R_BindingIsActive()`, `R_BindingIsLocked()` throws an error if the
+    // `env` doesn't contain the symbol
+    if !r_env_has(env, sym) {
+        let name = RSymbol::new(sym)?.to_string();
+        Err(harp::Error::MissingBindingError { name })
+    } else {
+        Ok(unsafe { R_BindingIsLocked(sym, env) == Rboolean_TRUE })
+    }
+}
+
+/// Check if bindings can be added to or removed from an environment
+pub fn r_env_is_locked(env: SEXP) -> bool {
+    unsafe { R_EnvironmentIsLocked(env) == Rboolean_TRUE }
+}
+
 pub fn r_env_is_pkg_env(env: SEXP) -> bool {
     unsafe { R_IsPackageEnv(env) == Rboolean_TRUE || env == R_BaseEnv }
 }
@@ -611,6 +725,98 @@ pub fn r_ns_env_name(env: SEXP) -> SEXP {
     }
 }
 
+/// The name of the package defining the function `sym`, looked up from `env`
+/// like R looks up the function of a call.
+///
+/// Returns `None` when `sym` isn't bound to a function or when the function
+/// isn't defined in a package namespace, e.g. because it was defined at top
+/// level. Unforced bindings are only forced when they are lazy-loaded from a
+/// package, since forcing other promises could have side effects.
+pub fn r_defining_package(sym: &str, env: SEXP) -> Option<String> {
+    for env in Environment::view(env).ancestors() {
+        let Ok(mut value) = env.find(sym) else {
+            continue;
+        };
+
+        if r_is_promise(value) {
+            if r_promise_is_forced(value) {
+                value = r_promise_value(value);
+            } else if r_promise_is_lazy_load_binding(value) {
+                value = r_promise_force(value).ok()?.sexp;
+            } else {
+                return None;
+            }
+        }
+
+        // Like R, skip non-function bindings
+        if r_is_function(value) {
+            return r_fn_package(value);
+        }
+    }
+
+    None
+}
+
+/// The name of the package namespace a function is defined in, if any.
+/// Primitive functions belong to base. Closures created within a package
+/// function, e.g. by a function factory, belong to that package.
+pub fn r_fn_package(fun: SEXP) -> Option<String> {
+    if matches!(r_typeof(fun), BUILTINSXP | SPECIALSXP) {
+        return Some(String::from("base"));
+    }
+    if r_typeof(fun) != CLOSXP {
+        return None;
+    }
+
+    // Like `topenv()`, stop at the first namespace or the global environment
+    for env in Environment::view(fn_env(fun)).ancestors() {
+        let env = env.inner.sexp;
+
+        if env == R_ENVS.global {
+            return None;
+        }
+        if r_env_is_ns_env(env) {
+            return RObject::view(r_ns_env_name(env)).try_into().ok();
+        }
+    }
+
+    None
+}
+
+/// Whether `pkg` is installed in one of the library paths. The package is
+/// neither loaded nor attached.
+pub fn r_is_installed(pkg: &str) -> bool {
+    let path = RFunction::new("base", "system.file")
+        .param("package", pkg)
+        .call()
+        .and_then(String::try_from);
+
+    matches!(path, Ok(path) if !path.is_empty())
+}
+
+/// The installed version of `pkg` as a string like `"1.2.0"`, read from its
+/// `DESCRIPTION` file without loading the package. Returns `None` if `pkg`
+/// isn't installed.
+pub fn r_package_version(pkg: &str) -> Option<String> {
+    if !r_is_installed(pkg) {
+        return None;
+    }
+
+    let version = RFunction::new("utils", "packageVersion")
+        .add(pkg)
+        .call()
+        .and_then(|version| RFunction::new("base", "as.character").add(version).call())
+        .and_then(String::try_from);
+
+    match version {
+        Ok(version) => Some(version),
+        Err(err) => {
+            log::warn!("Can't get version of package '{pkg}': {err:?}");
+            None
+        },
+    }
+}
+
 /// Returns `true` if `f` returns `true` for any node of the pairlist
 pub fn r_pairlist_any<F>(x: SEXP, f: F) -> bool
 where
@@ -645,17 +851,35 @@ pub fn r_poke_option(sym: SEXP, value: SEXP) -> SEXP {
     }
 }
 
-pub fn r_normalize_path(x: RObject) -> anyhow::Result<String> {
-    if !r_is_string(x.sexp) {
-        anyhow::bail!("Expected string for srcfile's filename");
+/// Normalize `path` the way R does: `~` is expanded with `path.expand()`,
+/// symlinks are resolved, and separators are `/` on all platforms.
+///
+/// If `must_exist` is `true`, a path that doesn't exist is an error. Otherwise
+/// it is returned expanded, and made absolute relative to the working
+/// directory of the R session.
+pub fn r_normalize_path(path: &str, must_exist: bool) -> Result<String> {
+    let normalized: String = RFunction::new("base", "normalizePath")
+        .param("path", path)
+        .param("winslash", "/")
+        .param("mustWork", must_exist)
+        .call()?
+        .try_into()?;
+
+    // `normalizePath()` returns non-existent paths as is, after tilde expansion
+    if std::path::Path::new(&normalized).is_absolute() {
+        return Ok(normalized);
     }
-    let path = RFunction::new("base", "normalizePath")
-        .param("path", x)
+
+    let wd: String = RFunction::new("base", "getwd").call()?.try_into()?;
+    let wd: String = RFunction::new("base", "normalizePath")
+        .param("path", wd)
         .param("winslash", "/")
         .param("mustWork", false)
         .call()?
-        .to::<String>()?;
-    Ok(path)
+        .try_into()?;
+
+    let relative = normalized.strip_prefix("./").unwrap_or(&normalized);
+    Ok(format!("{}/{relative}", wd.trim_end_matches('/')))
 }
 
 pub fn save_rds(x: SEXP, path: &str) {
@@ -733,6 +957,34 @@ pub fn r_format_s4(x: SEXP) -> Result<SEXP> {
     Ok(out.sexp)
 }
 
+/// Deparse `x` on a single line of at most `max_len` characters, appending
+/// `…` when truncated. The number of deparsed lines is capped with `nlines`
+/// so that large objects aren't fully deparsed.
+pub fn r_deparse_truncated(x: SEXP, max_len: usize) -> String {
+    // Each deparsed line contributes at least one character, so `max_len`
+    // lines are always enough to fill the output
+    let nlines = i32::try_from(max_len.max(1)).unwrap_or(i32::MAX);
+
+    let deparsed = RFunction::from("expr_deparse_collapse")
+        .add(r_expr_quote(x))
+        .param("nlines", nlines)
+        .call_in(unsafe { HARP_ENV.unwrap() })
+        .and_then(String::try_from);
+
+    let deparsed = match deparsed {
+        Ok(deparsed) => deparsed,
+        Err(err) => {
+            log::error!("Failed to deparse object: {err:?}");
+            return String::from("<deparse error>");
+        },
+    };
+
+    match deparsed.char_indices().nth(max_len) {
+        Some((end, _)) => format!("{}…", &deparsed[..end]),
+        None => deparsed,
+    }
+}
+
 pub fn r_subset_vec(x: SEXP, indices: Vec<i64>) -> Result<SEXP> {
     let env = unsafe { HARP_ENV.unwrap() };
     let indices: Vec<i64> = indices.into_iter().map(|i| i + 1).collect();
@@ -750,7 +1002,20 @@ mod tests {
     use crate::environment::R_ENVS;
     use crate::exec::RFunction;
     use crate::exec::RFunctionExt;
+    use crate::parse_eval_base;
     use crate::r_str_to_owned_utf8_unchecked;
+    use crate::r_symbol;
+    use crate::utils::r_binding_is_locked;
+    use crate::utils::r_classes_implicit;
+    use crate::utils::r_defining_package;
+    use crate::utils::r_deparse_truncated;
+    use crate::utils::r_env_is_locked;
+    use crate::utils::r_fn_package;
+    use crate::utils::r_is_installed;
+    use crate::utils::r_normalize_path;
+    use crate::utils::r_package_version;
+    use crate::utils::r_sexptype_name;
+    use crate::utils::r_typeof_name;
 
     #[test]
     fn test_r_str_to_utf8_replaces_invalid_utf8() {
@@ -781,4 +1046,194 @@ mod tests {
             assert_eq!(x, String::from(std::char::REPLACEMENT_CHARACTER));
         })
     }
+
+    #[test]
+    fn test_r_typeof_name() {
+        crate::r_task(|| {
+            let name = |code: &str| r_typeof_name(harp::parse_eval_base(code).unwrap().sexp);
+
+            assert_eq!(name("1L"), "integer");
+            assert_eq!(name("'a'"), "character");
+            assert_eq!(name("list()"), "list");
+            assert_eq!(name("function() NULL"), "closure");
+            assert_eq!(name("sum"), "builtin");
+            assert_eq!(name("factor('a')"), "factor");
+            assert_eq!(name("asS4(1)"), "S4");
+        })
+    }
+
+    #[test]
+    fn test_r_sexptype_name() {
+        assert_eq!(r_sexptype_name(libr::INTSXP), "INTSXP");
+        assert_eq!(r_sexptype_name(libr::CLOSXP), "CLOSXP");
+        assert_eq!(r_sexptype_name(100), "UNKNOWNSXP");
+    }
+
+    #[test]
+    fn test_r_binding_is_locked() {
+        crate::r_task(|| {
+            let env = parse_eval_base("local({ env <- new.env(); env$x <- 1; env$y <- 2; env })")
+                .unwrap();
+
+            assert!(!r_binding_is_locked(env.sexp, r_symbol!("x")).unwrap());
+
+            RFunction::new("base", "lockBinding")
+                .add("x")
+                .add(env.sexp)
+                .call()
+                .unwrap();
+
+            assert!(r_binding_is_locked(env.sexp, r_symbol!("x")).unwrap());
+            assert!(!r_binding_is_locked(env.sexp, r_symbol!("y")).unwrap());
+            assert!(r_binding_is_locked(env.sexp, r_symbol!("z")).is_err());
+
+            // Locking a binding doesn't lock its environment
+            assert!(!r_env_is_locked(env.sexp));
+        })
+    }
+
+    #[test]
+    fn test_r_env_is_locked() {
+        crate::r_task(|| {
+            let env = parse_eval_base("local({ env <- new.env(); env$x <- 1; env })").unwrap();
+            assert!(!r_env_is_locked(env.sexp));
+
+            RFunction::new("base", "lockEnvironment")
+                .add(env.sexp)
+                .call()
+                .unwrap();
+
+            assert!(r_env_is_locked(env.sexp));
+        })
+    }
+
+    #[test]
+    fn test_r_defining_package() {
+        crate::r_task(|| {
+            let lm = parse_eval_base("stats::lm").unwrap();
+            assert_eq!(r_fn_package(lm.sexp), Some(String::from("stats")));
+
+            let stats = parse_eval_base("asNamespace('stats')").unwrap();
+            assert_eq!(
+                r_defining_package("lm", stats.sexp),
+                Some(String::from("stats"))
+            );
+
+            // Primitives and base functions
+            assert_eq!(
+                r_defining_package("sum", R_ENVS.global),
+                Some(String::from("base"))
+            );
+            assert_eq!(
+                r_defining_package("identity", R_ENVS.global),
+                Some(String::from("base"))
+            );
+
+            // Functions defined outside of a namespace
+            let env = parse_eval_base(
+                "local({ env <- new.env(parent = globalenv()); env$f <- function() 1; env$g <- 1; env })",
+            )
+            .unwrap();
+            assert_eq!(r_defining_package("f", env.sexp), None);
+            assert_eq!(r_defining_package("g", env.sexp), None);
+            assert_eq!(r_defining_package("does_not_exist", env.sexp), None);
+        })
+    }
+
+    #[test]
+    fn test_r_is_installed() {
+        crate::r_task(|| {
+            assert!(r_is_installed("stats"));
+            assert!(!r_is_installed("notapackage.ark"));
+
+            // Querying doesn't attach the package
+            let search = parse_eval_base("search()").unwrap();
+            let search = Vec::<String>::try_from(search).unwrap();
+            assert!(!search.contains(&String::from("package:tools")));
+            assert!(r_is_installed("tools"));
+            let search = parse_eval_base("search()").unwrap();
+            let search = Vec::<String>::try_from(search).unwrap();
+            assert!(!search.contains(&String::from("package:tools")));
+        })
+    }
+
+    #[test]
+    fn test_r_package_version() {
+        crate::r_task(|| {
+            let expected = parse_eval_base("as.character(getRversion())").unwrap();
+            let expected = String::try_from(expected).unwrap();
+            assert_eq!(r_package_version("stats"), Some(expected));
+
+            assert_eq!(r_package_version("notapackage.ark"), None);
+        })
+    }
+
+    #[test]
+    fn test_r_deparse_truncated() {
+        crate::r_task(|| {
+            let x = parse_eval_base("1:1000 + 0").unwrap();
+
+            let out = r_deparse_truncated(x.sexp, 20);
+            assert_eq!(out, "c(1, 2, 3, 4, 5, 6, …");
+            assert_eq!(out.chars().count(), 21);
+
+            // Short deparses are left as is
+            let x = parse_eval_base("quote(f(x))").unwrap();
+            assert_eq!(r_deparse_truncated(x.sexp, 20), "f(x)");
+        })
+    }
+
+    #[test]
+    fn test_r_normalize_path_expands_tilde() {
+        crate::r_task(|| {
+            let home: String = parse_eval_base("path.expand('~')")
+                .unwrap()
+                .try_into()
+                .unwrap();
+            let home = home.replace('\\', "/");
+
+            let path = r_normalize_path("~/ark-test-nonexistent", false).unwrap();
+            assert_eq!(path, format!("{home}/ark-test-nonexistent"));
+
+            assert!(r_normalize_path("~/ark-test-nonexistent", true).is_err());
+        })
+    }
+
+    #[test]
+    fn test_r_normalize_path_relative() {
+        crate::r_task(|| {
+            let wd: String = parse_eval_base("normalizePath(getwd(), winslash = '/')")
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+            // Existing paths are resolved against the working directory
+            assert_eq!(r_normalize_path(".", true).unwrap(), wd);
+
+            // So are non-existent ones, when allowed
+            let path = r_normalize_path("ark-test-nonexistent/file.R", false).unwrap();
+            assert_eq!(path, format!("{wd}/ark-test-nonexistent/file.R"));
+
+            let path = r_normalize_path("./ark-test-nonexistent", false).unwrap();
+            assert_eq!(path, format!("{wd}/ark-test-nonexistent"));
+
+            assert!(r_normalize_path("ark-test-nonexistent/file.R", true).is_err());
+        })
+    }
+
+    #[test]
+    fn test_r_classes_implicit() {
+        crate::r_task(|| {
+            let classes = |code: &str| r_classes_implicit(*parse_eval_base(code).unwrap()).unwrap();
+
+            assert_eq!(classes("matrix(1, 2, 2)"), vec![
+                "matrix", "array", "double", "numeric"
+            ]);
+            assert_eq!(classes("1:3"), vec!["integer", "numeric"]);
+            assert_eq!(classes("function(x) x"), vec!["function"]);
+
+            // Objects with a class attribute dispatch on it
+            assert_eq!(classes("factor('a')"), vec!["factor"]);
+        })
+    }
 }
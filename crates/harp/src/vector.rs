@@ -25,6 +25,9 @@ pub use factor::Factor;
 pub mod integer_vector;
 pub use integer_vector::IntegerVector;
 
+pub mod integer64_vector;
+pub use integer64_vector::Integer64Vector;
+
 pub mod logical_vector;
 pub use logical_vector::LogicalVector;
 
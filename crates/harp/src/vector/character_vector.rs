@@ -5,6 +5,7 @@
 //
 //
 
+use std::borrow::Cow;
 use std::os::raw::c_char;
 
 use libr::cetype_t_CE_UTF8;
@@ -19,6 +20,7 @@ use libr::STRSXP;
 use crate::object::RObject;
 use crate::r_assert_type;
 use crate::r_chr_poke;
+use crate::utils::r_str_as_utf8_unchecked;
 use crate::utils::r_str_to_owned_utf8_unchecked;
 use crate::vector::FormatOptions;
 use crate::vector::Vector;
@@ -36,6 +38,24 @@ impl CharacterVector {
             std::slice::from_raw_parts(data, self.len())
         }
     }
+
+    /// Iterate over the strings of the vector, yielding `None` for `NA`.
+    ///
+    /// Unlike `iter()`, this borrows strings that are already UTF-8 (or
+    /// ASCII) from the vector rather than copying them, which makes it
+    /// cheaper for read-only scans like searching. Strings in other
+    /// encodings are translated, which requires an allocation.
+    pub fn iter_str(&self) -> impl Iterator<Item = Option<Cow<'_, str>>> + '_ {
+        self.slice().iter().map(|&x| {
+            if Self::is_na(&x) {
+                None
+            } else {
+                // Safety: The vector is protected by `self.object` and
+                // borrowed for the lifetime of the strings
+                Some(unsafe { r_str_as_utf8_unchecked(x) })
+            }
+        })
+    }
 }
 
 impl Vector for CharacterVector {
@@ -132,6 +152,33 @@ impl TryFrom<&CharacterVector> for Vec<String> {
     }
 }
 
+/// Builds a vector from optional strings, with `None` becoming `NA`.
+impl<S: AsRef<str>> FromIterator<Option<S>> for CharacterVector {
+    fn from_iter<I: IntoIterator<Item = Option<S>>>(iter: I) -> Self {
+        let values: Vec<Option<S>> = iter.into_iter().collect();
+        let vector = Self::with_length(values.len());
+
+        for (i, value) in values.into_iter().enumerate() {
+            let charsexp = match value {
+                Some(value) => {
+                    let value = value.as_ref();
+                    unsafe {
+                        Rf_mkCharLenCE(
+                            value.as_ptr() as *const c_char,
+                            value.len() as i32,
+                            cetype_t_CE_UTF8,
+                        )
+                    }
+                },
+                None => unsafe { R_NaString },
+            };
+            r_chr_poke(vector.data(), i as R_xlen_t, charsexp);
+        }
+
+        vector
+    }
+}
+
 impl From<CharacterVector> for RObject {
     fn from(value: CharacterVector) -> Self {
         value.object
@@ -140,6 +187,8 @@ impl From<CharacterVector> for RObject {
 
 #[cfg(test)]
 mod test {
+    use std::borrow::Cow;
+
     use libr::STRSXP;
 
     use crate::utils::r_typeof;
@@ -167,6 +216,37 @@ mod test {
         })
     }
 
+    #[test]
+    fn test_iter_str() {
+        crate::r_task(|| {
+            let x = harp::parse_eval_base(
+                r#"c(rep(c("a", "caf\u00e9", NA), 10000), iconv("caf\u00e9", "UTF-8", "latin1"))"#,
+            )
+            .unwrap();
+            let vector = unsafe { CharacterVector::new_unchecked(x.sexp) };
+
+            // Same results as the allocating iterator
+            let start = std::time::Instant::now();
+            let owned: Vec<Option<String>> = vector.iter().collect();
+            let owned_elapsed = start.elapsed();
+
+            let start = std::time::Instant::now();
+            let borrowed: Vec<Option<Cow<str>>> = vector.iter_str().collect();
+            let borrowed_elapsed = start.elapsed();
+
+            assert_eq!(owned.len(), borrowed.len());
+            for (owned, borrowed) in owned.iter().zip(borrowed.iter()) {
+                assert_eq!(owned.as_deref(), borrowed.as_deref());
+            }
+            log::trace!("iter(): {owned_elapsed:?}, iter_str(): {borrowed_elapsed:?}");
+
+            // UTF-8 strings are borrowed, latin1 strings are translated
+            assert!(matches!(borrowed[1], Some(Cow::Borrowed("caf\u{e9}"))));
+            assert!(borrowed[2].is_none());
+            assert!(matches!(borrowed.last().unwrap(), Some(Cow::Owned(x)) if x == "caf\u{e9}"));
+        })
+    }
+
     #[test]
     fn test_create() {
         crate::r_task(|| {
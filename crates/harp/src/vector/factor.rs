@@ -26,6 +26,22 @@ pub struct Factor {
     levels: CharacterVector,
 }
 
+impl Factor {
+    /// The level labels, in level order
+    pub fn levels(&self) -> Vec<String> {
+        self.levels
+            .iter()
+            .map(|level| level.unwrap_or_default())
+            .collect()
+    }
+
+    /// Whether the levels have a meaningful order, i.e. whether the factor
+    /// inherits from `ordered`
+    pub fn is_ordered(&self) -> bool {
+        self.object.inherits("ordered")
+    }
+}
+
 impl Vector for Factor {
     type Item = i32;
     type Type = i32;
@@ -80,3 +96,28 @@ impl Vector for Factor {
         self.levels.get_unchecked((x - 1) as isize).unwrap()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::vector::*;
+
+    #[test]
+    fn test_factor_levels() {
+        crate::r_task(|| {
+            let x =
+                harp::parse_eval_base("factor(c('b', 'a'), levels = c('b', 'a', 'c'))").unwrap();
+            let x = Factor::new(x.sexp).unwrap();
+            assert_eq!(x.levels(), vec!["b", "a", "c"]);
+            assert!(!x.is_ordered());
+
+            let x = harp::parse_eval_base(
+                "factor(c('lo', 'hi'), levels = c('lo', 'hi'), ordered = TRUE)",
+            )
+            .unwrap();
+            let x = Factor::new(x.sexp).unwrap();
+            assert_eq!(x.levels(), vec!["lo", "hi"]);
+            assert!(x.is_ordered());
+            assert_eq!(x.format_one(x.get_unchecked(1).unwrap(), None), "hi");
+        })
+    }
+}
@@ -0,0 +1,131 @@
+//
+// integer64_vector.rs
+//
+// Copyright (C) 2026 Posit Software, PBC. All rights reserved.
+//
+//
+
+use libr::R_xlen_t;
+use libr::REALSXP;
+use libr::REAL_ELT;
+use libr::SET_REAL_ELT;
+use libr::SEXP;
+
+use crate::environment::r_ns_env;
+use crate::object::RObject;
+use crate::utils::r_inherits;
+use crate::utils::r_typeof;
+use crate::vector::CharacterVector;
+use crate::vector::FormatOptions;
+use crate::vector::Vector;
+
+/// A `bit64::integer64` vector
+///
+/// `integer64` values are 64-bit integers stored in the bits of a double
+/// vector with class `integer64`. Reading them as doubles yields meaningless
+/// tiny numbers, so they need to be reinterpreted. `NA` is the smallest
+/// 64-bit integer.
+#[harp_macros::vector]
+pub struct Integer64Vector {
+    object: RObject,
+}
+
+impl Integer64Vector {
+    /// Whether `x` is an `integer64` vector that we can interpret as such.
+    ///
+    /// Requires bit64 to be loaded, since we rely on its methods for anything
+    /// beyond reading values, e.g. to compute summaries.
+    pub fn is_integer64(x: SEXP) -> bool {
+        r_typeof(x) == REALSXP && r_inherits(x, "integer64") && r_ns_env("bit64").is_ok()
+    }
+}
+
+impl Vector for Integer64Vector {
+    type Item = i64;
+    type Type = i64;
+    const SEXPTYPE: u32 = REALSXP;
+    type UnderlyingType = f64;
+    type CompareType = i64;
+
+    unsafe fn new_unchecked(object: impl Into<SEXP>) -> Self {
+        Self {
+            object: RObject::new(object.into()),
+        }
+    }
+
+    fn create<T>(data: T) -> Self
+    where
+        T: IntoIterator,
+        <T as IntoIterator>::IntoIter: ExactSizeIterator,
+        <T as IntoIterator>::Item: AsRef<Self::Item>,
+    {
+        data.into_iter().map(|x| Some(*x.as_ref())).collect()
+    }
+
+    fn data(&self) -> SEXP {
+        self.object.sexp
+    }
+
+    fn is_na(x: &Self::UnderlyingType) -> bool {
+        Self::convert_value(x) == i64::MIN
+    }
+
+    fn get_unchecked_elt(&self, index: isize) -> Self::UnderlyingType {
+        unsafe { REAL_ELT(self.data(), index as R_xlen_t) }
+    }
+
+    fn convert_value(x: &Self::UnderlyingType) -> Self::Type {
+        x.to_bits() as i64
+    }
+
+    fn format_one(&self, x: Self::Type, _option: Option<&FormatOptions>) -> String {
+        x.to_string()
+    }
+}
+
+/// Builds a vector from optional values, with `None` becoming `NA`.
+impl FromIterator<Option<i64>> for Integer64Vector {
+    fn from_iter<I: IntoIterator<Item = Option<i64>>>(iter: I) -> Self {
+        let values: Vec<Option<i64>> = iter.into_iter().collect();
+        let vector = Self::with_length(values.len());
+
+        for (i, value) in values.into_iter().enumerate() {
+            let value = f64::from_bits(value.unwrap_or(i64::MIN) as u64);
+            unsafe { SET_REAL_ELT(vector.data(), i as R_xlen_t, value) };
+        }
+
+        let class = CharacterVector::create(["integer64"]);
+        vector.object.set_attribute("class", class.object.sexp);
+
+        vector
+    }
+}
+
+impl From<Integer64Vector> for RObject {
+    fn from(value: Integer64Vector) -> Self {
+        value.object
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::vector::Integer64Vector;
+    use crate::vector::Vector;
+
+    #[test]
+    fn test_integer64_vector() {
+        crate::r_task(|| {
+            let vector: Integer64Vector = [Some(1), Some(-2), Some(9007199254740993), None]
+                .into_iter()
+                .collect();
+            assert_eq!(vector.get_unchecked(0), Some(1));
+            assert_eq!(vector.get_unchecked(1), Some(-2));
+
+            // Beyond the range of integers that doubles represent exactly
+            assert_eq!(vector.get_unchecked(2), Some(9007199254740993));
+
+            assert_eq!(vector.get_unchecked(3), None);
+            assert!(crate::utils::r_inherits(vector.data(), "integer64"));
+        })
+    }
+}
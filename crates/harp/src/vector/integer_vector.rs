@@ -11,6 +11,7 @@ use libr::Rf_allocVector;
 use libr::DATAPTR;
 use libr::INTEGER_ELT;
 use libr::INTSXP;
+use libr::SET_INTEGER_ELT;
 use libr::SEXP;
 
 use crate::object::RObject;
@@ -76,6 +77,30 @@ impl Vector for IntegerVector {
     }
 }
 
+/// Builds a vector from optional values, with `None` becoming `NA`.
+impl FromIterator<Option<i32>> for IntegerVector {
+    fn from_iter<I: IntoIterator<Item = Option<i32>>>(iter: I) -> Self {
+        let values: Vec<Option<i32>> = iter.into_iter().collect();
+        let vector = Self::with_length(values.len());
+
+        for (i, value) in values.into_iter().enumerate() {
+            let value = match value {
+                Some(value) => value,
+                None => unsafe { R_NaInt },
+            };
+            unsafe { SET_INTEGER_ELT(vector.data(), i as R_xlen_t, value) };
+        }
+
+        vector
+    }
+}
+
+impl From<IntegerVector> for RObject {
+    fn from(value: IntegerVector) -> Self {
+        value.object
+    }
+}
+
 impl TryFrom<&IntegerVector> for Vec<i32> {
     type Error = harp::Error;
 
@@ -11,6 +11,7 @@ use libr::Rf_allocVector;
 use libr::DATAPTR;
 use libr::LGLSXP;
 use libr::LOGICAL_ELT;
+use libr::SET_LOGICAL_ELT;
 use libr::SEXP;
 
 use crate::object::RObject;
@@ -80,6 +81,56 @@ impl Vector for LogicalVector {
     }
 }
 
+/// Three-valued logic helpers. `NA` values are neither true nor false.
+impl LogicalVector {
+    pub fn count_true(&self) -> usize {
+        self.iter().filter(|x| *x == Some(true)).count()
+    }
+
+    pub fn count_false(&self) -> usize {
+        self.iter().filter(|x| *x == Some(false)).count()
+    }
+
+    pub fn count_na(&self) -> usize {
+        self.iter().filter(|x| x.is_none()).count()
+    }
+
+    /// Like `any(x, na.rm = TRUE)`
+    pub fn any_true(&self) -> bool {
+        self.iter().any(|x| x == Some(true))
+    }
+
+    /// Like `all(x, na.rm = TRUE)`. Vectors of `NA` only, as well as empty
+    /// vectors, are all true.
+    pub fn all_true_ignoring_na(&self) -> bool {
+        self.iter().all(|x| x != Some(false))
+    }
+}
+
+/// Builds a vector from optional values, with `None` becoming `NA`.
+impl FromIterator<Option<bool>> for LogicalVector {
+    fn from_iter<I: IntoIterator<Item = Option<bool>>>(iter: I) -> Self {
+        let values: Vec<Option<bool>> = iter.into_iter().collect();
+        let vector = Self::with_length(values.len());
+
+        for (i, value) in values.into_iter().enumerate() {
+            let value = match value {
+                Some(value) => value as i32,
+                None => unsafe { R_NaInt },
+            };
+            unsafe { SET_LOGICAL_ELT(vector.data(), i as R_xlen_t, value) };
+        }
+
+        vector
+    }
+}
+
+impl From<LogicalVector> for RObject {
+    fn from(value: LogicalVector) -> Self {
+        value.object
+    }
+}
+
 impl TryFrom<&LogicalVector> for Vec<bool> {
     type Error = harp::Error;
 
@@ -87,3 +138,37 @@ impl TryFrom<&LogicalVector> for Vec<bool> {
         super::try_vec_from_r_vector(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::vector::LogicalVector;
+    use crate::vector::Vector;
+
+    #[test]
+    fn test_logical_vector_counts() {
+        crate::r_task(|| {
+            let x = harp::parse_eval_base("c(TRUE, FALSE, NA, TRUE, NA, TRUE)").unwrap();
+            let x = LogicalVector::new(x).unwrap();
+
+            assert_eq!(x.count_true(), 3);
+            assert_eq!(x.count_false(), 1);
+            assert_eq!(x.count_na(), 2);
+            assert!(x.any_true());
+            assert!(!x.all_true_ignoring_na());
+
+            let x = harp::parse_eval_base("c(TRUE, NA, TRUE)").unwrap();
+            let x = LogicalVector::new(x).unwrap();
+            assert!(x.all_true_ignoring_na());
+
+            let x = harp::parse_eval_base("c(NA, FALSE)").unwrap();
+            let x = LogicalVector::new(x).unwrap();
+            assert!(!x.any_true());
+
+            let x = harp::parse_eval_base("logical()").unwrap();
+            let x = LogicalVector::new(x).unwrap();
+            assert_eq!(x.count_na(), 0);
+            assert!(!x.any_true());
+            assert!(x.all_true_ignoring_na());
+        })
+    }
+}
@@ -4,6 +4,8 @@
 // Copyright (C) 2022 Posit Software, PBC. All rights reserved.
 //
 //
+use std::collections::HashMap;
+
 use libr::SEXP;
 
 use crate::object::RObject;
@@ -53,4 +55,53 @@ impl Names {
             },
         }
     }
+
+    /// Build an index from names to positions, for repeated lookups by name.
+    ///
+    /// With duplicate names, the first position wins, matching how R resolves
+    /// `x$foo` and `x[["foo"]]`. `NA` and empty names are never indexed.
+    pub fn index(&self) -> HashMap<String, usize> {
+        let mut index = HashMap::new();
+
+        let Some(names) = &self.data else {
+            return index;
+        };
+
+        for (i, name) in names.iter().enumerate() {
+            let Some(name) = name else {
+                continue;
+            };
+            if name.is_empty() {
+                continue;
+            }
+            index.entry(name).or_insert(i);
+        }
+
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vector::names::Names;
+
+    #[test]
+    fn test_names_index() {
+        crate::r_task(|| {
+            let x = harp::parse_eval_base("list(a = 1, b = 2, a = 3, 4, c = 5, b = 6)").unwrap();
+            let names = Names::new(x.sexp, |i| format!("[[{}]]", i + 1));
+            let index = names.index();
+
+            assert_eq!(index.len(), 3);
+            assert_eq!(index.get("a"), Some(&0));
+            assert_eq!(index.get("b"), Some(&1));
+            assert_eq!(index.get("c"), Some(&4));
+            assert_eq!(index.get(""), None);
+            assert_eq!(index.get("[[4]]"), None);
+
+            let x = harp::parse_eval_base("list(1, 2)").unwrap();
+            let names = Names::new(x.sexp, |i| format!("[[{}]]", i + 1));
+            assert!(names.index().is_empty());
+        })
+    }
 }
@@ -6,11 +6,13 @@
 //
 
 use libr::R_IsNA;
+use libr::R_NaReal;
 use libr::R_xlen_t;
 use libr::Rf_allocVector;
 use libr::DATAPTR;
 use libr::REALSXP;
 use libr::REAL_ELT;
+use libr::SET_REAL_ELT;
 use libr::SEXP;
 
 use crate::object::RObject;
@@ -76,6 +78,30 @@ impl Vector for NumericVector {
     }
 }
 
+/// Builds a vector from optional values, with `None` becoming `NA`.
+impl FromIterator<Option<f64>> for NumericVector {
+    fn from_iter<I: IntoIterator<Item = Option<f64>>>(iter: I) -> Self {
+        let values: Vec<Option<f64>> = iter.into_iter().collect();
+        let vector = Self::with_length(values.len());
+
+        for (i, value) in values.into_iter().enumerate() {
+            let value = match value {
+                Some(value) => value,
+                None => unsafe { R_NaReal },
+            };
+            unsafe { SET_REAL_ELT(vector.data(), i as R_xlen_t, value) };
+        }
+
+        vector
+    }
+}
+
+impl From<NumericVector> for RObject {
+    fn from(value: NumericVector) -> Self {
+        value.object
+    }
+}
+
 impl TryFrom<&NumericVector> for Vec<f64> {
     type Error = harp::Error;
 
@@ -5,6 +5,7 @@
 //
 //
 
+use base64::prelude::*;
 use libr::R_xlen_t;
 use libr::Rf_allocVector;
 use libr::DATAPTR;
@@ -21,6 +22,24 @@ pub struct RawVector {
     object: RObject,
 }
 
+impl RawVector {
+    /// Copy `bytes` into a new raw vector.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        unsafe { Self::new_unchecked(RObject::from(bytes)) }
+    }
+
+    /// Encode the bytes as an unpadded base64 string, as sent in plot comm
+    /// payloads.
+    pub fn to_base64(&self) -> String {
+        BASE64_STANDARD_NO_PAD.encode(self.bytes())
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // Always `Some` since the object is a raw vector
+        self.object.as_raw_slice().unwrap_or_default()
+    }
+}
+
 impl Vector for RawVector {
     type Item = u8;
     type Type = u8;
@@ -82,3 +101,28 @@ impl TryFrom<&RawVector> for Vec<u8> {
         super::try_vec_from_r_vector(value)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use base64::prelude::*;
+
+    use crate::vector::RawVector;
+    use crate::vector::Vector;
+
+    #[test]
+    fn test_base64_round_trip() {
+        crate::r_task(|| {
+            let bytes: Vec<u8> = (0..=255).collect();
+            let vector = RawVector::from_bytes(&bytes);
+            assert_eq!(vector.len(), 256);
+            assert_eq!(Vec::<u8>::try_from(&vector).unwrap(), bytes);
+
+            let encoded = vector.to_base64();
+            assert_eq!(BASE64_STANDARD_NO_PAD.decode(encoded).unwrap(), bytes);
+
+            assert_eq!(RawVector::from_bytes(b"ark").to_base64(), "YXJr");
+            assert_eq!(RawVector::from_bytes(b"R").to_base64(), "Ug");
+            assert_eq!(RawVector::from_bytes(&[]).to_base64(), "");
+        })
+    }
+}
@@ -5,8 +5,21 @@
 //
 //
 
+use std::cell::RefCell;
+
 use crate::RObject;
 
+type Finalizer = Box<dyn FnOnce()>;
+
+thread_local! {
+    /// Finalizers of live weak references, along with the weak reference
+    /// they belong to. R only passes the key to C finalizers, and the key of
+    /// our weak references is the referee itself, so we can't attach the
+    /// finalizer to the weak reference. Instead we look up the weak
+    /// references that R has cleared when any of them is finalized.
+    static FINALIZERS: RefCell<Vec<(libr::SEXP, Finalizer)>> = const { RefCell::new(Vec::new()) };
+}
+
 /// Weak reference to an R object.
 ///
 /// This is a wrapper around R weak references (see
@@ -16,7 +29,8 @@ use crate::RObject;
 /// `deref()` method, without preventing R from garbage collecting this object.
 /// When it gets GC'd by R, or when the weak ref is dropped, the supplied
 /// `finalizer` is run. This technique allows you to monitor the existence of R
-/// objects in the session.
+/// objects in the session, e.g. to drop cache entries for objects that the
+/// user has removed.
 ///
 /// Note that just because the weak reference is active does not mean that the
 /// object is still reachable. It might be lingering in memory until the next
@@ -27,75 +41,57 @@ pub struct RWeakRef {
 }
 
 impl RWeakRef {
-    pub fn new(obj: libr::SEXP, finalizer: impl FnOnce()) -> Self {
-        // Convert generic `FnOnce` to a boxed trait object. This must be an
-        // explicit `Box<dyn FnOnce()>`, not a `Box<impl FnOnce()>`, which you
-        // get if you don't specify the type. The latter is not a proper trait
-        // object.
-        let finalizer: Box<dyn FnOnce()> = Box::new(finalizer);
-
-        // Since `finalizer` is a trait object we need to double box it before
-        // calling `Box::into_raw()`. If we call `into_raw()` directly on the
-        // boxed trait object it returns a fat pointer with a vtable, which is
-        // not a valid C pointer we can pass across the FFI boundary. So we
-        // rebox it and convert the outer box to a pointer. See
-        // https://users.rust-lang.org/t/how-to-convert-box-dyn-fn-into-raw-pointer-and-then-call-it/104410.
-        let finalizer = Box::new(finalizer);
-
-        // Create a C pointer to the outer box and prevent it from being
-        // destructed on drop. The resource will now be managed by R.
-        let finalizer = Box::into_raw(finalizer);
-
-        // Wrap that address in an R external pointer.
-        let finalizer = RObject::new(unsafe {
-            libr::R_MakeExternalPtr(
-                finalizer as *mut std::ffi::c_void,
-                RObject::null().sexp,
-                RObject::null().sexp,
-            )
-        });
-
-        // This is the C callback that unpacks the external pointer when the
-        // weakref is finalized.
-        unsafe extern "C-unwind" fn finalize_weak_ref(key: libr::SEXP) {
-            let finalizer = libr::R_ExternalPtrAddr(key) as *mut Box<dyn FnOnce()>;
-
-            if finalizer.is_null() {
-                log::warn!("Weakref finalizer is unexpectedly NULL");
-                return;
+    pub fn new(obj: libr::SEXP, finalizer: impl FnOnce() + 'static) -> Self {
+        // This is the C callback run when the weakref is finalized. It
+        // receives the key, which has already been collected or is being
+        // released, so we find the finalizers to run from the weakrefs that R
+        // has cleared instead.
+        unsafe extern "C-unwind" fn finalize_weak_ref(_key: libr::SEXP) {
+            let stale = FINALIZERS.with_borrow_mut(|finalizers| {
+                finalizers
+                    .extract_if(.., |(weak_ref, _)| unsafe {
+                        libr::R_WeakRefKey(*weak_ref) == libr::R_NilValue
+                    })
+                    .collect::<Vec<_>>()
+            });
+
+            // Run outside of the borrow as finalizers might create or drop
+            // weakrefs of their own
+            for (_, finalizer) in stale {
+                finalizer();
             }
-
-            let finalizer = Box::from_raw(finalizer);
-            finalizer();
         }
 
-        // Finally, the weakref wraps `obj` and our finalizer.
+        // The weakref wraps `obj` as its key so that reachability of the
+        // weakref is determined by `obj`. There is no value to keep alive.
         let weak_ref = RObject::new(unsafe {
             libr::R_MakeWeakRefC(
-                finalizer.sexp, // Protected by weakref
-                obj,            // Not protected by weakref
+                obj, // Not protected by weakref
+                libr::R_NilValue,
                 Some(finalize_weak_ref),
                 libr::Rboolean_FALSE,
             )
         });
 
+        FINALIZERS.with_borrow_mut(|finalizers| {
+            finalizers.push((weak_ref.sexp, Box::new(finalizer)));
+        });
+
         Self { weak_ref }
     }
 
     /// Derefence weakref.
     ///
-    /// If the value is `None`, it means the weakref is now stale and the
-    /// finalizer has been run.
+    /// If the value is `None`, it means the referee has been garbage
+    /// collected and the finalizer has been run.
     pub fn deref(&self) -> Option<RObject> {
-        // If finalizer is `NULL` we know for sure the weakref is stale
+        // R clears the key once the weakref is finalized
         let key = unsafe { libr::R_WeakRefKey(self.weak_ref.sexp) };
         if key == RObject::null().sexp {
             return None;
         }
 
-        Some(RObject::new(unsafe {
-            libr::R_WeakRefValue(self.weak_ref.sexp)
-        }))
+        Some(RObject::new(key))
     }
 }
 
@@ -108,6 +104,9 @@ impl Drop for RWeakRef {
 
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
     use super::*;
     use crate::environment::Environment;
     use crate::parse_eval_base;
@@ -118,17 +117,30 @@ mod tests {
             let env = Environment::new(parse_eval_base("new.env()").unwrap());
 
             // Destructor runs when weakref is dropped
-            let mut has_run = false;
-            let weak_ref = RWeakRef::new(env.inner.sexp, || has_run = true);
+            let has_run = Rc::new(Cell::new(false));
+            let weak_ref = RWeakRef::new(env.inner.sexp, {
+                let has_run = has_run.clone();
+                move || has_run.set(true)
+            });
             drop(weak_ref);
-            assert!(has_run);
+            assert!(has_run.get());
+
+            // Referee is still reachable after a GC as long as there is a
+            // strong reference to it
+            let has_run = Rc::new(Cell::new(false));
+            let weak_ref = RWeakRef::new(env.inner.sexp, {
+                let has_run = has_run.clone();
+                move || has_run.set(true)
+            });
+            parse_eval_base("gc(full = TRUE)").unwrap();
+            assert!(!has_run.get());
+            assert_eq!(weak_ref.deref().unwrap().sexp, env.inner.sexp);
 
-            // Destructor runs when referee is gc'd
-            let mut has_run = false;
-            let _weak_ref = RWeakRef::new(env.inner.sexp, || has_run = true);
+            // Destructor runs and weakref is cleared when referee is gc'd
             drop(env);
             parse_eval_base("gc(full = TRUE)").unwrap();
-            assert!(has_run);
+            assert!(has_run.get());
+            assert!(weak_ref.deref().is_none());
         })
     }
 }
@@ -261,6 +261,30 @@ fn test_add_watched_file_updates_existing_content_preserves_placement() {
     assert_eq!(pkg.files(&db).len(), 1);
 }
 
+#[test]
+fn test_add_watched_file_promotes_orphan_editor_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut db = OakDatabase::new();
+    set_workspace_paths(&mut db, &[tmp.path().to_path_buf()], &HashSet::new());
+
+    // The editor opens a file that doesn't exist on disk yet
+    let fs_path = tmp.path().join("new.R");
+    let path = FilePath::from_path_buf(fs_path.clone()).unwrap();
+    let file_before = db.upsert_editor(path.clone(), "editor\n".to_string());
+    assert!(db.orphan_root().files(&db).contains(&file_before));
+
+    // Then saves it
+    fs::write(&fs_path, "editor\n").unwrap();
+    add_watched_file(&mut db, path.clone());
+
+    let file_after = db.file_by_path(&path).unwrap();
+    assert_eq!(file_before, file_after);
+    assert!(!db.orphan_root().files(&db).contains(&file_after));
+    let scripts = db.workspace_roots().roots(&db)[0].scripts(&db).clone();
+    assert_eq!(scripts, vec![file_after]);
+    assert_eq!(file_after.source_text(&db), "editor\n");
+}
+
 #[test]
 fn test_remove_watched_file_from_package() {
     let tmp = tempfile::tempdir().unwrap();
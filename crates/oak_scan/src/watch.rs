@@ -71,6 +71,18 @@ pub(crate) fn add_watched_file<DB: Db + DbInputs>(db: &mut DB, path: FilePath) {
         if existing.revision(db) != revision {
             existing.set_revision(db).to(revision);
         }
+
+        // An editor buffer for a file that wasn't on disk when it was opened
+        // (e.g. a new file saved for the first time) lives in the orphan
+        // root. Now that it exists on disk, give it the placement the scanner
+        // would have picked. `upsert_root_file()` drops the orphan reference.
+        if db.orphan_root().files(db).contains(&existing) {
+            if let Some(placement) = classify(db, fs_path) {
+                let entry = FileEntry { path, revision };
+                let file = upsert_root_file(db, placement.package_backpointer(), entry);
+                append_to_container(db, file, placement);
+            }
+        }
         return;
     }
 